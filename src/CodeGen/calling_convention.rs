@@ -1,7 +1,22 @@
-use crate::{Target::{x64::X64Reg, Arch, CallConv}, IR::TypeMetadata};
+use std::{error::Error, fmt::Display};
+
+use crate::{Target::{x64::X64Reg, Arch, CallConv}, IR::{DataLayout, FunctionType, TypeMetadata}};
 
 use super::Reg;
 
+/// How an argument or a return value is passed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgPassing {
+    /// in a register
+    Reg(Reg),
+    /// an aggregate which is split into eightbytes which are passed in registers
+    /// (with the type which is used for moving the eightbyte: `i64` or `f64`)
+    Regs(Vec<(Reg, TypeMetadata)>),
+    /// in memory: arguments are passed over the stack and return values are written to the adress
+    /// which the caller passes as a hidden first argument
+    Memory,
+}
+
 /// A more machine specifc calling convention
 /// (Just a wrapper around the normal calling convention but with some pretty handy functions)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,7 +31,7 @@ impl MachineCallingConvention {
             CallConv::WindowsFastCall => {
                 match arch {
                     Arch::X86_64 => match ty {
-                        TypeMetadata::f32 | TypeMetadata::f64 => Reg::x64(X64Reg::Xmm0),
+                        TypeMetadata::f32 | TypeMetadata::f64 | TypeMetadata::Vector(_) => Reg::x64(X64Reg::Xmm0),
                         _ => Reg::x64(X64Reg::Rax.sub_ty(ty))
                     },
                     _ => todo!()
@@ -25,7 +40,7 @@ impl MachineCallingConvention {
             CallConv::SystemV => {
                 match arch {
                     Arch::X86_64 => match ty {
-                        TypeMetadata::f32 | TypeMetadata::f64 => Reg::x64(X64Reg::Xmm0),
+                        TypeMetadata::f32 | TypeMetadata::f64 | TypeMetadata::Vector(_) => Reg::x64(X64Reg::Xmm0),
                         _ => Reg::x64(X64Reg::Rax.sub_ty(ty))
                     },
                     _ => todo!()
@@ -36,17 +51,26 @@ impl MachineCallingConvention {
         }
     }
     
+    /// Returns if the arguments and return values are passed over an operand stack (like on wasm)
+    pub fn operand_stack(&self) -> bool {
+        self.call_conv == CallConv::WasmBasicCAbi
+    }
+    
+    /// Returns if the value of the type is split into eightbytes
+    fn split(ty: TypeMetadata) -> bool {
+        ty.is_aggregate() || ty.is_int128()
+    }
+
     /// returns the args for the specifc architecture
     pub fn arg(&self, arch: Arch, ty: TypeMetadata, idx: usize) -> Option<Reg> {
         match self.call_conv {
             CallConv::WindowsFastCall => {
-                if TypeMetadata::f32 == ty || TypeMetadata::f64 == ty {
+                if TypeMetadata::f32 == ty || TypeMetadata::f64 == ty || ty.is_vector() {
                     return match arch {
                         Arch::X86_64 => {
                             let args = vec![
                                 Reg::x64(X64Reg::Xmm0), Reg::x64(X64Reg::Xmm1), Reg::x64(X64Reg::Xmm2),
-                                Reg::x64(X64Reg::Xmm3), Reg::x64(X64Reg::Xmm4), Reg::x64(X64Reg::Xmm5),
-                                Reg::x64(X64Reg::Xmm6), Reg::x64(X64Reg::Xmm7)
+                                Reg::x64(X64Reg::Xmm3)
                             ];
                             let arg = args.get(idx).cloned();
                             arg
@@ -69,7 +93,7 @@ impl MachineCallingConvention {
                 }
             },
             CallConv::SystemV => {
-                if TypeMetadata::f32 == ty || TypeMetadata::f64 == ty {
+                if TypeMetadata::f32 == ty || TypeMetadata::f64 == ty || ty.is_vector() {
                     return match arch {
                         Arch::X86_64 => {
                             let args = vec![
@@ -89,7 +113,7 @@ impl MachineCallingConvention {
                     Arch::X86_64 => {
                         let args = vec![
                             Reg::x64(X64Reg::Rdi.sub_ty(ty)), Reg::x64(X64Reg::Rsi.sub_ty(ty)), 
-                            Reg::x64(X64Reg::Rcx.sub_ty(ty)), Reg::x64(X64Reg::Rdx.sub_ty(ty)), 
+                            Reg::x64(X64Reg::Rdx.sub_ty(ty)), Reg::x64(X64Reg::Rcx.sub_ty(ty)), 
                             Reg::x64(X64Reg::R8.sub_ty(ty)), Reg::x64(X64Reg::R9.sub_ty(ty))
                         ];
                         let arg = args.get(idx).cloned();
@@ -103,7 +127,186 @@ impl MachineCallingConvention {
         }
     }
 
-    
+
+    /// returns the registers of all arguments of a function with the given argument types
+    /// (`None` means that the argument is passed over the stack)
+    pub fn arg_regs(&self, arch: Arch, args: &[TypeMetadata]) -> Vec<Option<Reg>> {
+        self.arg_passing(arch, args, TypeMetadata::Void).into_iter().map(|passing| match passing {
+            ArgPassing::Reg(reg) => Some(reg),
+            _ => None,
+        }).collect()
+    }
+
+    /// returns how the arguments of a function with the given argument and return types are passed
+    pub fn arg_passing(&self, arch: Arch, args: &[TypeMetadata], ret: TypeMetadata) -> Vec<ArgPassing> {
+        if self.operand_stack() {
+            return vec![ArgPassing::Memory; args.len()];
+        }
+
+        let mut passing = Vec::new();
+
+        // the adress for a return value in memory is the hidden first argument
+        let sret = self.ret_passing(arch, ret) == ArgPassing::Memory;
+
+        let mut gr_index = sret as usize;
+        let mut fp_index = 0;
+
+        for (index, ty) in args.iter().enumerate() {
+            // windows uses the position of the argument for both register types
+            let index = index + sret as usize;
+
+            let Some(eightbytes) = self.eightbytes(*ty).expect("unsupported arguments are rejected by `check`") else {
+                passing.push(ArgPassing::Memory);
+                continue;
+            };
+
+            if !Self::split(*ty) {
+                let reg = match self.call_conv {
+                    CallConv::WindowsFastCall => self.arg(arch, *ty, index),
+                    _ => if ty.float() || ty.is_vector() {
+                        fp_index += 1;
+                        self.arg(arch, *ty, fp_index - 1)
+                    } else {
+                        gr_index += 1;
+                        self.arg(arch, *ty, gr_index - 1)
+                    },
+                };
+
+                passing.push(match reg {
+                    Some(reg) => ArgPassing::Reg(reg),
+                    None => ArgPassing::Memory,
+                });
+
+                continue;
+            }
+
+            let mut regs = Vec::new();
+
+            let (mut gr, mut fp) = (gr_index, fp_index);
+
+            for eightbyte in &eightbytes {
+                let reg = match self.call_conv {
+                    CallConv::WindowsFastCall => self.arg(arch, *eightbyte, index),
+                    _ => if eightbyte.float() {
+                        fp += 1;
+                        self.arg(arch, *eightbyte, fp - 1)
+                    } else {
+                        gr += 1;
+                        self.arg(arch, *eightbyte, gr - 1)
+                    },
+                };
+
+                if let Some(reg) = reg {
+                    regs.push((reg, *eightbyte));
+                }
+            }
+
+            // an aggregate (or 128 bit integer) is passed over the stack if not all of its eightbytes fit into the left registers
+            if regs.len() == eightbytes.len() {
+                (gr_index, fp_index) = (gr, fp);
+
+                passing.push(ArgPassing::Regs(regs));
+            } else {
+                passing.push(ArgPassing::Memory);
+            }
+        }
+
+        passing
+    }
+
+    /// returns how a value of the type is returned
+    pub fn ret_passing(&self, arch: Arch, ty: TypeMetadata) -> ArgPassing {
+        if !Self::split(ty) {
+            return ArgPassing::Reg(self.return_reg(arch, ty));
+        }
+
+        let Some(eightbytes) = self.eightbytes(ty).expect("unsupported return values are rejected by `check`") else {
+            return ArgPassing::Memory;
+        };
+
+        let mut grs = vec![X64Reg::Rax, X64Reg::Rdx].into_iter();
+        let mut fps = vec![X64Reg::Xmm0, X64Reg::Xmm1].into_iter();
+
+        ArgPassing::Regs(eightbytes.into_iter().map(|eightbyte| {
+            let reg = if eightbyte.float() { fps.next() } else { grs.next() };
+
+            (Reg::x64(reg.expect("aggregates in registers have at most two eightbytes")), eightbyte)
+        }).collect())
+    }
+
+    /// Classifies the type: returns the types of the eightbytes which are passed in registers
+    /// or `None` if the value is passed in memory (and an error if the calling convention can't pass it)
+    /// 
+    /// Under SystemV aggregates up to 16 bytes are passed in registers.
+    /// An eightbyte goes into a fp register if it only contains floats.
+    /// 
+    /// Windows only passes aggregates with a size of 1, 2, 4 or 8 bytes in general purpose registers.
+    /// 
+    /// 128 bit integers are handled like a struct of two `i64`s.
+    /// 
+    /// Vectors are passed in one xmm register.
+    fn eightbytes(&self, ty: TypeMetadata) -> Result<Option<Vec<TypeMetadata>>, CallConvError> {
+        let unsupported = Err(CallConvError::UnsupportedType(self.call_conv, ty));
+
+        if ty.is_vector() && self.call_conv == CallConv::WindowsFastCall {
+            // windows passes vectors over a pointer to a copy
+            return unsupported;
+        }
+
+        if ty.is_int128() {
+            return match self.call_conv {
+                CallConv::SystemV => Ok(Some(vec![TypeMetadata::i64, TypeMetadata::i64])),
+                _ => unsupported,
+            };
+        }
+
+        if !ty.is_aggregate() {
+            return Ok(Some(vec![ty]));
+        }
+
+        let layout = DataLayout::default();
+        let size = layout.size(ty);
+
+        match self.call_conv {
+            CallConv::SystemV => {
+                if size > 16 {
+                    return Ok(None);
+                }
+
+                let flat = layout.flatten(ty);
+
+                Ok(Some((0..size.div_ceil(8)).map(|eightbyte| {
+                    let only_floats = flat.iter()
+                        .filter(|(offset, _)| offset / 8 == eightbyte)
+                        .all(|(_, scalar)| scalar.float());
+
+                    if only_floats { TypeMetadata::f64 } else { TypeMetadata::i64 }
+                }).collect()))
+            },
+            CallConv::WindowsFastCall => match size {
+                1 | 2 | 4 | 8 => Ok(Some(vec![TypeMetadata::i64])),
+                // windows passes other aggregates over a pointer to a copy
+                _ => unsupported,
+            },
+            _ => unsupported,
+        }
+    }
+
+    /// Checks if the calling convention can pass the arguments and the return value of the function type
+    /// 
+    /// The operand stack of wasm only takes scalars (and the lanes of vectors), so aggregates are rejected there
+    pub fn check(&self, ty: &FunctionType) -> Result<(), CallConvError> {
+        for (_, arg) in &ty.args {
+            self.eightbytes(*arg)?;
+        }
+
+        if Self::split(ty.ret) {
+            self.eightbytes(ty.ret)?;
+        }
+
+        Ok(())
+    }
+
     /// returns the args for the specifc architecture
     pub fn args(&self, arch: Arch, ty: TypeMetadata) -> Vec<Reg> {
         match self.call_conv {
@@ -143,7 +346,7 @@ impl MachineCallingConvention {
                 match arch {
                     Arch::X86_64 => vec![
                         Reg::x64(X64Reg::Rdi), Reg::x64(X64Reg::Rsi), 
-                        Reg::x64(X64Reg::Rcx), Reg::x64(X64Reg::Rdx), 
+                        Reg::x64(X64Reg::Rdx), Reg::x64(X64Reg::Rcx), 
                         Reg::x64(X64Reg::R8), Reg::x64(X64Reg::R9)
                     ],
                     _ => todo!()
@@ -174,8 +377,7 @@ impl MachineCallingConvention {
     pub fn shadow(&self, _: Arch) -> i64 {
         match self.call_conv {
             CallConv::WindowsFastCall => 32,
-            CallConv::SystemV => 16,
-            _ => 8,
+            _ => 0,
         }
     }
 
@@ -187,7 +389,12 @@ impl MachineCallingConvention {
     /// Returns the fp registers which are saved by the caller
     pub fn caller_saved_fps(&self, arch: Arch) -> Vec<Reg> {
         match (arch, self.call_conv) {
-            (Arch::X86_64, CallConv::SystemV) => Vec::new(),
+            (Arch::X86_64, CallConv::SystemV) => vec![
+                Reg::x64(X64Reg::Xmm0), Reg::x64(X64Reg::Xmm1), Reg::x64(X64Reg::Xmm2), Reg::x64(X64Reg::Xmm3),
+                Reg::x64(X64Reg::Xmm4), Reg::x64(X64Reg::Xmm5), Reg::x64(X64Reg::Xmm6), Reg::x64(X64Reg::Xmm7),
+                Reg::x64(X64Reg::Xmm8), Reg::x64(X64Reg::Xmm9), Reg::x64(X64Reg::Xmm10), Reg::x64(X64Reg::Xmm11),
+                Reg::x64(X64Reg::Xmm12), Reg::x64(X64Reg::Xmm13), Reg::x64(X64Reg::Xmm14), Reg::x64(X64Reg::Xmm15),
+            ],
             (Arch::X86_64, CallConv::WindowsFastCall) => vec![Reg::x64(X64Reg::Xmm0), Reg::x64(X64Reg::Xmm1), Reg::x64(X64Reg::Xmm2), Reg::x64(X64Reg::Xmm3), Reg::x64(X64Reg::Xmm4), Reg::x64(X64Reg::Xmm5)],
             _ => Vec::new(),
         }
@@ -201,4 +408,21 @@ impl MachineCallingConvention {
             _ => Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// An argument or return value which the calling convention can't pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallConvError {
+    /// The calling convention doesn't support values of the type
+    UnsupportedType(CallConv, TypeMetadata),
+}
+
+impl Display for CallConvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            CallConvError::UnsupportedType(call, ty) => format!("the calling convention {:?} doesn't support passing {}", call, ty),
+        })
+    }
+}
+
+impl Error for CallConvError {}
@@ -0,0 +1,88 @@
+use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand, Reg};
+use crate::IR::{Type, TypeMetadata, Var};
+
+use super::CompilationHelper;
+
+/// Aggregates are handled over their adress:
+///  - allocated variables (allocas, aggregate arguments and call results) are the stack slot which holds the aggregate
+///  - all other variables are pointers to the aggregate
+impl CompilationHelper {
+    /// Returns an operand which holds the adress of the aggregate
+    pub(crate) fn aggregate_adr(&self, var: &Var, loc: MachineOperand, mc_sink: &mut Vec<MachineInstr>) -> MachineOperand {
+        if !self.allocated_vars.contains(&var.name) {
+            return loc;
+        }
+
+        let mut adr = MachineInstr::new(MachineMnemonic::AdrMove);
+        adr.set_out(MachineOperand::Reg(self.tmp_reg));
+        adr.add_operand(loc);
+        adr.meta = TypeMetadata::ptr;
+        mc_sink.push(adr);
+
+        MachineOperand::Reg(self.tmp_reg)
+    }
+
+    /// Moves the eightbytes of the aggregate (or 128 bit integer) into the registers
+    pub(crate) fn load_eightbytes(&self, var: &Var, loc: MachineOperand, regs: &[(Reg, TypeMetadata)], mc_sink: &mut Vec<MachineInstr>) {
+        let in_slot = self.allocated_vars.contains(&var.name) || var.ty.is_int128();
+
+        if let (true, MachineOperand::Stack(off, _)) = (in_slot, loc) {
+            for (index, (reg, ty)) in regs.iter().enumerate() {
+                let mut instr = MachineInstr::new(MachineMnemonic::Move);
+                instr.set_out(MachineOperand::Reg(*reg));
+                instr.add_operand(MachineOperand::Stack(off - 8 * index as i64, *ty));
+                instr.meta = *ty;
+                mc_sink.push(instr);
+            }
+
+            return;
+        }
+
+        let mut adr = MachineInstr::new(MachineMnemonic::Move);
+        adr.set_out(MachineOperand::Reg(self.tmp_reg));
+        adr.add_operand(loc);
+        adr.meta = TypeMetadata::ptr;
+        mc_sink.push(adr);
+
+        // the temporary register (which holds the adress) is loaded last
+        let mut order: Vec<(usize, &(Reg, TypeMetadata))> = regs.iter().enumerate().collect();
+        order.sort_by_key(|(_, (reg, _))| *reg == self.tmp_reg);
+
+        for (index, (reg, ty)) in order {
+            let mut instr = MachineInstr::new(MachineMnemonic::Load);
+            instr.set_out(MachineOperand::Reg(*reg));
+            instr.add_operand(MachineOperand::Reg(self.tmp_reg));
+            instr.add_operand(MachineOperand::Imm(8.0 * index as f64));
+            instr.meta = *ty;
+            mc_sink.push(instr);
+        }
+    }
+
+    /// Moves the lower and upper half of the 128 bit constant into the registers
+    pub(crate) fn load_int128_const(&self, value: &Type, regs: &[(Reg, TypeMetadata)], mc_sink: &mut Vec<MachineInstr>) {
+        let value = match *value {
+            Type::i128(value) => value,
+            Type::u128(value) => value as i128,
+            value => value.val() as i128,
+        };
+
+        for (index, (reg, ty)) in regs.iter().enumerate() {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out(MachineOperand::Reg(*reg));
+            instr.add_operand(MachineOperand::Imm((value >> (64 * index)) as i64 as f64));
+            instr.meta = *ty;
+            mc_sink.push(instr);
+        }
+    }
+
+    /// Moves the registers into the eightbytes of the stack slot
+    pub(crate) fn store_eightbytes(&self, off: i64, regs: &[(Reg, TypeMetadata)], mc_sink: &mut Vec<MachineInstr>) {
+        for (index, (reg, ty)) in regs.iter().enumerate() {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out(MachineOperand::Stack(off - 8 * index as i64, *ty));
+            instr.add_operand(MachineOperand::Reg(*reg));
+            instr.meta = *ty;
+            mc_sink.push(instr);
+        }
+    }
+}
@@ -1,21 +1,34 @@
 use crate::prelude::Assign;
-use crate::IR::{Block, Const, Type, Var};
-use super::CompilationHelper;
+use crate::Obj::{Linkage, TlsModel};
+use crate::IR::{Block, Const, FunctionType, Type, TypeMetadata, Var};
+use super::{CompilationHelper, VarLocation};
 use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand};
 
 impl CompilationHelper {
     #[allow(missing_docs)]
     pub fn compile_assign_var_type(&mut self, node: &Assign<Var, Type>, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
         let out = *self.vars.get(&node.inner1.name).unwrap();
-        let mut instr = MachineInstr::new(MachineMnemonic::Move);
 
-        instr.set_out(out.into());
+        if let (VarLocation::Mem(off, _), Some(value)) = (out, node.inner2.int_val().filter(|_| node.inner1.ty.is_int128())) {
+            // the halves are moved seperatly (so the constant doesn't need to fit into a f64)
+            for index in 0..2 {
+                let mut instr = MachineInstr::new(MachineMnemonic::Move);
+                instr.set_out(MachineOperand::Stack(off - 8 * index, TypeMetadata::i64));
+                instr.add_operand(MachineOperand::Imm((value >> (64 * index)) as i64 as f64));
+                instr.meta = TypeMetadata::i64;
+                mc_sink.push(instr);
+            }
+        } else {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
 
-        instr.add_operand(MachineOperand::Imm(node.inner2.val()));
+            instr.set_out(out.into());
 
-        instr.meta = node.inner1.ty;
+            instr.add_operand(MachineOperand::Imm(node.inner2.val()));
 
-        mc_sink.push( instr );
+            instr.meta = node.inner1.ty;
+
+            mc_sink.push( instr );
+        }
 
         if let Some(phi_loc) = self.phi_vars.get(&node.inner1.name) {
             let mut instr = MachineInstr::new(MachineMnemonic::Move);
@@ -52,19 +65,29 @@ impl CompilationHelper {
     }
     
     #[allow(missing_docs)]
-    pub fn compile_assign_var_const(&mut self, node: &Assign<Var, Const>, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+    pub fn compile_assign_var_const(&mut self, node: &Assign<Var, Const>, mc_sink: &mut Vec<MachineInstr>, _: &Block, module: &mut crate::prelude::Module) {
         let out = *self.vars.get(&node.inner1.name).unwrap();
 
-        let mut instr = MachineInstr::new(
-            MachineMnemonic::AdressLoad(node.inner2.name.to_string())
-        );
+        // an operand stack machine (like wasm) only has one thread,
+        // so thread locals are accessed like every other global
+        let thread_local = module.consts.get(&node.inner2.name)
+            .filter(|constant| constant.thread_local && !self.call.operand_stack())
+            .map(|constant| constant.linkage);
 
-        instr.set_out(out.into());
+        if let Some(linkage) = thread_local {
+            self.compile_tls_adress_load(node, linkage, mc_sink, module);
+        } else {
+            let mut instr = MachineInstr::new(
+                MachineMnemonic::AdressLoad(node.inner2.name.to_string())
+            );
 
-        
-        instr.meta = node.inner1.ty; // is a pointer but i just wrote it here
+            instr.set_out(out.into());
 
-        mc_sink.push( instr );
+            
+            instr.meta = node.inner1.ty; // is a pointer but i just wrote it here
+
+            mc_sink.push( instr );
+        }
 
         if let Some(phi_loc) = self.phi_vars.get(&node.inner1.name) {
             let mut instr = MachineInstr::new(MachineMnemonic::Move);
@@ -73,4 +96,53 @@ impl CompilationHelper {
             mc_sink.push(instr);
         }
     }
-}
\ No newline at end of file
+
+    /// Loads the adress of a thread local
+    /// 
+    /// Position independent code calls `__tls_get_addr` (so the caller saved registers get saved),
+    /// else the offset from the thread pointer is known when linking (or loaded from the got if the symbol is imported)
+    fn compile_tls_adress_load(&mut self, node: &Assign<Var, Const>, linkage: Linkage, mc_sink: &mut Vec<MachineInstr>, module: &mut crate::prelude::Module) {
+        let out = *self.vars.get(&node.inner1.name).unwrap();
+
+        let model = if module.pic {
+            TlsModel::GeneralDynamic
+        } else if linkage.is_import() {
+            TlsModel::InitialExec
+        } else {
+            TlsModel::LocalExec
+        };
+
+        let mut instr = MachineInstr::new(
+            MachineMnemonic::TlsAdressLoad(node.inner2.name.to_string(), model)
+        );
+
+        instr.meta = TypeMetadata::ptr;
+
+        if model != TlsModel::GeneralDynamic {
+            instr.set_out(out.into());
+            mc_sink.push( instr );
+            return;
+        }
+
+        if !module.funcs.contains_key("__tls_get_addr") {
+            module.add("__tls_get_addr", &FunctionType::new(vec![("%0".into(), TypeMetadata::ptr)], TypeMetadata::ptr)).import();
+        }
+
+        let to_save = self.get_vars_to_save_for_call(node);
+        let saved = self.save_for_call(to_save, mc_sink);
+
+        let ret = MachineOperand::Reg(self.call.return_reg(self.arch, TypeMetadata::ptr));
+
+        instr.set_out(ret);
+        mc_sink.push( instr );
+
+        self.restore_after_call(saved, mc_sink);
+
+        let mut instr = MachineInstr::new(MachineMnemonic::Move);
+        instr.set_out(out.into());
+        instr.add_operand(ret);
+        instr.meta = TypeMetadata::ptr;
+
+        mc_sink.push( instr );
+    }
+}
@@ -1,22 +1,124 @@
 use std::collections::HashMap;
 
-use crate::{prelude::{Call, IROperand}, CodeGen::{MachineMnemonic, MachineOperand, Reg}, Target::Arch, IR::TypeMetadata};
+use crate::{prelude::{Call, IROperand, IndirectCall}, CodeGen::{ArgPassing, MachineMnemonic, MachineOperand, Reg}, IR::{TypeMetadata, Var}};
 use crate::IR::Block;
 use super::{CompilationHelper, VarLocation};
 use crate::CodeGen::MachineInstr;
 
 impl CompilationHelper {
     #[allow(missing_docs)]
-    pub fn compile_call(&mut self, node: &Call, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
-        let mut reg_args = 0;
-        let mut fp_reg_args = 0;
+    pub fn compile_call(&mut self, node: &Call, mc_sink: &mut Vec<MachineInstr>, _: &Block, module: &mut crate::prelude::Module) {
+        let to_save = self.get_vars_to_save_for_call(node);
 
+        self.compile_call_saving(node, to_save, mc_sink, module);
+    }
+
+    #[allow(missing_docs)]
+    pub fn compile_indirect_call(&mut self, node: &IndirectCall, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+        let to_save = self.get_vars_to_save_for_call(node);
+
+        let arg_types: Vec<TypeMetadata> = node.args.iter().enumerate().map(|(index, arg)| {
+            node.ty.args.get(index)
+                .map(|(_, ty)| *ty)
+                .unwrap_or(arg.get_ty())
+        }).collect();
+
+        let call = MachineMnemonic::CallIndirect(node.ty.to_owned());
+
+        self.compile_call_to(call, Some(&node.callee), &node.out, &node.args, &arg_types, to_save, mc_sink);
+
+        if let Some(phi_loc) = self.phi_vars.get(&node.out.name) {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out((*phi_loc).into());
+            instr.add_operand((*self.vars.get(&node.out.name).unwrap()).into());
+            instr.meta = node.out.ty;
+            mc_sink.push(instr);
+        }
+    }
+
+    /// Compiles the call and saves the caller saved registers of the variables around it
+    /// 
+    /// (calls which aren't in the ir (like libcalls) use the variables of the node they implement)
+    pub(crate) fn compile_call_saving(&mut self, node: &Call, to_save: Vec<(String, VarLocation)>, mc_sink: &mut Vec<MachineInstr>, module: &mut crate::prelude::Module) {
+        // aggregates are passed as pointers, so the types of the callee are used if it is known
+        let arg_types: Vec<TypeMetadata> = node.args.iter().enumerate().map(|(index, arg)| {
+            module.funcs.get(&node.func.name)
+                .and_then(|func| func.ty.args.get(index))
+                .map(|(_, ty)| *ty)
+                .unwrap_or(arg.get_ty())
+        }).collect();
+
+        let call = MachineMnemonic::Call(node.func.name.to_string());
+
+        self.compile_call_to(call, None, &node.out, &node.args, &arg_types, to_save, mc_sink);
+
+        if let Some(phi_loc) = self.phi_vars.get(&node.out.name) {
+            let loc = *self.vars.get(&node.out.name).unwrap();
+
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out((*phi_loc).into());
+            instr.add_operand(loc.into());
+            instr.meta = node.out.ty;
+            mc_sink.push(instr);
+        }
+    }
+
+    /// Returns the operand which holds the callee of an indirect call while the arguments are set up
+    /// 
+    /// The callee is moved into a stack slot if it lives in a register which is overwritten before the call
+    fn callee_operand(&mut self, callee: &Var, saved: &HashMap<String, ((i64, TypeMetadata), VarLocation)>, arg_passing: &[ArgPassing], ret: TypeMetadata, mc_sink: &mut Vec<MachineInstr>) -> MachineOperand {
+        let loc = *self.vars.get(&callee.name).unwrap_or_else(|| panic!("expected valid variable: {}", callee.name));
+
+        let VarLocation::Reg(reg) = loc else { return loc.into() };
+
+        if self.call.operand_stack() {
+            return loc.into();
+        }
+
+        let mut clobbered = vec![self.tmp_reg, self.call.return_reg(self.arch, TypeMetadata::ptr)];
+
+        for passing in arg_passing {
+            match passing {
+                ArgPassing::Reg(reg) => clobbered.push(*reg),
+                ArgPassing::Regs(regs) => clobbered.extend(regs.iter().map(|(reg, _)| *reg)),
+                ArgPassing::Memory => {},
+            }
+        }
+
+        if self.call.ret_passing(self.arch, ret) == ArgPassing::Memory {
+            clobbered.extend(self.call.arg(self.arch, TypeMetadata::ptr, 0));
+        }
+
+        if !Reg::contains_reg(reg, &clobbered) {
+            return loc.into();
+        }
+
+        // the register was already saved for the call
+        if let Some((save, _)) = saved.get(&callee.name) {
+            return MachineOperand::Stack(save.0, save.1);
+        }
+
+        let VarLocation::Mem(off, ty) = self.alloc_stack(TypeMetadata::ptr) else { unreachable!() };
+
+        let mut spill = MachineInstr::new(MachineMnemonic::Move);
+        spill.set_out(MachineOperand::Stack(off, ty));
+        spill.add_operand(loc.into());
+        spill.meta = TypeMetadata::ptr;
+        mc_sink.push(spill);
+
+        MachineOperand::Stack(off, ty)
+    }
+
+    /// Moves the variables which are in caller saved registers onto the stack
+    /// 
+    /// Returns the stack slots and the original locations of the saved variables
+    pub(crate) fn save_for_call(&mut self, to_save: Vec<(String, VarLocation)>, mc_sink: &mut Vec<MachineInstr>) -> HashMap<String, ((i64, TypeMetadata), VarLocation)> {
         let caller_grs = self.call.caller_saved_grs(self.arch);
         let caller_fps = self.call.caller_saved_fps(self.arch);
 
         let mut saved = HashMap::new();
         
-        for (name, loc) in self.get_vars_to_save_for_call(node) {
+        for (name, loc) in to_save {
             let typ = *self.var_types.get(&name).unwrap();
 
             match loc {
@@ -33,6 +135,7 @@ impl CompilationHelper {
 
                         save.set_out(MachineOperand::Stack(off.0, off.1));
                         save.add_operand(loc.into());
+                        save.meta = typ;
 
                         mc_sink.push(save);
                     }
@@ -41,160 +144,229 @@ impl CompilationHelper {
             }
         }
 
-        let mut pushes = Vec::new();
+        saved
+    }
+
+    /// Moves the saved variables back into their registers
+    pub(crate) fn restore_after_call(&mut self, saved: HashMap<String, ((i64, TypeMetadata), VarLocation)>, mc_sink: &mut Vec<MachineInstr>) {
+        for (_, (stack, original)) in saved {
+            let mut restore = MachineInstr::new( MachineMnemonic::Move);
+
+            restore.set_out(original.into());
+            restore.add_operand(MachineOperand::Stack(stack.0, stack.1));
+            restore.meta = stack.1;
+
+            mc_sink.push( restore );
+        }
+    }
+
+    /// Compiles a call (`callee` is the function pointer of indirect calls)
+    #[allow(clippy::too_many_arguments)]
+    fn compile_call_to(&mut self, call: MachineMnemonic, callee: Option<&Var>, out: &Var, args: &[IROperand], arg_types: &[TypeMetadata], to_save: Vec<(String, VarLocation)>, mc_sink: &mut Vec<MachineInstr>) {
+        let saved = self.save_for_call(to_save, mc_sink);
+
+        let ret = out.ty;
+
+        let arg_passing = self.call.arg_passing(self.arch, arg_types, ret);
+
+        let callee = callee.map(|callee| self.callee_operand(callee, &saved, &arg_passing, ret, mc_sink));
+
+        // arguments which don't fit into registers are passed over the stack
+        let stack_args: Vec<(&IROperand, TypeMetadata)> = args.iter()
+            .zip(arg_types.iter())
+            .zip(arg_passing.iter())
+            .filter(|(_, passing)| **passing == ArgPassing::Memory)
+            .map(|((arg, ty), _)| (arg, *ty))
+            .collect();
+
+        let stack_size: i64 = stack_args.iter()
+            .map(|(_, ty)| if ty.is_aggregate() || ty.is_int128() { ty.byteSize().div_ceil(8) as i64 * 8 } else { 8 })
+            .sum();
+        let shadow = self.call.shadow(self.arch);
+
+        // the stack needs to be 16 byte aligned at the call
+        let padding = (16 - stack_size % 16) % 16;
+
+        if padding > 0 {
+            let mut instr = MachineInstr::new(MachineMnemonic::CallStackPrepare);
+            instr.add_operand(MachineOperand::Imm(padding as f64));
+            mc_sink.push(instr);
+        }
 
-        let args = self.call.args(Arch::X86_64, TypeMetadata::i64);
-        let fp_args = self.call.args(Arch::X86_64, TypeMetadata::f64);
+        // the arguments are pushed in reverse order, so the first one is at the lowest adress
+        // (on an operand stack the first argument is pushed first)
+        let stack_args: Vec<&(&IROperand, TypeMetadata)> = if self.call.operand_stack() {
+            stack_args.iter().collect()
+        } else {
+            stack_args.iter().rev().collect()
+        };
+
+        for (arg, ty) in stack_args {
+            let mut instr = MachineInstr::new(MachineMnemonic::Push);
+            instr.meta = arg.get_ty();
 
-        for arg in &node.args {
             if let IROperand::Var(arg) = arg {
-                let src = self.vars.get(&arg.name).expect(&format!("expected valid variable: {}", arg.name));
-    
-                let arg_reg = if TypeMetadata::f32 == arg.ty || TypeMetadata::f64 == arg.ty {
-                    fp_args.get(fp_reg_args)
-                } else { 
-                    args.get(reg_args)
-                };
-    
-                let mut arg_reg = arg_reg.cloned();
-    
-                if let Some(reg) = arg_reg {
-                    arg_reg = Some(match reg {
-                        Reg::x64(x64) => Reg::x64(x64.sub_ty(arg.ty)),
-                        Reg::wasm(i, t) => Reg::wasm(i, t),
-                    })
+                let mut src = (*self.vars.get(&arg.name).unwrap_or_else(|| panic!("expected valid variable: {}", arg.name))).into();
+
+                if let Some((save, _)) = saved.get(&arg.name) {
+                    src = MachineOperand::Stack(save.0, save.1);
                 }
-                
-                if let Some(reg) = arg_reg {
-                    if !self.allocated_vars.contains(&arg.name) {
-                        let mut instr = MachineInstr::new(MachineMnemonic::Move);
-        
-                        let mut op = src.into();
-        
-                        if let Some((save, _)) = saved.get(&arg.name) {
-                            op = MachineOperand::Stack(save.0, save.1);
-                        }
-        
-                        instr.set_out(MachineOperand::Reg(reg));
-                        instr.add_operand(op);
-    
-                        instr.meta = arg.ty;
-    
-                        mc_sink.push( instr );
-                    } else {
-                        let mut instr = MachineInstr::new(MachineMnemonic::AdrMove);
-    
-                        instr.set_out(MachineOperand::Reg(reg));
-                        instr.add_operand(src.into());
-    
-                        instr.meta = arg.ty;
-    
-                        mc_sink.push( instr );
-                    }
+
+                if ty.is_aggregate() {
+                    instr.meta = *ty;
+                    instr.add_operand(self.aggregate_adr(arg, src, mc_sink));
+                } else if self.allocated_vars.contains(&arg.name) {
+                    let mut adr = MachineInstr::new(MachineMnemonic::AdrMove);
+                    adr.set_out(MachineOperand::Reg(self.tmp_reg));
+                    adr.add_operand(src);
+                    adr.meta = TypeMetadata::ptr;
+                    mc_sink.push( adr );
+
+                    instr.add_operand(MachineOperand::Reg(self.tmp_reg));
                 } else {
-                    if !self.allocated_vars.contains(&arg.name) {
-                        let mut instr = MachineInstr::new(MachineMnemonic::Push);
-                        instr.add_operand(src.into());
-                        pushes.push(arg.ty);
-                        mc_sink.push( instr );
-                    } else {
-                        let mut instr = MachineInstr::new(MachineMnemonic::AdrMove);
-    
-                        instr.set_out(MachineOperand::Reg(self.tmp_reg));
-                        instr.add_operand(src.into());
-                        mc_sink.push( instr );
-    
-                        let mut instr = MachineInstr::new(MachineMnemonic::Push);
-                        instr.add_operand(MachineOperand::Reg(self.tmp_reg));
-                        pushes.push(arg.ty);
-                        mc_sink.push( instr );
-                    }
+                    instr.add_operand(src);
                 }
             } else {
-                let arg_reg = if arg.get_ty().float() {
-                    fp_args.get(fp_reg_args)
-                } else { 
-                    args.get(reg_args)
+                instr.add_operand(arg.into_mi(self));
+            }
+
+            mc_sink.push( instr );
+        }
+
+        if shadow > 0 {
+            let mut instr = MachineInstr::new(MachineMnemonic::CallStackPrepare);
+            instr.add_operand(MachineOperand::Imm(shadow as f64));
+            mc_sink.push(instr);
+        }
+
+        for (arg, passing) in args.iter().zip(arg_passing.iter()) {
+            if let ArgPassing::Regs(regs) = passing {
+                let arg = match arg {
+                    IROperand::Var(arg) => arg,
+                    IROperand::Type(value) => {
+                        self.load_int128_const(value, regs, mc_sink);
+                        continue;
+                    },
                 };
 
-                let mut arg_reg = arg_reg.cloned();
+                let mut src = (*self.vars.get(&arg.name).unwrap_or_else(|| panic!("expected valid variable: {}", arg.name))).into();
 
-                if let Some(reg) = arg_reg {
-                    arg_reg = Some(match reg {
-                        Reg::x64(x64) => Reg::x64(x64.sub_ty(arg.get_ty())),
-                        Reg::wasm(i, t) => Reg::wasm(i, t),
-                    })
+                if let Some((save, _)) = saved.get(&arg.name) {
+                    src = MachineOperand::Stack(save.0, save.1);
                 }
 
-                if let Some(reg) = arg_reg {
+                self.load_eightbytes(arg, src, regs, mc_sink);
+
+                continue;
+            }
+
+            let ArgPassing::Reg(reg) = passing else { continue; };
+
+            let reg = match reg {
+                Reg::x64(x64) => Reg::x64(x64.sub_ty(arg.get_ty())),
+                Reg::wasm(i, t) => Reg::wasm(*i, *t),
+            };
+
+            if let IROperand::Var(arg) = arg {
+                let src = self.vars.get(&arg.name).unwrap_or_else(|| panic!("expected valid variable: {}", arg.name));
+
+                if !self.allocated_vars.contains(&arg.name) {
                     let mut instr = MachineInstr::new(MachineMnemonic::Move);
+
+                    let mut op = src.into();
+
+                    if let Some((save, _)) = saved.get(&arg.name) {
+                        op = MachineOperand::Stack(save.0, save.1);
+                    }
+
                     instr.set_out(MachineOperand::Reg(reg));
-                    instr.add_operand(arg.into_mi(self));
-                    mc_sink.push(instr);
+                    instr.add_operand(op);
+
+                    instr.meta = arg.ty;
+
+                    mc_sink.push( instr );
                 } else {
-                    let mut instr = MachineInstr::new(MachineMnemonic::Push);
-                    instr.add_operand(arg.into_mi(self));
-                    pushes.push(arg.get_ty());
+                    let mut instr = MachineInstr::new(MachineMnemonic::AdrMove);
+
+                    instr.set_out(MachineOperand::Reg(reg));
+                    instr.add_operand(src.into());
+
+                    instr.meta = arg.ty;
+
                     mc_sink.push( instr );
                 }
-            }
-    
-            if arg.get_ty().float() {
-                fp_reg_args += 1;
-            } else { 
-                reg_args += 1;
+            } else {
+                let mut instr = MachineInstr::new(MachineMnemonic::Move);
+                instr.set_out(MachineOperand::Reg(reg));
+                instr.add_operand(arg.into_mi(self));
+                instr.meta = arg.get_ty();
+                mc_sink.push(instr);
             }
         }
 
-        if !pushes.is_empty() {
-            mc_sink.push(MachineInstr::new(MachineMnemonic::CallStackPrepare));
+        let ret_passing = self.call.ret_passing(self.arch, ret);
+
+        // the adress of the stack slot for the return value is the hidden first argument
+        if ret_passing == ArgPassing::Memory {
+            let out = *self.vars.get(&out.name).unwrap();
+
+            let mut instr = MachineInstr::new(MachineMnemonic::AdrMove);
+            instr.set_out(MachineOperand::Reg(self.call.arg(self.arch, TypeMetadata::ptr, 0).expect("expected a register for the return adress")));
+            instr.add_operand(out.into());
+            instr.meta = TypeMetadata::ptr;
+            mc_sink.push(instr);
         }
 
-        mc_sink.push(MachineInstr::new(
-            MachineMnemonic::Call(node.func.name.to_string())
-        ));
-        
-        if !pushes.is_empty() {
-            mc_sink.push(MachineInstr::new(MachineMnemonic::CallStackRedo));
+        let mut instr = MachineInstr::new(call);
+
+        if let Some(callee) = callee {
+            instr.add_operand(callee);
         }
 
-        for (_, (stack, original)) in saved {
-            let mut restore = MachineInstr::new( MachineMnemonic::Move);
+        // the return value is taken from the operand stack
+        if self.call.operand_stack() && ret != TypeMetadata::Void {
+            instr.set_out((*self.vars.get(&out.name).unwrap()).into());
+            instr.meta = ret;
+        }
 
-            restore.set_out(original.into());
-            restore.add_operand(MachineOperand::Stack(stack.0, stack.1));
+        mc_sink.push(instr);
 
-            mc_sink.push( restore );
+        let reserved = padding + stack_size + shadow;
+
+        if reserved > 0 {
+            let mut instr = MachineInstr::new(MachineMnemonic::CallStackRedo);
+            instr.add_operand(MachineOperand::Imm(reserved as f64));
+            mc_sink.push(instr);
         }
 
-        for push_type in pushes {
-            let mut instr = MachineInstr::new( MachineMnemonic::PushCleanup );
-            instr.meta = push_type;
+        // aggregates in registers are stored before the saved registers get restored (which could overwrite them)
+        if let ArgPassing::Regs(regs) = &ret_passing {
+            if let Some(VarLocation::Mem(off, _)) = self.vars.get(&out.name) {
+                self.store_eightbytes(*off, regs, mc_sink);
+            }
+        }
 
-            mc_sink.push( instr );
+        self.restore_after_call(saved, mc_sink);
+
+        // aggregates and 128 bit integers are already stored in their stack slot
+        if ret.is_aggregate() || ret.is_int128() || self.call.operand_stack() {
+            return;
         }
 
         let mut instr = MachineInstr::new(MachineMnemonic::Move);
 
-        let loc = *self.vars.get(&node.out.name).unwrap();
+        let loc = *self.vars.get(&out.name).unwrap();
 
         instr.add_operand(
             MachineOperand::Reg(
-                self.call.return_reg(self.arch, node.func.ty.ret)
+                self.call.return_reg(self.arch, ret)
             )
         );
 
-        instr.meta = node.func.ty.ret;
+        instr.meta = ret;
 
         instr.set_out(loc.into());
 
         mc_sink.push(instr);
-
-        if let Some(phi_loc) = self.phi_vars.get(&node.func.name) {
-            let mut instr = MachineInstr::new(MachineMnemonic::Move);
-            instr.set_out((*phi_loc).into());
-            instr.add_operand(loc.into());
-            mc_sink.push(instr);
-        }
     }
 }
\ No newline at end of file
@@ -16,7 +16,8 @@ impl CompilationHelper {
             } else if node.inner1.get_ty().bitSize() > node.inner2.bitSize(){
                 MachineMnemonic::Downcast
             } else {
-                return;
+                // same size (like `i128` to `u128`): the value is just copied
+                |_| MachineMnemonic::Move
             }  
         }(node.inner1.get_ty());
 
@@ -1,4 +1,4 @@
-use crate::{prelude::Cmp, CodeGen::{MachineInstr, MachineMnemonic}, IR::{Block, TypeMetadata}};
+use crate::{prelude::Cmp, CodeGen::{MachineInstr, MachineMnemonic}, IR::Block};
 
 use super::CompilationHelper;
 
@@ -30,7 +30,7 @@ impl CompilationHelper {
             instr.set_out((*phi_loc).into());
             instr.add_operand(out.into());
 
-            instr.meta = TypeMetadata::u8;
+            instr.meta = node.out.ty;
 
             mc_sink.push(instr);
         }
@@ -1,37 +1,85 @@
 use super::CompilationHelper;
 use crate::{prelude::*, CodeGen::*};
+use crate::IR::DataLayout;
 
 impl CompilationHelper {
     #[allow(missing_docs)]
     pub fn compile_getelemptr(&mut self, node: &GetElemPtr, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
-        let vec = self.vars.get(&node.ptr.name).unwrap().into();
-        let out = self.vars.get(&node.out.name).unwrap().into();
-        let index = self.vars.get(&node.index.name).unwrap().into();
+        let ptr: MachineOperand = (*self.vars.get(&node.ptr.name).unwrap()).into();
+        let out: MachineOperand = (*self.vars.get(&node.out.name).unwrap()).into();
 
-        let mut indexcalc_instr = MachineInstr::new( MachineMnemonic::Mul );
-        indexcalc_instr.set_out(out);
-        indexcalc_instr.add_operand(index);
-        indexcalc_instr.add_operand(MachineOperand::Imm(node.ty.byteSize() as f64));
+        // stack variables (allocas and aggregates) are adressed over their stack slot
+        let base = if self.allocated_vars.contains(&node.ptr.name) {
+            MachineMnemonic::AdrMove
+        } else {
+            MachineMnemonic::Move
+        };
 
-        indexcalc_instr.meta = TypeMetadata::ptr;
+        if let Some(offset) = node.const_offset() {
+            let mut instr = MachineInstr::new(base);
+            instr.set_out(out);
+            instr.add_operand(ptr);
+            instr.meta = TypeMetadata::ptr;
+            mc_sink.push(instr);
 
-        mc_sink.push(indexcalc_instr);
+            if offset != 0 {
+                let mut add = MachineInstr::new(MachineMnemonic::Add);
+                add.set_out(out);
+                add.add_operand(out);
+                add.add_operand(MachineOperand::Imm(offset as f64));
+                add.meta = TypeMetadata::ptr;
+                mc_sink.push(add);
+            }
+        } else {
+            let IROperand::Var(index) = &node.index else { unreachable!() };
 
-        let mut add_instr = MachineInstr::new( MachineMnemonic::Add );
-        add_instr.set_out(out);
-        add_instr.add_operand(vec);
-        add_instr.add_operand(out);
+            let index_ty = index.ty;
+            let index: MachineOperand = (*self.vars.get(&index.name).unwrap()).into();
 
-        add_instr.meta = TypeMetadata::ptr;
+            // the index is extended to the pointer size
+            let mut instr = MachineInstr::new(if index_ty.bitSize() < TypeMetadata::ptr.bitSize() {
+                MachineMnemonic::Zext(index_ty)
+            } else {
+                MachineMnemonic::Move
+            });
+            instr.set_out(out);
+            instr.add_operand(index);
+            instr.meta = if index_ty.signed() { TypeMetadata::i64 } else { TypeMetadata::ptr };
+            mc_sink.push(instr);
 
-        mc_sink.push( add_instr );
+            let mut mul = MachineInstr::new(MachineMnemonic::Mul);
+            mul.set_out(out);
+            mul.add_operand(out);
+            mul.add_operand(MachineOperand::Imm(DataLayout::default().offset(node.ty, 1) as f64));
+            mul.meta = TypeMetadata::ptr;
+            mc_sink.push(mul);
 
-        let mut mov_instr = MachineInstr::new( MachineMnemonic::Load );
-        mov_instr.set_out(out);
-        mov_instr.add_operand(out);
+            let mut ptr = ptr;
 
-        mov_instr.meta = node.ty;
+            if base == MachineMnemonic::AdrMove {
+                let mut adr = MachineInstr::new(MachineMnemonic::AdrMove);
+                adr.set_out(MachineOperand::Reg(self.tmp_reg));
+                adr.add_operand(ptr);
+                adr.meta = TypeMetadata::ptr;
+                mc_sink.push(adr);
 
-        mc_sink.push( mov_instr );
+                ptr = MachineOperand::Reg(self.tmp_reg);
+            }
+
+            let mut add = MachineInstr::new(MachineMnemonic::Add);
+            add.set_out(out);
+            add.add_operand(ptr);
+            add.add_operand(out);
+            add.meta = TypeMetadata::ptr;
+            mc_sink.push(add);
+        }
+
+        if let Some(phi_loc) = self.phi_vars.get(&node.out.name) {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out((*phi_loc).into());
+            instr.add_operand(out);
+            instr.meta = TypeMetadata::ptr;
+            mc_sink.push(instr);
+        }
     }
-}
\ No newline at end of file
+}
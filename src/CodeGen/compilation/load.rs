@@ -1,12 +1,14 @@
-use crate::CodeGen::{MachineInstr, MachineMnemonic};
+use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand};
+use crate::prelude::IROperand;
 use crate::IR::{Block, ir::Load};
+use crate::IR::TypeMetadata;
 
 use super::CompilationHelper;
 
 impl CompilationHelper {
     #[allow(missing_docs)]
     pub fn compile_load(&mut self, node: &Load, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
-        let ptr = node.inner3.into_mi(self);
+        let ptr = self.ptr_operand(&node.inner3, mc_sink);
 
         let out = *self.vars.get(&node.inner1.name).unwrap();
         let out = out.into();
@@ -28,4 +30,27 @@ impl CompilationHelper {
         }
         
     }
-}
\ No newline at end of file
+}
+impl CompilationHelper {
+    /// Returns the operand which is used to adress the memory the pointer points to
+    /// 
+    /// Allocated variables are accessed over their stack slot. Other pointers which were spilled onto the stack
+    /// are moved into the temporary register first, so they don't get confused with stack slots.
+    pub(crate) fn ptr_operand(&mut self, ptr: &IROperand, mc_sink: &mut Vec<MachineInstr>) -> MachineOperand {
+        let op = ptr.into_mi(self);
+
+        let IROperand::Var(var) = ptr else { return op; };
+
+        if !matches!(op, MachineOperand::Stack(..)) || self.allocated_vars.contains(&var.name) {
+            return op;
+        }
+
+        let mut instr = MachineInstr::new(MachineMnemonic::Move);
+        instr.set_out(MachineOperand::Reg(self.tmp_reg));
+        instr.add_operand(op);
+        instr.meta = TypeMetadata::ptr;
+        mc_sink.push(instr);
+
+        MachineOperand::Reg(self.tmp_reg)
+    }
+}
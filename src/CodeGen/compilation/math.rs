@@ -1,13 +1,22 @@
 use crate::CodeGen::instr::{MachineMnemonic, MachineInstr};
 use crate::IR::ir::*;
-use crate::prelude::Block;
+use crate::IR::{FuncId, FunctionType, Type, TypeMetadata, Var};
+use crate::prelude::{Block, Module};
 use super::CompilationHelper;
 
 macro_rules! MathImpls {
-    ($func:ident, $node:ident, $mnemonic:expr) => {
+    ($func:ident, $node:ident, $mnemonic:expr $(, $signed:literal, $unsigned:literal)?) => {
         impl CompilationHelper {
             #[allow(missing_docs)]
-            pub(crate) fn $func(&mut self, node: &$node, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+            pub(crate) fn $func(&mut self, node: &$node, mc_sink: &mut Vec<MachineInstr>, _: &Block, _module: &mut crate::prelude::Module) {
+                $(
+                    if node.inner3.ty.is_int128() {
+                        let libcall = if node.inner3.ty.signed() { $signed } else { $unsigned };
+
+                        return self.compile_int128_libcall(libcall, node, [&node.inner1, &node.inner2], &node.inner3, mc_sink, _module);
+                    }
+                )?
+
                 let src1 = node.inner1.into_mi(self);
                 let src2 = node.inner2.into_mi(self);
         
@@ -27,6 +36,7 @@ macro_rules! MathImpls {
                     let mut instr = MachineInstr::new(MachineMnemonic::Move);
                     instr.set_out((*phi_loc).into());
                     instr.add_operand(out.into());
+                    instr.meta = node.inner3.ty;
                     mc_sink.push(instr);
                 }
             }
@@ -37,11 +47,50 @@ macro_rules! MathImpls {
 
 MathImpls!(compile_add, Add, MachineMnemonic::Add);
 MathImpls!(compile_and, And, MachineMnemonic::And);
-MathImpls!(compile_div, Div, MachineMnemonic::Div);
+MathImpls!(compile_div, Div, MachineMnemonic::Div, "__divti3", "__udivti3");
 MathImpls!(compile_mul, Mul, MachineMnemonic::Mul);
 MathImpls!(compile_or, Or, MachineMnemonic::Or);
 MathImpls!(compile_sub, Sub, MachineMnemonic::Sub);
 MathImpls!(compile_xor, Xor, MachineMnemonic::Xor);
-MathImpls!(compile_rem, Rem, MachineMnemonic::Rem);
-MathImpls!(compile_shl, Shl, MachineMnemonic::Shl);
-MathImpls!(compile_shr, Shr, MachineMnemonic::Shr);
\ No newline at end of file
+MathImpls!(compile_rem, Rem, MachineMnemonic::Rem, "__modti3", "__umodti3");
+MathImpls!(compile_shl, Shl, MachineMnemonic::Shl, "__ashlti3", "__ashlti3");
+MathImpls!(compile_shr, Shr, MachineMnemonic::Shr, "__ashrti3", "__lshrti3");
+
+impl CompilationHelper {
+    /// 128 bit divisions and shifts are calls to the functions of libgcc (or compiler-rt) which implement them
+    fn compile_int128_libcall(&mut self, name: &str, node: &dyn Ir, [ls, rs]: [&IROperand; 2], out: &Var, mc_sink: &mut Vec<MachineInstr>, module: &mut Module) {
+        // the shift amount is an `i32`
+        let rs_ty = if name.contains("sh") { TypeMetadata::i32 } else { out.ty };
+
+        let rs = match rs {
+            IROperand::Var(var) => IROperand::Var(Var { name: var.name.to_owned(), ty: rs_ty }),
+            IROperand::Type(value) => IROperand::Type(Type::from_int(rs_ty, value.val())),
+        };
+
+        let ty = FunctionType::new(vec![("%0".into(), out.ty), ("%1".into(), rs_ty)], out.ty);
+
+        if !module.funcs.contains_key(name) {
+            module.add(name, &ty).import();
+        }
+
+        let call = Call {
+            out: out.to_owned(),
+            func: FuncId { name: name.to_owned(), ty },
+            args: vec![ls.to_owned(), rs],
+        };
+
+        let to_save = self.scoped_vars_before_node(node.clone_box()).into_iter()
+            .map(|(var, location)| (var.name, location))
+            .collect();
+
+        self.compile_call_saving(&call, to_save, mc_sink, module);
+
+        if let Some(phi_loc) = self.phi_vars.get(&out.name) {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out((*phi_loc).into());
+            instr.add_operand((*self.vars.get(&out.name).unwrap()).into());
+            instr.meta = out.ty;
+            mc_sink.push(instr);
+        }
+    }
+}
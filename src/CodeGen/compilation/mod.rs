@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::prelude::Ir;
 use crate::Target::{Arch, CallConv};
-use crate::IR::{Function, TypeMetadata, Var};
+use crate::IR::{Function, FunctionAttribute, TypeMetadata, Var};
 
 use super::MachineOperand;
 use super::{calling_convention::MachineCallingConvention, reg::Reg, MCInstr, MachineInstr};
@@ -28,6 +28,8 @@ mod neg;
 
 mod select;
 mod getelemptr;
+mod aggregate;
+mod vector;
 
 /// handeles how constant imms are handeled (wether creating a const or just an instr op)
 /// 
@@ -64,6 +66,13 @@ pub struct Allocator {
     pub(crate) call: MachineCallingConvention,
 
     pub(crate) callee_save_registers: Vec<Reg>,
+
+    /// the return type of the function
+    pub(crate) ret_ty: TypeMetadata,
+    /// the stack slot which holds the adress for a return value which is returned in memory
+    pub(crate) sret: Option<i64>,
+    /// the argument registers which are stored onto the stack in the prolog (register, stack offset, type)
+    pub(crate) arg_spills: Vec<(Reg, i64, TypeMetadata)>,
 }
 /// helps with compilation
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -85,6 +94,13 @@ pub struct CompilationHelper {
     pub(crate) tmp_reg: Reg,
 
     pub(crate) fp_imm: ConstImmRules, 
+
+    /// if the target lowers instructions on vectors itself
+    /// (else they are split into instructions on the lanes)
+    pub(crate) vectors: bool,
+
+    /// if the function is naked (it gets no prolog and epilog)
+    pub(crate) naked: bool,
 }
 
 impl CompilationHelper {
@@ -98,6 +114,8 @@ impl CompilationHelper {
             lower: None,
             tmp_reg: tmp,
             fp_imm: ConstImmRules::InstrOp,
+            vectors: false,
+            naked: false,
             alloc: alloc,
             epilog: false,
             scopes: HashMap::new(),
@@ -120,6 +138,7 @@ impl CompilationHelper {
         self.allocated_vars = self.alloc.allocated_vars.to_owned();
         self.var_types      = self.alloc.var_types.to_owned();
         self.epilog         = self.alloc.epilog.to_owned();
+        self.naked          = func.has_attribute(FunctionAttribute::Naked);
     }
 
     pub(crate) fn alloc_stack(&mut self, ty: TypeMetadata) -> VarLocation {
@@ -156,8 +175,8 @@ impl CompilationHelper {
         got.to_owned()
     }
 
-    fn get_vars_to_save_for_call(&self, node: &crate::prelude::Call) -> Vec<(String, VarLocation)> {
-        let vars = self.scoped_vars_before_node(Box::new( node.clone() ));
+    fn get_vars_to_save_for_call(&self, node: &dyn Ir) -> Vec<(String, VarLocation)> {
+        let vars = self.scoped_vars_before_node(node.clone_box());
     
         let mut with_name = vec![];
         for (var, location) in vars {
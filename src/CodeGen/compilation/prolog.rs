@@ -5,6 +5,11 @@ use super::CompilationHelper;
 impl CompilationHelper {
     #[allow(missing_docs)]
     pub fn compile_prolog(&mut self, sink: &mut Vec<MachineInstr>) {
+        // naked functions set up their stack frame themself
+        if self.naked {
+            return;
+        }
+
         let mut instr = MachineInstr::new( MachineMnemonic::Prolog );
         instr.add_operand( MachineOperand::Imm(self.alloc.stack_off as f64) );
 
@@ -13,10 +18,25 @@ impl CompilationHelper {
         }
 
         sink.push( instr );
+
+        // arguments which are passed in registers but live on the stack
+        for (reg, off, ty) in &self.alloc.arg_spills {
+            let mut spill = MachineInstr::new( MachineMnemonic::Move );
+            spill.set_out( MachineOperand::Stack(*off, *ty) );
+            spill.add_operand( MachineOperand::Reg(*reg) );
+            spill.meta = *ty;
+            spill.turn_into_float_if_needed();
+
+            sink.push( spill );
+        }
     }
 
     #[allow(missing_docs)]
     pub fn compile_epilog(&mut self, sink: &mut Vec<MachineInstr>) {
+        if self.naked {
+            return;
+        }
+
         let mut instr = MachineInstr::new( MachineMnemonic::Epilog );
         instr.add_operand( MachineOperand::Imm(self.alloc.stack_off as f64) );
 
@@ -1,15 +1,121 @@
-use crate::prelude::{Return, Block};
-use crate::CodeGen::{MachineInstr, MachineMnemonic};
+use crate::prelude::{Block, IROperand, Return};
+use crate::CodeGen::{ArgPassing, MachineInstr, MachineMnemonic, MachineOperand};
+use crate::IR::TypeMetadata;
 
 use super::CompilationHelper;
 
 impl CompilationHelper {
     #[allow(missing_docs)]
     pub fn compile_ret(&mut self, node: &Return, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+        if self.alloc.ret_ty.is_aggregate() || self.alloc.ret_ty.is_int128() {
+            return self.compile_aggregate_ret(node, mc_sink);
+        }
+
+        let mut op = node.inner1.into_mi(self);
+
+        if let IROperand::Var(var) = &node.inner1 {
+            if self.allocated_vars.contains(&var.name) {
+                // we return the adress of the stack variable
+                let ret_reg = MachineOperand::Reg(self.call.return_reg(self.arch, var.ty));
+
+                let mut adr = MachineInstr::new(MachineMnemonic::AdrMove);
+                adr.set_out(ret_reg);
+                adr.add_operand(op);
+                adr.meta = var.ty;
+                mc_sink.push(adr);
+
+                op = ret_reg;
+            }
+        }
+
         let mut instr = MachineInstr::new(MachineMnemonic::Return);
-        instr.add_operand(node.inner1.into_mi(self));
+        instr.add_operand(op);
         instr.meta = node.inner1.get_ty();
 
         mc_sink.push( instr );
     }
-}
\ No newline at end of file
+
+    /// Returns an aggregate (or 128 bit integer) in registers or copies it into the memory the caller gave us
+    fn compile_aggregate_ret(&mut self, node: &Return, mc_sink: &mut Vec<MachineInstr>) {
+        let var = match &node.inner1 {
+            IROperand::Var(var) => var,
+            IROperand::Type(value) => {
+                let ArgPassing::Regs(regs) = self.call.ret_passing(self.arch, self.alloc.ret_ty) else { panic!("aggregates need to be variables") };
+
+                self.load_int128_const(value, &regs, mc_sink);
+
+                let mut instr = MachineInstr::new(MachineMnemonic::Return);
+                instr.add_operand(MachineOperand::Reg(regs[0].0));
+                instr.meta = regs[0].1;
+                mc_sink.push(instr);
+
+                return;
+            },
+        };
+
+        let loc: MachineOperand = (*self.vars.get(&var.name).unwrap_or_else(|| panic!("expected valid variable: {}", var.name))).into();
+
+        let mut instr = MachineInstr::new(MachineMnemonic::Return);
+
+        match self.call.ret_passing(self.arch, self.alloc.ret_ty) {
+            ArgPassing::Regs(regs) => {
+                self.load_eightbytes(var, loc, &regs, mc_sink);
+
+                let (reg, ty) = regs[0];
+                instr.add_operand(MachineOperand::Reg(reg));
+                instr.meta = ty;
+            },
+            ArgPassing::Memory => {
+                let sret = self.alloc.sret.expect("expected a return adress for the aggregate");
+                let sret = MachineOperand::Stack(sret, TypeMetadata::ptr);
+
+                let src = self.aggregate_adr(var, loc, mc_sink);
+
+                let mut copy = MachineInstr::new(MachineMnemonic::MemCopy);
+                copy.set_out(sret);
+                copy.add_operand(src);
+                copy.meta = self.alloc.ret_ty;
+                mc_sink.push(copy);
+
+                // the adress of the returned aggregate is returned too
+                instr.add_operand(sret);
+                instr.meta = TypeMetadata::ptr;
+            },
+            ArgPassing::Reg(_) => unreachable!(),
+        }
+
+        mc_sink.push(instr);
+    }
+
+    /// Inserts the epilog in front of the return instruction
+    /// 
+    /// The returned value is moved into the return register before the epilog,
+    /// because it could live in a callee saved register or on the stack
+    pub(crate) fn insert_epilog(&mut self, compiled: &mut Vec<MachineInstr>) {
+        let mut epilog = vec![];
+        self.compile_epilog(&mut epilog);
+
+        let Some(mut ret) = compiled.pop() else { unreachable!() };
+
+        if ret.meta != TypeMetadata::Void {
+            if let Some(op) = ret.operands.first().cloned() {
+                let ret_reg = MachineOperand::Reg(self.call.return_reg(self.arch, ret.meta));
+
+                if op != ret_reg {
+                    let mut mov = MachineInstr::new(MachineMnemonic::Move);
+                    mov.set_out(ret_reg);
+                    mov.add_operand(op);
+                    mov.meta = ret.meta;
+                    mov.turn_into_float_if_needed();
+
+                    compiled.push(mov);
+
+                    ret.operands = vec![ret_reg];
+                }
+            }
+        }
+
+        compiled.extend_from_slice(&epilog);
+        compiled.push(ret);
+    }
+}
@@ -13,7 +13,7 @@ impl CompilationHelper {
 
         let ty = node.yes.get_ty();
 
-        let mut yes_instr = MachineInstr::new(MachineMnemonic::MovIfZero);
+        let mut yes_instr = MachineInstr::new(MachineMnemonic::MovIfNotZero);
         
         yes_instr.set_out(out);
         yes_instr.add_operand(cond);
@@ -23,7 +23,7 @@ impl CompilationHelper {
 
         mc_sink.push(yes_instr);
 
-        let mut no_instr = MachineInstr::new(MachineMnemonic::MovIfNotZero);
+        let mut no_instr = MachineInstr::new(MachineMnemonic::MovIfZero);
         
         no_instr.set_out(out);
         no_instr.add_operand(cond);
@@ -32,5 +32,13 @@ impl CompilationHelper {
         no_instr.meta = ty;
 
         mc_sink.push(no_instr);
+
+        if let Some(phi_loc) = self.phi_vars.get(&node.out.name) {
+            let mut instr = MachineInstr::new(MachineMnemonic::Move);
+            instr.set_out((*phi_loc).into());
+            instr.add_operand(out);
+            instr.meta = node.out.ty;
+            mc_sink.push(instr);
+        }
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::prelude::{Store, Block};
+use crate::prelude::{Store, Block, IROperand};
 use crate::CodeGen::{MachineInstr, MachineMnemonic};
 
 use super::CompilationHelper;
@@ -8,8 +8,7 @@ impl CompilationHelper {
     pub fn compile_store(&mut self, node: &Store, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
         let in_var = node.inner2.into_mi(self);
 
-        let ptr = self.vars.get(&node.inner1.name).expect("expected valid variable");
-        let ptr = (*ptr).into();
+        let ptr = self.ptr_operand(&IROperand::Var(node.inner1.to_owned()), mc_sink);
 
         let mut instr = MachineInstr::new(MachineMnemonic::Store);
 
@@ -0,0 +1,79 @@
+use crate::prelude::{Block, ExtractElement, InsertElement, ShuffleVector};
+use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand};
+use crate::IR::{TypeMetadata, Var};
+
+use super::CompilationHelper;
+
+/// The elements of vectors are accessed over their lanes (see `MachineOperand::lane`),
+/// so the element nodes are compiled into moves of the element type
+impl CompilationHelper {
+    /// Splits the instructions on vectors into instructions on their lanes if the target doesn't lower vectors itself
+    pub(crate) fn scalarize_vectors(&self, instrs: &[MachineInstr]) -> Vec<MachineInstr> {
+        if self.vectors {
+            return instrs.to_vec();
+        }
+
+        instrs.iter().flat_map(|instr| instr.scalarize()).collect()
+    }
+
+    fn location(&self, var: &Var) -> MachineOperand {
+        (*self.vars.get(&var.name).expect("expected a location for the vector")).into()
+    }
+
+    fn move_lane(&self, out: MachineOperand, value: MachineOperand, ty: TypeMetadata, mc_sink: &mut Vec<MachineInstr>) {
+        let mut instr = MachineInstr::new(MachineMnemonic::Move);
+        instr.set_out(out);
+        instr.add_operand(value);
+        instr.meta = ty;
+        mc_sink.push(instr);
+    }
+
+    fn phi_move(&self, out: &Var, mc_sink: &mut Vec<MachineInstr>) {
+        if let Some(phi_loc) = self.phi_vars.get(&out.name) {
+            self.move_lane((*phi_loc).into(), self.location(out), out.ty, mc_sink);
+        }
+    }
+
+    #[allow(missing_docs)]
+    pub fn compile_extractelement(&mut self, node: &ExtractElement, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+        let elem = node.out.ty;
+
+        let lane = self.location(&node.vec).lane(elem, node.index);
+
+        self.move_lane(self.location(&node.out), lane, elem, mc_sink);
+
+        self.phi_move(&node.out, mc_sink);
+    }
+
+    #[allow(missing_docs)]
+    pub fn compile_insertelement(&mut self, node: &InsertElement, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+        let elem = node.value.get_ty();
+
+        let out = self.location(&node.out);
+        let value = node.value.into_mi(self);
+
+        self.move_lane(out, self.location(&node.vec), node.out.ty, mc_sink);
+        self.move_lane(out.lane(elem, node.index), value, elem, mc_sink);
+
+        self.phi_move(&node.out, mc_sink);
+    }
+
+    #[allow(missing_docs)]
+    pub fn compile_shufflevector(&mut self, node: &ShuffleVector, mc_sink: &mut Vec<MachineInstr>, _: &Block, _: &mut crate::prelude::Module) {
+        let (elem, lanes) = node.ls.ty.vector_lanes().expect("shufflevector expects vectors");
+
+        let (ls, rs, out) = (self.location(&node.ls), self.location(&node.rs), self.location(&node.out));
+
+        for (lane, index) in node.mask.iter().enumerate() {
+            let src = if *index < lanes {
+                ls.lane(elem, *index)
+            } else {
+                rs.lane(elem, *index - lanes)
+            };
+
+            self.move_lane(out.lane(elem, lane), src, elem, mc_sink);
+        }
+
+        self.phi_move(&node.out, mc_sink);
+    }
+}
@@ -2,8 +2,8 @@ use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::any::Any;
 use crate::prelude::CmpMode;
-use crate::Obj::Link;
-use crate::IR::{BlockId, Type, TypeMetadata};
+use crate::Obj::{Link, TlsModel};
+use crate::IR::{BlockId, FunctionType, Type, TypeMetadata};
 
 use super::reg::Reg;
 use super::{CompilationHelper, VarLocation};
@@ -64,6 +64,81 @@ impl MachineInstr {
         }
     }
 
+    /// Splits an instruction on a vector into one instruction for every lane
+    /// (for targets which don't support vectors or operations which the target doesn't support on vectors)
+    ///
+    /// Instructions on other types are returned unchanged
+    pub fn scalarize(&self) -> Vec<MachineInstr> {
+        let Some((elem, lanes)) = self.meta.vector_lanes() else {
+            return vec![self.to_owned()];
+        };
+
+        // the target returns the lanes together
+        if self.mnemonic == MachineMnemonic::Return {
+            return vec![self.to_owned()];
+        }
+
+        let mut out = Vec::new();
+
+        for lane in 0..lanes {
+            let mut instr = self.to_owned();
+            instr.meta = elem;
+
+            match self.mnemonic {
+                MachineMnemonic::Move | MachineMnemonic::Add | MachineMnemonic::And | MachineMnemonic::Div |
+                MachineMnemonic::Mul | MachineMnemonic::Or | MachineMnemonic::Sub | MachineMnemonic::Xor |
+                MachineMnemonic::Rem | MachineMnemonic::Neg | MachineMnemonic::Shl | MachineMnemonic::Shr |
+                MachineMnemonic::Push => {
+                    instr.operands = self.operands.iter().map(|op| op.lane(elem, lane)).collect();
+                    instr.out = self.out.map(|out| out.lane(elem, lane));
+                },
+                // op0: the pointer, op1: the optional displacement
+                MachineMnemonic::Load => {
+                    instr.operands = MachineOperand::ptr_lane(&self.operands, elem, lane);
+                    instr.out = self.out.map(|out| out.lane(elem, lane));
+                },
+                // out: the pointer, op0: the value, op1: the optional displacement
+                MachineMnemonic::Store => {
+                    let value = self.operands.first().expect("expected a value to store").lane(elem, lane);
+
+                    let ptr = [self.out.expect("expected a pointer to store to")].into_iter()
+                        .chain(self.operands.iter().skip(1).copied())
+                        .collect::<Vec<_>>();
+
+                    let mut ptr = MachineOperand::ptr_lane(&ptr, elem, lane).into_iter();
+
+                    instr.out = ptr.next();
+                    instr.operands = std::iter::once(value).chain(ptr).collect();
+                },
+                // op0: the condition (which is shared by all lanes), op1: the value
+                MachineMnemonic::MovIfZero | MachineMnemonic::MovIfNotZero => {
+                    instr.operands = self.operands.iter().enumerate()
+                        .map(|(index, op)| if index == 0 { *op } else { op.lane(elem, lane) })
+                        .collect();
+                    instr.out = self.out.map(|out| out.lane(elem, lane));
+                },
+                // the lanes of the source vector are casted into the lanes of the output
+                MachineMnemonic::Zext(from) | MachineMnemonic::Downcast(from) | MachineMnemonic::FCast(from) => {
+                    let from = from.vector_lanes().map_or(from, |(from, _)| from);
+
+                    instr.mnemonic = match self.mnemonic {
+                        MachineMnemonic::Zext(_) => MachineMnemonic::Zext(from),
+                        MachineMnemonic::Downcast(_) => MachineMnemonic::Downcast(from),
+                        _ => MachineMnemonic::FCast(from),
+                    };
+                    instr.operands = self.operands.iter().map(|op| op.lane(from, lane)).collect();
+                    instr.out = self.out.map(|out| out.lane(elem, lane));
+                },
+                // comparisons of vectors are rejected by the verifier (`cmp` yields one `i1`)
+                _ => panic!("{} can't be split into the lanes of {}", self.mnemonic, self.meta),
+            }
+
+            out.push(instr);
+        }
+
+        out
+    }
+
     /// Fixes the instruction imm based on the rules
     /// 
     /// Returns the fixed machine instr (maybe some got added so)
@@ -197,6 +272,38 @@ impl Display for MachineOperand {
     }
 }
 
+impl MachineOperand {
+    /// Returns the operand which holds the lane of the vector
+    ///
+    /// Vectors live in stack slots (the lanes are stored from the lowest adress upwards)
+    /// or in consecutive wasm locals. Constants are used for every lane.
+    pub(crate) fn lane(&self, elem: TypeMetadata, lane: usize) -> MachineOperand {
+        match *self {
+            MachineOperand::Imm(imm) => MachineOperand::Imm(imm),
+            MachineOperand::Stack(off, _) => MachineOperand::Stack(off - (lane * elem.byteSize()) as i64, elem),
+            MachineOperand::Reg(Reg::wasm(local, _)) => MachineOperand::Reg(Reg::wasm(local + lane as i32, elem)),
+            MachineOperand::Reg(reg) => panic!("the lanes of vectors in {:?} can't be adressed", reg),
+        }
+    }
+
+    /// Returns the operands of a load which reads the lane of the vector the pointer points to
+    fn ptr_lane(ptr: &[MachineOperand], elem: TypeMetadata, lane: usize) -> Vec<MachineOperand> {
+        match ptr {
+            // the stack slot which holds the memory
+            [slot @ MachineOperand::Stack(..), ..] => vec![slot.lane(elem, lane)],
+            [ptr, displ @ ..] => {
+                let displ = match displ.first() {
+                    Some(MachineOperand::Imm(displ)) => *displ,
+                    _ => 0.0,
+                };
+
+                vec![*ptr, MachineOperand::Imm(displ + (lane * elem.byteSize()) as f64)]
+            },
+            [] => panic!("expected a pointer"),
+        }
+    }
+}
+
 impl From<VarLocation> for MachineOperand {
     fn from(location: VarLocation) -> Self {
         match location {
@@ -252,10 +359,14 @@ pub enum MachineMnemonic {
     Downcast(/*from type*/TypeMetadata),
 
     Call(String),
+    /// calls the function the first operand points to
+    CallIndirect(FunctionType),
     Br(String),
     Return,
 
     AdressLoad(String),
+    /// loads the adress of the current threads copy of a thread local symbol
+    TlsAdressLoad(String, TlsModel),
     StackAlloc,
 
     Store,
@@ -286,6 +397,11 @@ pub enum MachineMnemonic {
     /// op0: variable
     /// op1: thingy to get moved
     MovIfNotZero,
+
+    /// out: adress of the destination
+    /// op0: adress of the source
+    /// (copies a value of the meta type, which is an aggregate)
+    MemCopy,
 }
 
 impl MachineMnemonic {
@@ -304,8 +420,10 @@ impl MachineMnemonic {
             MachineMnemonic::Zext(_) =>                "zext",
             MachineMnemonic::Downcast(_) =>            "dwcast",
             MachineMnemonic::Call(_) =>             "call",
+            MachineMnemonic::CallIndirect(_) =>     "callindirect",
             MachineMnemonic::Return =>              "return",
             MachineMnemonic::AdressLoad(_) =>       "adrload",
+            MachineMnemonic::TlsAdressLoad(..) =>   "tlsadrload",
             MachineMnemonic::Br(_) =>               "br",
             MachineMnemonic::BrCond(_, _) =>        "comparebr",
             MachineMnemonic::Compare(_) =>          "compare",
@@ -323,6 +441,7 @@ impl MachineMnemonic {
             MachineMnemonic::Neg =>                 "neg",
             MachineMnemonic::MovIfZero =>           "cmovz",
             MachineMnemonic::MovIfNotZero =>        "cmovnz",
+            MachineMnemonic::MemCopy =>             "memcpy",
             MachineMnemonic::Shl =>                 "shl",
             MachineMnemonic::Shr =>                 "shr",
             MachineMnemonic::FMove =>               "fmove",
@@ -348,6 +467,7 @@ impl Display for MachineMnemonic {
         write!(f, "{}", match self {
             MachineMnemonic::Call(target) => format!("{} {}", self.name(), target),
             MachineMnemonic::AdressLoad(adr) => format!("{} {}", self.name(), adr),
+            MachineMnemonic::TlsAdressLoad(adr, model) => format!("{} {} {:?}", self.name(), adr, model),
             _ => self.name().to_string()
         })
     }
@@ -70,7 +70,7 @@ macro_rules! ir_codegen_wrap {
 
             let mut fixed = Vec::new();
 
-            for inst in &mut area.compiled {
+            for mut inst in self.helper.scalarize_vectors(&area.compiled) {
                 inst.turn_into_float_if_needed();
                 fixed.extend_from_slice(
                     &inst.fix_const_imm(&mut self.helper, module)
@@ -170,6 +170,11 @@ impl IrCodeGenHelper {
         "Loweres the call node", 
         ir::Call
     );
+    ir_codegen_wrap!(
+        compile_indirect_call, 
+        "Loweres the indirect call node", 
+        ir::IndirectCall
+    );
     ir_codegen_wrap!(
         compile_cast, 
         "Loweres the cast node", 
@@ -215,6 +220,21 @@ impl IrCodeGenHelper {
         "Loweres the getelemptr node", 
         ir::GetElemPtr
     );
+    ir_codegen_wrap!(
+        compile_extractelement, 
+        "Loweres the extractelement node", 
+        ir::ExtractElement
+    );
+    ir_codegen_wrap!(
+        compile_insertelement, 
+        "Loweres the insertelement node", 
+        ir::InsertElement
+    );
+    ir_codegen_wrap!(
+        compile_shufflevector, 
+        "Loweres the shufflevector node", 
+        ir::ShuffleVector
+    );
 }
 
 impl Into<Vec<MachineInstr>> for IrCodeGenHelper {
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::prelude::{Br, BrCond, Function, Phi, Switch};
+use crate::IR::TypeMetadata;
+
+/// The range of linear positions in which a variable holds a value
+/// which is used later on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LiveRange {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl LiveRange {
+    /// Returns if the range is live at the given position
+    pub(crate) fn contains(&self, pos: usize) -> bool {
+        self.start <= pos && pos <= self.end
+    }
+}
+
+/// Computes the live ranges of all variables of a function.
+///
+/// All nodes of the function get a linear position (in block order, starting with 1).
+/// Position 0 is reserved for the function arguments.
+///
+/// Phi nodes are handled the same way the code generator handles them:
+/// the phi output gets written right after each incoming variable is defined.
+/// So the phi output is defined at every definition of an incoming variable
+/// and is then live along the block edges into the block of the phi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Liveness {
+    /// the live ranges of the variables
+    pub(crate) ranges: HashMap<String, LiveRange>,
+    /// the types of the variables
+    pub(crate) types: HashMap<String, TypeMetadata>,
+    /// the position of every node: `positions[block][node]`
+    pub(crate) positions: Vec<Vec<usize>>,
+}
+
+impl Liveness {
+    /// Runs the liveness analysis on the given function
+    pub(crate) fn analyze(func: &Function) -> Self {
+        let mut types = HashMap::new();
+
+        for (name, ty) in &func.ty.args {
+            types.insert(name.to_owned(), *ty);
+        }
+
+        // linearize the nodes
+        let mut positions = Vec::new();
+        let mut block_bounds = Vec::new();
+        let mut block_indexes = HashMap::new();
+
+        let mut pos = 1;
+
+        for (index, block) in func.blocks.iter().enumerate() {
+            block_indexes.insert(block.name.to_owned(), index);
+
+            let start = pos;
+            let mut block_positions = Vec::new();
+
+            for _ in &block.nodes {
+                block_positions.push(pos);
+                pos += 1;
+            }
+
+            // empty blocks still occupy one position
+            let end = if pos == start { pos += 1; start } else { pos - 1 };
+
+            positions.push(block_positions);
+            block_bounds.push((start, end));
+        }
+
+        // the phi output is written after each incoming var is defined
+        let mut phi_outs: HashMap<String, Vec<String>> = HashMap::new();
+
+        for block in &func.blocks {
+            for node in &block.nodes {
+                if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+                    types.insert(phi.out.name.to_owned(), phi.typ);
+
+                    for (_, var) in &phi.recive_from_blocks {
+                        phi_outs.entry(var.name.to_owned()).or_default().push(phi.out.name.to_owned());
+                    }
+                }
+            }
+        }
+
+        // collect the uses and defs of each node
+        let mut uses: Vec<Vec<Vec<String>>> = Vec::new();
+        let mut defs: Vec<Vec<Vec<String>>> = Vec::new();
+        let mut succs: Vec<Vec<usize>> = Vec::new();
+
+        for block in &func.blocks {
+            let mut block_uses = Vec::new();
+            let mut block_defs = Vec::new();
+            let mut block_succs = Vec::new();
+
+            for node in &block.nodes {
+                let mut node_uses = Vec::new();
+                let mut node_defs = Vec::new();
+
+                // the phi inputs are read at their definition (see above)
+                if node.as_any().downcast_ref::<Phi>().is_none() {
+                    for input in node.inputs() {
+                        types.insert(input.name.to_owned(), input.ty);
+                        node_uses.push(input.name);
+                    }
+
+                    if let Some(out) = node.output() {
+                        types.insert(out.name.to_owned(), out.ty);
+
+                        if let Some(outs) = phi_outs.get(&out.name) {
+                            node_defs.extend_from_slice(outs);
+                        }
+
+                        node_defs.push(out.name);
+                    }
+                }
+
+                let mut targets = Vec::new();
+
+                if let Some(br) = node.as_any().downcast_ref::<Br>() {
+                    targets.push(br.inner1.name.to_owned());
+                } else if let Some(br) = node.as_any().downcast_ref::<BrCond>() {
+                    targets.push(br.inner2.name.to_owned());
+                    targets.push(br.inner3.name.to_owned());
+                } else if let Some(switch) = node.as_any().downcast_ref::<Switch>() {
+                    for case in switch.cases.values() {
+                        targets.push(case.name.to_owned());
+                    }
+                    targets.push(switch.default.name.to_owned());
+                }
+
+                for target in targets {
+                    if let Some(index) = block_indexes.get(&target) {
+                        if !block_succs.contains(index) {
+                            block_succs.push(*index);
+                        }
+                    }
+                }
+
+                block_uses.push(node_uses);
+                block_defs.push(node_defs);
+            }
+
+            uses.push(block_uses);
+            defs.push(block_defs);
+            succs.push(block_succs);
+        }
+
+        // gen/kill sets of the blocks
+        let mut gens = Vec::new();
+        let mut kills = Vec::new();
+
+        for (block_uses, block_defs) in uses.iter().zip(defs.iter()) {
+            let mut gen = HashSet::new();
+            let mut kill = HashSet::new();
+
+            for (node_uses, node_defs) in block_uses.iter().zip(block_defs.iter()) {
+                for used in node_uses {
+                    if !kill.contains(used) {
+                        gen.insert(used.to_owned());
+                    }
+                }
+
+                for def in node_defs {
+                    kill.insert(def.to_owned());
+                }
+            }
+
+            gens.push(gen);
+            kills.push(kill);
+        }
+
+        // the dataflow for the live in/out sets
+        let mut live_in: Vec<HashSet<String>> = vec![HashSet::new(); func.blocks.len()];
+        let mut live_out: Vec<HashSet<String>> = vec![HashSet::new(); func.blocks.len()];
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for index in (0..func.blocks.len()).rev() {
+                let mut out = HashSet::new();
+
+                for succ in &succs[index] {
+                    out.extend(live_in[*succ].iter().cloned());
+                }
+
+                let mut inp = gens[index].clone();
+
+                for var in &out {
+                    if !kills[index].contains(var) {
+                        inp.insert(var.to_owned());
+                    }
+                }
+
+                if inp != live_in[index] || out != live_out[index] {
+                    live_in[index] = inp;
+                    live_out[index] = out;
+                    changed = true;
+                }
+            }
+        }
+
+        // build the ranges
+        let mut ranges: HashMap<String, LiveRange> = HashMap::new();
+
+        let mut cover = |name: &String, pos: usize| {
+            ranges.entry(name.to_owned())
+                .and_modify(|range| {
+                    range.start = range.start.min(pos);
+                    range.end = range.end.max(pos);
+                })
+                .or_insert(LiveRange { start: pos, end: pos });
+        };
+
+        for (name, _) in &func.ty.args {
+            cover(name, 0);
+        }
+
+        for index in 0..func.blocks.len() {
+            let (start, end) = block_bounds[index];
+
+            for var in &live_in[index] {
+                cover(var, start);
+            }
+
+            let mut live = live_out[index].clone();
+
+            for var in &live {
+                cover(var, end);
+            }
+
+            for (node, node_pos) in positions[index].iter().enumerate().rev() {
+                for def in &defs[index][node] {
+                    cover(def, *node_pos);
+                    live.remove(def);
+                }
+
+                for used in &uses[index][node] {
+                    cover(used, *node_pos);
+                    live.insert(used.to_owned());
+                }
+
+                for var in &live {
+                    cover(var, *node_pos);
+                }
+            }
+        }
+
+        Self {
+            ranges,
+            types,
+            positions,
+        }
+    }
+
+    /// Returns all variables which are live at the given position
+    pub(crate) fn live_at(&self, pos: usize) -> Vec<String> {
+        let mut live = Vec::new();
+
+        for (name, range) in &self.ranges {
+            if range.contains(pos) {
+                live.push(name.to_owned());
+            }
+        }
+
+        live
+    }
+}
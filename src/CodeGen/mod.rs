@@ -5,6 +5,7 @@ pub(crate) mod instr;
 pub(crate) mod compilation;
 pub(crate) mod calling_convention;
 pub(crate) mod ir_area;
+pub(crate) mod liveness;
 
 pub use reg_vec::*;
 pub use reg::*;
@@ -12,4 +13,5 @@ pub use settings::*;
 pub use instr::*;
 pub use calling_convention::*;
 pub use compilation::*;
-pub use ir_area::*;
\ No newline at end of file
+pub use ir_area::*;
+pub(crate) use liveness::*;
\ No newline at end of file
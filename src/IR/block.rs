@@ -18,7 +18,8 @@ impl Block {
             //func: func.clone(),
             name: name.to_string(),
             nodes: vec![],
-            varCount: func.ty.args.len(),
+            // continue the numbering of the previous block (so the names stay unique)
+            varCount: func.blocks.back().map(|block| block.varCount).unwrap_or(func.ty.args.len()),
         }
     }
 
@@ -35,6 +36,11 @@ impl Block {
         dump
     }
 
+    /// Returns the ir nodes of the block
+    pub fn nodes(&self) -> &[Box<dyn Ir>] {
+        &self.nodes
+    }
+
     pub(crate) fn push_ir(&mut self, ir: Box<dyn Ir>) {
         self.nodes.push( ir );
     }
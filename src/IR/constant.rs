@@ -1,12 +1,88 @@
-use crate::Obj::Linkage;
+use crate::Obj::{DataAttributes, Linkage};
 
+use super::Type;
 
+/// An element of a typed constant initializer
 #[derive(Debug, Clone, PartialEq, Eq)]
-/// A constant
+pub enum ConstElement {
+    /// An integer or a float (stored in little endian)
+    Value(Type),
+    /// Nested elements (e.g: an array or a structure)
+    Array(Vec<ConstElement>),
+    /// The adress of a symbol plus an addend (stored as a 64 bit pointer which gets relocated)
+    Adr(String, i64),
+}
+
+impl ConstElement {
+    /// Appends the bytes of the element and the relocations for the symbol adresses
+    fn encode(&self, data: &mut Vec<u8>, relocs: &mut Vec<(usize, String, i64)>) {
+        match self {
+            ConstElement::Value(value) => data.extend_from_slice(&match value {
+                Type::i1(val) => vec![*val as u8],
+                Type::u8(val) => val.to_le_bytes().to_vec(),
+                Type::u16(val) => val.to_le_bytes().to_vec(),
+                Type::u32(val) => val.to_le_bytes().to_vec(),
+                Type::u64(val) => val.to_le_bytes().to_vec(),
+                Type::i8(val) => val.to_le_bytes().to_vec(),
+                Type::i16(val) => val.to_le_bytes().to_vec(),
+                Type::i32(val) => val.to_le_bytes().to_vec(),
+                Type::i64(val) => val.to_le_bytes().to_vec(),
+                Type::u128(val) => val.to_le_bytes().to_vec(),
+                Type::i128(val) => val.to_le_bytes().to_vec(),
+                Type::ptr(adr) => adr.to_le_bytes().to_vec(),
+                Type::f32(val) => val.to_le_bytes().to_vec(),
+                Type::f64(val) => val.to_le_bytes().to_vec(),
+                Type::Void => vec![],
+            }),
+            ConstElement::Array(elements) => {
+                for element in elements {
+                    element.encode(data, relocs);
+                }
+            },
+            ConstElement::Adr(symbol, addend) => {
+                relocs.push((data.len(), symbol.to_owned(), *addend));
+                data.extend_from_slice(&[0; 8]);
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ConstElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConstElement::Value(value) => write!(f, "{}", value),
+            ConstElement::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(|element| element.to_string()).collect();
+
+                write!(f, "{{ {} }}", elements.join(", "))
+            },
+            ConstElement::Adr(symbol, 0) => write!(f, "ptr {}", symbol),
+            ConstElement::Adr(symbol, addend) => write!(f, "ptr {} {}", symbol, addend),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A constant (or a global variable if it is mutable)
 pub struct Const {
     pub(crate) name: String,
     pub(crate) data: Vec<u8>,
     pub(crate) linkage: Linkage,
+
+    /// the typed initializer (if the data was set using elements)
+    pub(crate) elements: Vec<ConstElement>,
+
+    /// if the data can be written
+    pub(crate) mutable: bool,
+    /// the alignment of the data
+    pub(crate) align: Option<usize>,
+    /// a custom section in which the data is placed
+    pub(crate) section: Option<String>,
+    /// the size of zero initialized data (which isn't stored but placed into `.bss`)
+    pub(crate) zero_init: Option<usize>,
+    /// if every thread has its own copy of the data
+    pub(crate) thread_local: bool,
 }
 
 impl Const {
@@ -16,19 +92,49 @@ impl Const {
             name: name,
             data: vec![],
             linkage: Linkage::Internal,
+            elements: vec![],
+            mutable: false,
+            align: None,
+            section: None,
+            zero_init: None,
+            thread_local: false,
         }
     }
 
     /// sets the data of the constant
     pub fn set(&mut self, data: Vec<u8>) {
         self.data = data;
+        self.elements = vec![];
+        self.zero_init = None;
+    }
+
+    /// sets the data of the constant to typed elements
+    /// 
+    /// Symbol adresses are stored as pointers which get relocated when the constant is placed
+    pub fn set_elements(&mut self, elements: Vec<ConstElement>) {
+        let mut data = vec![];
+
+        ConstElement::Array(elements.to_owned()).encode(&mut data, &mut vec![]);
+
+        self.data = data;
+        self.elements = elements;
+        self.zero_init = None;
+    }
+
+    /// Returns the offsets, symbols and addends of the pointers in the data
+    pub(crate) fn relocs(&self) -> Vec<(usize, String, i64)> {
+        let mut relocs = vec![];
+
+        ConstElement::Array(self.elements.to_owned()).encode(&mut vec![], &mut relocs);
+
+        relocs
     }
 
     /// gets the data of the constant
     pub fn get(&mut self) -> Vec<u8>{
-        self.data.clone()
+        self.bytes()
     }
-    
+
     /// Sets that the constant is externally visible
     pub fn extrn(&mut self) {
         self.linkage = Linkage::External;
@@ -43,4 +149,118 @@ impl Const {
     pub fn private(&mut self) {
         self.linkage = Linkage::Internal;
     }
-}
\ No newline at end of file
+
+    /// Sets that the constant is externally visible but can be overriden by another definition
+    pub fn weak(&mut self) {
+        self.linkage = Linkage::Weak;
+    }
+
+    /// Sets that the constant is imported from another object file but may be missing at link time
+    pub fn import_weak(&mut self) {
+        self.linkage = Linkage::ExternWeak;
+    }
+
+    /// Sets that the constant is visible to other object files but not outside of the linked shared library or executable
+    pub fn hidden(&mut self) {
+        self.linkage = Linkage::Hidden;
+    }
+
+    /// Makes the constant a common symbol of `size` zeros which the linker merges with the common symbols of the same name
+    pub fn common(&mut self, size: usize) {
+        self.zero_init(size);
+        self.linkage = Linkage::Common;
+    }
+
+    /// Sets that the constant can be defined in multiple object files of which the linker only keeps one
+    pub fn linkonce(&mut self) {
+        self.linkage = Linkage::LinkOnce;
+    }
+
+    /// Makes the constant writable (which makes it a global variable)
+    pub fn mutable(&mut self) {
+        self.mutable = true;
+    }
+
+    /// Sets the alignment of the data
+    pub fn align(&mut self, align: usize) {
+        self.align = Some(align);
+    }
+
+    /// Places the data into the given section
+    pub fn section(&mut self, section: &str) {
+        self.section = Some(section.to_owned());
+    }
+
+    /// Sets the data to `size` zeros which aren't stored in the object file (they land in `.bss`)
+    pub fn zero_init(&mut self, size: usize) {
+        self.data = vec![];
+        self.elements = vec![];
+        self.zero_init = Some(size);
+    }
+
+    /// Gives every thread its own copy of the data (it lands in `.tdata` or `.tbss`)
+    pub fn thread_local(&mut self) {
+        self.thread_local = true;
+    }
+
+    /// Returns if the constant is writable
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    /// Returns if every thread has its own copy of the data
+    pub fn is_thread_local(&self) -> bool {
+        self.thread_local
+    }
+
+    /// Returns the size of the data in bytes
+    pub fn size(&self) -> usize {
+        self.zero_init.unwrap_or(self.data.len())
+    }
+
+    /// Returns the data (zero initialized data is returned as zeros)
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        match self.zero_init {
+            Some(size) => vec![0; size],
+            None => self.data.clone(),
+        }
+    }
+
+    /// Returns the attributes which the object builder needs to place the data
+    pub(crate) fn attributes(&self) -> DataAttributes {
+        DataAttributes {
+            align: self.align.map(|align| align as u64),
+            section: self.section.to_owned(),
+            zero_init: self.zero_init.is_some(),
+            thread_local: self.thread_local,
+        }
+    }
+
+    /// Dumps the initializer and the attributes:
+    /// `[1, 2] align 8 section ".data.x"`, `{ i32 1, ptr x }` or `zeroinit 8 thread_local`
+    pub(crate) fn dump_init(&self) -> String {
+        let mut string = if let Some(size) = self.zero_init {
+            format!("zeroinit {}", size)
+        } else if !self.elements.is_empty() {
+            ConstElement::Array(self.elements.to_owned()).to_string()
+        } else {
+            let bytes: Vec<String> = self.data.iter().map(|byte| byte.to_string()).collect();
+
+            format!("[{}]", bytes.join(", "))
+        };
+
+        if let Some(align) = self.align {
+            string.push_str(&format!(" align {}", align));
+        }
+
+        if let Some(section) = &self.section {
+            string.push_str(&format!(" section \"{}\"", section));
+        }
+
+        if self.thread_local {
+            string.push_str(" thread_local");
+        }
+
+        string
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use super::block::BlockId;
 use super::Block;
@@ -6,12 +6,14 @@ use super::TypeMetadata;
 use super::Var;
 use super::VerifyError;
 use crate::prelude::PassManager;
+use crate::Optimizations::Analysis::AnalysisCache;
+use crate::Optimizations::Pass;
 use crate::Obj::Linkage;
 use crate::Support::ColorClass;
 use crate::Support::ColorProfile;
 
 /// Stores the function type
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FunctionType {
     /// The function arguments (stored as: num, type)
     pub args: Vec<(String, TypeMetadata)>,
@@ -53,6 +55,53 @@ impl FunctionType {
     }
 }
 
+/// An attribute which changes how a function is optimized or compiled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FunctionAttribute {
+    /// The function is inlined wherever possible (ignoring the cost model of the inliner)
+    AlwaysInline,
+    /// The function is never inlined
+    NoInline,
+    /// The function never returns to its caller (like `exit` or `abort`),
+    /// so everything after a call to it is unreachable
+    NoReturn,
+    /// The function is rarely called, so it isn't inlined
+    Cold,
+    /// The function gets no prolog and epilog (the stack frame is managed by the body)
+    Naked,
+    /// The function doesn't read or write memory and has no other side effects,
+    /// so calls whose result is unused can be removed
+    ReadNone,
+}
+
+impl FunctionAttribute {
+    /// Returns the attribute with the name used in the ir
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "alwaysinline" => FunctionAttribute::AlwaysInline,
+            "noinline" => FunctionAttribute::NoInline,
+            "noreturn" => FunctionAttribute::NoReturn,
+            "cold" => FunctionAttribute::Cold,
+            "naked" => FunctionAttribute::Naked,
+            "readnone" => FunctionAttribute::ReadNone,
+            _ => None?,
+        })
+    }
+}
+
+impl std::fmt::Display for FunctionAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            FunctionAttribute::AlwaysInline => "alwaysinline",
+            FunctionAttribute::NoInline => "noinline",
+            FunctionAttribute::NoReturn => "noreturn",
+            FunctionAttribute::Cold => "cold",
+            FunctionAttribute::Naked => "naked",
+            FunctionAttribute::ReadNone => "readnone",
+        })
+    }
+}
+
 /// A ir function with a known variable and arg size and count
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Function {
@@ -63,6 +112,8 @@ pub struct Function {
     
     pub(crate) linkage: Linkage,
     pub(crate) blocks: VecDeque<Block>,
+
+    pub(crate) attributes: Vec<FunctionAttribute>,
 }
 
 impl Function {
@@ -76,6 +127,8 @@ impl Function {
             name: name,
 
             linkage: Linkage::Internal,
+
+            attributes: Vec::new(),
         }
     }
 
@@ -94,16 +147,89 @@ impl Function {
         self.linkage = Linkage::Internal;
     }
 
+    /// Sets that the function is externally visible but can be overriden by another definition (same as: `__attribute__((weak))`)
+    pub fn weak(&mut self) {
+        self.linkage = Linkage::Weak;
+    }
+
+    /// Sets that the function is imported from another object file but may be missing at link time (it is then null)
+    pub fn import_weak(&mut self) {
+        self.linkage = Linkage::ExternWeak;
+    }
+
+    /// Sets that the function is visible to other object files but not outside of the linked shared library or executable
+    pub fn hidden(&mut self) {
+        self.linkage = Linkage::Hidden;
+    }
+
+    /// Sets that the function can be defined in multiple object files of which the linker only keeps one (same as an inline function in C++)
+    pub fn linkonce(&mut self) {
+        self.linkage = Linkage::LinkOnce;
+    }
+
+    /// Adds the attribute to the function
+    pub fn add_attribute(&mut self, attribute: FunctionAttribute) {
+        if !self.attributes.contains(&attribute) {
+            self.attributes.push(attribute);
+        }
+    }
+
+    /// Returns if the function has the attribute
+    pub fn has_attribute(&self, attribute: FunctionAttribute) -> bool {
+        self.attributes.contains(&attribute)
+    }
+
+    /// Sets that the function never returns
+    pub fn noreturn(&mut self) {
+        self.add_attribute(FunctionAttribute::NoReturn);
+    }
+
+    /// Sets that the function is never inlined
+    pub fn noinline(&mut self) {
+        self.add_attribute(FunctionAttribute::NoInline);
+    }
+
+    /// Sets that the function is inlined wherever possible
+    pub fn alwaysinline(&mut self) {
+        self.add_attribute(FunctionAttribute::AlwaysInline);
+    }
+
+    /// Sets that the function is rarely called
+    pub fn cold(&mut self) {
+        self.add_attribute(FunctionAttribute::Cold);
+    }
+
+    /// Sets that the function gets no prolog and epilog
+    pub fn naked(&mut self) {
+        self.add_attribute(FunctionAttribute::Naked);
+    }
+
+    /// Sets that the function has no side effects and doesn't access memory
+    pub fn readnone(&mut self) {
+        self.add_attribute(FunctionAttribute::ReadNone);
+    }
+
+    /// Returns the attributes like they are written after the arguments (e.g: ` noinline cold`)
+    fn dump_attributes(&self) -> String {
+        self.attributes.iter().map(|attribute| format!(" {}", attribute)).collect()
+    }
+
     /// Adds a new block to the function
     pub fn addBlock(&mut self, name: &str) -> BlockId {
         self.blocks.push_back(Block::new(name, &self));
         BlockId(name.to_owned())
     }
 
+    /// Returns the block with the given name
+    pub fn block(&self, name: &str) -> Option<&Block> {
+        self.blocks.iter().find(|block| block.name == name)
+    }
+
     /// Emits the Ir of the function into a string
     pub fn dump(&self) -> String {
-        if self.linkage == Linkage::Extern {
-            let string = format!("declare {} @{}({})\n",
+        if self.linkage.is_import() {
+            let string = format!("declare {}{} @{}({}){}\n",
+                if self.linkage == Linkage::ExternWeak { "extern_weak " } else { "" },
                 self.ty.ret,
                 self.name, {
                     let mut fmt = String::new();
@@ -119,13 +245,13 @@ impl Function {
                     }
         
                     fmt
-                });
+                }, self.dump_attributes());
             return string;
         }
 
         let mut string = String::new();
 
-        string += &format!("define {} {} @{}({}) {{\n", self.linkage, self.ty.ret, self.name, {
+        string += &format!("define {} {} @{}({}){} {{\n", self.linkage, self.ty.ret, self.name, {
             let mut fmt = String::new();
 
             for index in 0..self.ty.args.len() {
@@ -139,7 +265,7 @@ impl Function {
             }
 
             fmt
-        });
+        }, self.dump_attributes());
 
         for block in &self.blocks {
             string += &format!("{}\n", block.dump());
@@ -152,8 +278,8 @@ impl Function {
 
     /// Emits the Ir of the function into an colored string
     pub fn dumpColored(&self, profile: ColorProfile) -> String {
-        if self.linkage == Linkage::Extern {
-            let string = format!("{} {} {} @{}( {})\n",
+        if self.linkage.is_import() {
+            let string = format!("{} {} {} @{}( {}){}\n",
                 profile.markup("declare", ColorClass::Instr),
                 profile.markup(&format!("{}", self.linkage), ColorClass::Ty),
                 profile.markup(&self.ty.ret.to_string(), ColorClass::Ty),
@@ -173,13 +299,13 @@ impl Function {
                     }
         
                     fmt
-                });
+                }, profile.markup(&self.dump_attributes(), ColorClass::Ty));
             return string;
         }
 
         let mut string = String::new();
 
-        string += &format!("{} {} @{}({}) {{\n", 
+        string += &format!("{} {} @{}({}){} {{\n", 
                         profile.markup("define", ColorClass::Instr),
                         profile.markup(&self.ty.ret.to_string(), ColorClass::Ty), 
                         profile.markup(&self.name, ColorClass::Name), {
@@ -198,7 +324,7 @@ impl Function {
             }
 
             fmt
-        });
+        }, profile.markup(&self.dump_attributes(), ColorClass::Ty));
 
         for block in &self.blocks {
             string += &format!(" {}\n", block.dumpColored(profile));
@@ -220,11 +346,34 @@ impl Function {
         Ok(())
     }
     
-    /// Runs the pass manager over all blocks
-    pub fn runPassMngr(&mut self, mngr: &PassManager) {
-        for block in &mut self.blocks {
-            mngr.run(block);
+    /// Runs the pass manager over the function
+    /// 
+    /// Returns if any pass changed the function
+    pub fn runPassMngr(&mut self, mngr: &PassManager) -> bool {
+        mngr.run_func(self, &mut AnalysisCache::new())
+    }
+
+    /// Returns the names of all variables (and arguments) of the function
+    pub(crate) fn var_names(&self) -> HashSet<String> {
+        let mut names = HashSet::new();
+
+        for (name, _) in &self.ty.args {
+            names.insert(name.to_owned());
+        }
+
+        for block in &self.blocks {
+            for node in &block.nodes {
+                for input in node.inputs() {
+                    names.insert(input.name);
+                }
+
+                if let Some(out) = node.output() {
+                    names.insert(out.name);
+                }
+            }
         }
+
+        names
     }
 
     /// Constructs an id for the function
@@ -0,0 +1,179 @@
+use std::fmt::Display;
+use std::sync::Mutex;
+
+use super::TypeMetadata;
+
+/// The aggregate types which were used so far (a `TypeMetadata::Aggregate` or `TypeMetadata::Vector` is an index into it)
+///
+/// Structurally equal aggregates share one entry, so aggregate types can be compared by their index
+static AGGREGATES: Mutex<Vec<AggregateType>> = Mutex::new(Vec::new());
+
+/// A type which is made up of other types
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AggregateType {
+    /// a struct with the given field types (in memory order)
+    Struct(Vec<TypeMetadata>),
+    /// an array of `len` elements of the type
+    Array(TypeMetadata, usize),
+    /// a simd vector of `lanes` elements of the type (it is a value and not handled over its adress)
+    Vector(TypeMetadata, usize),
+}
+
+/// A reference to an interned `AggregateType`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AggregateId(u32);
+
+impl AggregateType {
+    /// Returns the id of the aggregate (it is added to the known aggregates if it's new)
+    pub fn intern(self) -> AggregateId {
+        let mut aggregates = AGGREGATES.lock().expect("the aggregate types aren't poisoned");
+
+        if let Some(index) = aggregates.iter().position(|known| *known == self) {
+            return AggregateId(index as u32);
+        }
+
+        aggregates.push(self);
+
+        AggregateId(aggregates.len() as u32 - 1)
+    }
+
+    /// Returns the type of the element with the index
+    pub fn elem(&self, index: usize) -> Option<TypeMetadata> {
+        match self {
+            AggregateType::Struct(fields) => fields.get(index).copied(),
+            AggregateType::Array(elem, len) | AggregateType::Vector(elem, len) => (index < *len).then_some(*elem),
+        }
+    }
+}
+
+impl AggregateId {
+    /// Returns the aggregate type
+    pub fn get(&self) -> AggregateType {
+        let aggregates = AGGREGATES.lock().expect("the aggregate types aren't poisoned");
+
+        aggregates[self.0 as usize].to_owned()
+    }
+}
+
+impl Display for AggregateType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregateType::Struct(fields) => {
+                let fields: Vec<String> = fields.iter().map(|field| field.to_string()).collect();
+                write!(f, "{{{}}}", fields.join(", "))
+            },
+            AggregateType::Array(elem, len) => write!(f, "[{} x {}]", len, elem),
+            AggregateType::Vector(elem, lanes) => write!(f, "<{} x {}>", lanes, elem),
+        }
+    }
+}
+
+/// The target dependent sizes and alignments of the types
+///
+/// Aggregates are laid out like c does it:
+///  - every field is placed at the next offset which fits its alignment
+///  - the alignment of an aggregate is the biggest alignment of its fields
+///  - the size is padded to a multiple of the alignment (so arrays of it stay aligned)
+///
+/// Vectors are aligned to their size (like `__m128`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataLayout {
+    /// the size of a pointer in bytes
+    pub ptr_size: usize,
+}
+
+impl Default for DataLayout {
+    /// The layout of x86-64 (it is the same for SystemV and windows)
+    fn default() -> Self {
+        Self { ptr_size: 8 }
+    }
+}
+
+impl DataLayout {
+    /// Returns the size of the type in bytes (with the padding)
+    pub fn size(&self, ty: TypeMetadata) -> usize {
+        match ty {
+            TypeMetadata::ptr => self.ptr_size,
+            TypeMetadata::Aggregate(aggregate) | TypeMetadata::Vector(aggregate) => match aggregate.get() {
+                AggregateType::Array(elem, len) | AggregateType::Vector(elem, len) => self.size(elem) * len,
+                AggregateType::Struct(fields) => {
+                    let end = self.offsets(&fields).last().map(|(offset, ty)| offset + self.size(*ty)).unwrap_or(0);
+
+                    align_to(end, self.align(ty))
+                },
+            },
+            _ => ty.byteSize(),
+        }
+    }
+
+    /// Returns the alignment of the type in bytes
+    pub fn align(&self, ty: TypeMetadata) -> usize {
+        match ty {
+            TypeMetadata::Void => 1,
+            TypeMetadata::Vector(_) => self.size(ty),
+            TypeMetadata::Aggregate(aggregate) => match aggregate.get() {
+                AggregateType::Array(elem, _) | AggregateType::Vector(elem, _) => self.align(elem),
+                AggregateType::Struct(fields) => fields.iter().map(|field| self.align(*field)).max().unwrap_or(1),
+            },
+            _ => self.size(ty),
+        }
+    }
+
+    /// Returns the offset of the element with the index inside of the aggregate
+    ///
+    /// For non aggregate types the memory is treated like an array of the type
+    pub fn offset(&self, ty: TypeMetadata, index: usize) -> usize {
+        match ty {
+            TypeMetadata::Aggregate(aggregate) | TypeMetadata::Vector(aggregate) => match aggregate.get() {
+                AggregateType::Array(elem, _) | AggregateType::Vector(elem, _) => self.size(elem) * index,
+                AggregateType::Struct(fields) => self.offsets(&fields)
+                    .get(index)
+                    .map(|(offset, _)| *offset)
+                    .expect("field index out of bounds"),
+            },
+            _ => self.size(ty) * index,
+        }
+    }
+
+    /// Returns the offsets of the fields
+    fn offsets(&self, fields: &[TypeMetadata]) -> Vec<(usize, TypeMetadata)> {
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+
+        for field in fields {
+            offset = align_to(offset, self.align(*field));
+            offsets.push((offset, *field));
+            offset += self.size(*field);
+        }
+
+        offsets
+    }
+
+    /// Returns the scalar types with their offsets which make up the type (in memory order)
+    pub fn flatten(&self, ty: TypeMetadata) -> Vec<(usize, TypeMetadata)> {
+        let (TypeMetadata::Aggregate(aggregate) | TypeMetadata::Vector(aggregate)) = ty else {
+            return vec![(0, ty)];
+        };
+
+        let elems = match aggregate.get() {
+            AggregateType::Array(elem, len) | AggregateType::Vector(elem, len) => vec![elem; len],
+            AggregateType::Struct(fields) => fields,
+        };
+
+        let mut flat = Vec::new();
+
+        for (index, elem) in elems.iter().enumerate() {
+            let offset = self.offset(ty, index);
+
+            for (inner, scalar) in self.flatten(*elem) {
+                flat.push((offset + inner, scalar));
+            }
+        }
+
+        flat
+    }
+}
+
+fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align.max(1)) * align.max(1)
+}
@@ -1,6 +1,7 @@
 mod module;
 mod func;
 mod typ;
+mod layout;
 mod block;
 mod var;
 mod constant;
@@ -16,10 +17,11 @@ use std::error::Error;
 use std::fmt::Display;
 
 pub use module::Module;
-pub use func::{Function, FunctionType, FnTy, Func, FuncId};
-pub use constant::Const;
+pub use func::{Function, FunctionAttribute, FunctionType, FnTy, Func, FuncId};
+pub use constant::{Const, ConstElement};
 pub use typ::Type;
 pub use typ::TypeMetadata;
+pub use layout::{AggregateId, AggregateType, DataLayout};
 pub use block::{Block, BlockId};
 pub use var::Var;
 
@@ -34,6 +36,12 @@ pub enum VerifyError {
     InvalidArgumentTypeFound,
     /// To many arguments were supplyed
     ToManyArgumentsWereSupplyed,
+    /// The index doesn't select an element of the type
+    InvalidElementIndex(TypeMetadata),
+    /// The callee of an indirect call isn't a pointer
+    CalleeNotPtr(TypeMetadata),
+    /// Vectors can't be compared (a comparison yields one `i1`)
+    VectorCmp(TypeMetadata),
 }
 
 impl Display for VerifyError {
@@ -61,7 +69,22 @@ impl Display for VerifyError {
                 format!(
                     "too many arguments were supplyed"
                 )
-            }
+            },
+            VerifyError::InvalidElementIndex(ty) => {
+                format!(
+                    "the index doesn't select an element of {} (struct fields need constant indices)", ty
+                )
+            },
+            VerifyError::CalleeNotPtr(ty) => {
+                format!(
+                    "the callee of an indirect call needs to be a ptr and not {}", ty
+                )
+            },
+            VerifyError::VectorCmp(ty) => {
+                format!(
+                    "cmp yields a single i1 and can't compare the vector type {}", ty
+                )
+            },
         })
     }
 }
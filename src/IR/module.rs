@@ -1,6 +1,7 @@
 use gimli::DwLang;
+use object::RelocationEncoding;
 
-use crate::{debug::{DebugLocation, DebugRegistry}, prelude::Triple, CodeGen::MachineInstr, Obj::{Decl, Link, Linkage, ObjectBuilder}, Optimizations::PassManager, Support::{ColorClass, ColorProfile}, Target::{Arch, TargetRegistry}};
+use crate::{debug::{DebugLocation, DebugRegistry}, prelude::Triple, CodeGen::MachineInstr, Obj::{Decl, Link, Linkage, ObjectBuilder}, Optimizations::{Analysis::ModuleAnalyses, Pass, PassManager}, Support::{ColorClass, ColorProfile}, Target::{Arch, TargetRegistry}};
 
 use super::{func::FunctionType, Const, Function, VerifyError};
 use std::{collections::HashMap, error::Error, fmt::Debug, fs::OpenOptions, io::Write, path::Path};
@@ -17,6 +18,9 @@ pub struct Module {
     pub(crate) const_index: usize,
 
     pub(crate) debug_passes: bool,
+
+    /// If the code is position independent (changes how thread locals are accessed)
+    pub(crate) pic: bool,
 }
 
 impl Module {
@@ -28,6 +32,7 @@ impl Module {
             dbg_registry: None,
             const_index: 0,
             debug_passes: false,
+            pic: false,
         }
     }
 
@@ -41,6 +46,11 @@ impl Module {
         self.debug_passes = true;
     }
 
+    /// Makes, that the generated code can be used in shared libraries
+    pub fn activate_pic(&mut self) {
+        self.pic = true;
+    }
+
     /// Adds a new function to the module
     pub fn add(&mut self, name: &str, ty: &FunctionType) -> &mut Function {
         self.funcs
@@ -60,15 +70,20 @@ impl Module {
         self.consts.get_mut(name).unwrap()
     }
 
+    /// Adds a new mutable global variable to the module
+    pub fn addGlobal(&mut self, name: &str) -> &mut Const {
+        let global = self.addConst(name);
+        global.mutable();
+        global
+    }
+
     /// Adds an already defined const to the module
     pub fn add_raw_const(&mut self, constant: Const) {
         self.consts.insert(constant.name.to_string(), constant);
     }
 
-    #[allow(dead_code)]
     /// Returns a read only reference to the given function name
-    /// ### Used for passes
-    pub(crate) fn getFunc(&self, name: &str) -> Option<&Function> {
+    pub fn getFunc(&self, name: &str) -> Option<&Function> {
         self.funcs.get(name)
     }
 
@@ -85,24 +100,16 @@ impl Module {
         let mut string = String::new();
 
         for (_, consta) in &self.consts {
-            let mut bytes = String::from("[ ");
-
-            for byte in &consta.data { 
-                bytes.push_str(&format!("{}, ", *byte));
-            }
-
-            if consta.data.len() > 0 {
-                bytes.remove(bytes.chars().count() - 1);
-                bytes.remove(bytes.chars().count() - 2);
-            }
-
-            bytes.push(']');
-
-            string += &format!("{} const {} = {}\n", match consta.linkage {
+            string += &format!("{} {} {} = {}\n", if consta.mutable { "global" } else { "const" }, match consta.linkage {
                 Linkage::Extern => "import",
                 Linkage::External => "extern",
                 Linkage::Internal => "intern",
-            }, consta.name,  bytes);
+                Linkage::Weak => "weak",
+                Linkage::ExternWeak => "extern_weak",
+                Linkage::Hidden => "hidden",
+                Linkage::Common => "common",
+                Linkage::LinkOnce => "linkonce",
+            }, consta.name, consta.dump_init());
         }
 
         for (_, func) in &self.funcs {
@@ -118,28 +125,20 @@ impl Module {
         let mut string = String::new();
 
         for (_, consta) in &self.consts {
-            let mut bytes = String::from("[ ");
-
-            for byte in &consta.data { 
-                bytes.push_str(&format!("{}, ", *byte));
-            }
-
-            if consta.data.len() > 0 {
-                bytes.remove(bytes.chars().count() - 1);
-                bytes.remove(bytes.chars().count() - 2);
-            }
-
-            bytes.push(']');
-
             string += &format!("{} {} {} = {}\n", 
+                profile.markup(if consta.mutable { "global" } else { "const" }, ColorClass::Instr), 
                 profile.markup(&match consta.linkage {
                     Linkage::Extern => "import",
                     Linkage::External => "extern",
                     Linkage::Internal => "intern",
+                    Linkage::Weak => "weak",
+                    Linkage::ExternWeak => "extern_weak",
+                    Linkage::Hidden => "hidden",
+                    Linkage::Common => "common",
+                    Linkage::LinkOnce => "linkonce",
                 }, ColorClass::Instr), 
-                profile.markup("const", ColorClass::Instr), 
                 profile.markup(&consta.name, ColorClass::Name), 
-                profile.markup(&bytes, ColorClass::Value)
+                profile.markup(&consta.dump_init(), ColorClass::Value)
             );
         }
 
@@ -161,21 +160,11 @@ impl Module {
         Ok(())
     }
 
-    /// Runs the pass manager over all functions
-    pub fn runPassMngr(&mut self, mngr: PassManager) {
-        for pass in &mngr.passes {
-            if self.debug_passes {
-                eprintln!("Running pass: {}", pass.name());
-            }
-
-            for (_, func) in &mut self.funcs {
-                pass.run_func(func);
-    
-                for block in &mut func.blocks {
-                    pass.run(block);
-                }
-            }
-        }
+    /// Runs the pass manager over the module
+    /// 
+    /// Returns if any pass changed the module
+    pub fn runPassMngr(&mut self, mngr: PassManager) -> bool {
+        mngr.run_mod(self, &mut ModuleAnalyses::new())
     }
 
     /// emits the machine code of the module into an object file (in the form of an object builder)
@@ -187,6 +176,8 @@ impl Module {
 
         let mut obj = ObjectBuilder::new(triple);
 
+        let declared: Vec<String> = self.funcs.keys().cloned().collect();
+
         for (name, func) in self.funcs.clone() {
             obj.decl( (&name, Decl::Function, func.linkage));
 
@@ -272,6 +263,8 @@ impl Module {
                             addend: link.addend,
                             special: false,
                             kind: link.kind,
+                            tls: link.tls,
+
                         });
                     }
                 }
@@ -298,11 +291,33 @@ impl Module {
             obj.define(&name, comp);
         }
 
+        // functions which got declared while compiling (like libcalls) are imported
+        for (name, func) in &self.funcs {
+            if func.linkage.is_import() && !declared.contains(name) {
+                obj.decl((name, Decl::Function, func.linkage));
+            }
+        }
+
         // NOT CHANGE THE ORDER CUZ FOR SOME ARCHS (LIKE X86) FPs ARE MADE USING CONSTS
         // WHICH WOULD LEED TO A PANIC
         for (_, consta) in &self.consts {
-            obj.decl((consta.name.as_str(), Decl::Constant, consta.linkage));
-            obj.define(&consta.name, consta.data.clone());
+            let decl = if consta.mutable { Decl::Data } else { Decl::Constant };
+
+            obj.decl((consta.name.as_str(), decl, consta.linkage));
+            obj.define_data(&consta.name, consta.bytes(), consta.attributes());
+
+            for (at, target, addend) in consta.relocs() {
+                obj.link_data(Link {
+                    from: consta.name.to_owned(),
+                    to: target,
+                    at: at,
+                    addend: addend,
+                    special: false,
+                    kind: RelocationEncoding::Generic,
+                    tls: None,
+
+                });
+            }
         }
 
         Ok((obj, self.dbg_registry.to_owned()))
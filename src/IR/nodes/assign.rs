@@ -2,10 +2,21 @@ use std::any::TypeId;
 
 use super::*;
 
+/// Formats the value (integers are printed exactly)
+fn value(ty: &Type) -> String {
+    match ty {
+        Type::u128(val) => val.to_string(),
+        _ => match ty.int_val() {
+            Some(val) => val.to_string(),
+            None => ty.val().to_string(),
+        },
+    }
+}
+
 impl Ir for Assign<Var, Type> {
     fn dump(&self) -> String {
         let meta: TypeMetadata = self.inner2.into();
-        format!("{} = {} {}", self.inner1.name, meta, self.inner2.val())
+        format!("{} = {} {}", self.inner1.name, meta, value(&self.inner2))
     }
 
     fn dumpColored(&self, profile: ColorProfile) -> String {
@@ -13,7 +24,7 @@ impl Ir for Assign<Var, Type> {
         format!("{} = {} {}", 
             profile.markup(&self.inner1.name, ColorClass::Var), 
             profile.markup(&meta.to_string(), ColorClass::Instr), 
-            profile.markup(&self.inner2.val().to_string(), ColorClass::Value),
+            profile.markup(&value(&self.inner2), ColorClass::Value),
         )
     }
 
@@ -60,6 +71,10 @@ impl Ir for Assign<Var, Type> {
     fn output(&self) -> Option<Var> {
         Some(self.inner1.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.inner1)
+    }
 }
 
 impl EvalOptVisitor for Assign<Var, Type> {
@@ -130,6 +145,10 @@ impl Ir for Assign<Var, Var> {
     fn output(&self) -> Option<Var> {
         Some(self.inner1.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.inner1)
+    }
 }
 
 impl EvalOptVisitor for Assign<Var, Var> {
@@ -194,6 +213,10 @@ impl Ir for Assign<Var, Const> {
     fn output(&self) -> Option<Var> {
         Some(self.inner1.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.inner1)
+    }
 }
 
 impl EvalOptVisitor for Assign<Var, Const> {
@@ -280,3 +303,16 @@ impl BuildAssign<&Const> for Function {
         out
     }
 }
+
+impl BuildAssign<&FuncId> for Function {
+    /// Loads the adress of the function (which can be called with `BuildIndirectCall`)
+    fn BuildAssign(&mut self, func: &FuncId) -> Var {
+        let block = self.blocks.back_mut().expect("the IRBuilder needs to have an current block\nConsider creating one");
+        
+        let out = Var::new(block, TypeMetadata::ptr);
+
+        block.push_ir(Assign::new(out.clone(), Const::new(func.name.to_owned())));
+
+        out
+    }
+}
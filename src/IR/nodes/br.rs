@@ -60,6 +60,10 @@ impl Ir for Br {
     fn output(&self) -> Option<Var> {
         None
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        None
+    }
 }
 
 impl EvalOptVisitor for Br {
@@ -135,17 +139,19 @@ impl Ir for BrCond {
     fn output(&self) -> Option<Var> {
         None
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        None
+    }
 }
 
 impl EvalOptVisitor for BrCond {
     fn maybe_inline(&self, vars: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
         if let Some(check) = vars.get(&self.inner1.name) {
-            let value = check.val() as i64;
-
-            if value == 0 {
-                Some(Br::new(self.inner3.to_owned()))
-            } else {
+            if check.val() != 0.0 {
                 Some(Br::new(self.inner2.to_owned()))
+            } else {
+                Some(Br::new(self.inner3.to_owned()))
             }
         } else { None }
     }
@@ -43,7 +43,7 @@ impl Ir for Call {
         let args = &self.func.ty.args;
         for arg in &self.args {
             if index < args.len() {
-                if matches!(args.get(index), Some((_, argty)) if *argty != (*arg).get_ty()) {
+                if matches!(args.get(index), Some((_, argty)) if !arg.get_ty().usable_as(*argty)) {
                     Err(VerifyError::InvalidArgumentTypeFound)?
                 }
             } else {
@@ -111,6 +111,10 @@ impl Ir for Call {
     fn output(&self) -> Option<Var> {
         Some(self.out.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
 }
 
 impl IsNode for Call {
@@ -167,3 +171,168 @@ impl BuildCall<&FuncId, Vec<IROperand>> for Function {
         out 
     }
 }
+
+impl Ir for IndirectCall {
+    fn dump(&self) -> String {
+        let mut fmt = String::new();
+        
+        for arg in &self.args {
+            fmt.push_str(&format!("{} ", arg))
+        }
+
+        format!("{} = call {} {} {}", self.out.name, self.ty.ret, self.callee.name, fmt)
+    }
+
+    fn dumpColored(&self, profile: ColorProfile) -> String {
+        let mut fmt = String::new();
+        
+        for arg in &self.args {
+            fmt.push_str(&profile.markup(&arg.to_string(), ColorClass::Var));
+            fmt.push(' ');
+        }
+
+        format!("{} = {} {} {} {}", 
+            profile.markup(&self.out.name, ColorClass::Var),
+            profile.markup("call", ColorClass::Instr),
+            profile.markup(&self.ty.ret.to_string(), ColorClass::Ty),
+            profile.markup(&self.callee.name, ColorClass::Var),
+            fmt
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn verify(&self, _: FunctionType) -> Result<(), VerifyError> {
+        if self.out.ty != self.ty.ret {
+            Err(VerifyError::Op0Op1TyNoMatch(self.out.ty, self.ty.ret))?
+        }
+
+        if self.callee.ty != TypeMetadata::ptr {
+            Err(VerifyError::CalleeNotPtr(self.callee.ty))?
+        }
+
+        for (index, arg) in self.args.iter().enumerate() {
+            match self.ty.args.get(index) {
+                Some((_, argty)) => if !arg.get_ty().usable_as(*argty) {
+                    Err(VerifyError::InvalidArgumentTypeFound)?
+                },
+                None => if !self.ty.any_args {
+                    Err(VerifyError::ToManyArgumentsWereSupplyed)?
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Ir> {
+        Box::from( self.clone() )
+    }
+
+    fn compile(&self, registry: &mut TargetBackendDescr, module: &mut crate::prelude::Module) {
+        registry.compile_indirect_call(self, module)
+    }
+
+    fn uses(&self, var: &Var) -> bool {
+        self.inputs().iter().any(|input| input.name == var.name)
+    }
+    
+    fn compile_dir(&self, compiler: &mut crate::CodeGen::IrCodeGenHelper, block: &crate::prelude::Block, module: &mut crate::prelude::Module) {
+        compiler.compile_indirect_call(self, block, module)
+    }
+    
+    fn inputs(&self) -> Vec<Var> {
+        let mut inputs = vec![self.callee.to_owned()];
+
+        for arg in &self.args {
+            if let IROperand::Var(arg) = &arg {
+                inputs.push(arg.to_owned());
+            }
+        }
+
+        inputs
+    }
+    
+    fn inputs_mut(&mut self) -> Vec<&mut Var> {
+        let mut inputs = vec![&mut self.callee];
+
+        for arg in &mut self.args {
+            if let IROperand::Var(arg) = arg {
+                inputs.push(arg);
+            }
+        }
+
+        inputs
+    }
+    
+    fn output(&self) -> Option<Var> {
+        Some(self.out.to_owned())
+    }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
+}
+
+impl IsNode for IndirectCall {
+    fn is_call(&self) -> bool {
+        true
+    }
+}
+
+impl IndirectCall {
+    /// Returns the variable which holds the adress of the called function
+    pub fn getCallee(&self) -> Var {
+        self.callee.to_owned()
+    }
+
+    /// Returns the type of the called function
+    pub fn getFuncTy(&self) -> FunctionType {
+        self.ty.to_owned()
+    }
+
+    /// Returns the arguments
+    pub fn getArgs(&self) -> Vec<IROperand> {
+        self.args.to_owned()
+    }
+
+    /// Returns the variable which stores the result of the call
+    pub fn getOutputVar(&self) -> Var {
+        self.out.to_owned()
+    }
+}
+
+impl EvalOptVisitor for IndirectCall {
+    fn maybe_inline(&self, _: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
+        None
+    }
+    
+    fn eval(&self) -> Option<Box<dyn Ir>> {
+        None
+    }
+}
+
+/// Trait for the indirect call instruction
+/// Used for overloading the BuildIndirectCall function
+pub trait BuildIndirectCall<T, U> {
+    /// builds a call of the function the pointer points to
+    fn BuildIndirectCall(&mut self, callee: T, ty: &FunctionType, args: U) -> Var;
+}
+impl BuildIndirectCall<Var, Vec<IROperand>> for Function {
+    fn BuildIndirectCall(&mut self, callee: Var, ty: &FunctionType, args: Vec<IROperand>) -> Var {
+        let block = self.blocks.back_mut().expect("the IRBuilder needs to have an current block\nConsider creating one");
+        
+        let out = Var::new(block, ty.ret);
+
+        block.push_ir(Box::new(IndirectCall {
+            out: out.to_owned(),
+            callee: callee,
+            ty: ty.to_owned(),
+            args: args,
+        }));
+
+        out 
+    }
+}
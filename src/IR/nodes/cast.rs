@@ -75,6 +75,10 @@ impl Ir for Cast {
     fn output(&self) -> Option<Var> {
         Some(self.inner3.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.inner3)
+    }
 }
 
 impl Cast {
@@ -118,7 +122,7 @@ impl EvalOptVisitor for Cast {
     fn maybe_inline(&self, vars: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
         if let IROperand::Var(value) = &self.inner1 {
             if let Some(var) = vars.get(&value.name) {
-                return Some(Assign::new(self.inner3.to_owned(), *var));
+                return Some(Assign::new(self.inner3.to_owned(), var.cast(self.inner2)));
             } 
         }
         
@@ -131,6 +135,8 @@ impl EvalOptVisitor for Cast {
                 IROperand::Type(ty) => Some(Assign::new(self.inner3.to_owned(), ty.to_owned())),
                 IROperand::Var(var) => Some(Assign::new(self.inner3.to_owned(), var.to_owned())),
             }
+        } else if let IROperand::Type(ty) = &self.inner1 {
+            Some(Assign::new(self.inner3.to_owned(), ty.cast(self.inner2)))
         } else { None }
     }
 }
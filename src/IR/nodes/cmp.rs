@@ -6,6 +6,9 @@ use crate::IR::{Function, Type, TypeMetadata, Var, VerifyError};
 use super::{Assign, Cmp, EvalOptVisitor, IROperand, Ir, IsNode};
 
 /// The "compare mode" (e.g: ls is equal to rs)
+///
+/// The signedness of the ordered comparisons (`>`, `<`, `>=`, `<=`) is taken from the type of the operands:
+/// `i8`-`i128` are compared signed, `i1`, `u8`-`u128` and `ptr` unsigned and `f32`/`f64` as floats
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CmpMode {
     /// ls == rs
@@ -60,6 +63,10 @@ impl Ir for Cmp {
             Err(VerifyError::Op0Op1TyNoMatch(self.ls.get_ty(), self.rs.get_ty()))?
         }
 
+        if self.ls.get_ty().is_vector() {
+            Err(VerifyError::VectorCmp(self.ls.get_ty()))?
+        }
+
         Ok(())
     }
 
@@ -115,9 +122,33 @@ impl Ir for Cmp {
     fn output(&self) -> Option<Var> {
         Some(self.out.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
 }
 
 fn calc_based_on_mode(mode: &CmpMode, ls: &Type, rs: &Type, out: Var) -> Option<Box<dyn Ir>> {
+    if let (Some(l), Some(r)) = (ls.int_val(), rs.int_val()) {
+        // integers are compared exactly (and unsigned integers without the sign)
+        let ordering = if TypeMetadata::from(*ls).signed() {
+            l.cmp(&r)
+        } else {
+            (l as u128).cmp(&(r as u128))
+        };
+
+        let condition_met = match mode {
+            CmpMode::Eqal => ordering.is_eq(),
+            CmpMode::NotEqal => ordering.is_ne(),
+            CmpMode::GreaterThan => ordering.is_gt(),
+            CmpMode::LessThan => ordering.is_lt(),
+            CmpMode::GreaterThanOrEqual => ordering.is_ge(),
+            CmpMode::LessThanOrEqual => ordering.is_le(),
+        };
+
+        return Some(Assign::new(out.to_owned(), Type::from_i128(out.ty, condition_met as i128)));
+    }
+
     let condition_met = match mode {
         CmpMode::Eqal => ls.val() == rs.val(),
         CmpMode::NotEqal => ls.val() != rs.val(),
@@ -127,7 +158,8 @@ fn calc_based_on_mode(mode: &CmpMode, ls: &Type, rs: &Type, out: Var) -> Option<
         CmpMode::LessThanOrEqual => ls.val() <= rs.val(),
     };
 
-    Some(Assign::new(out, Type::from_int(TypeMetadata::u8, condition_met as i8 as f64)))
+    let ty = out.ty;
+    Some(Assign::new(out, Type::from_int(ty, condition_met as i8 as f64)))
 }
 
 impl EvalOptVisitor for Cmp {
@@ -139,11 +171,21 @@ impl EvalOptVisitor for Cmp {
                     _ => None,
                 }
             },
+            (IROperand::Var(ls), IROperand::Type(rs)) => {
+                consts.get(&ls.name).and_then(|ls| calc_based_on_mode(&self.mode, ls, rs, self.out.to_owned()))
+            },
+            (IROperand::Type(ls), IROperand::Var(rs)) => {
+                consts.get(&rs.name).and_then(|rs| calc_based_on_mode(&self.mode, ls, rs, self.out.to_owned()))
+            },
             _ => None
         }
     }
     
     fn eval(&self) -> Option<Box<dyn Ir>> {
+        if let (IROperand::Type(ls), IROperand::Type(rs)) = (&self.ls, &self.rs) {
+            return calc_based_on_mode(&self.mode, ls, rs, self.out.to_owned());
+        }
+
         if self.ls == self.rs {
             let yes = match self.mode {
                 CmpMode::Eqal => 1,
@@ -220,7 +262,7 @@ impl BuildCmp<Var, Var> for Function {
     fn BuildCmp(&mut self, mode: CmpMode, ls: Var, rs: Var) -> Var {
         let block = self.blocks.back_mut().expect("the IRBuilder needs to have an current block\nConsider creating one");
         
-        let out = Var::new(block, TypeMetadata::u8);
+        let out = Var::new(block, TypeMetadata::i1);
 
         block.push_ir( Cmp::new(mode, IROperand::Var(ls), IROperand::Var(rs), out.to_owned()) );
 
@@ -232,7 +274,7 @@ impl BuildCmp<Var, Type> for Function {
     fn BuildCmp(&mut self, mode: CmpMode, ls: Var, rs: Type) -> Var {
         let block = self.blocks.back_mut().expect("the IRBuilder needs to have an current block\nConsider creating one");
         
-        let out = Var::new(block, TypeMetadata::u8);
+        let out = Var::new(block, TypeMetadata::i1);
 
         block.push_ir( Cmp::new(mode, IROperand::Var(ls), IROperand::Type(rs), out.to_owned()) );
 
@@ -244,7 +286,7 @@ impl BuildCmp<Type, Var> for Function {
     fn BuildCmp(&mut self, mode: CmpMode, ls: Type, rs: Var) -> Var {
         let block = self.blocks.back_mut().expect("the IRBuilder needs to have an current block\nConsider creating one");
         
-        let out = Var::new(block, TypeMetadata::u8);
+        let out = Var::new(block, TypeMetadata::i1);
 
         block.push_ir( Cmp::new(mode, IROperand::Type(ls), IROperand::Var(rs), out.to_owned()) );
 
@@ -256,7 +298,7 @@ impl BuildCmp<Type, Type> for Function {
     fn BuildCmp(&mut self, mode: CmpMode, ls: Type, rs: Type) -> Var {
         let block = self.blocks.back_mut().expect("the IRBuilder needs to have an current block\nConsider creating one");
         
-        let out = Var::new(block, TypeMetadata::u8);
+        let out = Var::new(block, TypeMetadata::i1);
 
         block.push_ir( Cmp::new(mode, IROperand::Type(ls), IROperand::Type(rs), out.to_owned()) );
 
@@ -61,6 +61,10 @@ impl Ir for DebugNode {
     fn output(&self) -> Option<crate::prelude::Var> {
         None
     }
+
+    fn output_mut(&mut self) -> Option<&mut crate::prelude::Var> {
+        None
+    }
 }
 
 impl EvalOptVisitor for DebugNode {
@@ -1,15 +1,30 @@
 use crate::prelude::*;
 use super::*;
 use std::fmt::Debug;
-
-/// extracts an element out of a vector
+use crate::IR::{AggregateType, DataLayout};
+
+/// computes the adress of an element inside of the memory a pointer points to
+///
+/// The type is the type of the memory:
+///  - for structs the index is the (constant) field index
+///  - for arrays it is the element index
+///  - for all other types the pointer is treated like an array of the type
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetElemPtr {
     pub(crate) ptr: Var,
     pub(crate) ty: TypeMetadata,
     pub(crate) out: Var,
 
-    pub(crate) index: Var,
+    pub(crate) index: IROperand,
+}
+
+impl GetElemPtr {
+    /// Returns the offset of the element in bytes if the index is a constant
+    pub fn const_offset(&self) -> Option<usize> {
+        let IROperand::Type(index) = self.index else { return None; };
+
+        Some(DataLayout::default().offset(self.ty, index.val() as usize))
+    }
 }
 
 impl Ir for GetElemPtr {
@@ -20,8 +35,8 @@ impl Ir for GetElemPtr {
             self.ptr.ty, 
             self.ptr.name, 
 
-            self.out.ty, 
-            self.out.name,
+            self.index.get_ty(), 
+            self.index,
 
             self.ty,
         )
@@ -33,9 +48,9 @@ impl Ir for GetElemPtr {
             profile.markup("getelemptr", ColorClass::Instr),
             profile.markup(&self.ptr.ty.to_string(), ColorClass::Ty),
             profile.markup(&self.ptr.name, ColorClass::Var),
-            profile.markup(&self.out.ty.to_string(), ColorClass::Ty),
-            profile.markup(&self.out.name, ColorClass::Value),
-            profile.markup(&self.ty.to_string(), ColorClass::Value),
+            profile.markup(&self.index.get_ty().to_string(), ColorClass::Ty),
+            profile.markup(&self.index.to_string(), ColorClass::Value),
+            profile.markup(&self.ty.to_string(), ColorClass::Ty),
         )
     }
 
@@ -44,6 +59,22 @@ impl Ir for GetElemPtr {
     }
 
     fn verify(&self, _: FunctionType) -> Result<(), VerifyError> {
+        let index_ty = self.index.get_ty();
+
+        if index_ty.float() || index_ty == TypeMetadata::Void || index_ty.is_aggregate() {
+            Err(VerifyError::InvalidElementIndex(self.ty))?
+        }
+
+        if let Some(AggregateType::Struct(fields)) = self.ty.aggregate() {
+            let IROperand::Type(index) = self.index else {
+                Err(VerifyError::InvalidElementIndex(self.ty))?
+            };
+
+            if index.val() < 0.0 || index.val() as usize >= fields.len() {
+                Err(VerifyError::InvalidElementIndex(self.ty))?
+            }
+        }
+
         Ok(())
     }
 
@@ -60,16 +91,32 @@ impl Ir for GetElemPtr {
     }
 
     fn inputs(&self) -> Vec<Var> {
-        vec![self.ptr.to_owned(), self.index.to_owned()]
+        let mut inputs = vec![self.ptr.to_owned()];
+
+        if let IROperand::Var(index) = &self.index {
+            inputs.push(index.to_owned());
+        }
+
+        inputs
     }
 
     fn inputs_mut(&mut self) -> Vec<&mut Var> {
-        vec![&mut self.ptr, &mut self.index]
+        let mut inputs = vec![&mut self.ptr];
+
+        if let IROperand::Var(index) = &mut self.index {
+            inputs.push(index);
+        }
+
+        inputs
     }
 
     fn output(&self) -> Option<Var> {
         Some(self.out.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
 }
 
 impl EvalOptVisitor for GetElemPtr {
@@ -88,31 +135,59 @@ impl IsNode for GetElemPtr {
     }
 }
 
-impl Function {
-    /// Builds the `getelemptr` node.
+/// The trait `BuildGetelemptr` is used to build the getelemptr node
+pub trait BuildGetelemptr<T> {
+    /// Builds the `getelemptr` node which returns the adress of the element with the index
+    /// inside of the memory (with the type `ty`) the pointer points to.
+    /// 
     /// It is used for example in this c code:
     /// ```no-run
-    /// int test(int a[5]) {
-    ///     return a[1];
+    /// struct vec { int x; long y; };
+    /// 
+    /// long test(struct vec* v, int a[5]) {
+    ///     return v->y + a[1];
     /// }
     /// ```
     /// 
     /// It would be compiled to this:
     /// ```no-run
-    /// define i32 @test(ptr %0) {
+    /// define i64 @test(ptr %0, ptr %1) {
     ///   entry:
-    ///     %const0 = i32 1
-    ///     %1 = getelemptr ptr %0, i32 %const0, i32
-    ///     ret i32 %1
+    ///     %2 = getelemptr ptr %0, i64 1, {i32, i64}
+    ///     %3 = load i64 %2
+    ///     %4 = getelemptr ptr %1, i64 1, i32
+    ///     %5 = load i32 %4
+    ///     ...
     /// }
     /// ```
-    pub fn BuildGetelemptr(&mut self, var: Var, index: Var, ty: TypeMetadata) -> Var {
+    fn BuildGetelemptr(&mut self, var: Var, index: T, ty: TypeMetadata) -> Var;
+}
+
+impl BuildGetelemptr<Var> for Function {
+    fn BuildGetelemptr(&mut self, var: Var, index: Var, ty: TypeMetadata) -> Var {
+        let block = self.blocks.back_mut().expect("expected current block");
+
+        let out = Var::new(block, TypeMetadata::ptr);
+
+        block.push_ir(Box::new(GetElemPtr {
+            index: IROperand::Var(index),
+            ptr: var,
+            out: out.clone(),
+            ty: ty,
+        }));
+
+        out.clone()
+    }
+}
+
+impl BuildGetelemptr<Type> for Function {
+    fn BuildGetelemptr(&mut self, var: Var, index: Type, ty: TypeMetadata) -> Var {
         let block = self.blocks.back_mut().expect("expected current block");
 
-        let out = Var::new(block, ty);
+        let out = Var::new(block, TypeMetadata::ptr);
 
         block.push_ir(Box::new(GetElemPtr {
-            index: index,
+            index: IROperand::Type(index),
             ptr: var,
             out: out.clone(),
             ty: ty,
@@ -120,4 +195,4 @@ impl Function {
 
         out.clone()
     }
-}
\ No newline at end of file
+}
@@ -63,6 +63,10 @@ impl Ir for Load {
     fn output(&self) -> Option<Var> {
         Some(self.inner1.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.inner1)
+    }
 }
 
 impl EvalOptVisitor for Load {
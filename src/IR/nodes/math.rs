@@ -126,7 +126,7 @@ macro_rules! MathIrNode {
                 let mut inputs = Vec::new();
 
                 if let IROperand::Var(ls) = &self.inner1 { inputs.push(ls.to_owned()); }
-                if let IROperand::Var(rs) = &self.inner1 { inputs.push(rs.to_owned()); }
+                if let IROperand::Var(rs) = &self.inner2 { inputs.push(rs.to_owned()); }
                 inputs
             }
     
@@ -145,6 +145,10 @@ macro_rules! MathIrNode {
             fn output(&self) -> Option<Var> {
                 Some(self.inner3.to_owned())
             }
+
+            fn output_mut(&mut self) -> Option<&mut Var> {
+                Some(&mut self.inner3)
+            }
         }
         
     };
@@ -161,6 +165,35 @@ MathIrNode!(Rem,    compile_rem,  BuildRem, BuildRem, "rem");
 MathIrNode!(Shl,    compile_shl,  BuildShl, BuildShl, "shl");
 MathIrNode!(Shr,    compile_shr,  BuildShr, BuildShr, "shr");
 
+/// Returns the value of an unsigned integer of the type (without the sign extension)
+fn unsigned(value: i128, ty: TypeMetadata) -> u128 {
+    match ty.bitSize() {
+        128 => value as u128,
+        bits => value as u128 & ((1 << bits) - 1),
+    }
+}
+
+/// Folds the operation on two constants of the type `ty`
+/// 
+/// Integers are calculated exactly (and wrap around like on the target), floats are calculated
+/// using `float`. Returns `None` if the operation can't be folded (e.g: a division through zero)
+fn fold(ls: &Type, rs: &Type, ty: TypeMetadata, int: impl Fn(i128, i128) -> Option<i128>, float: Option<fn(f64, f64) -> f64>) -> Option<Type> {
+    if ty.float() {
+        return Some(Type::from_int(ty, float?(ls.val(), rs.val())));
+    }
+
+    Some(Type::from_i128(ty, int(ls.int_val()?, rs.int_val()?)?))
+}
+
+/// Returns the shift amount if it is in the range of the type
+fn shift_amount(amount: i128, ty: TypeMetadata) -> Option<u32> {
+    if amount < 0 || amount >= ty.bitSize() as i128 {
+        None
+    } else {
+        Some(amount as u32)
+    }
+}
+
 impl EvalOptVisitor for Add {
     fn maybe_inline(&self, const_values: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
         let mut new_ls = self.inner1.to_owned();
@@ -204,8 +237,8 @@ impl EvalOptVisitor for Add {
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = ls.val() + rs.val();
-                let calc = Type::from_int(self.inner3.ty, calc);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls.wrapping_add(rs)), Some(|ls, rs| ls + rs))?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -246,14 +279,6 @@ impl EvalOptVisitor for Sub {
                 })
             }
         }
-        if let IROperand::Type(ty) = &self.inner1 {
-            if ty.val() == 0.0 {
-                return Some(match &self.inner2 {
-                    IROperand::Type(rs) => Assign::new(self.inner3.to_owned(), *rs),
-                    IROperand::Var(rs) => Assign::new(self.inner3.to_owned(), rs.to_owned()),
-                })
-            }
-        }
 
         if self.inner1 == self.inner2 {
             return Some(Assign::new(self.inner3.to_owned(), Type::from_int(self.inner3.ty, 0.0)))
@@ -262,8 +287,8 @@ impl EvalOptVisitor for Sub {
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = ls.val() - rs.val();
-                let calc = Type::from_int(self.inner3.ty, calc);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls.wrapping_sub(rs)), Some(|ls, rs| ls - rs))?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -301,8 +326,8 @@ impl EvalOptVisitor for Xor {
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = (ls.val() as i64) ^ (rs.val() as i64);
-                let calc = Type::from_int(self.inner3.ty, calc as f64);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls ^ rs), None)?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -337,8 +362,8 @@ impl EvalOptVisitor for Or {
     fn eval(&self) -> Option<Box<dyn Ir>> {
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = (ls.val() as i64) | (rs.val() as i64);
-                let calc = Type::from_int(self.inner3.ty, calc as f64);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls | rs), None)?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -373,8 +398,8 @@ impl EvalOptVisitor for And {
     fn eval(&self) -> Option<Box<dyn Ir>> {
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = (ls.val() as i64) & (rs.val() as i64);
-                let calc = Type::from_int(self.inner3.ty, calc as f64);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls & rs), None)?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -410,7 +435,10 @@ impl EvalOptVisitor for Mul {
             if ty.val() == 0.0 {
                 return Some(Assign::new(self.inner3.to_owned(), Type::from_int((*ty).into(), 0.0)));
             } else if ty.val() == 1.0 {
-                return Some(Assign::new(self.inner3.to_owned(), Type::from_int((*ty).into(), 0.0)));
+                return Some(match &self.inner2 {
+                    IROperand::Type(rs) => Assign::new(self.inner3.to_owned(), *rs),
+                    IROperand::Var(rs) => Assign::new(self.inner3.to_owned(), rs.to_owned()),
+                });
             }
         }
 
@@ -418,14 +446,17 @@ impl EvalOptVisitor for Mul {
             if ty.val() == 0.0 {
                 return Some(Assign::new(self.inner3.to_owned(), Type::from_int((*ty).into(), 0.0)));
             } else if ty.val() == 1.0 {
-                return Some(Assign::new(self.inner3.to_owned(), Type::from_int((*ty).into(), 0.0)));
+                return Some(match &self.inner1 {
+                    IROperand::Type(ls) => Assign::new(self.inner3.to_owned(), *ls),
+                    IROperand::Var(ls) => Assign::new(self.inner3.to_owned(), ls.to_owned()),
+                });
             }
         }
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = ls.val() * rs.val();
-                let calc = Type::from_int(self.inner3.ty, calc);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls.wrapping_mul(rs)), Some(|ls, rs| ls * rs))?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -463,14 +494,21 @@ impl EvalOptVisitor for Div {
 
         if let IROperand::Type(ty) = &self.inner2 {
             if ty.val() == 1.0 {
-                return Some(Assign::new(self.inner3.to_owned(), Type::from_int((*ty).into(), 1.0)));
+                return Some(match &self.inner1 {
+                    IROperand::Type(ls) => Assign::new(self.inner3.to_owned(), *ls),
+                    IROperand::Var(ls) => Assign::new(self.inner3.to_owned(), ls.to_owned()),
+                });
             } // we could check for 0 but this would hide runtime errors which (positivly) "change" runtime behauvior
         }
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = ls.val() / rs.val();
-                let calc = Type::from_int(self.inner3.ty, calc);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| if ty.signed() {
+                    ls.checked_div(rs)
+                } else {
+                    unsigned(ls, ty).checked_div(unsigned(rs, ty)).map(|calc| calc as i128)
+                }, Some(|ls, rs| ls / rs))?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -514,8 +552,12 @@ impl EvalOptVisitor for Rem {
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = ls.val() % rs.val();
-                let calc = Type::from_int(self.inner3.ty, calc);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| if ty.signed() {
+                    ls.checked_rem(rs)
+                } else {
+                    unsigned(ls, ty).checked_rem(unsigned(rs, ty)).map(|calc| calc as i128)
+                }, Some(|ls, rs| ls % rs))?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -558,8 +600,8 @@ impl EvalOptVisitor for Shl {
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = (ls.val() as i64) << (rs.val() as i64);
-                let calc = Type::from_int(self.inner3.ty, calc as f64);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| Some(ls << shift_amount(rs, ty)?), None)?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -606,8 +648,12 @@ impl EvalOptVisitor for Shr {
 
         if let IROperand::Type(ls) = &self.inner1 {
             if let IROperand::Type(rs) = &self.inner2 {
-                let calc = (ls.val() as i64) >> (rs.val() as i64);
-                let calc = Type::from_int(self.inner3.ty, calc as f64);
+                let ty = self.inner3.ty;
+                let calc = fold(ls, rs, ty, |ls, rs| if ty.signed() {
+                    Some(ls >> shift_amount(rs, ty)?)
+                } else {
+                    Some((unsigned(ls, ty) >> shift_amount(rs, ty)?) as i128)
+                }, None)?;
                 return Some(Assign::new(self.inner3.to_owned(), calc));
             }
         }
@@ -19,6 +19,7 @@ mod switch;
 mod neg;
 mod select;
 mod getelemptr;
+mod vector;
 
 pub use assign::*;
 pub use call::*;
@@ -32,6 +33,7 @@ pub use debug::*;
 pub use switch::*;
 pub use select::*;
 pub use getelemptr::*;
+pub use vector::*;
 
 macro_rules! IrTypeWith3 {
     ($name:tt, $param1:tt, $param2:tt, $param3:tt, $is_func:ident) => {
@@ -255,6 +257,15 @@ pub struct Call {
     pub(crate) args: Vec<IROperand>,
 }
 
+/// Indirect call ir node (calls the function the pointer points to)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndirectCall {
+    pub(crate) out: Var,
+    pub(crate) callee: Var,
+    pub(crate) ty: FunctionType,
+    pub(crate) args: Vec<IROperand>,
+}
+
 /// checks if the node is another node
 #[allow(missing_docs)]
 pub trait IsNode {
@@ -266,7 +277,9 @@ pub trait IsNode {
     fn is_call(&self) -> bool { false }
     fn is_cmp(&self) -> bool { false }
     fn is_debug(&self) -> bool { false }
+    fn is_extractelement(&self) -> bool { false }
     fn is_getelemptr(&self) -> bool { false }
+    fn is_insertelement(&self) -> bool { false }
     fn is_load(&self) -> bool { false }
     fn is_add(&self) -> bool { false }
     fn is_sub(&self) -> bool { false }
@@ -282,6 +295,7 @@ pub trait IsNode {
     fn is_phi(&self) -> bool { false }
     fn is_ret(&self) -> bool { false }
     fn is_select(&self) -> bool { false }
+    fn is_shufflevector(&self) -> bool { false }
     fn is_store(&self) -> bool { false }
     fn is_switch(&self) -> bool { false }
 }
@@ -328,6 +342,9 @@ pub trait Ir: Debug + Any + EvalOptVisitor + IsNode {
 
     /// returns the output var
     fn output(&self) -> Option<Var>;
+
+    /// returns a mutable reference to the output var of the node
+    fn output_mut(&mut self) -> Option<&mut Var>;
 }
 
 /// A trait used for constant propagination
@@ -419,6 +436,8 @@ impl std::fmt::Display for IROperand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
             IROperand::Type(ty) => match ty {
+                Type::i1(i) => format!("{}", *i as u8),
+
                 Type::u8(i) => format!("{i}"),
                 Type::u16(i) => format!("{i}"),
                 Type::u32(i) => format!("{i}"),
@@ -428,6 +447,9 @@ impl std::fmt::Display for IROperand {
                 Type::i16(i) => format!("{i}"),
                 Type::i32(i) => format!("{i}"),
                 Type::i64(i) => format!("{i}"),
+
+                Type::u128(i) => format!("{i}"),
+                Type::i128(i) => format!("{i}"),
     
                 Type::ptr(adr) => format!("{adr:#04x}"),
                 Type::Void => format!("0"),
@@ -49,14 +49,18 @@ impl Ir for Neg {
     fn output(&self) -> Option<Var> {
         Some(self.inner2.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.inner2)
+    }
 }
 
 impl EvalOptVisitor for Neg {
     fn maybe_inline(&self, const_values: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
         if let IROperand::Var(var) = &self.inner1 {
             if let Some(value) = const_values.get(&var.name) {
-                if !var.ty.float() {
-                    let ty = Type::from_int(var.ty, -(value.val() as i64) as f64);
+                if let Some(value) = value.int_val() {
+                    let ty = Type::from_i128(var.ty, value.wrapping_neg());
         
                     return Some(Assign::new(self.inner2.to_owned(), ty));
                 }
@@ -67,6 +71,15 @@ impl EvalOptVisitor for Neg {
     }
 
     fn eval(&self) -> Option<Box<dyn Ir>> {
+        if let IROperand::Type(value) = &self.inner1 {
+            let ty = match value.int_val() {
+                Some(value) => Type::from_i128(self.inner2.ty, value.wrapping_neg()),
+                None => Type::from_int(self.inner2.ty, -value.val()),
+            };
+
+            return Some(Assign::new(self.inner2.to_owned(), ty));
+        }
+
         None
     }
 }
@@ -83,6 +83,10 @@ impl Ir for Phi {
     fn output(&self) -> Option<crate::prelude::Var> {
         Some(self.out.to_owned())
     }
+
+    fn output_mut(&mut self) -> Option<&mut crate::prelude::Var> {
+        Some(&mut self.out)
+    }
 }
 
 impl EvalOptVisitor for Phi {
@@ -23,7 +23,7 @@ impl Ir for Return {
     fn verify(&self, FuncTy: FunctionType) -> Result<(), VerifyError> {
         let ty: TypeMetadata = self.inner1.get_ty();
 
-        if ty != FuncTy.ret {
+        if !ty.usable_as(FuncTy.ret) {
             Err(VerifyError::RetTyNotFnTy(ty, FuncTy.ret))?
         }
 
@@ -48,12 +48,17 @@ impl Ir for Return {
     }
     
     fn inputs_mut(&mut self) -> Vec<&mut Var> {
-        vec![]
+        if let IROperand::Var(ret) = &mut self.inner1 { vec![ret] }
+        else { vec![] }
     }
     
     fn output(&self) -> Option<Var> {
         None
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        None
+    }
 }
 
 impl EvalOptVisitor for Return {
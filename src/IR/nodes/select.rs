@@ -60,12 +60,21 @@ impl Ir for Select {
     }
     
     fn inputs_mut(&mut self) -> Vec<&mut Var> {
-        vec![&mut self.cond]
+        let mut inputs = vec![&mut self.cond];
+
+        if let IROperand::Var(var) = &mut self.yes { inputs.push(var); } 
+        if let IROperand::Var(var) = &mut self.no { inputs.push(var); } 
+
+        inputs
     }
 
     fn output(&self) -> Option<Var> {
         Some(self.out.clone())
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
 }
 
 impl EvalOptVisitor for Select {
@@ -73,7 +82,7 @@ impl EvalOptVisitor for Select {
         match (&self.yes, &self.no) {
             (IROperand::Type(yes), IROperand::Type(no)) => {
                 if let Some(cond) = const_values.get(&self.cond.name) {
-                    if cond.val() == 0.0 {
+                    if cond.val() != 0.0 {
                         Some(Assign::new(self.out.clone(), *yes))
                     } else {
                         Some(Assign::new(self.out.clone(), *no))
@@ -76,6 +76,10 @@ impl Ir for Store {
     fn output(&self) -> Option<Var> {
         None // technicly the ptr is the output
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        None
+    }
 }
 
 impl EvalOptVisitor for Store {
@@ -91,6 +91,10 @@ impl Ir for Switch {
     fn output(&self) -> Option<Var> {
         None
     }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        None
+    }
 }
 
 impl EvalOptVisitor for Switch {
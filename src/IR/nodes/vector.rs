@@ -0,0 +1,414 @@
+use crate::prelude::*;
+use super::*;
+use std::fmt::Debug;
+
+/// Reads the element with the (constant) index out of a vector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractElement {
+    pub(crate) vec: Var,
+    pub(crate) index: usize,
+    pub(crate) out: Var,
+}
+
+/// Returns a copy of the vector where the element with the (constant) index is replaced with the value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertElement {
+    pub(crate) vec: Var,
+    pub(crate) value: IROperand,
+    pub(crate) index: usize,
+    pub(crate) out: Var,
+}
+
+/// Builds a new vector out of the elements of two vectors
+///
+/// The mask selects the elements of the output: indices which are smaller than the lane count
+/// select elements of `ls` and all other indices select elements of `rs` (`index - lanes`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShuffleVector {
+    pub(crate) ls: Var,
+    pub(crate) rs: Var,
+    pub(crate) mask: Vec<usize>,
+    pub(crate) out: Var,
+}
+
+/// Returns the element type and the lane count of the vector (else an error)
+fn lanes_of(ty: TypeMetadata) -> Result<(TypeMetadata, usize), VerifyError> {
+    ty.vector_lanes().ok_or(VerifyError::InvalidElementIndex(ty))
+}
+
+impl Ir for ExtractElement {
+    fn dump(&self) -> String {
+        format!("{} = extractelement {} {}, {}", self.out.name, self.vec.ty, self.vec.name, self.index)
+    }
+
+    fn dumpColored(&self, profile: ColorProfile) -> String {
+        format!("{} = {} {} {}, {}",
+            profile.markup(&self.out.name, ColorClass::Var),
+            profile.markup("extractelement", ColorClass::Instr),
+            profile.markup(&self.vec.ty.to_string(), ColorClass::Ty),
+            profile.markup(&self.vec.name, ColorClass::Var),
+            profile.markup(&self.index.to_string(), ColorClass::Value),
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn verify(&self, _: FunctionType) -> Result<(), VerifyError> {
+        let (elem, lanes) = lanes_of(self.vec.ty)?;
+
+        if self.index >= lanes {
+            Err(VerifyError::InvalidElementIndex(self.vec.ty))?
+        }
+
+        if self.out.ty != elem {
+            Err(VerifyError::Op0Op1TyNoMatch(elem, self.out.ty))?
+        }
+
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Ir> {
+        Box::new( self.clone() )
+    }
+
+    fn compile(&self, registry: &mut TargetBackendDescr, module: &mut crate::prelude::Module) {
+        registry.compile_extractelement(self, module)
+    }
+
+    fn compile_dir(&self, compiler: &mut crate::CodeGen::IrCodeGenHelper, block: &crate::prelude::Block, module: &mut crate::prelude::Module) {
+        compiler.compile_extractelement(self, block, module)
+    }
+
+    fn inputs(&self) -> Vec<Var> {
+        vec![self.vec.to_owned()]
+    }
+
+    fn inputs_mut(&mut self) -> Vec<&mut Var> {
+        vec![&mut self.vec]
+    }
+
+    fn output(&self) -> Option<Var> {
+        Some(self.out.to_owned())
+    }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
+}
+
+impl Ir for InsertElement {
+    fn dump(&self) -> String {
+        format!("{} = insertelement {} {}, {} {}, {}",
+            self.out.name,
+            self.vec.ty,
+            self.vec.name,
+            self.value.get_ty(),
+            self.value,
+            self.index
+        )
+    }
+
+    fn dumpColored(&self, profile: ColorProfile) -> String {
+        format!("{} = {} {} {}, {} {}, {}",
+            profile.markup(&self.out.name, ColorClass::Var),
+            profile.markup("insertelement", ColorClass::Instr),
+            profile.markup(&self.vec.ty.to_string(), ColorClass::Ty),
+            profile.markup(&self.vec.name, ColorClass::Var),
+            profile.markup(&self.value.get_ty().to_string(), ColorClass::Ty),
+            profile.markup(&self.value.to_string(), ColorClass::Value),
+            profile.markup(&self.index.to_string(), ColorClass::Value),
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn verify(&self, _: FunctionType) -> Result<(), VerifyError> {
+        let (elem, lanes) = lanes_of(self.vec.ty)?;
+
+        if self.index >= lanes {
+            Err(VerifyError::InvalidElementIndex(self.vec.ty))?
+        }
+
+        if self.value.get_ty() != elem {
+            Err(VerifyError::Op0Op1TyNoMatch(elem, self.value.get_ty()))?
+        }
+
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Ir> {
+        Box::new( self.clone() )
+    }
+
+    fn compile(&self, registry: &mut TargetBackendDescr, module: &mut crate::prelude::Module) {
+        registry.compile_insertelement(self, module)
+    }
+
+    fn compile_dir(&self, compiler: &mut crate::CodeGen::IrCodeGenHelper, block: &crate::prelude::Block, module: &mut crate::prelude::Module) {
+        compiler.compile_insertelement(self, block, module)
+    }
+
+    fn inputs(&self) -> Vec<Var> {
+        let mut inputs = vec![self.vec.to_owned()];
+
+        if let IROperand::Var(value) = &self.value {
+            inputs.push(value.to_owned());
+        }
+
+        inputs
+    }
+
+    fn inputs_mut(&mut self) -> Vec<&mut Var> {
+        let mut inputs = vec![&mut self.vec];
+
+        if let IROperand::Var(value) = &mut self.value {
+            inputs.push(value);
+        }
+
+        inputs
+    }
+
+    fn output(&self) -> Option<Var> {
+        Some(self.out.to_owned())
+    }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
+}
+
+impl Ir for ShuffleVector {
+    fn dump(&self) -> String {
+        let mask: Vec<String> = self.mask.iter().map(|index| index.to_string()).collect();
+
+        format!("{} = shufflevector {} {}, {}, [{}]", self.out.name, self.ls.ty, self.ls.name, self.rs.name, mask.join(", "))
+    }
+
+    fn dumpColored(&self, profile: ColorProfile) -> String {
+        let mask: Vec<String> = self.mask.iter().map(|index| profile.markup(&index.to_string(), ColorClass::Value)).collect();
+
+        format!("{} = {} {} {}, {}, [{}]",
+            profile.markup(&self.out.name, ColorClass::Var),
+            profile.markup("shufflevector", ColorClass::Instr),
+            profile.markup(&self.ls.ty.to_string(), ColorClass::Ty),
+            profile.markup(&self.ls.name, ColorClass::Var),
+            profile.markup(&self.rs.name, ColorClass::Var),
+            mask.join(", "),
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn verify(&self, _: FunctionType) -> Result<(), VerifyError> {
+        let (elem, lanes) = lanes_of(self.ls.ty)?;
+
+        if self.ls.ty != self.rs.ty {
+            Err(VerifyError::Op0Op1TyNoMatch(self.ls.ty, self.rs.ty))?
+        }
+
+        if self.mask.iter().any(|index| *index >= 2 * lanes) {
+            Err(VerifyError::InvalidElementIndex(self.ls.ty))?
+        }
+
+        let ty = TypeMetadata::vector(elem, self.mask.len());
+
+        if self.out.ty != ty {
+            Err(VerifyError::Op0Op1TyNoMatch(ty, self.out.ty))?
+        }
+
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Ir> {
+        Box::new( self.clone() )
+    }
+
+    fn compile(&self, registry: &mut TargetBackendDescr, module: &mut crate::prelude::Module) {
+        registry.compile_shufflevector(self, module)
+    }
+
+    fn compile_dir(&self, compiler: &mut crate::CodeGen::IrCodeGenHelper, block: &crate::prelude::Block, module: &mut crate::prelude::Module) {
+        compiler.compile_shufflevector(self, block, module)
+    }
+
+    fn inputs(&self) -> Vec<Var> {
+        vec![self.ls.to_owned(), self.rs.to_owned()]
+    }
+
+    fn inputs_mut(&mut self) -> Vec<&mut Var> {
+        vec![&mut self.ls, &mut self.rs]
+    }
+
+    fn output(&self) -> Option<Var> {
+        Some(self.out.to_owned())
+    }
+
+    fn output_mut(&mut self) -> Option<&mut Var> {
+        Some(&mut self.out)
+    }
+}
+
+impl EvalOptVisitor for ExtractElement {
+    fn maybe_inline(&self, _: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
+        None
+    }
+
+    fn eval(&self) -> Option<Box<dyn Ir>> {
+        None
+    }
+}
+
+impl EvalOptVisitor for InsertElement {
+    fn maybe_inline(&self, const_values: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
+        let IROperand::Var(value) = &self.value else { return None; };
+
+        let value = const_values.get(&value.name)?;
+
+        Some(Box::new(InsertElement {
+            vec: self.vec.to_owned(),
+            value: IROperand::Type(*value),
+            index: self.index,
+            out: self.out.to_owned(),
+        }))
+    }
+
+    fn eval(&self) -> Option<Box<dyn Ir>> {
+        None
+    }
+}
+
+impl EvalOptVisitor for ShuffleVector {
+    fn maybe_inline(&self, _: &HashMap<String, Type>) -> Option<Box<dyn Ir>> {
+        None
+    }
+
+    fn eval(&self) -> Option<Box<dyn Ir>> {
+        None
+    }
+}
+
+impl IsNode for ExtractElement {
+    fn is_extractelement(&self) -> bool {
+        true
+    }
+}
+
+impl IsNode for InsertElement {
+    fn is_insertelement(&self) -> bool {
+        true
+    }
+}
+
+impl IsNode for ShuffleVector {
+    fn is_shufflevector(&self) -> bool {
+        true
+    }
+}
+
+/// The trait `BuildExtractElement` is used to build the extractelement node
+pub trait BuildExtractElement {
+    /// Builds the `extractelement` node which returns the element with the index out of the vector
+    ///
+    /// ```no-run
+    /// %1 = extractelement <4 x f32> %0, 2
+    /// ```
+    fn BuildExtractElement(&mut self, vec: Var, index: usize) -> Var;
+}
+
+impl BuildExtractElement for Function {
+    fn BuildExtractElement(&mut self, vec: Var, index: usize) -> Var {
+        let block = self.blocks.back_mut().expect("expected current block");
+
+        let (elem, _) = vec.ty.vector_lanes().expect("extractelement expects a vector");
+
+        let out = Var::new(block, elem);
+
+        block.push_ir(Box::new(ExtractElement {
+            vec: vec,
+            index: index,
+            out: out.clone(),
+        }));
+
+        out
+    }
+}
+
+/// The trait `BuildInsertElement` is used to build the insertelement node
+pub trait BuildInsertElement<T> {
+    /// Builds the `insertelement` node which returns the vector with the element at the index replaced by the value
+    ///
+    /// ```no-run
+    /// %2 = insertelement <4 x f32> %0, f32 %1, 2
+    /// ```
+    fn BuildInsertElement(&mut self, vec: Var, value: T, index: usize) -> Var;
+}
+
+impl BuildInsertElement<Var> for Function {
+    fn BuildInsertElement(&mut self, vec: Var, value: Var, index: usize) -> Var {
+        let block = self.blocks.back_mut().expect("expected current block");
+
+        let out = Var::new(block, vec.ty);
+
+        block.push_ir(Box::new(InsertElement {
+            vec: vec,
+            value: IROperand::Var(value),
+            index: index,
+            out: out.clone(),
+        }));
+
+        out
+    }
+}
+
+impl BuildInsertElement<Type> for Function {
+    fn BuildInsertElement(&mut self, vec: Var, value: Type, index: usize) -> Var {
+        let block = self.blocks.back_mut().expect("expected current block");
+
+        let out = Var::new(block, vec.ty);
+
+        block.push_ir(Box::new(InsertElement {
+            vec: vec,
+            value: IROperand::Type(value),
+            index: index,
+            out: out.clone(),
+        }));
+
+        out
+    }
+}
+
+/// The trait `BuildShuffleVector` is used to build the shufflevector node
+pub trait BuildShuffleVector {
+    /// Builds the `shufflevector` node which selects the elements of the output out of both vectors
+    ///
+    /// For example this reverses `%0` (the second vector isn't used):
+    /// ```no-run
+    /// %1 = shufflevector <4 x i32> %0, %0, [3, 2, 1, 0]
+    /// ```
+    fn BuildShuffleVector(&mut self, ls: Var, rs: Var, mask: Vec<usize>) -> Var;
+}
+
+impl BuildShuffleVector for Function {
+    fn BuildShuffleVector(&mut self, ls: Var, rs: Var, mask: Vec<usize>) -> Var {
+        let block = self.blocks.back_mut().expect("expected current block");
+
+        let (elem, _) = ls.ty.vector_lanes().expect("shufflevector expects vectors");
+
+        let out = Var::new(block, TypeMetadata::vector(elem, mask.len()));
+
+        block.push_ir(Box::new(ShuffleVector {
+            ls: ls,
+            rs: rs,
+            mask: mask,
+            out: out.clone(),
+        }));
+
+        out
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::{BTreeMap, VecDeque};
 
-use crate::{Obj::Linkage, IR::{Block, Const, Function, FunctionType, Module, TypeMetadata}};
+use crate::{Obj::Linkage, IR::{Block, Const, Function, FunctionAttribute, FunctionType, Module, TypeMetadata}};
 
 use super::parser::{IrBlock, IrInstr, IrStmt};
 
@@ -25,7 +25,7 @@ impl IrGen {
         }
     }
 
-    fn gen_func(&mut self, name: String, ret: TypeMetadata, args:  (BTreeMap<String, TypeMetadata>, bool), body: Vec<(String, IrBlock)>, scope: Linkage) {
+    fn gen_func(&mut self, name: String, ret: TypeMetadata, args:  (BTreeMap<String, TypeMetadata>, bool), body: Vec<(String, IrBlock)>, scope: Linkage, attributes: Vec<FunctionAttribute>) {
         let mut ty = FunctionType::new(Vec::new(), ret);
 
         for (name, arg) in &args.0 {
@@ -37,6 +37,7 @@ impl IrGen {
             name: name,
             linkage: scope,
             blocks: VecDeque::new(),
+            attributes: attributes,
         };
 
         for (name, block) in body {
@@ -66,18 +67,32 @@ impl IrGen {
     pub fn gen(&mut self) {
         for stmt in self.input.clone() {
             match stmt {
-                IrStmt::Func { name, ret, args, body, scope, location: _ } => self.gen_func(name, ret, args, body, scope),
-                IrStmt::Const { name, data, location: _, scope } => self.gen_const(name, data, scope),
+                IrStmt::Func { name, ret, args, body, scope, attributes, location: _ } => self.gen_func(name, ret, args, body, scope, attributes),
+                IrStmt::Const { .. } => self.gen_const(stmt),
             }
         }
     }
 
-    fn gen_const(&mut self, name: String, data: Vec<u8>, scope: Linkage) {
-        let raw = Const {
-            name: name,
-            data: data,
-            linkage: scope,
-        };
+    fn gen_const(&mut self, stmt: IrStmt) {
+        let IrStmt::Const { name, data, location: _, scope, mutable, zero_init, align, section, thread_local, elements } = stmt else { unreachable!() };
+
+        let mut raw = Const::new(name);
+
+        if elements.is_empty() {
+            raw.set(data);
+        } else {
+            raw.set_elements(elements);
+        }
+
+        if let Some(size) = zero_init {
+            raw.zero_init(size);
+        }
+
+        raw.linkage = scope;
+        raw.mutable = mutable;
+        raw.align = align;
+        raw.section = section;
+        raw.thread_local = thread_local;
 
         self.consts.push( raw );
     }
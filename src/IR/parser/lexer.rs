@@ -28,6 +28,9 @@ pub enum TokenType {
     /// const
     Const,
 
+    /// global
+    Global,
+
     /// ,
     Comma,
 
@@ -55,6 +58,12 @@ pub enum TokenType {
     /// ]
     RSquare,
 
+    /// <
+    LAngle,
+
+    /// >
+    RAngle,
+
     /// a-zA-Z.
     Ident(String),
 
@@ -108,6 +117,7 @@ impl TokenType {
         match self {
             TokenType::Dot => ":",
             TokenType::Const => "const",
+            TokenType::Global => "global",
             TokenType::Comma => ",",
             TokenType::Var(_) => "var",
             TokenType::Equal => "=",
@@ -117,6 +127,8 @@ impl TokenType {
             TokenType::RBracket => "}",
             TokenType::LSquare => "[",
             TokenType::RSquare => "]",
+            TokenType::LAngle => "<",
+            TokenType::RAngle => ">",
             TokenType::Ident(_) => "ident",
             TokenType::String(_) => "string",
             TokenType::Int(_) => "int",
@@ -174,6 +186,7 @@ impl IrLexer {
         keys.insert("declare".into(), TokenType::Declare);
         keys.insert("define".into(), TokenType::Define);
         keys.insert("const".into(), TokenType::Const);
+        keys.insert("global".into(), TokenType::Global);
         keys.insert("cond".into(), TokenType::Cond);
 
         let input = format!("{}\n", input);
@@ -297,10 +310,12 @@ impl IrLexer {
             '(' => ty = Some(TokenType::LParam),
             '{' => ty = Some(TokenType::LBracket),
             '[' => ty = Some(TokenType::LSquare),
+            '<' => ty = Some(TokenType::LAngle),
 
             ')' => ty = Some(TokenType::RParam),
             '}' => ty = Some(TokenType::RBracket),
             ']' => ty = Some(TokenType::RSquare),
+            '>' => ty = Some(TokenType::RAngle),
 
             ':' => ty = Some(TokenType::Dot),
             ',' => ty = Some(TokenType::Comma),
@@ -60,9 +60,9 @@ pub enum IrError {
     /// Expected token
     ExpectedTokenButFoundAnUnexpectedOne{ 
         /// the token which was found
-        found: lexer::Token, 
+        found: Box<lexer::Token>, 
         /// the token which was expected
-        expected: lexer::Token 
+        expected: Box<lexer::Token> 
     },
 
     /// unkown instruction
@@ -138,7 +138,15 @@ pub enum IrError {
         loc: Loc,
         /// the branch name
         branch: String,
-    }
+    },
+
+    /// the callee of an indirect call isn't a pointer
+    CalleeNotPtr {
+        /// location
+        loc: Loc,
+        /// the type of the callee
+        found: TypeMetadata,
+    },
 }
 
 impl Display for IrError {
@@ -336,6 +344,17 @@ impl Display for IrError {
 
                 fab.to_string()
             }
+
+            IrError::CalleeNotPtr { loc, found } => {
+                let mut fab = Support::Error::new("callee isn't a pointer", "", "", "");
+
+                fab.deactivateLocationDisplay();
+
+                fab.setCodeLine(loc.line_string.to_owned());
+                fab.addWhere(format!("indirect calls need a ptr as their callee but found: {found}"), loc.coloumn, loc.length);
+
+                fab.to_string()
+            }
         })
     }
 }
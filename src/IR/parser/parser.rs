@@ -1,10 +1,10 @@
 use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::path::PathBuf;
 
-use crate::prelude::{Alloca, Cmp, CmpMode, DebugNode, GetElemPtr, IROperand, Ir, Load, Neg, Phi, Select, Store, Switch};
+use crate::prelude::{Alloca, Cmp, CmpMode, DebugNode, ExtractElement, GetElemPtr, IROperand, InsertElement, Ir, Load, Neg, Phi, Select, ShuffleVector, Store, Switch};
 use crate::Obj::Linkage;
 use crate::IR::block::BlockId;
-use crate::IR::{ir, Block, Const, FnTy, Type, TypeMetadata, Var};
+use crate::IR::{ir, Block, Const, ConstElement, FnTy, FunctionAttribute, Type, TypeMetadata, Var, VerifyError};
 
 use super::lexer::{Loc, Token, TokenType};
 use super::IrError;
@@ -42,15 +42,22 @@ pub enum IrStmt {
         args: (BTreeMap<String, TypeMetadata>, /*unlim args*/bool), 
         body: Vec<(String, IrBlock)>,
         scope: Linkage,
+        attributes: Vec<FunctionAttribute>,
 
         location: Loc,
     },
-    /// a constant
+    /// a constant (or a global variable)
     Const{
         name: String,
         data: Vec<u8>, 
         location: Loc,
         scope: Linkage,
+        mutable: bool,
+        zero_init: Option<usize>,
+        align: Option<usize>,
+        section: Option<String>,
+        thread_local: bool,
+        elements: Vec<ConstElement>,
     },
 }
 
@@ -92,7 +99,7 @@ impl IrParser {
         match &tok.typ {
             TokenType::Declare => self.parse_declare(),
             TokenType::Define => self.parse_define(),
-            TokenType::Const => self.parse_const(),
+            TokenType::Const | TokenType::Global => self.parse_const(),
 
             _ => Err(IrError::UnexpectedToken(tok.clone())),
         }
@@ -102,9 +109,18 @@ impl IrParser {
         let name;
         let mut args = BTreeMap::new();
         
+        let mut link = Linkage::Extern;
+
         self.expect( TokenType::Declare )?;
         self.input.pop_front(); // advance over declare
 
+        if let TokenType::Ident(ident) = &self.current_token()?.typ {
+            if ident == "extern_weak" {
+                link = Linkage::ExternWeak;
+                self.input.pop_front();
+            }
+        }
+
         let ret = self.parse_type()?;
         self.input.pop_front();
 
@@ -164,10 +180,13 @@ impl IrParser {
         self.expect(TokenType::RParam)?;
         self.input.pop_front(); // the closing param )
 
+        let attributes = self.parse_attributes()?;
+
         Ok(IrStmt::Func { 
             name: name, 
             body: Vec::new(),
-            scope: Linkage::Extern,
+            scope: link,
+            attributes: attributes,
             args: (args, unlim),
             ret: ret,
 
@@ -185,23 +204,26 @@ impl IrParser {
         self.expect( TokenType::Define )?;
         self.input.pop_front(); // advance over define
 
-        let ret = self.parse_type()?;
-        self.input.pop_front();
-
-        let curr = self.current_token()?;
-
-        if let TokenType::Ident(ident) = &curr.typ {
-            link = match ident.as_str() {
-                "local" | "internal" | "private" => Linkage::Internal,
-                "public" | "external" => Linkage::External,
-                _ => Err(IrError::Unkown { 
-                    what: "linkage".to_owned(), 
-                    name: ident.to_owned(), 
-                    loc: curr.loc.clone()
-                })?
+        // the linkage is optional (types are identifiers too)
+        if let TokenType::Ident(ident) = &self.current_token()?.typ {
+            let parsed = match ident.as_str() {
+                "local" | "internal" | "private" => Some(Linkage::Internal),
+                "public" | "external" => Some(Linkage::External),
+                "weak" => Some(Linkage::Weak),
+                "hidden" => Some(Linkage::Hidden),
+                "linkonce" => Some(Linkage::LinkOnce),
+                _ => None,
             };
+
+            if let Some(parsed) = parsed {
+                link = parsed;
+                self.input.pop_front();
+            }
         }
 
+        let ret = self.parse_type()?;
+        self.input.pop_front();
+
         self.expect( TokenType::Func(String::new()) )?;
 
         let tok = self.current_token()?;
@@ -246,7 +268,8 @@ impl IrParser {
 
         self.input.pop_front(); // the closing param )
 
-        
+        let attributes = self.parse_attributes()?;
+
         self.expect(TokenType::LBracket)?;
         self.input.pop_front();
 
@@ -269,14 +292,41 @@ impl IrParser {
             body: body,
             args: (args, false),
             scope: link,
+            attributes: attributes,
             ret: ret,
 
             location: loc,
         })
     }
 
+    /// Parses the attributes after the arguments of a function (e.g: `noinline cold`)
+    fn parse_attributes(&mut self) -> Result<Vec<FunctionAttribute>, IrError> {
+        let mut attributes = vec![];
+
+        while let Some(tok) = self.input.front() {
+            let TokenType::Ident(ident) = &tok.typ else { break; };
+
+            let Some(attribute) = FunctionAttribute::from_name(ident) else {
+                Err(IrError::Unkown { 
+                    what: "function attribute".to_owned(), 
+                    name: ident.to_owned(), 
+                    loc: tok.loc.clone()
+                })?
+            };
+
+            if !attributes.contains(&attribute) {
+                attributes.push(attribute);
+            }
+
+            self.input.pop_front();
+        }
+
+        Ok(attributes)
+    }
+
     fn parse_const(&mut self) -> Result<IrStmt, IrError> {
-        self.expect(TokenType::Const)?;
+        // globals are parsed like constants but are mutable
+        let mutable = self.current_token()?.typ == TokenType::Global;
 
         self.input.pop_front();
 
@@ -306,7 +356,27 @@ impl IrParser {
                 },
                 "import" => {
                     parsed_scope = true;
-                    scope = Linkage::External
+                    scope = Linkage::Extern
+                },
+                "weak" => {
+                    parsed_scope = true;
+                    scope = Linkage::Weak
+                },
+                "extern_weak" => {
+                    parsed_scope = true;
+                    scope = Linkage::ExternWeak
+                },
+                "hidden" => {
+                    parsed_scope = true;
+                    scope = Linkage::Hidden
+                },
+                "common" => {
+                    parsed_scope = true;
+                    scope = Linkage::Common
+                },
+                "linkonce" => {
+                    parsed_scope = true;
+                    scope = Linkage::LinkOnce
                 },
                 _ => {
                     name = ident.to_string();
@@ -335,17 +405,25 @@ impl IrParser {
         let current = self.current_token()?;
 
         let mut data = vec![];
+        let mut elements = vec![];
+        let mut zero_init = None;
 
         match &current.typ {
             TokenType::String(x) => data = x.as_bytes().to_vec(),
             TokenType::LSquare => data = self.parse_data_array()?, 
+            TokenType::LBracket => elements = self.parse_const_elements()?,
+            TokenType::Ident(ident) if ident == "zeroinit" => {
+                self.input.pop_front();
+
+                zero_init = Some(self.parse_const_attribute_int()?);
+            },
 
             _=> Err(IrError::ExpectedTokenButFoundAnUnexpectedOne { 
-                found: current.clone(), 
-                expected: Token { 
+                found: Box::new(current.clone()), 
+                expected: Box::new(Token { 
                     typ: TokenType::LSquare, 
                     loc: current.loc.clone() 
-                } 
+                }) 
             })?
         };
 
@@ -357,14 +435,110 @@ impl IrParser {
 
         self.input.pop_front();
 
+        let mut align = None;
+        let mut section = None;
+        let mut thread_local = false;
+
+        while let Some(Token { typ: TokenType::Ident(attribute), .. }) = self.input.front() {
+            match attribute.as_str() {
+                "align" => {
+                    self.input.pop_front();
+
+                    align = Some(self.parse_const_attribute_int()?);
+                    self.input.pop_front();
+                },
+                "section" => {
+                    self.input.pop_front();
+
+                    self.expect(TokenType::String(String::new()))?;
+                    if let TokenType::String(name) = &self.current_token()?.typ {
+                        section = Some(name.to_owned());
+                    }
+                    self.input.pop_front();
+                },
+                "thread_local" => {
+                    self.input.pop_front();
+
+                    thread_local = true;
+                },
+                _ => break,
+            }
+        }
+
         Ok(IrStmt::Const { 
             name: name, 
             data: data,
             location: location,
             scope: scope,
+            mutable: mutable,
+            zero_init: zero_init,
+            align: align,
+            section: section,
+            thread_local: thread_local,
+            elements: elements,
         })
     }
 
+    /// Parses typed constant elements: `{ i32 1, ptr symbol 4, { i8 1, i8 2 } }`
+    /// (the closing bracket is not consumed)
+    fn parse_const_elements(&mut self) -> Result<Vec<ConstElement>, IrError> {
+        self.expect(TokenType::LBracket)?;
+        self.input.pop_front();
+
+        let mut elements = vec![];
+
+        loop {
+            let current = self.current_token()?.clone();
+
+            match &current.typ {
+                TokenType::RBracket => break,
+                TokenType::LBracket => elements.push(ConstElement::Array(self.parse_const_elements()?)),
+                TokenType::Ident(ty) => {
+                    let Some(ty) = TypeMetadata::parse(ty.to_owned()) else {
+                        Err(IrError::UnexpectedToken(current.clone()))?
+                    };
+
+                    self.input.pop_front();
+
+                    let value = self.current_token()?.clone();
+
+                    match &value.typ {
+                        TokenType::Int(int) => elements.push(ConstElement::Value(Type::from_int(ty, *int))),
+                        TokenType::Ident(symbol) if ty == TypeMetadata::ptr => {
+                            // the optional addend follows the symbol
+                            let addend = if let Some(Token { typ: TokenType::Int(addend), .. }) = self.input.get(1) {
+                                let addend = *addend as i64;
+                                self.input.pop_front();
+                                addend
+                            } else { 0 };
+
+                            elements.push(ConstElement::Adr(symbol.to_owned(), addend));
+                        },
+                        _ => Err(IrError::UnexpectedToken(value.clone()))?,
+                    }
+                },
+                _ => Err(IrError::UnexpectedToken(current.clone()))?,
+            }
+
+            self.input.pop_front();
+
+            if TokenType::Comma == self.current_token()?.typ {
+                self.input.pop_front();
+            }
+        }
+
+        Ok(elements)
+    }
+
+    /// Parses the integer of `zeroinit ...` or `align ...` (the token is not consumed)
+    fn parse_const_attribute_int(&mut self) -> Result<usize, IrError> {
+        self.expect(TokenType::Int(0.0))?;
+
+        let TokenType::Int(int) = &self.current_token()?.typ else { unreachable!() };
+
+        Ok(*int as usize)
+    }
+
     fn parse_block(&mut self) -> Result<(String, IrBlock), IrError> {
         self.expect(TokenType::Block(String::new()))?;
 
@@ -442,6 +616,9 @@ impl IrParser {
                         "shl" => self.parse_shl(name)?,
                         "shr" => self.parse_shr(name)?,
                         "getelemptr" => self.parse_getelemptr(name)?,
+                        "extractelement" => self.parse_extractelement(name)?,
+                        "insertelement" => self.parse_insertelement(name)?,
+                        "shufflevector" => self.parse_shufflevector(name)?,
                         _ => {
                             let ty = self.parse_type()?;
                             self.input.pop_front(); // the type
@@ -571,13 +748,20 @@ impl IrParser {
         let func_ty = self.parse_type()?;
         self.input.pop_front();
 
-        self.expect(TokenType::Ident(String::from("func name")))?;
+        // indirect calls call the function the variable points to
+        let callee = if let TokenType::Var(callee) = &self.current_token()?.typ {
+            Some(Var { name: callee.to_owned(), ty: TypeMetadata::ptr })
+        } else {
+            self.expect(TokenType::Ident(String::from("func name")))?;
+            None
+        };
 
         let target = &self.current_token()?;
 
-        let target = if let TokenType::Ident(ident) = &target.typ {
-            ident.to_owned()
-        } else { unreachable!() };
+        let target = match &target.typ {
+            TokenType::Ident(ident) | TokenType::Var(ident) => ident.to_owned(),
+            _ => unreachable!(),
+        };
 
         self.input.pop_front(); // function name
 
@@ -589,13 +773,15 @@ impl IrParser {
         let mut args = vec![];
 
         loop {
-            let ty = if let TokenType::Ident(ty) = &self.current_token()?.typ {
-                if let Some(ty) = TypeMetadata::parse(ty.to_owned()) {
+            let ty = match &self.current_token()?.typ {
+                TokenType::Ident(ty) => if let Some(ty) = TypeMetadata::parse(ty.to_owned()) {
                     ty
                 } else {
                     break
-                }
-            } else { break; };
+                },
+                TokenType::LBracket | TokenType::LSquare | TokenType::LAngle => self.parse_type()?,
+                _ => break,
+            };
 
             self.input.pop_front();
 
@@ -605,6 +791,12 @@ impl IrParser {
             args.push(arg);
         }
 
+        if let Some(callee) = callee {
+            let ty = FnTy(args.iter().map(|arg| arg.get_ty()).collect(), func_ty);
+
+            return Ok(Box::new(ir::IndirectCall { out, callee, ty, args }));
+        }
+
         Ok(Box::new(ir::Call {
             func: crate::IR::FuncId {
                 ty: FnTy(vec![], func_ty),
@@ -775,11 +967,11 @@ impl IrParser {
             Ok(token.clone())
         } else {
             Err(IrError::ExpectedTokenButFoundAnUnexpectedOne { 
-                found: token.clone(), 
-                expected: Token { 
+                found: Box::new(token.clone()), 
+                expected: Box::new(Token { 
                     typ: typ, 
                     loc: token.loc.clone()
-                } 
+                }) 
             })?
         }   
     }
@@ -797,11 +989,11 @@ impl IrParser {
 
         if ident.to_owned() != expected {
             Err(IrError::ExpectedTokenButFoundAnUnexpectedOne { 
-                found: self.current_token()?.clone(), 
-                expected: Token { 
+                found: Box::new(self.current_token()?.clone()), 
+                expected: Box::new(Token { 
                     typ: TokenType::Ident(expected.to_owned()), 
                     loc: self.current_token()?.loc.clone() 
-                }
+                })
             })?
         }
 
@@ -811,17 +1003,24 @@ impl IrParser {
     fn parse_type(&mut self) -> Result<TypeMetadata, IrError> {
         let token = self.current_token()?;
 
+        match token.typ {
+            TokenType::LBracket => return self.parse_struct_type(),
+            TokenType::LSquare => return self.parse_array_type(),
+            TokenType::LAngle => return self.parse_vector_type(),
+            _ => {},
+        }
+
         let mut ident = String::new();
 
         if let TokenType::Ident(text) = &token.typ {
             ident = text.to_string();
         } else {
             Err(IrError::ExpectedTokenButFoundAnUnexpectedOne { 
-                found: token.clone(), 
-                expected: Token { 
+                found: Box::new(token.clone()), 
+                expected: Box::new(Token { 
                     typ: TokenType::Ident("abc".to_string()), 
                     loc: token.loc.clone()
-                } 
+                }) 
             })?
         }
 
@@ -832,6 +1031,67 @@ impl IrParser {
         }
     }
 
+    /// Parses `{T, T, ...}` (the closing `}` is left for the caller)
+    fn parse_struct_type(&mut self) -> Result<TypeMetadata, IrError> {
+        self.input.pop_front(); // {
+
+        let mut fields = Vec::new();
+
+        loop {
+            if let TokenType::RBracket = self.current_token()?.typ {
+                break;
+            }
+
+            if !fields.is_empty() {
+                self.expect(TokenType::Comma)?;
+                self.input.pop_front();
+            }
+
+            fields.push(self.parse_type()?);
+            self.input.pop_front();
+        }
+
+        Ok(TypeMetadata::structure(fields))
+    }
+
+    /// Parses `[N x T]` (the closing `]` is left for the caller)
+    fn parse_array_type(&mut self) -> Result<TypeMetadata, IrError> {
+        self.input.pop_front(); // [
+
+        let len = self.expect(TokenType::Int(0.0))?;
+        let TokenType::Int(len) = len.typ else { unreachable!() };
+        self.input.pop_front();
+
+        self.expect_ident("x".into())?;
+        self.input.pop_front();
+
+        let elem = self.parse_type()?;
+        self.input.pop_front();
+
+        self.expect(TokenType::RSquare)?;
+
+        Ok(TypeMetadata::array(elem, len as usize))
+    }
+
+    /// Parses `<N x T>` (the closing `>` is left for the caller)
+    fn parse_vector_type(&mut self) -> Result<TypeMetadata, IrError> {
+        self.input.pop_front(); // <
+
+        let lanes = self.expect(TokenType::Int(0.0))?;
+        let TokenType::Int(lanes) = lanes.typ else { unreachable!() };
+        self.input.pop_front();
+
+        self.expect_ident("x".into())?;
+        self.input.pop_front();
+
+        let elem = self.parse_type()?;
+        self.input.pop_front();
+
+        self.expect(TokenType::RAngle)?;
+
+        Ok(TypeMetadata::vector(elem, lanes as usize))
+    }
+
     fn parse_cmp(&mut self, var: String) -> Result<Box<dyn Ir>, IrError> {
         self.input.pop_front();
 
@@ -858,9 +1118,15 @@ impl IrParser {
 
         self.input.pop_front();
 
+        let loc = self.current_token()?.loc.to_owned();
+
         let ty = self.parse_type()?;
         self.input.pop_front();
 
+        if ty.is_vector() {
+            Err(IrError::Boxed { loc: loc, err: Box::new(VerifyError::VectorCmp(ty)) })?
+        }
+
         let ls = self.parse_operand(ty)?;
         self.input.pop_front();
 
@@ -874,7 +1140,7 @@ impl IrParser {
 
         Ok(Cmp::new(mode, ls, rs, Var {
             name: var,
-            ty: TypeMetadata::u8
+            ty: TypeMetadata::i1
         }))
     }
 
@@ -1091,31 +1357,142 @@ impl IrParser {
         let index_ty = self.parse_type()?;
         self.input.pop_front();
 
-        self.expect(TokenType::Var(String::new()))?;
-        let index = if let TokenType::Var(index) = &self.current_token()?.typ {
-            Var {
-                name: index.to_owned(),
-                ty: index_ty
-            }
-        } else { unreachable!() };
+        let index = self.parse_operand(index_ty)?;
         self.input.pop_front();
 
         self.expect(TokenType::Comma)?;
         self.input.pop_front();
 
-        let out_ty = self.parse_type()?;
+        let ty = self.parse_type()?;
         self.input.pop_front();
 
         Ok(GetElemPtr {
             ptr: ptr,
-            ty: out_ty,
+            ty: ty,
             out: Var {
                 name: var,
-                ty: out_ty
+                ty: TypeMetadata::ptr
             },
             index: index,
         }.clone_box())
     }
+
+    /// Parses a constant element index (the token is left for the caller)
+    fn parse_index(&mut self) -> Result<usize, IrError> {
+        let index = self.expect(TokenType::Int(0.0))?;
+        let TokenType::Int(index) = index.typ else { unreachable!() };
+
+        Ok(index as usize)
+    }
+
+    fn parse_extractelement(&mut self, var: String) -> Result<Box<dyn Ir>, IrError> {
+        self.input.pop_front(); // extractelement
+
+        let vec_ty = self.parse_type()?;
+        self.input.pop_front();
+
+        let vec = self.expect(TokenType::Var(String::new()))?;
+        let TokenType::Var(vec) = vec.typ else { unreachable!() };
+        self.input.pop_front();
+
+        self.expect(TokenType::Comma)?;
+        self.input.pop_front();
+
+        let index = self.parse_index()?;
+        self.input.pop_front();
+
+        let elem = vec_ty.vector_lanes().map(|(elem, _)| elem).unwrap_or(vec_ty);
+
+        Ok(Box::new(ExtractElement {
+            vec: Var { name: vec, ty: vec_ty },
+            index: index,
+            out: Var { name: var, ty: elem },
+        }))
+    }
+
+    fn parse_insertelement(&mut self, var: String) -> Result<Box<dyn Ir>, IrError> {
+        self.input.pop_front(); // insertelement
+
+        let vec_ty = self.parse_type()?;
+        self.input.pop_front();
+
+        let vec = self.expect(TokenType::Var(String::new()))?;
+        let TokenType::Var(vec) = vec.typ else { unreachable!() };
+        self.input.pop_front();
+
+        self.expect(TokenType::Comma)?;
+        self.input.pop_front();
+
+        let value_ty = self.parse_type()?;
+        self.input.pop_front();
+
+        let value = self.parse_operand(value_ty)?;
+        self.input.pop_front();
+
+        self.expect(TokenType::Comma)?;
+        self.input.pop_front();
+
+        let index = self.parse_index()?;
+        self.input.pop_front();
+
+        Ok(Box::new(InsertElement {
+            vec: Var { name: vec, ty: vec_ty },
+            value: value,
+            index: index,
+            out: Var { name: var, ty: vec_ty },
+        }))
+    }
+
+    fn parse_shufflevector(&mut self, var: String) -> Result<Box<dyn Ir>, IrError> {
+        self.input.pop_front(); // shufflevector
+
+        let ty = self.parse_type()?;
+        self.input.pop_front();
+
+        let ls = self.expect(TokenType::Var(String::new()))?;
+        let TokenType::Var(ls) = ls.typ else { unreachable!() };
+        self.input.pop_front();
+
+        self.expect(TokenType::Comma)?;
+        self.input.pop_front();
+
+        let rs = self.expect(TokenType::Var(String::new()))?;
+        let TokenType::Var(rs) = rs.typ else { unreachable!() };
+        self.input.pop_front();
+
+        self.expect(TokenType::Comma)?;
+        self.input.pop_front();
+
+        self.expect(TokenType::LSquare)?;
+        self.input.pop_front();
+
+        let mut mask = Vec::new();
+
+        loop {
+            if let TokenType::RSquare = self.current_token()?.typ {
+                break;
+            }
+
+            if !mask.is_empty() {
+                self.expect(TokenType::Comma)?;
+                self.input.pop_front();
+            }
+
+            mask.push(self.parse_index()?);
+            self.input.pop_front();
+        }
+
+        self.input.pop_front(); // ]
+
+        let elem = ty.vector_lanes().map(|(elem, _)| elem).unwrap_or(ty);
+
+        Ok(Box::new(ShuffleVector {
+            ls: Var { name: ls, ty: ty },
+            rs: Var { name: rs, ty: ty },
+            out: Var { name: var, ty: TypeMetadata::vector(elem, mask.len()) },
+            mask: mask,
+        }))
+    }
 }
 
 macro_rules! ParserImplParseMath {
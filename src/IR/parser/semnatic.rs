@@ -1,7 +1,7 @@
 use std::collections::{BTreeMap, HashMap};
 
 use crate::Obj::Linkage;
-use crate::IR::{BlockId, Const, FunctionType, TypeMetadata, Var};
+use crate::IR::{BlockId, Const, ConstElement, FunctionType, TypeMetadata, Var};
 
 use crate::prelude::ir::*;
 
@@ -32,16 +32,16 @@ impl<'a> IrSemnatic<'a> {
     pub fn verify(&mut self) -> Result<(), IrError> {
         for stmt in &self.input.clone() {
             match stmt {
-                IrStmt::Func { name, ret, args, body, scope, location } => self.add_func(name, *ret, args, scope, body, location)?,
-                IrStmt::Const { name, data: _, location, scope } => self.add_const(name, scope, location)?
+                IrStmt::Func { name, ret, args, body, scope, attributes: _, location } => self.add_func(name, *ret, args, scope, body, location)?,
+                IrStmt::Const { name, location, scope, .. } => self.add_const(name, scope, location)?
             }
         }
 
         let mut stmts = self.input.to_vec();
         for stmt in &mut stmts {
             match stmt {
-                IrStmt::Func { name, ret, args, body, scope, location } => self.analizye_func(name, *ret, args, body, *scope, location)?,
-                IrStmt::Const { name, data, location, scope } => self.analyize_const(name, data, location, *scope)?,
+                IrStmt::Func { name, ret, args, body, scope, attributes: _, location } => self.analizye_func(name, *ret, args, body, *scope, location)?,
+                IrStmt::Const { name, data, location, scope, elements, .. } => self.analyize_const(name, data, elements, location, *scope)?,
             }
         }
 
@@ -106,7 +106,7 @@ impl<'a> IrSemnatic<'a> {
             vars.insert(name.to_owned(), *ty);
         }
 
-        if Linkage::Extern == scope && body.len() > 0 {
+        if scope.is_import() && !body.is_empty() {
             Err(IrError::ExternFunWithBody {
                 name: name.to_owned(),
                 loc: loc.to_owned(),
@@ -177,6 +177,8 @@ impl<'a> IrSemnatic<'a> {
                     self.analiyze_assign_const(&mut vars, node, loc.to_owned())?;
                 } else if let Some(node) = any.downcast_ref::<Call>() {
                     self.analyize_call(&mut vars, node, loc.to_owned())?;
+                } else if let Some(node) = any.downcast_ref::<IndirectCall>() {
+                    self.analyize_indirect_call(&mut vars, node, loc.to_owned())?;
                 } else if let Some(node) = any.downcast_ref::<Br>() {
                     self.analiyze_block(func, node, loc.to_owned())?;
                 } else if let Some(node) = any.downcast_ref::<Phi>() {
@@ -243,7 +245,7 @@ impl<'a> IrSemnatic<'a> {
 
         if let IROperand::Var(var_to_return) = &node.inner1 {
             if let Some(var) = vars.get(&var_to_return.name) {
-                if *var != fsig {
+                if !var.usable_as(fsig) {
                     Err(IrError::FuncWrongReturnTyoe { 
                         expected: fsig, 
                         found: *var, 
@@ -272,9 +274,11 @@ impl<'a> IrSemnatic<'a> {
 
     fn analiyze_assign_const(&mut self, _vars: &mut HashMap<String, TypeMetadata>, node: &Assign<Var, Const>, loc: Loc) -> Result<(), IrError> {
         let name = &node.inner2.name;
-        if !self.const_sigs.contains_key(name) {
+
+        // the adress of a function can also be loaded
+        if !self.const_sigs.contains_key(name) && !self.func_sigs.contains_key(name) {
             Err(IrError::Unkown {
-                what: "const".to_owned(),
+                what: "const or function".to_owned(),
                 loc: loc.to_owned(),
                 name: name.to_owned(),
             })?
@@ -321,7 +325,7 @@ impl<'a> IrSemnatic<'a> {
             } else { &arg.get_ty() };
 
             if let Some((_, expected)) = sig.args.get(index) {
-                if *expected != *arg {
+                if !arg.usable_as(*expected) {
                     Err(IrError::WrongArgument {
                         loc: loc.to_owned(),
                         index: index,
@@ -345,6 +349,25 @@ impl<'a> IrSemnatic<'a> {
         Ok(())
     }
 
+    fn analyize_indirect_call(&mut self, vars: &mut HashMap<String, TypeMetadata>, node: &IndirectCall, loc: Loc) -> Result<(), IrError> {
+        let Some(callee) = vars.get(&node.callee.name) else {
+            Err(IrError::Unkown { 
+                what: "variable".to_owned(), 
+                name: node.callee.name.to_owned(), 
+                loc: loc.to_owned(), 
+            })?
+        };
+
+        if *callee != TypeMetadata::ptr {
+            Err(IrError::CalleeNotPtr {
+                loc: loc,
+                found: *callee,
+            })?
+        }
+
+        Ok(())
+    }
+
     fn analiyze_block(&mut self, func: &String, node: &Br, loc: Loc) -> Result<(), IrError> {
         let br_block = &node.inner1.name;
 
@@ -402,8 +425,23 @@ impl<'a> IrSemnatic<'a> {
 
         Ok(())
     }
-    fn analyize_const(&mut self, _: &String, _: &Vec<u8>, _: &Loc, _: Linkage) -> Result<(), IrError> {
-        Ok(()) // what can go wrong on constants?
+    fn analyize_const(&mut self, _: &String, _: &Vec<u8>, elements: &Vec<ConstElement>, loc: &Loc, _: Linkage) -> Result<(), IrError> {
+        // the adresses in the initializer need to be of known symbols
+        for element in elements {
+            match element {
+                ConstElement::Value(_) => {},
+                ConstElement::Array(elements) => self.analyize_const(&String::new(), &vec![], elements, loc, Linkage::Internal)?,
+                ConstElement::Adr(symbol, _) => if !self.func_sigs.contains_key(symbol) && !self.const_sigs.contains_key(symbol) {
+                    Err(IrError::Unkown {
+                        what: "symbol".into(),
+                        name: symbol.to_owned(),
+                        loc: loc.clone(),
+                    })?
+                },
+            }
+        }
+
+        Ok(())
     }
 
     fn analyze_switch(&mut self, func: &String, vars: &mut HashMap<String, TypeMetadata>, node: &Switch, loc: Loc) -> Result<(), IrError> {
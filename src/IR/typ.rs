@@ -1,10 +1,15 @@
 use std::fmt::Display;
 
+use super::layout::{AggregateId, AggregateType, DataLayout};
+
 /// Stores a type and a value of that type
 /// 
 /// If you want an empty Type consider using `TypeMetadata`
 #[derive(Debug, Clone, Copy)]
 pub enum Type {
+    /// A boolean (the result of comparisons)
+    i1(bool),
+
     /// Just an u8 with a value
     u8(u8),
     /// Just an u16 with a value
@@ -22,6 +27,10 @@ pub enum Type {
     i32(i32),
     /// Just an i64 with a value
     i64(i64),
+    /// Just an u128 with a value
+    u128(u128),
+    /// Just an i128 with a value
+    i128(i128),
     /// 64Bit pointer
     ptr(i64),
 
@@ -38,6 +47,7 @@ pub enum Type {
 impl PartialEq for Type {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
+            (Self::i1(l0), Self::i1(r0)) => l0 == r0,
             (Self::u8(l0), Self::u8(r0)) => l0 == r0,
             (Self::u16(l0), Self::u16(r0)) => l0 == r0,
             (Self::u32(l0), Self::u32(r0)) => l0 == r0,
@@ -46,6 +56,8 @@ impl PartialEq for Type {
             (Self::i16(l0), Self::i16(r0)) => l0 == r0,
             (Self::i32(l0), Self::i32(r0)) => l0 == r0,
             (Self::i64(l0), Self::i64(r0)) => l0 == r0,
+            (Self::u128(l0), Self::u128(r0)) => l0 == r0,
+            (Self::i128(l0), Self::i128(r0)) => l0 == r0,
             (Self::ptr(l0), Self::ptr(r0)) => l0 == r0,
             (Self::f32(l0), Self::f32(r0)) => l0 == r0,
             (Self::f64(l0), Self::f64(r0)) => l0 == r0,
@@ -65,6 +77,9 @@ impl std::hash::Hash for Type {
 /// Stores type metadata (just the type without data)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TypeMetadata {
+    /// i1 (a boolean)
+    i1,
+
     /// u8
     u8,
     /// u16
@@ -82,6 +97,10 @@ pub enum TypeMetadata {
     i32,
     /// i64
     i64,
+    /// u128
+    u128,
+    /// i128
+    i128,
     /// ptr
     ptr,
 
@@ -93,18 +112,27 @@ pub enum TypeMetadata {
 
     /// f64
     f64,
+
+    /// A struct or an array (see `AggregateType`)
+    Aggregate(AggregateId),
+
+    /// A simd vector like `<4 x f32>` (see `AggregateType::Vector`)
+    Vector(AggregateId),
 }
 
 impl Type {
     /// Returns the inner value
     pub fn val(&self) -> f64 {
         match self {
+            Type::i1(val) => *val as u8 as f64,
             Type::u16(val) => *val as f64,
             Type::u32(val) => *val as f64,
             Type::u64(val) => *val as f64,
             Type::i16(val) => *val as f64,
             Type::i32(val) => *val as f64,
             Type::i64(val) => *val as f64,
+            Type::u128(val) => *val as f64,
+            Type::i128(val) => *val as f64,
             Type::ptr(adr) => *adr as f64,
             Type::Void => 0.0,
             Type::u8(val) => *val as f64,
@@ -117,20 +145,86 @@ impl Type {
     /// puts the intenger into a type respecting the type metadata
     pub fn from_int(ty: TypeMetadata, value: f64) -> Self {
         match ty {
+            TypeMetadata::i1 => Type::i1(value as i64 & 1 == 1),
             TypeMetadata::u16 => Type::u16(value as u16),
             TypeMetadata::u32 => Type::u32(value as u32),
             TypeMetadata::u64 => Type::u64(value as u64),
             TypeMetadata::i16 => Type::i16(value as i16),
             TypeMetadata::i32 => Type::i32(value as i32),
             TypeMetadata::i64 => Type::i64(value as i64),
+            TypeMetadata::u128 => Type::u128(value as u128),
+            TypeMetadata::i128 => Type::i128(value as i128),
             TypeMetadata::ptr => Type::ptr(value as i64),
             TypeMetadata::Void => Type::Void,
             TypeMetadata::u8 => Type::u8(value as u8),
             TypeMetadata::i8 => Type::i8(value as i8),
             TypeMetadata::f32 => Type::f32(value as f32),
             TypeMetadata::f64 => Type::f64(value as f64),
+            TypeMetadata::Aggregate(_) | TypeMetadata::Vector(_) => Type::Void,
+        }
+    }
+
+    /// Returns the exact value of an integer (`u128`s are reinterpreted as `i128`)
+    /// or `None` if the type isn't an integer
+    pub fn int_val(&self) -> Option<i128> {
+        Some(match self {
+            Type::i1(val) => *val as i128,
+            Type::u8(val) => *val as i128,
+            Type::u16(val) => *val as i128,
+            Type::u32(val) => *val as i128,
+            Type::u64(val) => *val as i128,
+            Type::i8(val) => *val as i128,
+            Type::i16(val) => *val as i128,
+            Type::i32(val) => *val as i128,
+            Type::i64(val) => *val as i128,
+            Type::u128(val) => *val as i128,
+            Type::i128(val) => *val,
+            Type::ptr(adr) => *adr as i128,
+            Type::Void | Type::f32(_) | Type::f64(_) => return None,
+        })
+    }
+
+    /// puts the integer into a type respecting the type metadata (the value gets truncated to the size of the type)
+    pub fn from_i128(ty: TypeMetadata, value: i128) -> Self {
+        match ty {
+            TypeMetadata::i1 => Type::i1(value & 1 == 1),
+            TypeMetadata::u8 => Type::u8(value as u8),
+            TypeMetadata::u16 => Type::u16(value as u16),
+            TypeMetadata::u32 => Type::u32(value as u32),
+            TypeMetadata::u64 => Type::u64(value as u64),
+            TypeMetadata::i8 => Type::i8(value as i8),
+            TypeMetadata::i16 => Type::i16(value as i16),
+            TypeMetadata::i32 => Type::i32(value as i32),
+            TypeMetadata::i64 => Type::i64(value as i64),
+            TypeMetadata::u128 => Type::u128(value as u128),
+            TypeMetadata::i128 => Type::i128(value),
+            TypeMetadata::ptr => Type::ptr(value as i64),
+            TypeMetadata::f32 => Type::f32(value as f32),
+            TypeMetadata::f64 => Type::f64(value as f64),
+            TypeMetadata::Void | TypeMetadata::Aggregate(_) | TypeMetadata::Vector(_) => Type::Void,
         }
     }
+
+    /// Converts the value into the type
+    /// 
+    /// Integers are truncated or extended exactly like the backends do it
+    /// (with the sign if the target type is signed, else with zeros)
+    pub fn cast(&self, ty: TypeMetadata) -> Self {
+        let Some(value) = self.int_val().filter(|_| !ty.float()) else {
+            return Type::from_int(ty, self.val());
+        };
+
+        // booleans are stored in a byte
+        let shift = 128 - TypeMetadata::from(*self).byteSize() as u32 * 8;
+
+        let value = if ty.signed() {
+            (value << shift) >> shift
+        } else {
+            ((value as u128) << shift >> shift) as i128
+        };
+
+        Type::from_i128(ty, value)
+    }
 }
 
 impl TypeMetadata {
@@ -138,35 +232,100 @@ impl TypeMetadata {
     #[inline]
     pub fn bitSize(&self) -> usize {
         match self {
+            TypeMetadata::i1 => 1,
             TypeMetadata::u8 | TypeMetadata::i8 => 8,
             TypeMetadata::u16 | TypeMetadata::i16 => 16,
             TypeMetadata::u32 | TypeMetadata::i32 => 32,
             TypeMetadata::u64 | TypeMetadata::i64 => 64,
+            TypeMetadata::u128 | TypeMetadata::i128 => 128,
             TypeMetadata::ptr => 64,
             TypeMetadata::Void => 0,
 
-            TypeMetadata::f32 => 4,
-            TypeMetadata::f64 => 8,
+            TypeMetadata::f32 => 32,
+            TypeMetadata::f64 => 64,
+
+            TypeMetadata::Aggregate(_) | TypeMetadata::Vector(_) => self.byteSize() * 8,
         }
     }
 
     /// Returns the size of the type in bytes
     #[inline]
     pub fn byteSize(&self) -> usize {
-        if *self != TypeMetadata::Void {
-            self.bitSize() / 8
-        } else {
-            0
+        match self {
+            TypeMetadata::Void => 0,
+            // booleans are stored in a byte
+            TypeMetadata::i1 => 1,
+            TypeMetadata::Aggregate(_) | TypeMetadata::Vector(_) => DataLayout::default().size(*self),
+            _ => self.bitSize() / 8,
         }
     }
 
+    /// Creates a struct type with the fields
+    pub fn structure(fields: Vec<TypeMetadata>) -> Self {
+        TypeMetadata::Aggregate(AggregateType::Struct(fields).intern())
+    }
+
+    /// Creates an array type with `len` elements of the type `elem`
+    pub fn array(elem: TypeMetadata, len: usize) -> Self {
+        TypeMetadata::Aggregate(AggregateType::Array(elem, len).intern())
+    }
+
+    /// Creates a vector type with `lanes` elements of the type `elem`
+    pub fn vector(elem: TypeMetadata, lanes: usize) -> Self {
+        TypeMetadata::Vector(AggregateType::Vector(elem, lanes).intern())
+    }
+
+    /// Returns the aggregate type if it is one
+    pub fn aggregate(&self) -> Option<AggregateType> {
+        match self {
+            TypeMetadata::Aggregate(aggregate) => Some(aggregate.get()),
+            _ => None,
+        }
+    }
+
+    /// Returns if it is a struct or an array
+    #[inline]
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, TypeMetadata::Aggregate(_))
+    }
+
+    /// Returns if it is a vector
+    #[inline]
+    pub fn is_vector(&self) -> bool {
+        matches!(self, TypeMetadata::Vector(_))
+    }
+
+    /// Returns the element type and the number of lanes if it is a vector
+    pub fn vector_lanes(&self) -> Option<(TypeMetadata, usize)> {
+        let TypeMetadata::Vector(vector) = self else { return None; };
+
+        let AggregateType::Vector(elem, lanes) = vector.get() else { unreachable!("vector types are interned as vectors") };
+
+        Some((elem, lanes))
+    }
+
+    /// Returns if it is `i128` or `u128` (they need two registers on 64 bit targets)
+    #[inline]
+    pub fn is_int128(&self) -> bool {
+        matches!(self, TypeMetadata::i128 | TypeMetadata::u128)
+    }
+
+    /// Returns if a value of the type can be used where a value of the expected type is required
+    /// 
+    /// Aggregates are handled over their adress, so pointers can be used as aggregates
+    pub fn usable_as(&self, expected: TypeMetadata) -> bool {
+        *self == expected || (*self == TypeMetadata::ptr && expected.is_aggregate())
+    }
+
     /// Returns if it is a signed type
     #[inline]
     pub fn signed(&self) -> bool {
         match self {
+            TypeMetadata::i8 => true,
             TypeMetadata::i16 => true,
             TypeMetadata::i32 => true,
             TypeMetadata::i64 => true,
+            TypeMetadata::i128 => true,
 
             _ => false,
         }
@@ -186,6 +345,8 @@ impl TypeMetadata {
     #[inline]
     pub fn parse(string: String) -> Option<Self> {
         match string.as_str() {
+            "i1" => Some(TypeMetadata::i1),
+
             "u8" => Some(TypeMetadata::u8),
             "u16" => Some(TypeMetadata::u16),
             "u32" => Some(TypeMetadata::u32),
@@ -196,6 +357,9 @@ impl TypeMetadata {
             "i32" => Some(TypeMetadata::i32),
             "i64" => Some(TypeMetadata::i64),
 
+            "u128" => Some(TypeMetadata::u128),
+            "i128" => Some(TypeMetadata::i128),
+
             "ptr" => Some(TypeMetadata::ptr),
 
             "void" => Some(TypeMetadata::Void),
@@ -211,6 +375,8 @@ impl TypeMetadata {
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match &self {
+            Type::i1(i) => format!("i1 {}", *i as u8),
+
             Type::u8(i) => format!("u8 {}", i),
             Type::u16(i) => format!("u16 {}", i),
             Type::u32(i) => format!("u32 {}", i),
@@ -221,6 +387,9 @@ impl Display for Type {
             Type::i32(i) => format!("i32 {}", i),
             Type::i64(i) => format!("i64 {}", i),
 
+            Type::u128(i) => format!("u128 {}", i),
+            Type::i128(i) => format!("i128 {}", i),
+
             Type::ptr(adr) => format!("ptr {:#04x}", adr),
             Type::Void => format!("void"),
 
@@ -239,6 +408,8 @@ impl crate::Support::AsAny for Type {
 impl Display for TypeMetadata {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match &self {
+            TypeMetadata::i1 => "i1",
+
             TypeMetadata::u8 => "u8",
             TypeMetadata::u16 => "u16",
             TypeMetadata::u32 => "u32",
@@ -249,11 +420,16 @@ impl Display for TypeMetadata {
             TypeMetadata::i32 => "i32",
             TypeMetadata::i64 => "i64",
 
+            TypeMetadata::u128 => "u128",
+            TypeMetadata::i128 => "i128",
+
             TypeMetadata::ptr => "ptr",
             TypeMetadata::Void => "void",
 
             TypeMetadata::f32 => "f32",
             TypeMetadata::f64 => "f64",
+
+            TypeMetadata::Aggregate(aggregate) | TypeMetadata::Vector(aggregate) => return write!(f, "{}", aggregate.get()),
         })
     }
 }
@@ -261,6 +437,8 @@ impl Display for TypeMetadata {
 impl From<Type> for TypeMetadata {
     fn from(value: Type) -> Self {
         match value {
+            Type::i1(_) => TypeMetadata::i1,
+
             Type::u8(_) => TypeMetadata::u8,
             Type::u16(_) => TypeMetadata::u16,
             Type::u32(_) => TypeMetadata::u32,
@@ -271,6 +449,9 @@ impl From<Type> for TypeMetadata {
             Type::i32(_) => TypeMetadata::i32,
             Type::i64(_) => TypeMetadata::i64,
 
+            Type::u128(_) => TypeMetadata::u128,
+            Type::i128(_) => TypeMetadata::i128,
+
             Type::ptr(_) => TypeMetadata::ptr,
             Type::Void => TypeMetadata::Void,
 
@@ -283,6 +464,8 @@ impl From<Type> for TypeMetadata {
 impl From<TypeMetadata> for Type {
     fn from(value: TypeMetadata) -> Self {
         match value {
+            TypeMetadata::i1 => Type::i1(false),
+
             TypeMetadata::u8 => Type::u8(0),
             TypeMetadata::u16 => Type::u16(0),
             TypeMetadata::u32 => Type::u32(0),
@@ -293,11 +476,16 @@ impl From<TypeMetadata> for Type {
             TypeMetadata::i32 => Type::i32(0),
             TypeMetadata::i64 => Type::i64(0),
 
+            TypeMetadata::u128 => Type::u128(0),
+            TypeMetadata::i128 => Type::i128(0),
+
             TypeMetadata::ptr => Type::ptr(0),
             TypeMetadata::Void => Type::Void,
 
             TypeMetadata::f32 => Type::f32(0.0),
             TypeMetadata::f64 => Type::f64(0.0),
+
+            TypeMetadata::Aggregate(_) | TypeMetadata::Vector(_) => Type::Void,
         }
     }
 }
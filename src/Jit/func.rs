@@ -19,6 +19,8 @@ pub struct JitFunction<T> {
     pub code: Vec<u8>,
     tmp: Vec<T>,
     mem: *mut c_void,
+    /// pointers which need the absolute adress (the offset of the pointer and the offset it points to)
+    pub(crate) abs_relocs: Vec<(usize, usize)>,
 }
 
 impl<T> JitFunction<T> {
@@ -28,6 +30,7 @@ impl<T> JitFunction<T> {
             code: code,
             tmp: vec![],
             mem: 0 as *mut c_void,
+            abs_relocs: vec![],
         }
     }
 
@@ -40,13 +43,24 @@ impl<T> JitFunction<T> {
 
         ptr::copy_nonoverlapping(self.code.as_ptr(), mem as *mut u8, self.code.len());
 
+        // the memory is at another adress every time so the pointers are written now
+        for (at, target) in &self.abs_relocs {
+            let adr = (mem as usize + target) as u64;
+
+            ptr::copy_nonoverlapping(adr.to_le_bytes().as_ptr(), (mem as *mut u8).add(*at), 8);
+        }
+
         self.mem = mem;
 
         mem
     }
 
     /// Frees the allocated function memory
+    ///
+    /// The memory is copied back first, so written data (like global variables) is kept for the next call
     pub unsafe fn free(&mut self) {
+        ptr::copy_nonoverlapping(self.mem as *const u8, self.code.as_mut_ptr(), self.code.len());
+
         dealloc_executable_memory(self.mem, self.code.len());
     }
 
@@ -6,10 +6,14 @@ use super::JitFunction;
 /// The JitLink dynamiclly links multible functions into one JitFunction
 pub struct JitLinker {
     funcs: HashMap<String, (Vec<u8>, /*entry*/bool)>,
-    labels: HashMap<String, Vec<u8>>,
+    labels: HashMap<String, (Vec<u8>, /*align*/usize)>,
     
     pub(crate) reloc_with_custom_actions: Vec<(Link, Box<dyn Fn(Link, &mut Vec<u8>, usize)>)>,
     pub(crate) relocs: Vec<Link>,
+    pub(crate) abs_relocs: Vec<Link>,
+
+    /// the absolute pointers of the last link (the offset of the pointer and the offset it points to)
+    abs_patches: Vec<(usize, usize)>,
 }
 
 impl JitLinker {
@@ -21,6 +25,9 @@ impl JitLinker {
 
             reloc_with_custom_actions: Vec::new(),
             relocs: Vec::new(),
+            abs_relocs: Vec::new(),
+
+            abs_patches: Vec::new(),
         }
     }
 
@@ -31,7 +38,12 @@ impl JitLinker {
 
     /// Adds a label
     pub fn add_label(&mut self, name: &str, data: Vec<u8>) {
-        self.labels.insert(name.to_string(), data);
+        self.add_aligned_label(name, data, 1);
+    }
+
+    /// Adds a label which is placed at a multiple of `align`
+    pub fn add_aligned_label(&mut self, name: &str, data: Vec<u8>, align: usize) {
+        self.labels.insert(name.to_string(), (data, align.max(1)));
     }
 
     /// Adds an relocation
@@ -39,6 +51,12 @@ impl JitLinker {
         self.relocs.push(link);
     }
 
+    /// Adds a relocation which stores the absolute adress of `link.to` (plus the addend)
+    /// as a 64 bit pointer at the offset `link.at` of `link.from`
+    pub fn add_abs_reloc(&mut self, link: Link) {
+        self.abs_relocs.push(link);
+    }
+
     /// Adds a relocation with an custom callback
     /// 
     /// ### NOTE:
@@ -72,7 +90,10 @@ impl JitLinker {
             out.extend_from_slice(&data);
         }
 
-        for (label, data) in &self.labels {
+        // the code is placed at the start of a page, so aligning the offset also aligns the adress
+        for (label, (data, align)) in &self.labels {
+            out.resize(out.len().next_multiple_of(*align), 0);
+
             positions.insert(label.as_str(), out.len());
             out.extend_from_slice(&data);
         }
@@ -105,12 +126,30 @@ impl JitLinker {
             set_byte(offset + 4, to[4]);
         }
 
+        // the adress of the code is only known when it is placed into memory,
+        // so only the offset is written here and the jit function adds the adress
+        self.abs_patches.clear();
+
+        for reloc in &self.abs_relocs {
+            let from = *positions.get(reloc.from.as_str()).unwrap_or_else(|| panic!("Unkown symbol: {}", reloc.from));
+            let to = *positions.get(reloc.to.as_str()).unwrap_or_else(|| panic!("Unkown symbol: {}", reloc.to));
+
+            let at = from + reloc.at;
+            let target = (to as i64 + reloc.addend) as usize;
+
+            out[at..at + 8].copy_from_slice(&(target as u64).to_le_bytes());
+
+            self.abs_patches.push((at, target));
+        }
+
         out
     }
 
     /// Links the code and puts it into a page aligned `JitFunction`
     pub unsafe fn engine<T>(&mut self) -> JitFunction<T> {
-        let func: JitFunction<T> = JitFunction::new(self.link());
+        let mut func: JitFunction<T> = JitFunction::new(self.link());
+
+        func.abs_relocs = self.abs_patches.to_owned();
 
         func
     }
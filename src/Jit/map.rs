@@ -15,8 +15,10 @@ enum SymbolType {
 pub struct JitMap {
     symbols: HashMap<String, Vec<u8>>,
     symbol_types: HashMap<String, SymbolType>,
+    aligns: HashMap<String, usize>,
     entry_symbol: String,
     relocs: Vec<Link>,
+    data_relocs: Vec<Link>,
 
     abs_symbols: HashMap<String, usize>,
 
@@ -30,7 +32,9 @@ impl JitMap {
             symbols: HashMap::new(),
             entry_symbol: String::new(),
             symbol_types: HashMap::new(),
+            aligns: HashMap::new(),
             relocs: Vec::new(),
+            data_relocs: Vec::new(),
             abs_symbols: HashMap::new(),
             deal_with_abs_symbols: None,
         }
@@ -44,8 +48,16 @@ impl JitMap {
 
     /// adds a data to the map
     pub fn define_data(&mut self, name: &String, data: Vec<u8>) {
+        self.define_aligned_data(name, data, 1);
+    }
+
+    /// adds a data to the map which is placed at an adress that is a multiple of `align`
+    /// 
+    /// The data is writable, so it can also be used for global variables
+    pub fn define_aligned_data(&mut self, name: &String, data: Vec<u8>, align: usize) {
         self.symbols.insert(name.to_owned(), data);
         self.symbol_types.insert(name.to_owned(), SymbolType::Data);
+        self.aligns.insert(name.to_owned(), align);
     }
 
     /// adds a relocation to the map
@@ -53,6 +65,11 @@ impl JitMap {
         self.relocs.push( reloc );
     }
 
+    /// adds a relocation which writes the absolute adress of `reloc.to` into the data `reloc.from`
+    pub fn data_reloc(&mut self, reloc: Link) {
+        self.data_relocs.push( reloc );
+    }
+
     /// Sets the absolute adress of 
     pub fn setAbsAdr(&mut self, symbol: &String, adr: usize) {
         if self.deal_with_abs_symbols.is_none() {
@@ -74,7 +91,9 @@ impl JitMap {
 
                 linker.add_func(&name, data.to_owned(), entry);
             } else { // is data
-                linker.add_label(&name, data.to_owned());
+                let align = self.aligns.get(name).copied().unwrap_or(1);
+
+                linker.add_aligned_label(name, data.to_owned(), align);
             }
         }
         
@@ -98,6 +117,10 @@ impl JitMap {
             linker.add_reloc(reloc.to_owned());
         }
 
+        for reloc in &self.data_relocs {
+            linker.add_abs_reloc(reloc.to_owned());
+        }
+
         linker
     }
 
@@ -43,10 +43,18 @@ impl Module {
                 }
             }
 
+            let attributes = obj.attributes.get(name).cloned().unwrap_or_default();
+
+            if attributes.thread_local {
+                Err(JitError::UnsupportedThreadLocals)?
+            }
+
+            let align = attributes.align.unwrap_or(1);
+
             match typ {
                 Decl::Function => map.define_func(name, data.to_owned()),
-                Decl::Data => map.define_data(name, data.to_owned()),
-                Decl::Constant => map.define_data(name, data.to_owned()),
+                Decl::Data => map.define_aligned_data(name, data.to_owned(), align as usize),
+                Decl::Constant => map.define_aligned_data(name, data.to_owned(), align as usize),
             }
         }
 
@@ -54,6 +62,10 @@ impl Module {
             map.reloc(reloc.to_owned());
         }
 
+        for reloc in &obj.data_links {
+            map.data_reloc(reloc.to_owned());
+        }
+
         map.deal_with_abs_symbols = match Triple::host().arch {
             Arch::X86_64 | Arch::X86 => Some(Box::new(crate::Target::x64::abs_jit::X64AbsSymDealer {})),
             _ => None,
@@ -72,6 +84,8 @@ pub enum JitError {
     UnsupportedImports,
     /// The host target does not support jit
     HostDoesntSupportJit,
+    /// Found a thread local but jit maps don't have thread local storage
+    UnsupportedThreadLocals,
 }
 
 impl Display for JitError {
@@ -80,6 +94,7 @@ impl Display for JitError {
             JitError::TripleDoesntMatchHostOnes => "for constructing a jit map, the triple needs to be the host ones",
             JitError::UnsupportedImports => "jit maps don't support extern symbol imports",
             JitError::HostDoesntSupportJit => "the host doesn't support jit",
+            JitError::UnsupportedThreadLocals => "jit maps don't support thread local symbols",
         })
     }
 }
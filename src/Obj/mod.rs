@@ -4,7 +4,8 @@ mod exe;
 
 pub use wrapper::{
     ObjectBuilder,
-    Decl, Link, Linkage,
+    Decl, Link, Linkage, TlsModel,
+    DataAttributes,
 };
 pub use dll::*;
 pub use exe::*;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use gimli::write::{Address, DwarfUnit, EndianVec, FileId, Range, RelocateWriter};
 use gimli::LittleEndian;
-use object::write::{Object, Relocation, SectionId, Symbol, SymbolId, SymbolSection};
-use object::{Architecture, BinaryFormat, Endianness, FileFlags, RelocationEncoding, RelocationFlags, RelocationKind, SectionKind, SymbolFlags, SymbolKind, SymbolScope};
+use object::write::{Comdat, Object, Relocation, SectionId, Symbol, SymbolId, SymbolSection};
+use object::{Architecture, ComdatKind, BinaryFormat, SectionFlags, Endianness, FileFlags, RelocationEncoding, RelocationFlags, RelocationKind, SectionKind, SymbolFlags, SymbolKind, SymbolScope};
 
 use crate::debug::DebugRegistry;
 use crate::prelude::Triple;
@@ -16,6 +16,7 @@ use std::io::Write;
 enum ObjectError {
     UnsupportedArch(Arch),
     DefWithoutDecl(String),
+    UnsupportedThreadLocal(String),
 }
 
 impl std::fmt::Display for ObjectError {
@@ -23,6 +24,7 @@ impl std::fmt::Display for ObjectError {
         write!(f, "{}", match self {
             ObjectError::UnsupportedArch(arch) => format!("unsupported architecture for writing to object files: {:?}", arch),
             ObjectError::DefWithoutDecl(name) => format!("definition without an corresponding decleration: {}", name),
+            ObjectError::UnsupportedThreadLocal(name) => format!("thread local storage is only supported in elf objects: {}", name),
         })
     }
 }
@@ -73,6 +75,30 @@ pub enum Decl {
     Constant,
 }
 
+/// Describes how a data symbol is placed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataAttributes {
+    /// The alignment of the data (it is byte aligned if none is given)
+    pub align: Option<u64>,
+    /// The section into which the data is placed (instead of `.data`, `.rodata` or `.bss`)
+    pub section: Option<String>,
+    /// The data only consists of zeros and lands in `.bss`
+    pub zero_init: bool,
+    /// Every thread has its own copy of the data (it lands in `.tdata` or `.tbss`)
+    pub thread_local: bool,
+}
+
+/// How the adress of a thread local symbol is computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TlsModel {
+    /// The symbol is defined in the executable, so it is at a fixed offset from the thread pointer
+    LocalExec,
+    /// The offset from the thread pointer is loaded from the global offset table
+    InitialExec,
+    /// The adress is returned by `__tls_get_addr` (works for shared libraries)
+    GeneralDynamic,
+}
+
 /// Links from one symbol to another
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Link {
@@ -88,6 +114,8 @@ pub struct Link {
     pub special: bool,
     /// the type
     pub kind: RelocationEncoding,
+    /// The access model if the link is to a thread local symbol
+    pub tls: Option<TlsModel>,
 }
 
 /// The linkage of the target symbol
@@ -99,6 +127,30 @@ pub enum Linkage {
     Extern,
     /// Only aviable in the object file
     Internal,
+    /// Can be seen outside of the object file, but another (not weak) definition replaces it
+    Weak,
+    /// From another object file, but the adress is null if no object file defines it
+    ExternWeak,
+    /// Can be seen by the other object files, but not outside of the linked executable or shared library
+    Hidden,
+    /// Zero initialized data which is merged with the common symbols of the other object files
+    /// (like tentative definitions in c)
+    Common,
+    /// Is placed into its own COMDAT group, so the linker only keeps one of the definitions
+    /// (like inline functions or templates)
+    LinkOnce,
+}
+
+impl Linkage {
+    /// Returns if the symbol is defined in another object file
+    pub fn is_import(&self) -> bool {
+        matches!(self, Linkage::Extern | Linkage::ExternWeak)
+    }
+
+    /// Returns if the symbol can be removed when it isn't used in the object file
+    pub fn is_discardable(&self) -> bool {
+        matches!(self, Linkage::Internal | Linkage::LinkOnce)
+    }
 }
 
 impl Display for Linkage {
@@ -107,6 +159,11 @@ impl Display for Linkage {
             Linkage::External => "",
             Linkage::Extern => "extern",
             Linkage::Internal => "local",
+            Linkage::Weak => "weak",
+            Linkage::ExternWeak => "extern_weak",
+            Linkage::Hidden => "hidden",
+            Linkage::Common => "common",
+            Linkage::LinkOnce => "linkonce",
         })
     }
 }
@@ -116,7 +173,9 @@ impl Display for Linkage {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ObjectBuilder {
     pub(crate) defines: BTreeMap<String, Vec<u8>>,
+    pub(crate) attributes: BTreeMap<String, DataAttributes>,
     pub(crate) links: Vec<Link>,
+    pub(crate) data_links: Vec<Link>,
 
     pub(crate) decls: Vec<(String, Decl, Linkage)>,
 
@@ -135,8 +194,10 @@ impl ObjectBuilder {
     pub fn new(triple: Triple) -> Self {
         Self {
             defines: BTreeMap::new(),
+            attributes: BTreeMap::new(),
 
             links: vec![],
+            data_links: vec![],
             decls: vec![],
 
             flags: None,
@@ -165,11 +226,23 @@ impl ObjectBuilder {
         self.defines.insert(name.to_string(), data);
     }
 
+    /// Defines a data symbol which is placed like the attributes say
+    pub fn define_data(&mut self, name: &str, data: Vec<u8>, attributes: DataAttributes) {
+        self.defines.insert(name.to_string(), data);
+        self.attributes.insert(name.to_string(), attributes);
+    }
+
     /// Links from one symbol to another
     pub fn link(&mut self, link: Link) {
         self.links.push(link);
     }
 
+    /// Stores the absolute adress of `link.to` (plus the addend) as a 64 bit pointer
+    /// at the offset `link.at` of the data symbol `link.from`
+    pub fn link_data(&mut self, link: Link) {
+        self.data_links.push(link);
+    }
+
     /// Writes the object file into the the specified file
     pub fn emit(&self, mut file: File, debug: Option<DebugRegistry>) -> Result<(), Box<dyn Error>> {
         if let Some(bytes) = &self.just_write_bytes {
@@ -238,9 +311,11 @@ impl ObjectBuilder {
             obj.flags = flags;
         }
 
-        let secText = obj.add_section(vec![], ".text".as_bytes().to_vec(), SectionKind::Text);
-        let secData = obj.add_section(vec![], ".data".as_bytes().to_vec(), SectionKind::Data);
-        let secConsts = obj.add_section(vec![], ".rodata".as_bytes().to_vec(), SectionKind::ReadOnlyData);
+        let mut sections: BTreeMap<String, SectionId> = BTreeMap::new();
+
+        for (name, kind) in [(".text", SectionKind::Text), (".data", SectionKind::Data), (".rodata", SectionKind::ReadOnlyData), (".bss", SectionKind::UninitializedData)] {
+            sections.insert(name.to_owned(), obj.add_section(vec![], name.as_bytes().to_vec(), kind));
+        }
 
         let mut syms: BTreeMap<String, (Option<SectionId>, Option</*offsest*/u64>, SymbolId, Decl, /*size*/u64, /*link*/Linkage)> = BTreeMap::new();
 
@@ -264,7 +339,57 @@ impl ObjectBuilder {
             let decl = decl.unwrap();
             let link = link.unwrap();
 
-            let sym = obj.add_symbol(Symbol {
+            let attributes = self.attributes.get(&name).cloned().unwrap_or_default();
+
+            // constants with pointers need to be written by the dynamic loader before they get read only
+            let relocated = *decl == Decl::Constant && !attributes.zero_init && self.data_links.iter().any(|link| link.from == name);
+
+            if attributes.thread_local && obj.format() != BinaryFormat::Elf {
+                Err( ObjectError::UnsupportedThreadLocal(name.clone()) )?
+            }
+
+            let (section_name, kind) = if let Some(section) = &attributes.section {
+                let kind = match decl {
+                    Decl::Function => SectionKind::Text,
+                    _ if attributes.thread_local && attributes.zero_init => SectionKind::UninitializedTls,
+                    _ if attributes.thread_local => SectionKind::Tls,
+                    _ if attributes.zero_init => SectionKind::UninitializedData,
+                    Decl::Data => SectionKind::Data,
+                    Decl::Constant => SectionKind::ReadOnlyData,
+                };
+
+                (section.to_owned(), kind)
+            } else if attributes.thread_local {
+                if attributes.zero_init {
+                    (".tbss".to_owned(), SectionKind::UninitializedTls)
+                } else {
+                    (".tdata".to_owned(), SectionKind::Tls)
+                }
+            } else if relocated {
+                (".data.rel.ro".to_owned(), SectionKind::ReadOnlyDataWithRel)
+            } else {
+                match decl {
+                    Decl::Function => (".text".to_owned(), SectionKind::Text),
+                    _ if attributes.zero_init => (".bss".to_owned(), SectionKind::UninitializedData),
+                    Decl::Data => (".data".to_owned(), SectionKind::Data),
+                    Decl::Constant => (".rodata".to_owned(), SectionKind::ReadOnlyData),
+                }
+            };
+
+            // linkonce symbols get their own section, so the linker can drop the duplicated ones
+            let section_name = if *link == Linkage::LinkOnce {
+                format!("{}.{}", section_name, name)
+            } else {
+                section_name
+            };
+
+            let section = *sections.entry(section_name.to_owned()).or_insert_with(|| {
+                obj.add_section(vec![], section_name.as_bytes().to_vec(), kind)
+            });
+
+            let align = attributes.align.unwrap_or(align);
+
+            let symbol = Symbol {
                 name: name.clone().as_bytes().to_vec(),
                 value: 0,
                 size: {
@@ -277,31 +402,35 @@ impl ObjectBuilder {
                 kind: {
                     match decl {
                         Decl::Function => SymbolKind::Text,
+                        _ if attributes.thread_local => SymbolKind::Tls,
                         Decl::Data => SymbolKind::Data,
                         Decl::Constant => SymbolKind::Data,
                     }
                 },
                 scope: {
                     match link {
-                        Linkage::Extern => SymbolScope::Dynamic,
-                        Linkage::External => SymbolScope::Linkage,
                         Linkage::Internal => SymbolScope::Compilation,
+                        Linkage::Hidden => SymbolScope::Linkage,
+                        _ => SymbolScope::Dynamic,
                     }
                 },
-                weak: false,
+                weak: matches!(link, Linkage::Weak | Linkage::ExternWeak | Linkage::LinkOnce),
                 section: {
-                    if *link != Linkage::Extern {
-                        match decl {
-                            Decl::Function => SymbolSection::Section(secText),
-                            Decl::Data => SymbolSection::Section(secData),
-                            Decl::Constant => SymbolSection::Section(secConsts),
-                        }
+                    if !link.is_import() {
+                        SymbolSection::Section(section)
                     } else {
                         SymbolSection::Undefined
                     }
                 },
                 flags: SymbolFlags::None,
-            });
+            };
+
+            let sym = if *link == Linkage::Common {
+                // common symbols only have a size and an alignment (which elf stores as the value)
+                obj.add_common_symbol(Symbol { value: align, ..symbol }, data.len() as u64, align)
+            } else {
+                obj.add_symbol(symbol)
+            };
 
             /*let def_section = match decl {
                 Decl::Function => obj.add_subsection(StandardSection::Text, name.as_bytes()),
@@ -310,14 +439,27 @@ impl ObjectBuilder {
             };*/
 
 
-            if *link != Linkage::Extern {
-                let def_offset = match decl {
-                    Decl::Function => obj.add_symbol_data(sym, secText, &data, align),
-                    Decl::Data => obj.add_symbol_data(sym, secData, &data, align),
-                    Decl::Constant => obj.add_symbol_data(sym, secConsts, &data, align),
+            if !link.is_import() && *link != Linkage::Common {
+                let def_offset = if attributes.zero_init {
+                    obj.add_symbol_bss(sym, section, data.len() as u64, align)
+                } else {
+                    obj.add_symbol_data(sym, section, &data, align)
                 };
+
+                if *link == Linkage::LinkOnce {
+                    // the members of an elf group need to be marked
+                    if let SectionFlags::Elf { sh_flags } = obj.section_flags(obj.section(section)) {
+                        *obj.section_flags_mut(section) = SectionFlags::Elf { sh_flags: sh_flags | object::elf::SHF_GROUP as u64 };
+                    }
+
+                    obj.add_comdat(Comdat {
+                        kind: ComdatKind::Any,
+                        symbol: sym,
+                        sections: vec![section],
+                    });
+                }
     
-                syms.insert(name.clone(), (None, Some(def_offset), sym, *decl, data.len() as u64, *link));
+                syms.insert(name.clone(), (Some(section), Some(def_offset), sym, *decl, data.len() as u64, *link));
             } else {
                 syms.insert(name.clone(), (None, None, sym, *decl, 0, *link));
             }
@@ -328,7 +470,7 @@ impl ObjectBuilder {
                 continue;
             }
 
-            if *link != Linkage::Extern {
+            if !link.is_import() {
                 panic!("symbols which aren't imported need their own data. consider defining one using 'obj.define(\"symbol\", vec![])'");
             }
 
@@ -344,7 +486,7 @@ impl ObjectBuilder {
                     }
                 },
                 scope: SymbolScope::Dynamic,
-                weak: false,
+                weak: *link == Linkage::ExternWeak,
                 section: SymbolSection::Undefined,
                 flags: SymbolFlags::None,
             });
@@ -353,7 +495,7 @@ impl ObjectBuilder {
         }
 
         for link in &self.links {
-            let (_, off, _, _, _, _) = syms.get(&link.from).expect("expectd valid link source");
+            let (section, off, _, _, _, _) = syms.get(&link.from).expect("expectd valid link source");
             let (_, _, to_sym, _decl, _, _) = syms.get(&link.to).expect("expected valid link destination");
 
             let addend = 0;/*match decl {
@@ -362,14 +504,45 @@ impl ObjectBuilder {
             };*/
             let offset = -3;
 
-            obj.add_relocation(secText, Relocation {
+            // thread local relocations have no generic kind, so the elf ones are used
+            let flags = match link.tls {
+                Some(TlsModel::LocalExec) => RelocationFlags::Elf { r_type: object::elf::R_X86_64_TPOFF32 },
+                Some(TlsModel::InitialExec) => RelocationFlags::Elf { r_type: object::elf::R_X86_64_GOTTPOFF },
+                Some(TlsModel::GeneralDynamic) => RelocationFlags::Elf { r_type: object::elf::R_X86_64_TLSGD },
+                None => RelocationFlags::Generic { 
+                    kind: RelocationKind::PltRelative, 
+                    encoding: link.kind, 
+                    size: 32, 
+                },
+            };
+
+            // the code isn't in .text if it is linkonce or in a custom section
+            let section = section.unwrap_or(sections[".text"]);
+
+            obj.add_relocation(section, Relocation {
                 offset: (link.at as i64 + offset) as u64 + {if let Some(off) = off { *off } else { 0 }},
                 symbol: to_sym.to_owned(),
                 addend: link.addend + addend,
+                flags: flags,
+            })?;
+        }
+
+        for link in &self.data_links {
+            let (section, off, _, _, _, _) = syms.get(&link.from).expect("expectd valid link source");
+            let (_, _, to_sym, _, _, _) = syms.get(&link.to).expect("expected valid link destination");
+
+            let (Some(section), Some(off)) = (section, off) else {
+                panic!("data links need to be inside of defined data: {}", link.from)
+            };
+
+            obj.add_relocation(*section, Relocation {
+                offset: off + link.at as u64,
+                symbol: to_sym.to_owned(),
+                addend: link.addend,
                 flags: RelocationFlags::Generic { 
-                    kind: RelocationKind::PltRelative, 
-                    encoding: link.kind, 
-                    size: 32, 
+                    kind: RelocationKind::Absolute, 
+                    encoding: RelocationEncoding::Generic, 
+                    size: 64, 
                 },
             })?;
         }
@@ -0,0 +1,154 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use crate::IR::{ir::*, Function, Var};
+
+/// If two pointers can point to the same memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasResult {
+    /// The pointers never point to the same memory
+    NoAlias,
+    /// The pointers may point to the same memory
+    MayAlias,
+    /// The pointers always point to the same memory
+    MustAlias,
+}
+
+/// Where a pointer points into
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PointerInfo {
+    /// the alloca the pointer points into
+    base: String,
+    /// if the pointer points to the start of the alloca
+    exact: bool,
+}
+
+/// A simple alias analysis for pointers which are derived from allocas
+///
+/// Pointers which are the output of an alloca, a copy of them or a `getelemptr` on them
+/// point into the memory of the alloca. Pointers into different allocas never alias.
+///
+/// An alloca escapes if a pointer into it is used by anything else than a load, a store
+/// (as the pointer) or a `getelemptr`. Pointers of unknown origin (arguments, loads, ...)
+/// and calls can only access the memory of escaped allocas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasAnalysis {
+    pointers: HashMap<String, PointerInfo>,
+    escaped: HashSet<String>,
+}
+
+impl AliasAnalysis {
+    /// Analyzes the pointers of the function
+    pub fn analyze(func: &Function) -> Self {
+        let mut pointers = HashMap::new();
+
+        for block in &func.blocks {
+            for node in &block.nodes {
+                if let Some(alloca) = node.as_any().downcast_ref::<Alloca>() {
+                    pointers.insert(alloca.inner1.name.to_owned(), PointerInfo { base: alloca.inner1.name.to_owned(), exact: true });
+                }
+            }
+        }
+
+        // the derived pointers (the pointers they are derived from are defined before them)
+        let mut found = true;
+
+        while found {
+            found = false;
+
+            for block in &func.blocks {
+                for node in &block.nodes {
+                    let derived = if let Some(gep) = node.as_any().downcast_ref::<GetElemPtr>() {
+                        pointers.get(&gep.ptr.name).map(|info| (gep.out.to_owned(), PointerInfo { base: info.base.to_owned(), exact: false }))
+                    } else if let Some(copy) = node.as_any().downcast_ref::<Assign<Var, Var>>() {
+                        pointers.get(&copy.inner2.name).map(|info| (copy.inner1.to_owned(), info.to_owned()))
+                    } else {
+                        None
+                    };
+
+                    if let Some((out, info)) = derived {
+                        if let Entry::Vacant(entry) = pointers.entry(out.name) {
+                            entry.insert(info);
+                            found = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut escaped = HashSet::new();
+
+        for block in &func.blocks {
+            for node in &block.nodes {
+                let uses: Vec<Var> = if let Some(store) = node.as_any().downcast_ref::<Store>() {
+                    // storing the pointer itself lets it escape
+                    match &store.inner2 {
+                        IROperand::Var(value) => vec![value.to_owned()],
+                        IROperand::Type(_) => Vec::new(),
+                    }
+                } else if node.is_load() || node.as_any().downcast_ref::<GetElemPtr>().is_some() || node.as_any().downcast_ref::<Assign<Var, Var>>().is_some() {
+                    Vec::new()
+                } else {
+                    node.inputs()
+                };
+
+                for var in uses {
+                    if let Some(info) = pointers.get(&var.name) {
+                        escaped.insert(info.base.to_owned());
+                    }
+                }
+            }
+        }
+
+        Self {
+            pointers: pointers,
+            escaped: escaped,
+        }
+    }
+
+    /// Returns the alloca the pointer points into (if it is known)
+    pub fn base(&self, ptr: &str) -> Option<&String> {
+        self.pointers.get(ptr).map(|info| &info.base)
+    }
+
+    /// Returns if the pointer points to the start of an alloca
+    pub fn is_exact(&self, ptr: &str) -> bool {
+        self.pointers.get(ptr).is_some_and(|info| info.exact)
+    }
+
+    /// Returns the pointer which names the memory the pointer points to
+    /// (it is the same for all pointers to the start of an alloca)
+    pub fn location(&self, ptr: &str) -> String {
+        match self.pointers.get(ptr) {
+            Some(info) if info.exact => info.base.to_owned(),
+            _ => ptr.to_owned(),
+        }
+    }
+
+    /// Returns if the alloca escapes (so other functions or unknown pointers can access it)
+    pub fn escapes(&self, alloca: &str) -> bool {
+        self.escaped.contains(alloca)
+    }
+
+    /// Returns if the memory the pointer points to can be accessed by calls or unknown pointers
+    pub fn is_visible(&self, ptr: &str) -> bool {
+        match self.base(ptr) {
+            Some(base) => self.escapes(base),
+            None => true,
+        }
+    }
+
+    /// Returns if the pointers can point to the same memory
+    pub fn alias(&self, a: &str, b: &str) -> AliasResult {
+        if a == b {
+            return AliasResult::MustAlias;
+        }
+
+        match (self.pointers.get(a), self.pointers.get(b)) {
+            (Some(a), Some(b)) if a.base != b.base => AliasResult::NoAlias,
+            (Some(a), Some(b)) if a.exact && b.exact => AliasResult::MustAlias,
+            (Some(_), Some(_)) => AliasResult::MayAlias,
+            (Some(known), None) | (None, Some(known)) if !self.escapes(&known.base) => AliasResult::NoAlias,
+            _ => AliasResult::MayAlias,
+        }
+    }
+}
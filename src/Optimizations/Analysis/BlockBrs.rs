@@ -18,7 +18,7 @@ impl<'a> BlockBranchAnalysis<'a> {
     /// ```
     /// 
     /// Would still be identified as a branch to block `some_block`
-    pub fn analyze(blocks: &'a Vec<Block>) -> Self {
+    pub fn analyze(blocks: impl IntoIterator<Item = &'a Block>) -> Self {
         let mut branches = HashMap::new();
 
         for block in blocks {
@@ -0,0 +1,155 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::IR::Function;
+
+use super::{AliasAnalysis, ControlFlowGraph, DominanceFrontiers, DominatorTree, LoopAnalysis, PostDominatorTree};
+
+/// The trait all analyses which can be cached need to implement
+pub trait Analysis: Any {
+    /// Runs the analysis on the function
+    /// 
+    /// Other analyses which are needed can be requested from `analyses`
+    fn compute(func: &Function, analyses: &mut AnalysisCache) -> Self where Self: Sized;
+
+    /// Returns if the analysis only depends on the blocks and the branches between them
+    fn cfg_only() -> bool where Self: Sized {
+        false
+    }
+}
+
+/// Caches the analyses of one function
+/// 
+/// The pass manager invalidates the cache after a pass changed the function
+#[derive(Default)]
+pub struct AnalysisCache {
+    pub(crate) entries: HashMap<TypeId, (Rc<dyn Any>, bool)>,
+}
+
+impl AnalysisCache {
+    /// Creates a new empty analysis cache
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the analysis of the function (it gets computed if it isn't cached)
+    pub fn get<T: Analysis>(&mut self, func: &Function) -> Rc<T> {
+        if let Some(cached) = self.cached::<T>() {
+            return cached;
+        }
+
+        let analysis = Rc::new(T::compute(func, self));
+
+        self.entries.insert(TypeId::of::<T>(), (analysis.clone(), T::cfg_only()));
+
+        analysis
+    }
+
+    /// Returns the analysis if it is cached
+    pub fn cached<T: Analysis>(&self) -> Option<Rc<T>> {
+        let (analysis, _) = self.entries.get(&TypeId::of::<T>())?;
+
+        analysis.clone().downcast::<T>().ok()
+    }
+
+    /// Removes the analyses from the cache
+    /// 
+    /// If the control flow graph was preserved, the analyses which
+    /// only depend on it are kept
+    pub fn invalidate(&mut self, preserves_cfg: bool) {
+        self.entries.retain(|_, (_, cfg_only)| preserves_cfg && *cfg_only);
+    }
+}
+
+/// Caches the analyses of all functions of a module
+#[derive(Default)]
+pub struct ModuleAnalyses {
+    pub(crate) funcs: HashMap<String, AnalysisCache>,
+}
+
+impl ModuleAnalyses {
+    /// Creates a new empty cache
+    pub fn new() -> Self {
+        Self {
+            funcs: HashMap::new(),
+        }
+    }
+
+    /// Returns the analysis cache of the function
+    pub fn func(&mut self, name: &str) -> &mut AnalysisCache {
+        self.funcs.entry(name.to_owned()).or_default()
+    }
+
+    /// Invalidates the analyses of the function
+    pub fn invalidate(&mut self, name: &str) {
+        self.funcs.remove(name);
+    }
+
+    /// Invalidates the analyses of all functions
+    pub fn invalidate_all(&mut self) {
+        self.funcs.clear();
+    }
+}
+
+impl Analysis for ControlFlowGraph {
+    fn compute(func: &Function, _: &mut AnalysisCache) -> Self {
+        ControlFlowGraph::analyze(func)
+    }
+
+    fn cfg_only() -> bool {
+        true
+    }
+}
+
+impl Analysis for DominatorTree {
+    fn compute(func: &Function, analyses: &mut AnalysisCache) -> Self {
+        DominatorTree::analyze(&analyses.get::<ControlFlowGraph>(func))
+    }
+
+    fn cfg_only() -> bool {
+        true
+    }
+}
+
+impl Analysis for DominanceFrontiers {
+    fn compute(func: &Function, analyses: &mut AnalysisCache) -> Self {
+        let cfg = analyses.get::<ControlFlowGraph>(func);
+
+        analyses.get::<DominatorTree>(func).frontiers(&cfg)
+    }
+
+    fn cfg_only() -> bool {
+        true
+    }
+}
+
+impl Analysis for PostDominatorTree {
+    fn compute(func: &Function, analyses: &mut AnalysisCache) -> Self {
+        PostDominatorTree::analyze(&analyses.get::<ControlFlowGraph>(func))
+    }
+
+    fn cfg_only() -> bool {
+        true
+    }
+}
+
+impl Analysis for LoopAnalysis {
+    fn compute(func: &Function, analyses: &mut AnalysisCache) -> Self {
+        let cfg = analyses.get::<ControlFlowGraph>(func);
+
+        LoopAnalysis::analyze(&cfg, &analyses.get::<DominatorTree>(func))
+    }
+
+    fn cfg_only() -> bool {
+        true
+    }
+}
+
+impl Analysis for AliasAnalysis {
+    fn compute(func: &Function, _: &mut AnalysisCache) -> Self {
+        AliasAnalysis::analyze(func)
+    }
+}
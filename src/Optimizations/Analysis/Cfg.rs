@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use crate::IR::Function;
+
+use super::BlockBranchAnalysis;
+
+/// The control flow graph of a function
+/// 
+/// The blocks are identified by their index in `Function::blocks`.
+/// The first block is the entry block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlFlowGraph {
+    pub(crate) names: Vec<String>,
+    pub(crate) indexes: HashMap<String, usize>,
+    pub(crate) succs: Vec<Vec<usize>>,
+    pub(crate) preds: Vec<Vec<usize>>,
+}
+
+impl ControlFlowGraph {
+    /// Builds the control flow graph of the function
+    pub fn analyze(func: &Function) -> Self {
+        let mut names = Vec::new();
+        let mut indexes = HashMap::new();
+
+        for (index, block) in func.blocks.iter().enumerate() {
+            names.push(block.name.to_owned());
+            indexes.insert(block.name.to_owned(), index);
+        }
+
+        let branches = BlockBranchAnalysis::analyze(&func.blocks);
+
+        let mut succs = vec![Vec::new(); names.len()];
+        let mut preds = vec![Vec::new(); names.len()];
+
+        for (index, name) in names.iter().enumerate() {
+            let Some(targets) = branches.branches.get(name) else { continue; };
+
+            for target in targets {
+                let Some(target) = indexes.get(&target.name) else { continue; };
+
+                if !succs[index].contains(target) {
+                    succs[index].push(*target);
+                    preds[*target].push(index);
+                }
+            }
+        }
+
+        Self {
+            names: names,
+            indexes: indexes,
+            succs: succs,
+            preds: preds,
+        }
+    }
+
+    /// Returns the amount of blocks
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns if the function has no blocks
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the index of the block with the given name
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indexes.get(name).copied()
+    }
+
+    /// Returns the name of the block
+    pub fn name(&self, block: usize) -> &String {
+        &self.names[block]
+    }
+
+    /// Returns the blocks the block can branch to
+    pub fn succs(&self, block: usize) -> &[usize] {
+        &self.succs[block]
+    }
+
+    /// Returns the blocks which can branch to the block
+    pub fn preds(&self, block: usize) -> &[usize] {
+        &self.preds[block]
+    }
+
+    /// Returns all blocks which can be reached from the entry block in reverse postorder
+    pub fn reverse_postorder(&self) -> Vec<usize> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        postorder(0, &self.succs).into_iter().rev().collect()
+    }
+
+    /// Returns if the block can be reached from the entry block
+    pub fn reachable(&self, block: usize) -> bool {
+        self.reverse_postorder().contains(&block)
+    }
+}
+
+/// Returns the postorder of all nodes reachable from `start`
+pub(crate) fn postorder(start: usize, succs: &[Vec<usize>]) -> Vec<usize> {
+    let mut order = Vec::new();
+    let mut visited = vec![false; succs.len()];
+
+    // iterative dfs, so deep graphs don't overflow the stack
+    let mut stack = vec![(start, 0)];
+    visited[start] = true;
+
+    while let Some((node, child)) = stack.pop() {
+        if let Some(next) = succs[node].get(child) {
+            stack.push((node, child + 1));
+
+            if !visited[*next] {
+                visited[*next] = true;
+                stack.push((*next, 0));
+            }
+        } else {
+            order.push(node);
+        }
+    }
+
+    order
+}
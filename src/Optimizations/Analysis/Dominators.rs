@@ -0,0 +1,300 @@
+use super::Cfg::postorder;
+use super::ControlFlowGraph;
+
+/// The immediate dominator tree of a function
+/// 
+/// A block `a` dominates the block `b` if every path from the entry to `b` goes through `a`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DominatorTree {
+    pub(crate) idoms: Vec<Option<usize>>,
+    pub(crate) children: Vec<Vec<usize>>,
+    pub(crate) root: Option<usize>,
+}
+
+impl DominatorTree {
+    /// Computes the dominator tree of the control flow graph
+    pub fn analyze(cfg: &ControlFlowGraph) -> Self {
+        if cfg.is_empty() {
+            return Self { idoms: Vec::new(), children: Vec::new(), root: None };
+        }
+
+        let idoms = compute_idoms(0, &cfg.succs, &cfg.preds);
+
+        Self::from_idoms(idoms, Some(0))
+    }
+
+    pub(crate) fn from_idoms(idoms: Vec<Option<usize>>, root: Option<usize>) -> Self {
+        let mut children = vec![Vec::new(); idoms.len()];
+
+        for (block, idom) in idoms.iter().enumerate() {
+            if let Some(idom) = idom {
+                children[*idom].push(block);
+            }
+        }
+
+        Self {
+            idoms: idoms,
+            children: children,
+            root: root,
+        }
+    }
+
+    /// Returns the immediate dominator of the block
+    /// (`None` for the entry block and unreachable blocks)
+    pub fn idom(&self, block: usize) -> Option<usize> {
+        self.idoms[block]
+    }
+
+    /// Returns the blocks which are immediately dominated by the block
+    pub fn children(&self, block: usize) -> &[usize] {
+        &self.children[block]
+    }
+
+    /// Returns if the block is in the tree (so if it is reachable)
+    pub fn contains(&self, block: usize) -> bool {
+        Some(block) == self.root || self.idoms[block].is_some()
+    }
+
+    /// Returns if the block `a` dominates the block `b`
+    /// (every block dominates itself)
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        if !self.contains(b) {
+            return false;
+        }
+
+        let mut current = Some(b);
+
+        while let Some(block) = current {
+            if block == a {
+                return true;
+            }
+
+            current = self.idoms[block];
+        }
+
+        false
+    }
+
+    /// Returns the blocks of the tree in preorder (starting with the root)
+    pub fn preorder(&self) -> Vec<usize> {
+        let mut order = Vec::new();
+
+        let Some(root) = self.root else { return order; };
+
+        let mut stack = vec![root];
+
+        while let Some(block) = stack.pop() {
+            order.push(block);
+
+            for child in self.children[block].iter().rev() {
+                stack.push(*child);
+            }
+        }
+
+        order
+    }
+
+    /// Computes the dominance frontiers
+    pub fn frontiers(&self, cfg: &ControlFlowGraph) -> DominanceFrontiers {
+        DominanceFrontiers::compute(self, &cfg.preds)
+    }
+}
+
+/// The post dominator tree of a function
+/// 
+/// A block `a` post dominates the block `b` if every path from `b` to an exit of the function goes through `a`.
+/// 
+/// All blocks without successors (e.g: which return) are connected to a virtual exit block which is the root
+/// of the tree. Blocks which can't reach an exit (like infinite loops) aren't in the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostDominatorTree {
+    pub(crate) tree: DominatorTree,
+    pub(crate) exit: usize,
+}
+
+impl PostDominatorTree {
+    /// Computes the post dominator tree of the control flow graph
+    pub fn analyze(cfg: &ControlFlowGraph) -> Self {
+        let exit = cfg.len();
+
+        // the reversed control flow graph
+        let mut succs = cfg.preds.clone();
+        let mut preds = cfg.succs.clone();
+
+        succs.push(Vec::new());
+        preds.push(Vec::new());
+
+        for (block, block_succs) in cfg.succs.iter().enumerate() {
+            if block_succs.is_empty() {
+                succs[exit].push(block);
+                preds[block].push(exit);
+            }
+        }
+
+        let idoms = compute_idoms(exit, &succs, &preds);
+
+        Self {
+            tree: DominatorTree::from_idoms(idoms, Some(exit)),
+            exit: exit,
+        }
+    }
+
+    /// Returns the immediate post dominator of the block
+    /// (`None` if the block is only post dominated by the virtual exit or isn't in the tree)
+    pub fn ipdom(&self, block: usize) -> Option<usize> {
+        self.tree.idom(block).filter(|ipdom| *ipdom != self.exit)
+    }
+
+    /// Returns the blocks which are immediately post dominated by the block
+    pub fn children(&self, block: usize) -> &[usize] {
+        self.tree.children(block)
+    }
+
+    /// Returns the blocks which are immediately post dominated by the virtual exit
+    pub fn exits(&self) -> &[usize] {
+        self.tree.children(self.exit)
+    }
+
+    /// Returns if the block can reach an exit
+    pub fn contains(&self, block: usize) -> bool {
+        block != self.exit && self.tree.contains(block)
+    }
+
+    /// Returns if the block `a` post dominates the block `b`
+    /// (every block post dominates itself)
+    pub fn post_dominates(&self, a: usize, b: usize) -> bool {
+        self.tree.dominates(a, b)
+    }
+}
+
+/// The dominance frontier of a block `a` are all blocks `b` where `a` dominates a predecessor of `b`
+/// but does not strictly dominate `b`
+/// 
+/// This is where phi nodes are needed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DominanceFrontiers {
+    pub(crate) frontiers: Vec<Vec<usize>>,
+}
+
+impl DominanceFrontiers {
+    pub(crate) fn compute(tree: &DominatorTree, preds: &[Vec<usize>]) -> Self {
+        let mut frontiers = vec![Vec::new(); preds.len()];
+
+        for (block, block_preds) in preds.iter().enumerate() {
+            if block_preds.len() < 2 || !tree.contains(block) {
+                continue;
+            }
+
+            for pred in block_preds {
+                if !tree.contains(*pred) {
+                    continue;
+                }
+
+                let mut runner = Some(*pred);
+
+                while let Some(current) = runner {
+                    if Some(current) == tree.idoms[block] {
+                        break;
+                    }
+
+                    if !frontiers[current].contains(&block) {
+                        frontiers[current].push(block);
+                    }
+
+                    runner = tree.idoms[current];
+                }
+            }
+        }
+
+        for frontier in &mut frontiers {
+            frontier.sort();
+        }
+
+        Self {
+            frontiers: frontiers,
+        }
+    }
+
+    /// Returns the dominance frontier of the block
+    pub fn frontier(&self, block: usize) -> &[usize] {
+        &self.frontiers[block]
+    }
+
+    /// Returns the iterated dominance frontier of the blocks
+    pub fn iterated(&self, blocks: &[usize]) -> Vec<usize> {
+        let mut out: Vec<usize> = Vec::new();
+        let mut worklist = blocks.to_vec();
+
+        while let Some(block) = worklist.pop() {
+            for frontier in &self.frontiers[block] {
+                if !out.contains(frontier) {
+                    out.push(*frontier);
+                    worklist.push(*frontier);
+                }
+            }
+        }
+
+        out.sort();
+        out
+    }
+}
+
+/// Computes the immediate dominators with the algorithm of Cooper, Harvey and Kennedy
+/// ("A Simple, Fast Dominance Algorithm")
+pub(crate) fn compute_idoms(root: usize, succs: &[Vec<usize>], preds: &[Vec<usize>]) -> Vec<Option<usize>> {
+    let order = postorder(root, succs);
+
+    let mut postorder_index = vec![usize::MAX; succs.len()];
+    for (index, block) in order.iter().enumerate() {
+        postorder_index[*block] = index;
+    }
+
+    let mut idoms: Vec<Option<usize>> = vec![None; succs.len()];
+    idoms[root] = Some(root);
+
+    let intersect = |idoms: &Vec<Option<usize>>, mut a: usize, mut b: usize| {
+        while a != b {
+            while postorder_index[a] < postorder_index[b] {
+                a = idoms[a].expect("expected processed block");
+            }
+            while postorder_index[b] < postorder_index[a] {
+                b = idoms[b].expect("expected processed block");
+            }
+        }
+        a
+    };
+
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        for block in order.iter().rev() {
+            if *block == root {
+                continue;
+            }
+
+            let mut new_idom = None;
+
+            for pred in &preds[*block] {
+                if idoms[*pred].is_none() {
+                    continue;
+                }
+
+                new_idom = Some(match new_idom {
+                    None => *pred,
+                    Some(current) => intersect(&idoms, *pred, current),
+                });
+            }
+
+            if new_idom.is_some() && idoms[*block] != new_idom {
+                idoms[*block] = new_idom;
+                changed = true;
+            }
+        }
+    }
+
+    idoms[root] = None;
+
+    idoms
+}
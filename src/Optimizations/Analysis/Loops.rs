@@ -0,0 +1,154 @@
+use super::{ControlFlowGraph, DominatorTree};
+
+/// A natural loop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    /// The block which dominates all blocks of the loop
+    pub header: usize,
+    /// All blocks of the loop (including the header and the blocks of nested loops)
+    pub blocks: Vec<usize>,
+    /// The blocks which branch back to the header
+    pub latches: Vec<usize>,
+    /// The blocks outside of the loop which are branched to from inside the loop
+    pub exits: Vec<usize>,
+    /// The block outside of the loop which is the only predecessor of the header
+    /// and only branches to the header
+    pub preheader: Option<usize>,
+    /// The index of the innermost loop which contains this loop
+    pub parent: Option<usize>,
+    /// The nesting depth (outermost loops have a depth of 1)
+    pub depth: usize,
+}
+
+impl Loop {
+    /// Returns if the block is part of the loop
+    pub fn contains(&self, block: usize) -> bool {
+        self.blocks.contains(&block)
+    }
+}
+
+/// Finds all natural loops of a function
+/// 
+/// A natural loop is found for every back edge (a branch to a block which dominates the branching block).
+/// Back edges to the same header are merged into one loop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopAnalysis {
+    pub(crate) loops: Vec<Loop>,
+    /// the innermost loop of each block
+    pub(crate) innermost: Vec<Option<usize>>,
+}
+
+impl LoopAnalysis {
+    /// Finds the loops of the control flow graph
+    pub fn analyze(cfg: &ControlFlowGraph, dom: &DominatorTree) -> Self {
+        let mut loops: Vec<Loop> = Vec::new();
+
+        for header in dom.preorder() {
+            let latches: Vec<usize> = cfg.preds(header).iter()
+                .copied()
+                .filter(|pred| dom.dominates(header, *pred))
+                .collect();
+
+            if latches.is_empty() {
+                continue;
+            }
+
+            // walk backwards from the latches till we reach the header
+            let mut blocks = vec![header];
+            let mut worklist = latches.clone();
+
+            while let Some(block) = worklist.pop() {
+                if blocks.contains(&block) || !dom.contains(block) {
+                    continue;
+                }
+
+                blocks.push(block);
+                worklist.extend_from_slice(cfg.preds(block));
+            }
+
+            blocks.sort();
+
+            let mut exits = Vec::new();
+
+            for block in &blocks {
+                for succ in cfg.succs(*block) {
+                    if !blocks.contains(succ) && !exits.contains(succ) {
+                        exits.push(*succ);
+                    }
+                }
+            }
+
+            let outside_preds: Vec<usize> = cfg.preds(header).iter()
+                .copied()
+                .filter(|pred| !blocks.contains(pred))
+                .collect();
+
+            let preheader = match outside_preds.as_slice() {
+                [pred] if cfg.succs(*pred) == [header] => Some(*pred),
+                _ => None,
+            };
+
+            loops.push(Loop {
+                header: header,
+                blocks: blocks,
+                latches: latches,
+                exits: exits,
+                preheader: preheader,
+                parent: None,
+                depth: 1,
+            });
+        }
+
+        // the headers are visited in dominator tree preorder, so outer loops come first
+        for index in 0..loops.len() {
+            let header = loops[index].header;
+
+            let parent = (0..index).rev()
+                .find(|outer| loops[*outer].contains(header));
+
+            if let Some(parent) = parent {
+                loops[index].parent = Some(parent);
+                loops[index].depth = loops[parent].depth + 1;
+            }
+        }
+
+        let mut innermost: Vec<Option<usize>> = vec![None; cfg.len()];
+
+        for (index, lp) in loops.iter().enumerate() {
+            for block in &lp.blocks {
+                // inner loops come later
+                innermost[*block] = Some(index);
+            }
+        }
+
+        Self {
+            loops: loops,
+            innermost: innermost,
+        }
+    }
+
+    /// Returns all loops (outer loops come before the loops they contain)
+    pub fn loops(&self) -> &[Loop] {
+        &self.loops
+    }
+
+    /// Returns the loop with the index
+    pub fn get(&self, index: usize) -> &Loop {
+        &self.loops[index]
+    }
+
+    /// Returns the index of the innermost loop which contains the block
+    pub fn loop_of(&self, block: usize) -> Option<usize> {
+        self.innermost[block]
+    }
+
+    /// Returns the loop nesting depth of the block (0 if it isn't in a loop)
+    pub fn depth(&self, block: usize) -> usize {
+        self.innermost[block].map(|lp| self.loops[lp].depth).unwrap_or(0)
+    }
+
+    /// Returns the index of the loop with the header
+    pub fn loop_with_header(&self, header: usize) -> Option<usize> {
+        self.loops.iter().position(|lp| lp.header == header)
+    }
+}
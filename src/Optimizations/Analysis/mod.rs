@@ -1,4 +1,19 @@
 #[allow(hidden_glob_reexports)]
+mod Alias;
+#[allow(hidden_glob_reexports)]
 mod BlockBrs;
+#[allow(hidden_glob_reexports)]
+mod Cache;
+#[allow(hidden_glob_reexports)]
+mod Cfg;
+#[allow(hidden_glob_reexports)]
+mod Dominators;
+#[allow(hidden_glob_reexports)]
+mod Loops;
 
-pub use BlockBrs::*;
\ No newline at end of file
+pub use Alias::*;
+pub use BlockBrs::*;
+pub use Cache::*;
+pub use Cfg::*;
+pub use Dominators::*;
+pub use Loops::*;
@@ -0,0 +1,203 @@
+use super::rules::{copy, int, same_var, wrap, Definitions};
+use crate::Optimizations::Passes::InstrCombinePass;
+use crate::IR::{ir::*, TypeMetadata, Var};
+
+/// Returns `log2(value)` if the operand is a constant power of two
+fn log2(operand: &IROperand) -> Option<u32> {
+    let value = int(operand)?;
+
+    if value > 0 && value & (value - 1) == 0 {
+        Some(value.trailing_zeros())
+    } else {
+        None
+    }
+}
+
+fn unsigned(ty: TypeMetadata) -> bool {
+    matches!(ty, TypeMetadata::u8 | TypeMetadata::u16 | TypeMetadata::u32 | TypeMetadata::u64 | TypeMetadata::u128)
+}
+
+/// Splits the operands into a variable and a constant (in any order)
+fn var_and_const(ls: &IROperand, rs: &IROperand) -> Option<(Var, i128)> {
+    match (ls, rs) {
+        (IROperand::Var(var), constant) | (constant, IROperand::Var(var)) => Some((var.to_owned(), int(constant)?)),
+        _ => None,
+    }
+}
+
+fn zero(out: &Var) -> Box<dyn Ir> {
+    Assign::new(out.to_owned(), wrap(out.ty, 0))
+}
+
+impl InstrCombinePass {
+    /// `x + 0`, `0 + x` -> `x`
+    pub(crate) fn identityZero(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        if int(rs) == Some(0) { return Some(copy(out, ls)); }
+        if int(ls) == Some(0) { return Some(copy(out, rs)); }
+
+        None
+    }
+
+    /// `x - 0` -> `x`
+    pub(crate) fn rightIdentityZero(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        (int(rs) == Some(0)).then(|| copy(out, ls))
+    }
+
+    /// `x * 1`, `1 * x` -> `x`
+    pub(crate) fn identityOne(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        if int(rs) == Some(1) { return Some(copy(out, ls)); }
+        if int(ls) == Some(1) { return Some(copy(out, rs)); }
+
+        None
+    }
+
+    /// `x / 1` -> `x`
+    pub(crate) fn rightIdentityOne(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        (int(rs) == Some(1)).then(|| copy(out, ls))
+    }
+
+    /// `x * 0`, `0 * x` -> `0`
+    pub(crate) fn absorbZero(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        (int(ls) == Some(0) || int(rs) == Some(0)).then(|| zero(out))
+    }
+
+    /// `x - x` -> `0`
+    pub(crate) fn selfIsZero(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        same_var(ls, rs).then(|| zero(out))
+    }
+
+    /// `x | x` -> `x`
+    pub(crate) fn idempotent(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        same_var(ls, rs).then(|| copy(out, ls))
+    }
+
+    /// `(x + c1) + c2` -> `x + (c1 + c2)`, `(x - c1) + c2` -> `x + (c2 - c1)`, `(c1 - x) + c2` -> `(c1 + c2) - x`
+    pub(crate) fn addReassociate(ls: &IROperand, rs: &IROperand, out: &Var, defs: &Definitions) -> Option<Box<dyn Ir>> {
+        let (inner, c2) = var_and_const(ls, rs)?;
+
+        if inner.ty != out.ty {
+            return None;
+        }
+
+        let inner = IROperand::Var(inner);
+
+        if let Some(add) = defs.get::<Add>(&inner) {
+            let (x, c1) = var_and_const(&add.inner1, &add.inner2)?;
+
+            return Some(Add::new(IROperand::Var(x), IROperand::Type(wrap(out.ty, c1 + c2)), out.to_owned()));
+        }
+
+        let sub = defs.get::<Sub>(&inner)?;
+
+        match (&sub.inner1, &sub.inner2) {
+            (IROperand::Var(x), c1) => Some(Add::new(IROperand::Var(x.to_owned()), IROperand::Type(wrap(out.ty, c2 - int(c1)?)), out.to_owned())),
+            (c1, IROperand::Var(x)) => Some(Sub::new(IROperand::Type(wrap(out.ty, int(c1)? + c2)), IROperand::Var(x.to_owned()), out.to_owned())),
+            _ => None,
+        }
+    }
+
+    /// `(x + c1) - c2` -> `x + (c1 - c2)`, `(x - c1) - c2` -> `x + -(c1 + c2)`
+    pub(crate) fn subReassociate(ls: &IROperand, rs: &IROperand, out: &Var, defs: &Definitions) -> Option<Box<dyn Ir>> {
+        let IROperand::Var(inner) = ls else { return None; };
+        let c2 = int(rs)?;
+
+        if inner.ty != out.ty {
+            return None;
+        }
+
+        let (x, c1) = if let Some(add) = defs.get::<Add>(ls) {
+            var_and_const(&add.inner1, &add.inner2)?
+        } else {
+            let sub = defs.get::<Sub>(ls)?;
+
+            let IROperand::Var(x) = &sub.inner1 else { return None; };
+
+            (x.to_owned(), -int(&sub.inner2)?)
+        };
+
+        Some(Add::new(IROperand::Var(x), IROperand::Type(wrap(out.ty, c1 - c2)), out.to_owned()))
+    }
+
+    /// `x * 2^n` -> `x << n`
+    pub(crate) fn mulPow2(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        let (x, shift) = match (log2(ls), log2(rs)) {
+            (_, Some(shift)) => (ls, shift),
+            (Some(shift), _) => (rs, shift),
+            _ => return None,
+        };
+
+        Some(Shl::new(x.to_owned(), IROperand::Type(wrap(out.ty, shift as i128)), out.to_owned()))
+    }
+
+    /// `x / 2^n` -> `x >> n` (only for unsigned types, signed divisions round towards zero)
+    pub(crate) fn udivPow2(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        if !unsigned(out.ty) {
+            return None;
+        }
+
+        let shift = log2(rs)?;
+
+        Some(Shr::new(ls.to_owned(), IROperand::Type(wrap(out.ty, shift as i128)), out.to_owned()))
+    }
+
+    /// `x % 2^n` -> `x & (2^n - 1)` (only for unsigned types)
+    pub(crate) fn uremPow2(ls: &IROperand, rs: &IROperand, out: &Var, _: &Definitions) -> Option<Box<dyn Ir>> {
+        if !unsigned(out.ty) || log2(rs).is_none() {
+            return None;
+        }
+
+        let mask = int(rs)? - 1;
+
+        Some(And::new(ls.to_owned(), IROperand::Type(wrap(out.ty, mask)), out.to_owned()))
+    }
+
+    /// `x == x` -> `1`, `x < x` -> `0`, ...
+    pub(crate) fn cmpSelf(cmp: &Cmp, _: &Definitions) -> Option<Box<dyn Ir>> {
+        if !same_var(&cmp.ls, &cmp.rs) {
+            return None;
+        }
+
+        let result = match cmp.mode {
+            CmpMode::Eqal | CmpMode::GreaterThanOrEqual | CmpMode::LessThanOrEqual => 1,
+            CmpMode::NotEqal | CmpMode::GreaterThan | CmpMode::LessThan => 0,
+        };
+
+        Some(Assign::new(cmp.out.to_owned(), wrap(cmp.out.ty, result)))
+    }
+
+    /// `-(-x)` -> `x`
+    pub(crate) fn negNeg(neg: &Neg, defs: &Definitions) -> Option<Box<dyn Ir>> {
+        let inner = defs.get::<Neg>(&neg.inner1)?;
+
+        if inner.inner1.get_ty() != neg.inner2.ty {
+            return None;
+        }
+
+        Some(copy(&neg.inner2, &inner.inner1))
+    }
+
+    /// Removes the inner cast if the outer cast gives the same result without it:
+    ///  - `cast (cast x to T1) to T2` -> `x` if `x` is a `T2` and `T1` isn't smaller than `T2`
+    ///  - `cast (cast x to T1) to T2` -> `cast x to T2` if both casts truncate
+    pub(crate) fn castCast(cast: &Cast, defs: &Definitions) -> Option<Box<dyn Ir>> {
+        let inner = defs.get::<Cast>(&cast.inner1)?;
+
+        let x = &inner.inner1;
+
+        let (source, middle, target) = (x.get_ty(), inner.inner2, cast.inner2);
+
+        if [source, middle, target].iter().any(|ty| ty.float() || *ty == TypeMetadata::Void) {
+            return None;
+        }
+
+        if source == target && middle.bitSize() >= source.bitSize() {
+            return Some(copy(&cast.inner3, x));
+        }
+
+        if target.bitSize() <= middle.bitSize() && middle.bitSize() <= source.bitSize() {
+            return Some(Cast::new(x.to_owned(), target, cast.inner3.to_owned()));
+        }
+
+        None
+    }
+}
@@ -1,3 +1,4 @@
+use super::rules::Definitions;
 use crate::Optimizations::Passes::InstrCombinePass;
 use crate::IR::ir::*;
 
@@ -18,10 +19,8 @@ fn optimizeSelectToCast(SI: &Select) -> Option<Box<dyn Ir>> {
 }
 
 impl InstrCombinePass {
-    /// Tries to optimize an select node
-    pub(crate) fn opt_select(SI: &Select) -> Option<Box<dyn Ir>> {
-        if let Some(opt) = optimizeSelectToCast(SI) { return Some(opt) };
-
-        None
+    /// `select %c, 1, 0` -> `cast %c`
+    pub(crate) fn selectToCast(SI: &Select, _: &Definitions) -> Option<Box<dyn Ir>> {
+        optimizeSelectToCast(SI)
     }
 }
\ No newline at end of file
@@ -1 +1,3 @@
-mod InstrCombineSelect;
\ No newline at end of file
+mod InstrCombineMath;
+mod InstrCombineSelect;
+pub(crate) mod rules;
\ No newline at end of file
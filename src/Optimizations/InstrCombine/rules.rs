@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use crate::IR::{ir::*, Function, Type, TypeMetadata, Var};
+use crate::Optimizations::Passes::InstrCombinePass;
+
+/// The nodes which define the variables of a function
+/// (so rules can look at the nodes which compute the inputs of a node)
+pub(crate) struct Definitions {
+    defs: HashMap<String, Box<dyn Ir>>,
+}
+
+impl Definitions {
+    /// Collects the definitions of all variables of the function
+    pub(crate) fn new(func: &Function) -> Self {
+        let mut defs = Definitions { defs: HashMap::new() };
+
+        for block in &func.blocks {
+            for node in &block.nodes {
+                defs.update(node.as_ref());
+            }
+        }
+
+        defs
+    }
+
+    /// Sets the node as the definition of its output
+    pub(crate) fn update(&mut self, node: &dyn Ir) {
+        if let Some(out) = node.output() {
+            self.defs.insert(out.name, node.clone_box());
+        }
+    }
+
+    /// Returns the node which defines the operand if it is a `T`
+    pub(crate) fn get<T: 'static>(&self, operand: &IROperand) -> Option<&T> {
+        let IROperand::Var(var) = operand else { return None; };
+
+        self.defs.get(&var.name)?.as_any().downcast_ref::<T>()
+    }
+}
+
+/// A peephole rule: returns the node which replaces the node (if the rule matches)
+pub(crate) struct Rule {
+    pub(crate) apply: fn(&dyn Ir, &Definitions) -> Option<Box<dyn Ir>>,
+}
+
+/// A rule for a node with two operands and an output (like `add`)
+macro_rules! BinaryRule {
+    ($node:ident, $func:path) => {
+        Rule {
+            apply: |node, defs| {
+                let node = node.as_any().downcast_ref::<$node>()?;
+                $func(&node.inner1, &node.inner2, &node.inner3, defs)
+            },
+        }
+    };
+}
+
+/// A rule which gets the node as its type
+macro_rules! NodeRule {
+    ($node:ident, $func:path) => {
+        Rule {
+            apply: |node, defs| $func(node.as_any().downcast_ref::<$node>()?, defs),
+        }
+    };
+}
+
+/// All rules of the instruction combine pass (the first rule which matches a node is applied)
+pub(crate) static RULES: &[Rule] = &[
+    // x + 0, x ^ 0, x | 0
+    BinaryRule!(Add, InstrCombinePass::identityZero),
+    BinaryRule!(Xor, InstrCombinePass::identityZero),
+    BinaryRule!(Or, InstrCombinePass::identityZero),
+    // x - 0, x << 0, x >> 0
+    BinaryRule!(Sub, InstrCombinePass::rightIdentityZero),
+    BinaryRule!(Shl, InstrCombinePass::rightIdentityZero),
+    BinaryRule!(Shr, InstrCombinePass::rightIdentityZero),
+    // x * 1, x / 1
+    BinaryRule!(Mul, InstrCombinePass::identityOne),
+    BinaryRule!(Div, InstrCombinePass::rightIdentityOne),
+    // x * 0, x & 0
+    BinaryRule!(Mul, InstrCombinePass::absorbZero),
+    BinaryRule!(And, InstrCombinePass::absorbZero),
+    // x - x, x ^ x
+    BinaryRule!(Sub, InstrCombinePass::selfIsZero),
+    BinaryRule!(Xor, InstrCombinePass::selfIsZero),
+    // x | x, x & x
+    BinaryRule!(Or, InstrCombinePass::idempotent),
+    BinaryRule!(And, InstrCombinePass::idempotent),
+    // (x + c1) + c2
+    BinaryRule!(Add, InstrCombinePass::addReassociate),
+    BinaryRule!(Sub, InstrCombinePass::subReassociate),
+    // strength reduction
+    BinaryRule!(Mul, InstrCombinePass::mulPow2),
+    BinaryRule!(Div, InstrCombinePass::udivPow2),
+    BinaryRule!(Rem, InstrCombinePass::uremPow2),
+    NodeRule!(Cmp, InstrCombinePass::cmpSelf),
+    NodeRule!(Neg, InstrCombinePass::negNeg),
+    NodeRule!(Cast, InstrCombinePass::castCast),
+    NodeRule!(Select, InstrCombinePass::selectToCast),
+];
+
+/// Returns the value of an integer constant
+pub(crate) fn int(operand: &IROperand) -> Option<i128> {
+    let IROperand::Type(value) = operand else { return None; };
+
+    Some(match *value {
+        Type::i1(value) => value as i128,
+        Type::u8(value) => value as i128,
+        Type::u16(value) => value as i128,
+        Type::u32(value) => value as i128,
+        Type::u64(value) => value as i128,
+        Type::i8(value) => value as i128,
+        Type::i16(value) => value as i128,
+        Type::i32(value) => value as i128,
+        Type::i64(value) => value as i128,
+        Type::u128(value) => value as i128,
+        Type::i128(value) => value,
+        Type::ptr(value) => value as i128,
+        _ => return None,
+    })
+}
+
+/// Puts the integer into a constant of the type (it wraps around like the machine would)
+pub(crate) fn wrap(ty: TypeMetadata, value: i128) -> Type {
+    match ty {
+        TypeMetadata::i1 => Type::i1(value & 1 == 1),
+        TypeMetadata::u8 => Type::u8(value as u8),
+        TypeMetadata::u16 => Type::u16(value as u16),
+        TypeMetadata::u32 => Type::u32(value as u32),
+        TypeMetadata::u64 => Type::u64(value as u64),
+        TypeMetadata::i8 => Type::i8(value as i8),
+        TypeMetadata::i16 => Type::i16(value as i16),
+        TypeMetadata::i32 => Type::i32(value as i32),
+        TypeMetadata::i64 => Type::i64(value as i64),
+        TypeMetadata::u128 => Type::u128(value as u128),
+        TypeMetadata::i128 => Type::i128(value),
+        TypeMetadata::ptr => Type::ptr(value as i64),
+        _ => Type::from_int(ty, value as f64),
+    }
+}
+
+/// Returns a node which copies the operand into the output
+pub(crate) fn copy(out: &Var, operand: &IROperand) -> Box<dyn Ir> {
+    match operand {
+        IROperand::Var(var) => Assign::new(out.to_owned(), var.to_owned()),
+        IROperand::Type(value) => Assign::new(out.to_owned(), *value),
+    }
+}
+
+/// Returns if both operands are the same variable
+pub(crate) fn same_var(ls: &IROperand, rs: &IROperand) -> bool {
+    matches!((ls, rs), (IROperand::Var(ls), IROperand::Var(rs)) if ls.name == rs.name)
+}
+
+/// Returns if the node works on floats (most rules don't hold for them)
+fn uses_floats(node: &dyn Ir) -> bool {
+    node.output().is_some_and(|out| out.ty.float()) || node.inputs().iter().any(|input| input.ty.float())
+}
+
+impl InstrCombinePass {
+    /// Applies the first rule which matches the node
+    pub(crate) fn opt1(node: &dyn Ir, defs: &Definitions) -> Option<Box<dyn Ir>> {
+        if uses_floats(node) {
+            return None;
+        }
+
+        RULES.iter().find_map(|rule| (rule.apply)(node, defs))
+    }
+}
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
-use crate::{prelude::*, Optimizations::Pass};
+use crate::{prelude::*, Optimizations::{Analysis::AnalysisCache, Pass}};
 
 /// ## Pass ConstantEvaluation <br>
 /// precomputes constant values
+///
+/// Conditional branches and switches on constants are folded into unconditional branches,
+/// so the control flow graph only stays valid when no terminator was folded
 pub(crate) struct ConstantEvaluation {}
 
 /// Creates a new ConstantEvaluation pass which is heap allocated
@@ -16,16 +19,24 @@ impl Pass for ConstantEvaluation {
         "ConstantEvaluation"
     }
 
-    fn run(&self, block: &mut crate::prelude::Block) {
+    fn run(&self, block: &mut crate::prelude::Block) -> bool {
         let mut const_values = HashMap::new();
 
+        let mut changed = false;
+
         for node in block.nodes.iter_mut() {
             if let Some(inlined) = node.maybe_inline(&const_values) {
-                node.replace( inlined )
+                if &inlined != node {
+                    node.replace( inlined );
+                    changed = true;
+                }
             }
 
             if let Some(eval) = node.eval() {
-                node.replace( eval );
+                if &eval != node {
+                    node.replace( eval );
+                    changed = true;
+                }
             }
 
             if let Some(node) = node.as_any().downcast_ref::<Assign<Var, Type>>() {
@@ -33,5 +44,27 @@ impl Pass for ConstantEvaluation {
 
             }    
         }
+
+        changed
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        let mut changed = false;
+
+        for block in &mut func.blocks {
+            let terminator = block.nodes.last().cloned();
+
+            changed |= self.run(block);
+
+            if block.nodes.last().cloned() != terminator {
+                analyses.invalidate(false);
+            }
+        }
+
+        changed
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        true
     }
 }
\ No newline at end of file
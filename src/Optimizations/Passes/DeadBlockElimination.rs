@@ -1,8 +1,16 @@
+use std::collections::HashSet;
+
+use crate::Optimizations::Analysis::{AnalysisCache, ControlFlowGraph, ModuleAnalyses};
+use crate::Optimizations::utils::{prune_phis, remove_blocks};
 use crate::Optimizations::Pass;
-use crate::IR::{ir::Br, ir::BrCond};
+use crate::IR::ir::{Call, IROperand, Return};
+use crate::IR::{Function, FunctionAttribute, Module, Type};
 
 /// ## Pass DeadBlockElimination <br>
-/// deletes unused blocks
+/// deletes blocks which can't be reached from the entry block
+///
+/// When the pass runs on the entire module, the nodes after calls to `NoReturn` functions
+/// get replaced with a `ret` (so the blocks which are only reached through them get deleted as well)
 pub(crate) struct DeadBlockElimination {
 }
 
@@ -15,35 +23,80 @@ impl Pass for DeadBlockElimination {
     fn name(&self) -> &'static str {
         "DeadBlockElimination"
     }
-    
-    fn run_func(&self, func: &mut crate::prelude::Function) {
-        let mut used_blocks = Vec::new();
-
-        // CHECK FOR ALL USED BLOCKS
-
-        for block in &func.blocks {
-            for node in &block.nodes {
-                if let Some(br) = node.as_any().downcast_ref::<Br>() {
-                    used_blocks.push(br.inner1.name.to_owned());
-                }
-
-                if let Some(br) = node.as_any().downcast_ref::<BrCond>() {
-                    used_blocks.push(br.inner2.name.to_owned());
-                    used_blocks.push(br.inner3.name.to_owned());
-                }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        eliminate(func, analyses, &HashSet::new())
+    }
+
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        let noreturn: HashSet<String> = module.funcs.values()
+            .filter(|func| func.has_attribute(FunctionAttribute::NoReturn))
+            .map(|func| func.name.to_owned())
+            .collect();
+
+        let mut changed = false;
+
+        for (name, func) in &mut module.funcs {
+            let cache = analyses.func(name);
+
+            if eliminate(func, cache, &noreturn) {
+                cache.invalidate(self.preserves_cfg());
+                changed = true;
             }
         }
 
-        // REMOVE UNUSED BLOCKS
-        
-        let mut index = 0;
+        changed
+    }
+}
 
-        for block in func.blocks.clone() {
-            if !used_blocks.contains(&block.name) && index != 0 { // do not remove first block
-                func.blocks.remove(index);
-            } else { // no removed block
-                index += 1;
-            }
+/// Deletes the nodes after calls to the `noreturn` functions and the blocks which can't be reached
+fn eliminate(func: &mut Function, analyses: &mut AnalysisCache, noreturn: &HashSet<String>) -> bool {
+    if func.blocks.is_empty() {
+        return false;
+    }
+
+    let mut cut = HashSet::new();
+
+    let ret = func.ty.ret;
+
+    for block in func.blocks.iter_mut() {
+        let Some(call) = block.nodes.iter().position(|node| {
+            node.as_any().downcast_ref::<Call>().is_some_and(|call| noreturn.contains(&call.func.name))
+        }) else { continue; };
+
+        // the cut block still needs a terminator (aggregates and vectors don't have a constant to return)
+        if call + 1 < block.nodes.len() && !ret.is_aggregate() && !ret.is_vector() {
+            block.nodes.truncate(call + 1);
+            block.push_ir(Return::new(IROperand::Type(Type::from_int(ret, 0.0))));
+
+            cut.insert(block.name.to_owned());
         }
     }
-}
\ No newline at end of file
+
+    if !cut.is_empty() {
+        // the cut blocks don't branch anywhere anymore
+        for block in func.blocks.iter_mut() {
+            prune_phis(block, |name| !cut.contains(name));
+        }
+
+        analyses.invalidate(false);
+    }
+
+    let cfg = analyses.get::<ControlFlowGraph>(func);
+
+    let reachable: HashSet<usize> = cfg.reverse_postorder().into_iter().collect();
+
+    if reachable.len() == func.blocks.len() {
+        return !cut.is_empty();
+    }
+
+    let removed: HashSet<String> = (0..func.blocks.len())
+        .filter(|block| !reachable.contains(block))
+        .map(|block| func.blocks[block].name.to_owned())
+        .collect();
+
+    // the entry block is always reachable
+    remove_blocks(func, &removed);
+
+    true
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use crate::Optimizations::Analysis::ModuleAnalyses;
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Const, Function, Module, Var};
+
+/// ## Pass DeadGlobalElimination <br>
+/// deletes internal (and linkonce) functions and constants which are never referenced
+///
+/// Starting from the externally visible functions and constants (and the symbols of the keep list)
+/// all functions which are called and all constants whose adress is loaded (or which are referenced
+/// by the initializers of used constants) are marked as used.
+/// The internal and linkonce functions and constants which aren't used get removed
+/// (another object file which uses a linkonce symbol has its own definition).
+pub(crate) struct DeadGlobalElimination_ {
+    keep: Vec<String>,
+}
+
+/// Creates a new DeadGlobalElimination pass which is heap allocated
+pub fn DeadGlobalElimination() -> Box<dyn Pass> {
+    Box::from( DeadGlobalElimination_ { keep: Vec::new() } )
+}
+
+/// Creates a new DeadGlobalElimination pass which also keeps the given symbols (e.g: the entry points of a jit)
+pub fn DeadGlobalEliminationKeeping(keep: Vec<String>) -> Box<dyn Pass> {
+    Box::from( DeadGlobalElimination_ { keep: keep } )
+}
+
+impl Pass for DeadGlobalElimination_ {
+    fn name(&self) -> &'static str {
+        "DeadGlobalElimination"
+    }
+
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        let mut worklist: Vec<String> = module.funcs.values()
+            .filter(|func| !func.linkage.is_discardable())
+            .map(|func| func.name.to_owned())
+            .chain(module.consts.values().filter(|constant| !constant.linkage.is_discardable()).map(|constant| constant.name.to_owned()))
+            .chain(self.keep.iter().cloned())
+            .collect();
+
+        let mut used = HashSet::new();
+
+        while let Some(name) = worklist.pop() {
+            if !used.insert(name.to_owned()) {
+                continue;
+            }
+
+            if let Some(func) = module.funcs.get(&name) {
+                worklist.extend(references(func));
+            }
+
+            if let Some(constant) = module.consts.get(&name) {
+                worklist.extend(constant.relocs().into_iter().map(|(_, symbol, _)| symbol));
+            }
+        }
+
+        let dead_funcs: Vec<String> = module.funcs.values()
+            .filter(|func| func.linkage.is_discardable() && !used.contains(&func.name))
+            .map(|func| func.name.to_owned())
+            .collect();
+
+        let consts = module.consts.len();
+
+        module.consts.retain(|name, constant| !constant.linkage.is_discardable() || used.contains(name));
+
+        for name in &dead_funcs {
+            module.funcs.remove(name);
+            analyses.invalidate(name);
+        }
+
+        !dead_funcs.is_empty() || consts != module.consts.len()
+    }
+}
+
+/// Returns the names of the functions and constants the function references
+fn references(func: &Function) -> Vec<String> {
+    let mut references = Vec::new();
+
+    for block in &func.blocks {
+        for node in &block.nodes {
+            if let Some(call) = node.as_any().downcast_ref::<Call>() {
+                references.push(call.func.name.to_owned());
+            } else if let Some(adr) = node.as_any().downcast_ref::<Assign<Var, Const>>() {
+                references.push(adr.inner2.name.to_owned());
+            }
+        }
+    }
+
+    references
+}
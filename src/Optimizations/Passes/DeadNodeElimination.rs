@@ -1,8 +1,15 @@
+use std::collections::HashSet;
+
+use crate::Optimizations::Analysis::{AnalysisCache, ModuleAnalyses};
 use crate::Optimizations::Pass;
-use crate::IR::ir::Phi;
+use crate::IR::ir::{Call, Phi};
+use crate::IR::{Function, FunctionAttribute, Module};
 
 /// ## Pass DeadNodeElimination <br>
 /// deletes unused nodes
+///
+/// Unused calls are only deleted if the callee is a `ReadNone` function of the module
+/// (which returns), so they only get deleted when the pass runs on the entire module
 pub(crate) struct DeadNodeElimination_;
 
 /// Creates a new DeadNodeElimination pass which is heap allocated
@@ -15,66 +22,100 @@ impl Pass for DeadNodeElimination_ {
         "DeadNodeElimination"
     }
     
-    fn run_func(&self, func: &mut crate::prelude::Function) {
-        for _ in 0..2 { // iterate two times, cuz then we can remove dependants with a dept of 1
-            let mut used: Vec<String> = Vec::new();
+    fn run_func(&self, func: &mut Function, _: &mut AnalysisCache) -> bool {
+        eliminate(func, &HashSet::new())
+    }
 
-            let mut to_remove = Vec::new();
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        let pure: HashSet<String> = module.funcs.values()
+            .filter(|func| func.has_attribute(FunctionAttribute::ReadNone) && !func.has_attribute(FunctionAttribute::NoReturn))
+            .map(|func| func.name.to_owned())
+            .collect();
 
-            // first iterate over all phis
+        let mut changed = false;
 
-            for block in func.blocks.iter() {
-                for node in &block.nodes {
-                    if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
-                        for (_, reciver) in &phi.recive_from_blocks {
-                            used.push(reciver.name.to_owned());
-                        }
+        for (name, func) in &mut module.funcs {
+            if eliminate(func, &pure) {
+                analyses.func(name).invalidate(self.preserves_cfg());
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        true
+    }
+}
+
+/// Deletes the unused nodes of the function (and the unused calls to the `pure` functions)
+fn eliminate(func: &mut Function, pure: &HashSet<String>) -> bool {
+    let mut changed = false;
+
+    for _ in 0..2 { // iterate two times, cuz then we can remove dependants with a dept of 1
+        let mut used: Vec<String> = Vec::new();
+
+        let mut to_remove = Vec::new();
+
+        // first iterate over all phis
+
+        for block in func.blocks.iter() {
+            for node in &block.nodes {
+                if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+                    for (_, reciver) in &phi.recive_from_blocks {
+                        used.push(reciver.name.to_owned());
                     }
                 }
             }
+        }
 
-            // now we can iterate over all normal nodes
+        // now we can iterate over all normal nodes
 
-            for block in func.blocks.iter().rev() {
-                let iter = block.nodes.iter();
-                let iter = iter.rev();
+        for block in func.blocks.iter().rev() {
+            let iter = block.nodes.iter();
+            let iter = iter.rev();
 
-                let mut index = iter.len();
+            let mut index = iter.len();
 
-                for node in iter {
-                    let inputs =  node.inputs();
-                    let out = node.output();
-        
-                    for input in inputs {
-                        if !used.contains(&input.name) {
-                            used.push(input.name);
-                        }
+            for node in iter {
+                let inputs =  node.inputs();
+                let out = node.output();
+    
+                for input in inputs {
+                    if !used.contains(&input.name) {
+                        used.push(input.name);
                     }
+                }
+
+                if let Some(out) = out {
+                    if !used.contains(&out.name) {
+                        let pure_call = node.as_any().downcast_ref::<Call>().is_some_and(|call| pure.contains(&call.func.name));
 
-                    if let Some(out) = out {
-                        if !used.contains(&out.name) {
-                            if !node.is_call() {
-                                // node isn't a call
-                                to_remove.push((block.name.clone(), index - 1));
-                            }
+                        if !node.is_call() || pure_call {
+                            // node isn't a call (or a call without side effects)
+                            to_remove.push((block.name.clone(), index - 1));
                         }
                     }
-
-                    index -= 1;
                 }
+
+                index -= 1;
             }
+        }
 
-            for block in &mut func.blocks {
-                let off = 0;
+        for block in &mut func.blocks {
+            let off = 0;
 
-                for (target_block, node) in &to_remove {
-                    if target_block == &block.name {
-                        block.nodes.remove((*node - off) as usize);
+            for (target_block, node) in &to_remove {
+                if target_block == &block.name {
+                    block.nodes.remove(*node - off);
+                    changed = true;
 
-                        //off += 1;
-                    }
+                    //off += 1;
                 }
             }
-        }  
+        }
     }
-}
\ No newline at end of file
+
+    changed
+}
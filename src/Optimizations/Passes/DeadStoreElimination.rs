@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Optimizations::Analysis::{AliasAnalysis, AliasResult, AnalysisCache};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Function, TypeMetadata};
+
+/// ## Pass DeadStoreElimination <br>
+/// deletes stores whose value is never read
+///
+/// A store is dead if:
+///  - the same memory is overwritten later in the block before anything could read it
+///  - it stores into an alloca which is never loaded from (and doesn't escape, see `AliasAnalysis`)
+pub(crate) struct DeadStoreElimination_;
+
+/// Creates a new DeadStoreElimination pass which is heap allocated
+pub fn DeadStoreElimination() -> Box<dyn Pass> {
+    Box::from( DeadStoreElimination_ {} )
+}
+
+impl Pass for DeadStoreElimination_ {
+    fn name(&self) -> &'static str {
+        "DeadStoreElimination"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        let alias = analyses.get::<AliasAnalysis>(func);
+
+        // the allocas which are read
+        let mut loaded = HashSet::new();
+
+        for block in &func.blocks {
+            for node in &block.nodes {
+                let Some(load) = node.as_any().downcast_ref::<Load>() else { continue; };
+                let IROperand::Var(ptr) = &load.inner3 else { continue; };
+
+                if let Some(base) = alias.base(&ptr.name) {
+                    loaded.insert(base.to_owned());
+                }
+            }
+        }
+
+        let mut changed = false;
+
+        for block in func.blocks.iter_mut() {
+            // the memory which gets overwritten later in the block: location -> stored type
+            let mut overwritten: HashMap<String, TypeMetadata> = HashMap::new();
+
+            let mut index = block.nodes.len();
+
+            while index > 0 {
+                index -= 1;
+
+                let node = &block.nodes[index];
+
+                if let Some(store) = node.as_any().downcast_ref::<Store>() {
+                    let ptr = &store.inner1.name;
+                    let location = alias.location(ptr);
+
+                    let never_read = alias.base(ptr).is_some_and(|base| !alias.escapes(base) && !loaded.contains(base));
+
+                    if never_read || overwritten.get(&location) == Some(&store.inner2.get_ty()) {
+                        block.nodes.remove(index);
+                        changed = true;
+                        continue;
+                    }
+
+                    overwritten.insert(location, store.inner2.get_ty());
+                    continue;
+                }
+
+                if let Some(load) = node.as_any().downcast_ref::<Load>() {
+                    match &load.inner3 {
+                        IROperand::Var(ptr) => overwritten.retain(|location, _| alias.alias(location, &ptr.name) == AliasResult::NoAlias),
+                        IROperand::Type(_) => overwritten.retain(|location, _| !alias.is_visible(location)),
+                    }
+
+                    continue;
+                }
+
+                // the called function could read the memory it can access
+                if node.is_call() {
+                    overwritten.retain(|location, _| !alias.is_visible(location));
+                }
+            }
+        }
+
+        changed
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::Optimizations::Analysis::{AnalysisCache, DominatorTree};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Function, TypeMetadata, Var};
+
+/// ## Pass GlobalValueNumbering <br>
+/// replaces computations which were already computed before with the earlier result
+///
+/// The computations are identified by their opcode and their operands. A computation
+/// is available in all blocks which are dominated by the block which computes it.
+///
+/// Loads are only reused if no store or call can write to the memory between them:
+/// in functions without any stores and calls across the dominator tree, else only inside of one block.
+pub(crate) struct GlobalValueNumbering_;
+
+/// Creates a new GlobalValueNumbering pass which is heap allocated
+pub fn GlobalValueNumbering() -> Box<dyn Pass> {
+    Box::from( GlobalValueNumbering_ {} )
+}
+
+impl Pass for GlobalValueNumbering_ {
+    fn name(&self) -> &'static str {
+        "GlobalValueNumbering"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        if func.blocks.is_empty() {
+            return false;
+        }
+
+        let dom = analyses.get::<DominatorTree>(func);
+
+        let writes_memory = func.blocks.iter().any(|block| {
+            block.nodes.iter().any(|node| node.is_store() || node.is_call())
+        });
+
+        let mut numbering = ValueNumbering {
+            replaced: HashMap::new(),
+            writes_memory: writes_memory,
+            dom: &dom,
+            changed: false,
+        };
+
+        numbering.number(func, 0, HashMap::new());
+
+        numbering.changed
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        true
+    }
+}
+
+/// The operation of an expression
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Opcode {
+    Add,
+    Sub,
+    Xor,
+    Or,
+    And,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    Neg,
+    Cast,
+    Cmp(CmpMode),
+    Select,
+    Load,
+    GetElemPtr(TypeMetadata),
+}
+
+impl Opcode {
+    fn commutative(&self) -> bool {
+        matches!(self, Opcode::Add | Opcode::Xor | Opcode::Or | Opcode::And | Opcode::Mul | Opcode::Cmp(CmpMode::Eqal) | Opcode::Cmp(CmpMode::NotEqal))
+    }
+}
+
+/// A computation (the type is the type of the result)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Expression {
+    op: Opcode,
+    ty: TypeMetadata,
+    operands: Vec<IROperand>,
+}
+
+impl Expression {
+    fn new(op: Opcode, ty: TypeMetadata, mut operands: Vec<IROperand>) -> Self {
+        // so `a + b` and `b + a` are the same expression
+        if op.commutative() {
+            operands.sort_by(|a, b| operand_order(a).partial_cmp(&operand_order(b)).unwrap_or(std::cmp::Ordering::Equal));
+        }
+
+        Self {
+            op: op,
+            ty: ty,
+            operands: operands,
+        }
+    }
+}
+
+/// Variables come first (ordered by name), then the constants (ordered by value)
+fn operand_order(operand: &IROperand) -> (bool, &str, f64) {
+    match operand {
+        IROperand::Var(var) => (false, var.name.as_str(), 0.0),
+        IROperand::Type(value) => (true, "", value.val()),
+    }
+}
+
+macro_rules! BinaryExpression {
+    ($node:expr, $($name:ident),*) => {
+        $(
+            if let Some(node) = $node.as_any().downcast_ref::<$name>() {
+                return Some(Expression::new(Opcode::$name, node.inner3.ty, vec![node.inner1.to_owned(), node.inner2.to_owned()]));
+            }
+        )*
+    };
+}
+
+/// Returns the expression which the node computes (`None` if the node isn't a pure computation)
+fn expression(node: &dyn Ir) -> Option<Expression> {
+    BinaryExpression!(node, Add, Sub, Xor, Or, And, Mul, Div, Rem, Shl, Shr);
+
+    if let Some(neg) = node.as_any().downcast_ref::<Neg>() {
+        return Some(Expression::new(Opcode::Neg, neg.inner2.ty, vec![neg.inner1.to_owned()]));
+    }
+
+    if let Some(cast) = node.as_any().downcast_ref::<Cast>() {
+        return Some(Expression::new(Opcode::Cast, cast.inner2, vec![cast.inner1.to_owned()]));
+    }
+
+    if let Some(cmp) = node.as_any().downcast_ref::<Cmp>() {
+        return Some(Expression::new(Opcode::Cmp(cmp.mode), cmp.out.ty, vec![cmp.ls.to_owned(), cmp.rs.to_owned()]));
+    }
+
+    if let Some(select) = node.as_any().downcast_ref::<Select>() {
+        let operands = vec![IROperand::Var(select.cond.to_owned()), select.yes.to_owned(), select.no.to_owned()];
+        return Some(Expression::new(Opcode::Select, select.out.ty, operands));
+    }
+
+    if let Some(load) = node.as_any().downcast_ref::<Load>() {
+        return Some(Expression::new(Opcode::Load, load.inner2, vec![load.inner3.to_owned()]));
+    }
+
+    if let Some(gep) = node.as_any().downcast_ref::<GetElemPtr>() {
+        let operands = vec![IROperand::Var(gep.ptr.to_owned()), gep.index.to_owned()];
+        return Some(Expression::new(Opcode::GetElemPtr(gep.ty), gep.out.ty, operands));
+    }
+
+    None
+}
+
+struct ValueNumbering<'a> {
+    /// the redundant variables and the variables which computed the value first
+    replaced: HashMap<String, Var>,
+    /// if the function contains stores or calls
+    writes_memory: bool,
+    dom: &'a DominatorTree,
+    changed: bool,
+}
+
+impl ValueNumbering<'_> {
+    /// Numbers the block and then all blocks it dominates.
+    ///
+    /// `available` are the expressions which were computed in the blocks which dominate the block
+    fn number(&mut self, func: &mut Function, block: usize, mut available: HashMap<Expression, Var>) {
+        // loads which are only valid till the next store or call
+        let mut loads: HashMap<Expression, Var> = HashMap::new();
+
+        for node in func.blocks[block].nodes.iter_mut() {
+            // the phis need to keep their own copies (the values are written at their definitions)
+            if !node.is_phi() {
+                for input in node.inputs_mut() {
+                    if let Some(replacement) = self.replaced.get(&input.name) {
+                        input.name = replacement.name.to_owned();
+                        self.changed = true;
+                    }
+                }
+            }
+
+            if node.is_store() || node.is_call() {
+                loads.clear();
+                continue;
+            }
+
+            let Some(expr) = expression(node.as_ref()) else { continue; };
+            let Some(out) = node.output() else { continue; };
+
+            let table = if expr.op == Opcode::Load && self.writes_memory {
+                &mut loads
+            } else {
+                &mut available
+            };
+
+            match table.get(&expr) {
+                Some(earlier) => {
+                    self.replaced.insert(out.name.to_owned(), earlier.to_owned());
+
+                    *node = Assign::new(out, earlier.to_owned());
+                    self.changed = true;
+                },
+                None => {
+                    table.insert(expr, out);
+                },
+            }
+        }
+
+        for child in self.dom.children(block).to_vec() {
+            self.number(func, child, available.clone());
+        }
+    }
+}
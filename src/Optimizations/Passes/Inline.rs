@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+
+use crate::Obj::Linkage;
+use crate::Optimizations::Analysis::ModuleAnalyses;
+use crate::Optimizations::utils::{is_terminator, phi_block, rename_blocks, rename_blocks_in, rename_vars, NameGenerator};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Block, BlockId, Function, FunctionAttribute, Module, Type, TypeMetadata, Var};
+
+/// The default maximal cost of a function which is inlined
+pub const DEFAULT_INLINE_THRESHOLD: isize = 40;
+
+/// ## Pass Inline <br>
+/// inlines calls to functions of the same module
+///
+/// The cost of inlining a call is the size of the callee (calls are more expensive),
+/// reduced by a bonus for every constant argument and a bonus if the callee is
+/// only called once and isn't visible outside of the module.
+/// Calls with a cost lower than the threshold get inlined.
+///
+/// Functions with the attribute `AlwaysInline` are always inlined,
+/// functions with the attribute `NoInline` or `Naked` never.
+/// `Cold` functions are only inlined if they are `AlwaysInline`.
+pub(crate) struct Inline_ {
+    pub(crate) threshold: isize,
+}
+
+/// Creates a new Inline pass which is heap allocated
+pub fn Inline() -> Box<dyn Pass> {
+    Box::from( Inline_ { threshold: DEFAULT_INLINE_THRESHOLD } )
+}
+
+/// Creates a new Inline pass with a custom threshold which is heap allocated
+pub fn InlineWithThreshold(threshold: isize) -> Box<dyn Pass> {
+    Box::from( Inline_ { threshold: threshold } )
+}
+
+/// The bonus for every constant argument
+const CONST_ARG_BONUS: isize = 3;
+/// The bonus for internal functions which are only called once (they can be removed after inlining)
+const SINGLE_CALL_BONUS: isize = 20;
+/// The cost of a call inside of the callee
+const CALL_COST: isize = 5;
+
+impl Pass for Inline_ {
+    fn name(&self) -> &'static str {
+        "Inline"
+    }
+
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        // the callees are inlined how they looked before the pass ran
+        // (so recursive functions don't get inlined into themselfs forever)
+        let callees = module.funcs.clone();
+
+        let mut call_counts: HashMap<String, usize> = HashMap::new();
+
+        for func in module.funcs.values() {
+            for block in &func.blocks {
+                for node in &block.nodes {
+                    if let Some(call) = node.as_any().downcast_ref::<Call>() {
+                        *call_counts.entry(call.func.name.to_owned()).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let mut changed = false;
+
+        for (name, func) in &mut module.funcs {
+            let mut inliner = Inliner {
+                names: NameGenerator::new(func.var_names()),
+                blocks: NameGenerator::new(func.blocks.iter().map(|block| block.name.to_owned()).collect()),
+                inlined: 0,
+            };
+
+            let mut block = 0;
+
+            while block < func.blocks.len() {
+                let mut next = block + 1;
+
+                for node in 0..func.blocks[block].nodes.len() {
+                    let Some(call) = func.blocks[block].nodes[node].as_any().downcast_ref::<Call>() else { continue; };
+                    let call = call.to_owned();
+
+                    let Some(callee) = callees.get(&call.func.name) else { continue; };
+
+                    if !self.should_inline(func, callee, &call, call_counts.get(&callee.name).copied().unwrap_or(0)) {
+                        continue;
+                    }
+
+                    // continue after the inlined blocks
+                    next = block + inliner.inline(func, block, node, &call, callee);
+                    changed = true;
+                    break;
+                }
+
+                block = next;
+            }
+
+            if inliner.inlined > 0 {
+                analyses.invalidate(name);
+            }
+        }
+
+        changed
+    }
+}
+
+impl Inline_ {
+    fn should_inline(&self, caller: &Function, callee: &Function, call: &Call, call_count: usize) -> bool {
+        if !can_inline(caller, callee, call) {
+            return false;
+        }
+
+        if callee.has_attribute(FunctionAttribute::NoInline) {
+            return false;
+        }
+
+        if callee.has_attribute(FunctionAttribute::AlwaysInline) {
+            return true;
+        }
+
+        if callee.has_attribute(FunctionAttribute::Cold) {
+            return false;
+        }
+
+        inline_cost(callee, call, call_count) <= self.threshold
+    }
+}
+
+/// Returns if the call can be inlined at all
+fn can_inline(caller: &Function, callee: &Function, call: &Call) -> bool {
+    // we need the body of the function (and a weak definition can be replaced when linking)
+    if callee.linkage.is_import() || callee.linkage == Linkage::Weak || callee.blocks.is_empty() {
+        return false;
+    }
+
+    // the body of a naked function expects to have its own stack frame
+    if callee.has_attribute(FunctionAttribute::Naked) {
+        return false;
+    }
+
+    if callee.name == caller.name || callee.ty.any_args || call.args.len() != callee.ty.args.len() || call.out.ty != callee.ty.ret {
+        return false;
+    }
+
+    if call.args.iter().zip(&callee.ty.args).any(|(arg, (_, ty))| arg.get_ty() != *ty) {
+        return false;
+    }
+
+    // aggregates are passed by value (a copy), but inlining would share them
+    if callee.ty.ret.is_aggregate() || callee.ty.args.iter().any(|(_, ty)| ty.is_aggregate()) {
+        return false;
+    }
+
+    // recursive functions
+    !callee.blocks.iter().any(|block| {
+        block.nodes.iter().any(|node| {
+            node.as_any().downcast_ref::<Call>().is_some_and(|call| call.func.name == callee.name)
+        })
+    })
+}
+
+/// Returns the cost of inlining the call
+pub(crate) fn inline_cost(callee: &Function, call: &Call, call_count: usize) -> isize {
+    let mut cost = 0;
+
+    for block in &callee.blocks {
+        for node in &block.nodes {
+            cost += if node.is_call() {
+                CALL_COST
+            } else if node.is_alloca() || node.is_phi() || node.is_debug() || node.is_br() {
+                0
+            } else {
+                1
+            };
+        }
+    }
+
+    cost -= CONST_ARG_BONUS * call.args.iter().filter(|arg| arg.is_type()).count() as isize;
+
+    if call_count == 1 && callee.linkage.is_discardable() {
+        cost -= SINGLE_CALL_BONUS;
+    }
+
+    cost
+}
+
+struct Inliner {
+    names: NameGenerator,
+    blocks: NameGenerator,
+    inlined: usize,
+}
+
+impl Inliner {
+    /// Inlines the call (the node in the block).
+    ///
+    /// ```text
+    /// block:                      block:
+    ///   ...                         ...
+    ///   %out = call f %x    =>      %arg = %x
+    ///   ...                         br f_entry
+    ///                             f_entry:
+    ///                               ...
+    ///                               br block_cont
+    ///                             block_cont:
+    ///                               %out = phi [...]
+    ///                               ...
+    /// ```
+    ///
+    /// Returns the amount of blocks which were added (+ 1)
+    fn inline(&mut self, func: &mut Function, block: usize, node: usize, call: &Call, callee: &Function) -> usize {
+        let prefix = format!("{}_inl{}", callee.name.replace(|chr: char| !chr.is_alphanumeric() && chr != '_', "_"), self.inlined);
+        self.inlined += 1;
+
+        // rename the variables
+        let mut vars = HashMap::new();
+
+        for name in callee.var_names() {
+            let new = self.names.fresh(&format!("%{}_{}", prefix, name.trim_start_matches('%')));
+            vars.insert(name, new);
+        }
+
+        // rename the blocks
+        let mut block_names = HashMap::new();
+
+        for callee_block in &callee.blocks {
+            let new = self.blocks.fresh(&format!("{}_{}", prefix, callee_block.name));
+            block_names.insert(callee_block.name.to_owned(), new);
+        }
+
+        let cont = self.blocks.fresh(&format!("{}_cont", func.blocks[block].name));
+
+        // split the block
+        let caller_block = &mut func.blocks[block];
+        let caller_name = caller_block.name.to_owned();
+
+        let tail = caller_block.nodes.split_off(node + 1);
+        caller_block.nodes.pop(); // the call
+
+        for (arg, (name, ty)) in call.args.iter().zip(&callee.ty.args) {
+            let var = Var { name: vars[name].to_owned(), ty: *ty };
+
+            caller_block.nodes.push(match arg {
+                IROperand::Var(value) => Assign::new(var, value.to_owned()),
+                IROperand::Type(value) => Assign::new(var, *value),
+            });
+        }
+
+        caller_block.nodes.push(Br::new(BlockId { name: block_names[&callee.blocks[0].name].to_owned() }));
+
+        // the blocks after the call now start in the continuation block
+        for other in func.blocks.iter_mut() {
+            for node in &mut other.nodes {
+                if !node.is_phi() {
+                    continue;
+                }
+
+                if let Some(renamed) = rename_blocks(node.as_ref(), |name| (name == caller_name).then(|| cont.to_owned())) {
+                    *node = renamed;
+                }
+            }
+        }
+
+        // clone the callee
+        let mut returns: Vec<(String, Var)> = Vec::new();
+        let mut inlined_blocks = Vec::new();
+
+        for callee_block in &callee.blocks {
+            let mut inlined = Block {
+                name: block_names[&callee_block.name].to_owned(),
+                nodes: Vec::new(),
+                varCount: func.ty.args.len(),
+            };
+
+            for node in &callee_block.nodes {
+                if let Some(ret) = node.as_any().downcast_ref::<Return>() {
+                    if call.out.ty != TypeMetadata::Void {
+                        let copy = Var { name: self.names.fresh(&format!("%{}_ret", prefix)), ty: call.out.ty };
+
+                        inlined.nodes.push(match &ret.inner1 {
+                            IROperand::Var(value) => Assign::new(copy.to_owned(), Var { name: vars[&value.name].to_owned(), ty: value.ty }),
+                            IROperand::Type(value) => Assign::new(copy.to_owned(), *value),
+                        });
+
+                        returns.push((inlined.name.to_owned(), copy));
+                    }
+
+                    inlined.nodes.push(Br::new(BlockId { name: cont.to_owned() }));
+                    break;
+                }
+
+                let mut node = node.clone_box();
+                rename_vars(&mut node, &vars);
+
+                let terminates = is_terminator(node.as_ref());
+
+                inlined.nodes.push(node);
+
+                if terminates {
+                    break;
+                }
+            }
+
+            rename_blocks_in(&mut inlined, |name| block_names.get(name).cloned());
+
+            inlined_blocks.push(inlined);
+        }
+
+        // the continuation block
+        let mut cont_block = Block {
+            name: cont.to_owned(),
+            nodes: Vec::new(),
+            varCount: func.ty.args.len(),
+        };
+
+        if call.out.ty != TypeMetadata::Void {
+            cont_block.nodes.push(match returns.as_slice() {
+                [] => Assign::new(call.out.to_owned(), Type::from_int(call.out.ty, 0.0)),
+                [(_, value)] => Assign::new(call.out.to_owned(), value.to_owned()),
+                _ => {
+                    let recives = returns.iter()
+                        .map(|(block, value)| (phi_block(block), value.to_owned()))
+                        .collect();
+
+                    Box::new(Phi::new(call.out.to_owned(), recives, call.out.ty))
+                },
+            });
+        }
+
+        cont_block.nodes.extend(tail);
+
+        let added = inlined_blocks.len() + 1;
+
+        for (index, inlined) in inlined_blocks.into_iter().chain(std::iter::once(cont_block)).enumerate() {
+            func.blocks.insert(block + 1 + index, inlined);
+        }
+
+        added
+    }
+}
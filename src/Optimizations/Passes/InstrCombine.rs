@@ -1,7 +1,11 @@
-use crate::prelude::{Ir, Replace, Block, Function};
+use crate::prelude::{Replace, Block, Function};
+use crate::Optimizations::InstrCombine::rules::Definitions;
+use crate::Optimizations::Analysis::AnalysisCache;
 use crate::Optimizations::Pass;
 
 /// The instruction combine pass is used to combine multiple instructions into one
+///
+/// It applies the peephole rules of `InstrCombine::rules::RULES` to every node
 pub(crate) struct InstrCombinePass;
 
 /// The instruction combine pass is used to combine multiple instructions into one
@@ -14,50 +18,42 @@ impl Pass for InstrCombinePass {
         "InstrCombine"
     }
 
-    fn run_func(&self, func: &mut crate::prelude::Function) {
-        InstrCombinePass::opt_func(func);
+    fn run_func(&self, func: &mut crate::prelude::Function, _: &mut AnalysisCache) -> bool {
+        let mut changed = InstrCombinePass::opt_func(func);
+
+        let mut defs = Definitions::new(func);
 
         for block in &mut func.blocks {
-            InstrCombinePass::opt_block(block);
+            changed |= InstrCombinePass::opt_block(block);
 
             for node in &mut block.nodes {
-                if let Some(opt) = InstrCombinePass::opt1(node) {
+                if let Some(opt) = InstrCombinePass::opt1(node.as_ref(), &defs) {
+                    defs.update(opt.as_ref());
                     node.replace(opt);
+                    changed = true;
                     continue;
                 }
             }
         }
 
+        changed
     }
-}
 
-macro_rules! opt1_impl {
-    ($node:expr, $check:tt, $func:expr, $($ty:tt)*) => {
-        if let Some(node) = $node.as_any().downcast_ref::<$($ty)*>() {
-            if let Some(optimized) = $func(node) {
-                return Some(optimized);
-            }
-        }
-    };
+    fn preserves_cfg(&self) -> bool {
+        true
+    }
 }
 
 impl InstrCombinePass {
-    /// Optimizes a single instruction into a more performant one
-    pub(crate) fn opt1(node: &Box<dyn Ir>) -> Option<Box<dyn Ir>> {
-        use crate::IR::ir::*;
-
-        opt1_impl!(node, is_select, InstrCombinePass::opt_select, Select);
-
-        None
-    }
-
     /// Optimizes the block by combining instructions
-    pub(crate) fn opt_block(_block: &mut Block) {
+    pub(crate) fn opt_block(_block: &mut Block) -> bool {
         // TODO
+        false
     }
 
     /// Optimizes the function by combining instructions
-    pub(crate) fn opt_func(_func: &mut Function) {
+    pub(crate) fn opt_func(_func: &mut Function) -> bool {
         // TODO
+        false
     }
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+
+use crate::Optimizations::Analysis::{AnalysisCache, ControlFlowGraph, Loop, LoopAnalysis};
+use crate::Optimizations::utils::{insert_before_terminator, is_speculatable, phi_block, rename_blocks, NameGenerator};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Block, BlockId, Function, Var};
+
+/// ## Pass LoopInvariantCodeMotion <br>
+/// moves computations which are the same in every iteration out of loops
+///
+/// The computations are moved into the preheader of the loop (a block which
+/// only branches to the header). If the loop doesn't have one, it is created.
+///
+/// Only nodes without side effects are moved (no calls, loads or stores)
+/// and divisions only if they can't divide by zero.
+pub(crate) struct LoopInvariantCodeMotion_;
+
+/// Creates a new LoopInvariantCodeMotion pass which is heap allocated
+pub fn LoopInvariantCodeMotion() -> Box<dyn Pass> {
+    Box::from( LoopInvariantCodeMotion_ {} )
+}
+
+impl Pass for LoopInvariantCodeMotion_ {
+    fn name(&self) -> &'static str {
+        "LoopInvariantCodeMotion"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        if func.blocks.is_empty() {
+            return false;
+        }
+
+        let mut changed = false;
+
+        // create the missing preheaders (one at a time, because it changes the control flow graph)
+        loop {
+            let cfg = analyses.get::<ControlFlowGraph>(func);
+            let loops = analyses.get::<LoopAnalysis>(func);
+
+            // the entry block has no preds we could redirect
+            let missing = loops.loops().iter().find(|lp| lp.preheader.is_none() && lp.header != 0);
+
+            let Some(lp) = missing else { break; };
+
+            if !insert_preheader(func, &cfg, lp) {
+                break;
+            }
+
+            analyses.invalidate(false);
+            changed = true;
+        }
+
+        let cfg = analyses.get::<ControlFlowGraph>(func);
+        let loops = analyses.get::<LoopAnalysis>(func);
+
+        // the copies for phis need to stay where they are
+        let phi_inputs: HashSet<String> = func.blocks.iter()
+            .flat_map(|block| block.nodes.iter())
+            .filter(|node| node.is_phi())
+            .flat_map(|node| node.inputs())
+            .map(|var| var.name)
+            .collect();
+
+        // inner loops first, so the nodes can move out of multiple loops
+        for lp in loops.loops().iter().rev() {
+            let Some(preheader) = lp.preheader else { continue; };
+
+            let order: Vec<usize> = cfg.reverse_postorder().into_iter()
+                .filter(|block| lp.contains(*block))
+                .collect();
+
+            let hoisted = hoist(func, lp, &order, &phi_inputs);
+
+            if !hoisted.is_empty() {
+                insert_before_terminator(&mut func.blocks[preheader], hoisted);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+/// Removes the invariant nodes out of the loop and returns them (in the order they need to be executed)
+fn hoist(func: &mut Function, lp: &Loop, order: &[usize], phi_inputs: &HashSet<String>) -> Vec<Box<dyn Ir>> {
+    let mut defined_in_loop: HashSet<String> = HashSet::new();
+
+    for block in &lp.blocks {
+        for node in &func.blocks[*block].nodes {
+            if let Some(out) = node.output() {
+                defined_in_loop.insert(out.name);
+            }
+        }
+    }
+
+    let mut hoisted = Vec::new();
+    let mut found = true;
+
+    while found {
+        found = false;
+
+        for block in order {
+            let nodes = &mut func.blocks[*block].nodes;
+            let mut index = 0;
+
+            while index < nodes.len() {
+                let node = &nodes[index];
+
+                let invariant = is_speculatable(node.as_ref())
+                    && node.output().is_some_and(|out| !phi_inputs.contains(&out.name))
+                    && node.inputs().iter().all(|input| !defined_in_loop.contains(&input.name));
+
+                if !invariant {
+                    index += 1;
+                    continue;
+                }
+
+                let node = nodes.remove(index);
+
+                if let Some(out) = node.output() {
+                    defined_in_loop.remove(&out.name);
+                }
+
+                hoisted.push(node);
+                found = true;
+            }
+        }
+    }
+
+    hoisted
+}
+
+/// Creates a block which only branches to the header of the loop and
+/// redirects all branches from outside of the loop to it
+///
+/// Returns false if the preheader can't be created
+fn insert_preheader(func: &mut Function, cfg: &ControlFlowGraph, lp: &Loop) -> bool {
+    let outside: Vec<usize> = cfg.preds(lp.header).iter()
+        .copied()
+        .filter(|pred| !lp.contains(*pred))
+        .collect();
+
+    if outside.is_empty() {
+        return false;
+    }
+
+    let header = func.blocks[lp.header].name.to_owned();
+    let outside_names: Vec<String> = outside.iter().map(|pred| func.blocks[*pred].name.to_owned()).collect();
+
+    let mut blocks = NameGenerator::new(func.blocks.iter().map(|block| block.name.to_owned()).collect());
+    let mut names = NameGenerator::new(func.var_names());
+
+    let preheader_name = blocks.fresh(&format!("{}_preheader", header));
+
+    let mut preheader = Block {
+        name: preheader_name.to_owned(),
+        nodes: Vec::new(),
+        varCount: func.ty.args.len(),
+    };
+
+    let mut copies = Vec::new();
+
+    // the values of the phis are now merged in the preheader
+    for node in func.blocks[lp.header].nodes.iter_mut() {
+        let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+
+        let (outer, inner): (Vec<_>, Vec<_>) = phi.recive_from_blocks.iter()
+            .map(|(block, var)| (phi_block(&block.name), var.to_owned()))
+            .partition(|(block, _)| outside_names.contains(&block.name));
+
+        let value = match outer.as_slice() {
+            [] => continue,
+            [(_, value)] => value.to_owned(),
+            _ => {
+                let merged = Var { name: names.fresh(&format!("{}_merge", phi.out.name)), ty: phi.typ };
+                preheader.nodes.push(Box::new(Phi::new(merged.to_owned(), outer, phi.typ)));
+                merged
+            },
+        };
+
+        // the phis need a copy at the end of the block they recive from
+        let copy = Var { name: names.fresh(&format!("{}_pre", phi.out.name)), ty: phi.typ };
+        copies.push(Assign::new(copy.to_owned(), value));
+
+        let mut recives = inner;
+        recives.push((phi_block(&preheader_name), copy));
+
+        *node = Box::new(Phi::new(phi.out.to_owned(), recives, phi.typ));
+    }
+
+    preheader.nodes.extend(copies);
+    preheader.nodes.push(Br::new(BlockId { name: header.to_owned() }));
+
+    for pred in &outside {
+        for node in func.blocks[*pred].nodes.iter_mut() {
+            if let Some(renamed) = rename_blocks(node.as_ref(), |name| (name == header).then(|| preheader_name.to_owned())) {
+                if !node.is_phi() {
+                    *node = renamed;
+                }
+            }
+        }
+    }
+
+    func.blocks.insert(lp.header, preheader);
+
+    true
+}
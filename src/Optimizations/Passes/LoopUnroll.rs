@@ -0,0 +1,651 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Optimizations::Analysis::{AnalysisCache, ControlFlowGraph, Loop, LoopAnalysis};
+use crate::Optimizations::utils::{insert_before_terminator, phi_block, rename_blocks, rename_blocks_in, rename_vars, NameGenerator};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Block, BlockId, Function, Type, Var};
+
+/// The default amount of copies of the loop body when unrolling partially
+pub const DEFAULT_UNROLL_FACTOR: usize = 2;
+/// The default maximal trip count of loops which get fully unrolled
+pub const DEFAULT_FULL_UNROLL_COUNT: usize = 8;
+
+/// The maximal amount of nodes an unrolled loop can have
+const UNROLL_SIZE_LIMIT: usize = 256;
+
+/// ## Pass LoopUnroll <br>
+/// copies the body of loops
+///
+/// Loops with a constant trip count (an induction variable which starts at a constant,
+/// is increased by a constant and compared against a constant) which isn't higher
+/// than `max_trip_count` get fully unrolled (the loop is replaced by the copies).
+///
+/// The body of other loops gets copied `factor` times (each copy still checks the condition).
+///
+/// Only innermost loops with one latch which only exit from the header are unrolled.
+pub(crate) struct LoopUnroll_ {
+    pub(crate) factor: usize,
+    pub(crate) max_trip_count: usize,
+}
+
+/// Creates a new LoopUnroll pass which is heap allocated
+pub fn LoopUnroll() -> Box<dyn Pass> {
+    Box::from( LoopUnroll_ { factor: DEFAULT_UNROLL_FACTOR, max_trip_count: DEFAULT_FULL_UNROLL_COUNT } )
+}
+
+/// Creates a new LoopUnroll pass with a custom unroll factor and
+/// maximal trip count for full unrolling which is heap allocated
+pub fn LoopUnrollWith(factor: usize, max_trip_count: usize) -> Box<dyn Pass> {
+    Box::from( LoopUnroll_ { factor: factor, max_trip_count: max_trip_count } )
+}
+
+impl Pass for LoopUnroll_ {
+    fn name(&self) -> &'static str {
+        "LoopUnroll"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        if func.blocks.is_empty() {
+            return false;
+        }
+
+        let mut changed = false;
+
+        // the headers of the loops which were already looked at
+        let mut visited: HashSet<String> = HashSet::new();
+
+        loop {
+            let cfg = analyses.get::<ControlFlowGraph>(func);
+            let loops = analyses.get::<LoopAnalysis>(func);
+
+            let next = loops.loops().iter()
+                .find(|lp| !visited.contains(&func.blocks[lp.header].name));
+
+            let Some(lp) = next else { break; };
+
+            visited.insert(func.blocks[lp.header].name.to_owned());
+
+            let Some(mut shape) = LoopShape::analyze(func, &cfg, &loops, lp) else { continue; };
+
+            let size: usize = lp.blocks.iter().map(|block| func.blocks[*block].nodes.len()).sum();
+
+            let unrolled = match shape.trip_count(func, self.max_trip_count) {
+                Some(trip_count) if trip_count * size <= UNROLL_SIZE_LIMIT => {
+                    shape.unroll_fully(func, trip_count);
+                    true
+                },
+                _ if self.factor > 1 && self.factor * size <= UNROLL_SIZE_LIMIT => shape.unroll_partially(func, self.factor),
+                _ => false,
+            };
+
+            if unrolled {
+                analyses.invalidate(false);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+/// A loop which can be unrolled
+struct LoopShape {
+    header: String,
+    latch: String,
+    preheader: String,
+    exit: String,
+    /// the blocks of the loop in the order they appear in the function
+    blocks: Vec<String>,
+    /// the variables which are defined in the loop
+    defs: HashSet<String>,
+}
+
+impl LoopShape {
+    fn analyze(func: &Function, cfg: &ControlFlowGraph, loops: &LoopAnalysis, lp: &Loop) -> Option<Self> {
+        // only innermost loops
+        if loops.loops().iter().any(|other| other.header != lp.header && lp.contains(other.header)) {
+            return None;
+        }
+
+        let [latch] = lp.latches.as_slice() else { return None; };
+        let [exit] = lp.exits.as_slice() else { return None; };
+
+        // only the header can leave the loop
+        for block in &lp.blocks {
+            if *block != lp.header && cfg.succs(*block).iter().any(|succ| !lp.contains(*succ)) {
+                return None;
+            }
+        }
+
+        let outside: Vec<usize> = cfg.preds(lp.header).iter().copied().filter(|pred| !lp.contains(*pred)).collect();
+        let [preheader] = outside.as_slice() else { return None; };
+
+        if !func.blocks[lp.header].nodes.last().is_some_and(|node| node.is_brcond()) {
+            return None;
+        }
+
+        let (latch_name, preheader_name) = (&func.blocks[*latch].name, &func.blocks[*preheader].name);
+
+        // the phis of the header are replaced by the values of the previous copy
+        for node in &func.blocks[lp.header].nodes {
+            let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+
+            if Self::incoming(phi, latch_name).is_none() || Self::incoming(phi, preheader_name).is_none() {
+                return None;
+            }
+        }
+
+        let mut defs = HashSet::new();
+
+        for block in &lp.blocks {
+            for node in &func.blocks[*block].nodes {
+                // the copies would allocate new memory
+                if node.is_alloca() {
+                    return None;
+                }
+
+                if let Some(out) = node.output() {
+                    defs.insert(out.name);
+                }
+            }
+        }
+
+        let name = |block: &usize| func.blocks[*block].name.to_owned();
+
+        Some(Self {
+            header: name(&lp.header),
+            latch: name(latch),
+            preheader: name(preheader),
+            exit: name(exit),
+            blocks: lp.blocks.iter().map(name).collect(),
+            defs: defs,
+        })
+    }
+
+    fn block<'a>(&self, func: &'a Function, name: &str) -> &'a Block {
+        func.blocks.iter().find(|block| block.name == name).expect("the blocks of the loop exist")
+    }
+
+    fn header_phis<'a>(&self, func: &'a Function) -> Vec<&'a Phi> {
+        self.block(func, &self.header).nodes.iter()
+            .filter_map(|node| node.as_any().downcast_ref::<Phi>())
+            .collect()
+    }
+
+    /// Returns the value the phi recives from the block
+    fn incoming(phi: &Phi, block: &str) -> Option<Var> {
+        phi.recive_from_blocks.iter()
+            .find(|(from, _)| from.name == block)
+            .map(|(_, var)| var.to_owned())
+    }
+
+    /// Returns how often the body of the loop is executed if it is a constant
+    /// which isn't higher than `max`
+    fn trip_count(&self, func: &Function, max: usize) -> Option<usize> {
+        let defs = Definitions::new(func);
+
+        let header = self.block(func, &self.header);
+        let br = header.nodes.last()?.as_any().downcast_ref::<BrCond>()?;
+
+        // does the loop continue if the condition is true
+        let continue_if = br.inner2.name != self.exit;
+
+        let cmp = defs.get(&br.inner1.name)?.as_any().downcast_ref::<Cmp>()?;
+
+        // the induction variable and the bound
+        let (phi, bound, swapped) = match (&cmp.ls, &cmp.rs) {
+            (IROperand::Var(ls), rs) if self.is_phi(func, &defs.root(&ls.name)) => (defs.root(&ls.name), defs.constant(rs)?, false),
+            (ls, IROperand::Var(rs)) if self.is_phi(func, &defs.root(&rs.name)) => (defs.root(&rs.name), defs.constant(ls)?, true),
+            _ => return None,
+        };
+
+        let phi = self.header_phis(func).into_iter().find(|node| node.out.name == phi)?;
+
+        let start = defs.constant(&IROperand::Var(Self::incoming(phi, &self.preheader)?))?;
+
+        // the increment: `%next = add %phi, step`
+        let next = defs.root(&Self::incoming(phi, &self.latch)?.name);
+        let next = defs.get(&next)?;
+
+        let step = if let Some(add) = next.as_any().downcast_ref::<Add>() {
+            match (&add.inner1, &add.inner2) {
+                (IROperand::Var(var), step) | (step, IROperand::Var(var)) if defs.root(&var.name) == phi.out.name => defs.constant(step)?.val(),
+                _ => return None,
+            }
+        } else if let Some(sub) = next.as_any().downcast_ref::<Sub>() {
+            match (&sub.inner1, &sub.inner2) {
+                (IROperand::Var(var), step) if defs.root(&var.name) == phi.out.name => -defs.constant(step)?.val(),
+                _ => return None,
+            }
+        } else {
+            return None;
+        };
+
+        let mut value = start;
+
+        for trip_count in 0..=max {
+            let (ls, rs) = if swapped { (bound, value) } else { (value, bound) };
+
+            let cond = match cmp.mode {
+                CmpMode::Eqal => ls.val() == rs.val(),
+                CmpMode::NotEqal => ls.val() != rs.val(),
+                CmpMode::GreaterThan => ls.val() > rs.val(),
+                CmpMode::LessThan => ls.val() < rs.val(),
+                CmpMode::GreaterThanOrEqual => ls.val() >= rs.val(),
+                CmpMode::LessThanOrEqual => ls.val() <= rs.val(),
+            };
+
+            if cond != continue_if {
+                return Some(trip_count);
+            }
+
+            value = Type::from_int(phi.typ, value.val() + step);
+        }
+
+        None
+    }
+
+    fn is_phi(&self, func: &Function, name: &str) -> bool {
+        self.header_phis(func).iter().any(|phi| phi.out.name == name)
+    }
+
+    /// Replaces the loop with `trip_count` copies of its body and
+    /// one copy of the header which branches to the exit
+    fn unroll_fully(&self, func: &mut Function, trip_count: usize) {
+        let mut copies = Copies::new(func, self, trip_count, false);
+
+        let mut blocks = Vec::new();
+
+        for copy in 0..trip_count {
+            // the back edge goes to the header of the next copy
+            let next_header = if copy + 1 < trip_count { copies.block(copy + 1, &self.header) } else { self.header.to_owned() };
+
+            for name in &self.blocks {
+                let mut block = copies.clone_block(func, self, copy, name, &next_header);
+
+                if *name == self.header {
+                    // the condition is always true
+                    let br = block.nodes.pop().expect("the header ends with a branch");
+                    let br = br.as_any().downcast_ref::<BrCond>().expect("the header ends with a branch");
+
+                    let target = if br.inner2.name == self.exit { &br.inner3 } else { &br.inner2 };
+                    block.nodes.push(Br::new(target.to_owned()));
+                }
+
+                blocks.push(block);
+            }
+        }
+
+        // the last check of the condition
+        let last = trip_count.checked_sub(1);
+
+        let header = func.blocks.iter_mut().find(|block| block.name == self.header).expect("the header exists");
+
+        for node in header.nodes.iter_mut() {
+            if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+                let value = copies.phi_value(phi, self, last);
+                *node = Assign::new(phi.out.to_owned(), value);
+            } else if node.is_brcond() {
+                *node = Br::new(BlockId { name: self.exit.to_owned() });
+            }
+        }
+
+        // remove the old loop
+        func.blocks.retain(|block| block.name == self.header || !self.blocks.contains(&block.name));
+
+        let first = if trip_count > 0 { copies.block(0, &self.header) } else { self.header.to_owned() };
+        self.redirect_preheader(func, &first);
+
+        let position = func.blocks.iter().position(|block| block.name == self.header).expect("the header exists");
+
+        for (index, block) in blocks.into_iter().enumerate() {
+            func.blocks.insert(position + index, block);
+        }
+    }
+
+    /// Copies the body of the loop `factor` times (the original loop is the first copy)
+    fn unroll_partially(&mut self, func: &mut Function, factor: usize) -> bool {
+        // every copy can exit the loop, so the values which are used after the loop need to be merged
+        let mut live_out: Vec<Var> = Vec::new();
+
+        for block in &func.blocks {
+            if self.blocks.contains(&block.name) {
+                continue;
+            }
+
+            for node in &block.nodes {
+                if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+                    let from_header = Self::incoming(phi, &self.header);
+
+                    // the value needs to be a copy in the header
+                    if block.name == self.exit && from_header.is_some_and(|var| !self.header_defines(func, &var.name)) {
+                        return false;
+                    }
+
+                    if block.name != self.exit && node.inputs().iter().any(|input| self.defs.contains(&input.name)) {
+                        return false;
+                    }
+
+                    continue;
+                }
+
+                for input in node.inputs() {
+                    if !self.defs.contains(&input.name) || live_out.contains(&input) {
+                        continue;
+                    }
+
+                    // only the values of the header are available after the loop
+                    if !self.header_defines(func, &input.name) {
+                        return false;
+                    }
+
+                    live_out.push(input);
+                }
+            }
+        }
+
+        if !live_out.is_empty() {
+            let cfg = ControlFlowGraph::analyze(func);
+
+            let exit = cfg.index(&self.exit).expect("the exit exists");
+            let header = cfg.index(&self.header).expect("the header exists");
+
+            // the merged values need to be available on all paths to the exit
+            if cfg.preds(exit) != [header] {
+                return false;
+            }
+
+            self.merge_live_out(func, &live_out);
+        }
+
+        let mut copies = Copies::new(func, self, factor, true);
+
+        let mut blocks = Vec::new();
+
+        for copy in 1..factor {
+            let next_header = if copy + 1 < factor { copies.block(copy + 1, &self.header) } else { self.header.to_owned() };
+
+            for name in &self.blocks {
+                blocks.push(copies.clone_block(func, self, copy, name, &next_header));
+            }
+        }
+
+        // the original loop is the first copy
+        let next_header = copies.block(1, &self.header);
+        let latch = copies.block(factor - 1, &self.latch);
+
+        for block in func.blocks.iter_mut() {
+            if block.name == self.latch {
+                for node in block.nodes.iter_mut() {
+                    if node.is_phi() {
+                        continue;
+                    }
+
+                    if let Some(renamed) = rename_blocks(node.as_ref(), |name| (name == self.header).then(|| next_header.to_owned())) {
+                        *node = renamed;
+                    }
+                }
+            }
+
+            if block.name == self.header {
+                for node in block.nodes.iter_mut() {
+                    let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+
+                    let recives = phi.recive_from_blocks.iter()
+                        .map(|(from, var)| if from.name == self.latch {
+                            (phi_block(&latch), copies.var(factor - 1, var))
+                        } else {
+                            (phi_block(&from.name), var.to_owned())
+                        })
+                        .collect();
+
+                    *node = Box::new(Phi::new(phi.out.to_owned(), recives, phi.typ));
+                }
+            }
+
+            // the exit can now be reached from every copy of the header
+            if block.name == self.exit {
+                for node in block.nodes.iter_mut() {
+                    let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+                    let Some(value) = Self::incoming(phi, &self.header) else { continue; };
+
+                    let mut recives: Vec<(Block, Var)> = phi.recive_from_blocks.iter()
+                        .map(|(from, var)| (phi_block(&from.name), var.to_owned()))
+                        .collect();
+
+                    for copy in 1..factor {
+                        recives.push((phi_block(&copies.block(copy, &self.header)), copies.var(copy, &value)));
+                    }
+
+                    *node = Box::new(Phi::new(phi.out.to_owned(), recives, phi.typ));
+                }
+            }
+        }
+
+        let position = func.blocks.iter()
+            .rposition(|block| self.blocks.contains(&block.name))
+            .expect("the loop has blocks");
+
+        for (index, block) in blocks.into_iter().enumerate() {
+            func.blocks.insert(position + 1 + index, block);
+        }
+
+        true
+    }
+
+    /// Merges the values which are used after the loop in a phi at the start of the exit
+    /// (which then also recives the values of the copies)
+    fn merge_live_out(&mut self, func: &mut Function, live_out: &[Var]) {
+        let mut names = NameGenerator::new(func.var_names());
+
+        let mut merged = HashMap::new();
+        let mut copies = Vec::new();
+        let mut phis = Vec::new();
+
+        for var in live_out {
+            // the phis need a copy at the end of the block they recive from
+            let copy = Var { name: names.fresh(&format!("{}_lcssa", var.name)), ty: var.ty };
+            let out = Var { name: names.fresh(&format!("{}_exit", var.name)), ty: var.ty };
+
+            copies.push(Assign::new(copy.to_owned(), var.to_owned()));
+            phis.push(Box::new(Phi::new(out.to_owned(), vec![(phi_block(&self.header), copy.to_owned())], var.ty)) as Box<dyn Ir>);
+
+            self.defs.insert(copy.name);
+            merged.insert(var.name.to_owned(), out.name);
+        }
+
+        for block in func.blocks.iter_mut() {
+            if block.name == self.header {
+                insert_before_terminator(block, std::mem::take(&mut copies));
+                continue;
+            }
+
+            if self.blocks.contains(&block.name) {
+                continue;
+            }
+
+            for node in block.nodes.iter_mut() {
+                if !node.is_phi() {
+                    for input in node.inputs_mut() {
+                        if let Some(name) = merged.get(&input.name) {
+                            input.name = name.to_owned();
+                        }
+                    }
+                }
+            }
+
+            if block.name == self.exit {
+                block.nodes.splice(0..0, std::mem::take(&mut phis));
+            }
+        }
+    }
+
+    fn header_defines(&self, func: &Function, var: &str) -> bool {
+        self.block(func, &self.header).nodes.iter().any(|node| node.output().is_some_and(|out| out.name == var))
+    }
+
+    fn redirect_preheader(&self, func: &mut Function, target: &str) {
+        let Some(preheader) = func.blocks.iter_mut().find(|block| block.name == self.preheader) else { return; };
+
+        for node in preheader.nodes.iter_mut() {
+            if node.is_phi() {
+                continue;
+            }
+
+            if let Some(renamed) = rename_blocks(node.as_ref(), |name| (name == self.header).then(|| target.to_owned())) {
+                *node = renamed;
+            }
+        }
+    }
+}
+
+/// The names of the variables and blocks of the copies of the loop
+struct Copies {
+    vars: Vec<HashMap<String, String>>,
+    blocks: Vec<HashMap<String, String>>,
+}
+
+impl Copies {
+    /// If `original_first` is set, the first copy is the original loop (with the original names)
+    fn new(func: &Function, shape: &LoopShape, count: usize, original_first: bool) -> Self {
+        let mut var_names = NameGenerator::new(func.var_names());
+        let mut block_names = NameGenerator::new(func.blocks.iter().map(|block| block.name.to_owned()).collect());
+
+        let mut vars = Vec::new();
+        let mut blocks = Vec::new();
+
+        for copy in 0..count {
+            if copy == 0 && original_first {
+                vars.push(shape.defs.iter().map(|var| (var.to_owned(), var.to_owned())).collect());
+                blocks.push(shape.blocks.iter().map(|block| (block.to_owned(), block.to_owned())).collect());
+                continue;
+            }
+
+            vars.push(shape.defs.iter()
+                .map(|var| (var.to_owned(), var_names.fresh(&format!("{}_u{}", var, copy))))
+                .collect());
+
+            blocks.push(shape.blocks.iter()
+                .map(|block| (block.to_owned(), block_names.fresh(&format!("{}_u{}", block, copy))))
+                .collect());
+        }
+
+        Self {
+            vars: vars,
+            blocks: blocks,
+        }
+    }
+
+    fn block(&self, copy: usize, name: &str) -> String {
+        self.blocks[copy][name].to_owned()
+    }
+
+    /// Returns the variable in the copy (variables from outside of the loop stay the same)
+    fn var(&self, copy: usize, var: &Var) -> Var {
+        Var {
+            name: self.vars[copy].get(&var.name).cloned().unwrap_or(var.name.to_owned()),
+            ty: var.ty,
+        }
+    }
+
+    /// Returns the value of the header phi in the copy after `previous`
+    /// (`None` if it is the first iteration)
+    fn phi_value(&self, phi: &Phi, shape: &LoopShape, previous: Option<usize>) -> Var {
+        match previous {
+            Some(previous) => self.var(previous, &LoopShape::incoming(phi, &shape.latch).expect("the header phis recive from the latch")),
+            None => LoopShape::incoming(phi, &shape.preheader).expect("the header phis recive from the preheader"),
+        }
+    }
+
+    /// Copies the block of the loop. The phis of the header are replaced by the values
+    /// of the previous copy and the back edge goes to `next_header`
+    fn clone_block(&mut self, func: &Function, shape: &LoopShape, copy: usize, name: &str, next_header: &str) -> Block {
+        let original = shape.block(func, name);
+
+        let mut block = Block {
+            name: self.block(copy, name),
+            nodes: Vec::new(),
+            varCount: func.ty.args.len(),
+        };
+
+        for node in &original.nodes {
+            if name == shape.header {
+                if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+                    let value = self.phi_value(phi, shape, copy.checked_sub(1));
+                    block.nodes.push(Assign::new(self.var(copy, &phi.out), value));
+                    continue;
+                }
+            }
+
+            let mut node = node.clone_box();
+            rename_vars(&mut node, &self.vars[copy]);
+            block.nodes.push(node);
+        }
+
+        let blocks = &self.blocks[copy];
+
+        // all branches to the header are back edges
+        rename_blocks_in(&mut block, |target| {
+            if target == shape.header {
+                Some(next_header.to_owned())
+            } else {
+                blocks.get(target).cloned()
+            }
+        });
+
+        block
+    }
+}
+
+/// The nodes which define the variables of a function
+struct Definitions<'a> {
+    defs: HashMap<String, &'a dyn Ir>,
+}
+
+impl<'a> Definitions<'a> {
+    fn new(func: &'a Function) -> Self {
+        let mut defs = HashMap::new();
+
+        for block in &func.blocks {
+            for node in &block.nodes {
+                if let Some(out) = node.output() {
+                    defs.insert(out.name, node.as_ref());
+                }
+            }
+        }
+
+        Self {
+            defs: defs,
+        }
+    }
+
+    fn get(&self, var: &str) -> Option<&'a dyn Ir> {
+        self.defs.get(var).copied()
+    }
+
+    /// Follows the copies (`%a = %b`) to the variable which computes the value
+    fn root(&self, var: &str) -> String {
+        let mut var = var.to_owned();
+
+        // the copies can't form a cycle (except through phis), but lets be safe
+        for _ in 0..self.defs.len() {
+            match self.get(&var).and_then(|node| node.as_any().downcast_ref::<Assign<Var, Var>>()) {
+                Some(copy) => var = copy.inner2.name.to_owned(),
+                None => break,
+            }
+        }
+
+        var
+    }
+
+    /// Returns the operand if it is a constant
+    fn constant(&self, operand: &IROperand) -> Option<Type> {
+        match operand {
+            IROperand::Type(value) => Some(*value),
+            IROperand::Var(var) => self.get(&self.root(&var.name))?
+                .as_any()
+                .downcast_ref::<Assign<Var, Type>>()
+                .map(|assign| assign.inner2),
+        }
+    }
+}
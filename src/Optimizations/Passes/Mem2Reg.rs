@@ -0,0 +1,272 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Optimizations::Analysis::{AnalysisCache, ControlFlowGraph, DominanceFrontiers, DominatorTree};
+use crate::Optimizations::utils::{insert_before_terminator, phi_block};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Block, Function, Type, TypeMetadata, Var};
+
+/// ## Pass Mem2Reg <br>
+/// promotes allocas which are only loaded and stored into variables
+///
+/// The phi nodes are inserted at the iterated dominance frontier of the stores.
+/// Then all loads are replaced with the value which was stored last
+/// (found by walking the dominator tree).
+///
+/// Allocas which escape (e.g: are passed to a call) aren't touched.
+pub(crate) struct Mem2Reg_;
+
+/// Creates a new Mem2Reg pass which is heap allocated
+pub fn Mem2Reg() -> Box<dyn Pass> {
+    Box::from( Mem2Reg_ {} )
+}
+
+impl Pass for Mem2Reg_ {
+    fn name(&self) -> &'static str {
+        "Mem2Reg"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        let allocas = promotable_allocas(func);
+
+        if allocas.is_empty() {
+            return false;
+        }
+
+        let cfg = analyses.get::<ControlFlowGraph>(func);
+        let dom = analyses.get::<DominatorTree>(func);
+        let frontiers = analyses.get::<DominanceFrontiers>(func);
+
+        let mut renamer = Renamer {
+            used_names: func.var_names(),
+            phis: HashMap::new(),
+            incoming: HashMap::new(),
+            allocas: allocas,
+            cfg: &cfg,
+            dom: &dom,
+        };
+
+        // place the phis
+        let mut alloca_names: Vec<String> = renamer.allocas.keys().cloned().collect();
+        alloca_names.sort();
+
+        for alloca in &alloca_names {
+            let ty = renamer.allocas[alloca];
+
+            let mut store_blocks = Vec::new();
+
+            for (index, block) in func.blocks.iter().enumerate() {
+                let stores = block.nodes.iter().any(|node| {
+                    node.as_any().downcast_ref::<Store>().is_some_and(|store| &store.inner1.name == alloca)
+                });
+
+                if stores && dom.contains(index) {
+                    store_blocks.push(index);
+                }
+            }
+
+            for block in frontiers.iterated(&store_blocks) {
+                let out = Var { name: renamer.fresh_name(), ty: ty };
+                renamer.phis.entry(block).or_default().push((alloca.to_owned(), out));
+            }
+        }
+
+        if !cfg.is_empty() {
+            renamer.rename(func, 0, HashMap::new());
+        }
+
+        // the allocas in unreachable blocks
+        for (index, block) in func.blocks.iter_mut().enumerate() {
+            if !dom.contains(index) {
+                renamer.rewrite(block, &mut HashMap::new());
+            }
+        }
+
+        // insert the phis
+        for (block, phis) in &renamer.phis {
+            for (alloca, out) in phis.iter().rev() {
+                let recives = renamer.incoming.remove(&(*block, alloca.to_owned())).unwrap_or_default();
+
+                func.blocks[*block].nodes.insert(0, Box::new( Phi::new(out.to_owned(), recives, out.ty) ));
+            }
+        }
+
+        true
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the allocas which are only used as the pointer of loads and stores
+fn promotable_allocas(func: &Function) -> HashMap<String, TypeMetadata> {
+    let mut allocas = HashMap::new();
+
+    for block in &func.blocks {
+        for node in &block.nodes {
+            if let Some(alloca) = node.as_any().downcast_ref::<Alloca>() {
+                allocas.insert(alloca.inner1.name.to_owned(), alloca.inner2);
+            }
+        }
+    }
+
+    let mut escaped = HashSet::new();
+
+    for block in &func.blocks {
+        for node in &block.nodes {
+            if let Some(load) = node.as_any().downcast_ref::<Load>() {
+                if let IROperand::Var(ptr) = &load.inner3 {
+                    if allocas.get(&ptr.name).is_some_and(|ty| *ty != load.inner2) {
+                        escaped.insert(ptr.name.to_owned());
+                    }
+                }
+                continue;
+            }
+
+            if let Some(store) = node.as_any().downcast_ref::<Store>() {
+                if allocas.get(&store.inner1.name).is_some_and(|ty| *ty != store.inner2.get_ty()) {
+                    escaped.insert(store.inner1.name.to_owned());
+                }
+
+                // storing the pointer itself lets it escape
+                if let IROperand::Var(value) = &store.inner2 {
+                    escaped.insert(value.name.to_owned());
+                }
+                continue;
+            }
+
+            if node.as_any().downcast_ref::<Alloca>().is_some() {
+                continue;
+            }
+
+            for input in node.inputs() {
+                escaped.insert(input.name);
+            }
+        }
+    }
+
+    allocas.retain(|name, _| !escaped.contains(name));
+    allocas
+}
+
+struct Renamer<'a> {
+    used_names: HashSet<String>,
+    /// the phis of each block: (alloca, phi output)
+    phis: HashMap<usize, Vec<(String, Var)>>,
+    /// the incoming values of each phi: (block, alloca) -> recives
+    incoming: HashMap<(usize, String), Vec<(Block, Var)>>,
+    allocas: HashMap<String, TypeMetadata>,
+    cfg: &'a ControlFlowGraph,
+    dom: &'a DominatorTree,
+}
+
+impl Renamer<'_> {
+    fn fresh_name(&mut self) -> String {
+        let mut index = self.used_names.len();
+
+        loop {
+            let name = format!("%mem2reg{}", index);
+
+            if self.used_names.insert(name.to_owned()) {
+                return name;
+            }
+
+            index += 1;
+        }
+    }
+
+    fn value(&self, values: &HashMap<String, IROperand>, alloca: &String) -> IROperand {
+        match values.get(alloca) {
+            Some(value) => value.to_owned(),
+            // reading before any store
+            None => IROperand::Type(Type::from_int(self.allocas[alloca], 0.0)),
+        }
+    }
+
+    /// Replaces the loads and removes the stores and allocas of the block
+    fn rewrite(&self, block: &mut Block, values: &mut HashMap<String, IROperand>) {
+        let mut nodes = Vec::new();
+
+        for node in block.nodes.drain(..) {
+            if let Some(alloca) = node.as_any().downcast_ref::<Alloca>() {
+                if self.allocas.contains_key(&alloca.inner1.name) {
+                    continue;
+                }
+            }
+
+            if let Some(store) = node.as_any().downcast_ref::<Store>() {
+                if self.allocas.contains_key(&store.inner1.name) {
+                    values.insert(store.inner1.name.to_owned(), store.inner2.to_owned());
+                    continue;
+                }
+            }
+
+            if let Some(load) = node.as_any().downcast_ref::<Load>() {
+                if let IROperand::Var(ptr) = &load.inner3 {
+                    if self.allocas.contains_key(&ptr.name) {
+                        let out = load.inner1.to_owned();
+
+                        nodes.push(match self.value(values, &ptr.name) {
+                            IROperand::Var(var) => Assign::new(out, var),
+                            IROperand::Type(constant) => Assign::new(out, constant),
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            nodes.push(node);
+        }
+
+        block.nodes = nodes;
+    }
+
+    fn rename(&mut self, func: &mut Function, block: usize, mut values: HashMap<String, IROperand>) {
+        if let Some(phis) = self.phis.get(&block) {
+            for (alloca, out) in phis {
+                values.insert(alloca.to_owned(), IROperand::Var(out.to_owned()));
+            }
+        }
+
+        self.rewrite(&mut func.blocks[block], &mut values);
+
+        // every phi gets its own copy of the incoming value, which is defined
+        // at the end of the predecessor (the code generator writes the phi output
+        // where the incoming variable is defined).
+        // The values are first copied into temporarys, so a phi output which is overwritten
+        // can't be read by another copy (e.g: when two variables are swapped in a loop)
+        let mut temps = Vec::new();
+        let mut copies = Vec::new();
+
+        for succ in self.cfg.succs(block).to_vec() {
+            let Some(phis) = self.phis.get(&succ).cloned() else { continue; };
+
+            for (alloca, out) in phis {
+                let ty = out.ty;
+
+                let copy = Var { name: self.fresh_name(), ty: ty };
+
+                match self.value(&values, &alloca) {
+                    IROperand::Var(var) => {
+                        let temp = Var { name: self.fresh_name(), ty: ty };
+                        temps.push(Assign::new(temp.to_owned(), var));
+                        copies.push(Assign::new(copy.to_owned(), temp));
+                    },
+                    IROperand::Type(constant) => copies.push(Assign::new(copy.to_owned(), constant)),
+                };
+
+                let pred = phi_block(&func.blocks[block].name);
+
+                self.incoming.entry((succ, alloca)).or_default().push((pred, copy));
+            }
+        }
+
+        if !copies.is_empty() {
+            insert_before_terminator(&mut func.blocks[block], temps.into_iter().chain(copies).collect());
+        }
+
+        for child in self.dom.children(block).to_vec() {
+            self.rename(func, child, values.clone());
+        }
+    }
+}
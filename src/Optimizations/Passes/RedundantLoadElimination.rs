@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::Optimizations::Analysis::{AliasAnalysis, AliasResult, AnalysisCache, ControlFlowGraph};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, Function, TypeMetadata};
+
+/// ## Pass RedundantLoadElimination <br>
+/// replaces loads with the value which is known to be in the memory
+///
+/// The value is known after a store (the stored value) or a load (the loaded value)
+/// from the same pointer. It is forgotten when the memory could be changed: by a store to a pointer
+/// which may alias or by a call (if the memory is visible to other functions, see `AliasAnalysis`).
+///
+/// The values are also known at the start of a block if all predecessors know the same value.
+/// Blocks which are reached over a back edge (loops) start without any known values.
+pub(crate) struct RedundantLoadElimination_;
+
+/// Creates a new RedundantLoadElimination pass which is heap allocated
+pub fn RedundantLoadElimination() -> Box<dyn Pass> {
+    Box::from( RedundantLoadElimination_ {} )
+}
+
+impl Pass for RedundantLoadElimination_ {
+    fn name(&self) -> &'static str {
+        "RedundantLoadElimination"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        if func.blocks.is_empty() {
+            return false;
+        }
+
+        let cfg = analyses.get::<ControlFlowGraph>(func);
+        let alias = analyses.get::<AliasAnalysis>(func);
+
+        let mut known_after: HashMap<usize, Memory> = HashMap::new();
+        let mut changed = false;
+
+        for block in cfg.reverse_postorder() {
+            let preds = cfg.preds(block);
+
+            let mut memory = if block == 0 || preds.iter().any(|pred| !known_after.contains_key(pred)) {
+                Memory::new()
+            } else {
+                let mut preds = preds.iter().map(|pred| &known_after[pred]);
+                let first = preds.next().cloned().unwrap_or_default();
+
+                preds.fold(first, |memory, other| memory.intersect(other))
+            };
+
+            for node in func.blocks[block].nodes.iter_mut() {
+                if let Some(load) = node.as_any().downcast_ref::<Load>() {
+                    let IROperand::Var(ptr) = &load.inner3 else { continue; };
+
+                    let location = alias.location(&ptr.name);
+
+                    match memory.values.get(&location) {
+                        Some((ty, value)) if *ty == load.inner2 => {
+                            *node = match value {
+                                IROperand::Var(var) => Assign::new(load.inner1.to_owned(), var.to_owned()),
+                                IROperand::Type(value) => Assign::new(load.inner1.to_owned(), *value),
+                            };
+                            changed = true;
+                        },
+                        _ => {
+                            memory.values.insert(location, (load.inner2, IROperand::Var(load.inner1.to_owned())));
+                        },
+                    }
+
+                    continue;
+                }
+
+                if let Some(store) = node.as_any().downcast_ref::<Store>() {
+                    let location = alias.location(&store.inner1.name);
+
+                    memory.values.retain(|other, _| alias.alias(other, &location) == AliasResult::NoAlias);
+                    memory.values.insert(location, (store.inner2.get_ty(), store.inner2.to_owned()));
+
+                    continue;
+                }
+
+                // the called function could change the memory it can access
+                if node.is_call() {
+                    memory.values.retain(|location, _| !alias.is_visible(location));
+                }
+            }
+
+            known_after.insert(block, memory);
+        }
+
+        changed
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        true
+    }
+}
+
+/// The values which are known to be in the memory
+#[derive(Debug, Clone, Default)]
+struct Memory {
+    /// location (see `AliasAnalysis::location`) -> (type, value)
+    values: HashMap<String, (TypeMetadata, IROperand)>,
+}
+
+impl Memory {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the values which are known in both
+    fn intersect(mut self, other: &Memory) -> Memory {
+        self.values.retain(|location, value| other.values.get(location) == Some(value));
+        self
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Optimizations::Analysis::{AnalysisCache, ControlFlowGraph, LoopAnalysis};
+use crate::Optimizations::utils::{insert_before_terminator, is_speculatable, prune_phis, remove_blocks, rename_blocks, rename_blocks_in};
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, BlockId, Function, Type, Var};
+
+/// How many nodes of one side of a branch are moved in front of the branch
+/// to turn it into a select
+const SPECULATION_LIMIT: usize = 4;
+
+/// ## Pass SimplifyCFG <br>
+/// simplifies the control flow graph
+///
+/// It does the following till nothing changes anymore:
+///  - removes blocks which can't be reached
+///  - replaces branches on constant conditions with `br`
+///  - merges blocks into their predecessor (if it only branches to them)
+///  - removes blocks which only branch to another block (except preheaders of loops)
+///  - replaces simple if-else constructs (diamonds) with `select`
+pub(crate) struct SimplifyCFG_;
+
+/// Creates a new SimplifyCFG pass which is heap allocated
+pub fn SimplifyCFG() -> Box<dyn Pass> {
+    Box::from( SimplifyCFG_ {} )
+}
+
+impl Pass for SimplifyCFG_ {
+    fn name(&self) -> &'static str {
+        "SimplifyCFG"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        if func.blocks.is_empty() {
+            return false;
+        }
+
+        let mut changed = false;
+
+        // every simplification changes the control flow graph, so it needs to be analyzed again
+        loop {
+            let cfg = analyses.get::<ControlFlowGraph>(func);
+
+            let simplified = remove_unreachable(func, &cfg)
+                || remove_stale_incomings(func, &cfg)
+                || fold_branches(func, &cfg)
+                || merge_block(func, &cfg)
+                || remove_forwarding_block(func, &cfg, &analyses.get::<LoopAnalysis>(func))
+                || form_select(func, &cfg);
+
+            if !simplified {
+                break;
+            }
+
+            analyses.invalidate(false);
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+/// Removes the blocks which can't be reached from the entry block
+fn remove_unreachable(func: &mut Function, cfg: &ControlFlowGraph) -> bool {
+    let reachable: HashSet<usize> = cfg.reverse_postorder().into_iter().collect();
+
+    if reachable.len() == func.blocks.len() {
+        return false;
+    }
+
+    let removed: HashSet<String> = (0..func.blocks.len())
+        .filter(|block| !reachable.contains(block))
+        .map(|block| func.blocks[block].name.to_owned())
+        .collect();
+
+    remove_blocks(func, &removed);
+
+    true
+}
+
+/// Removes the phi incomings from blocks which don't branch to the phi anymore
+/// (e.g: because a conditional branch was folded by another pass)
+fn remove_stale_incomings(func: &mut Function, cfg: &ControlFlowGraph) -> bool {
+    let mut changed = false;
+
+    for (index, block) in func.blocks.iter_mut().enumerate() {
+        let preds: HashSet<&String> = cfg.preds(index).iter()
+            .map(|pred| cfg.name(*pred))
+            .collect();
+
+        let stale = block.nodes.iter().any(|node| {
+            node.as_any().downcast_ref::<Phi>().is_some_and(|phi| {
+                phi.recive_from_blocks.iter().any(|(from, _)| !preds.contains(&from.name))
+            })
+        });
+
+        if stale {
+            prune_phis(block, |name| preds.contains(&name.to_owned()));
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Replaces the branches which can only go to one block with `br`
+fn fold_branches(func: &mut Function, cfg: &ControlFlowGraph) -> bool {
+    let consts: HashMap<String, Type> = func.blocks.iter()
+        .flat_map(|block| block.nodes.iter())
+        .filter_map(|node| node.as_any().downcast_ref::<Assign<Var, Type>>())
+        .map(|assign| (assign.inner1.name.to_owned(), assign.inner2))
+        .collect();
+
+    // (block, target which was removed)
+    let mut removed_edges = Vec::new();
+
+    for (index, block) in func.blocks.iter_mut().enumerate() {
+        let Some(node) = block.nodes.last_mut() else { continue; };
+
+        let target = if let Some(br) = node.as_any().downcast_ref::<BrCond>() {
+            if br.inner2 == br.inner3 {
+                br.inner2.to_owned()
+            } else if let Some(cond) = consts.get(&br.inner1.name) {
+                if cond.val() != 0.0 { br.inner2.to_owned() } else { br.inner3.to_owned() }
+            } else {
+                continue;
+            }
+        } else if let Some(switch) = node.as_any().downcast_ref::<Switch>() {
+            if let Some(value) = consts.get(&switch.to_switch.name) {
+                switch.cases.iter()
+                    .find(|(case, _)| case.val() == value.val())
+                    .map(|(_, target)| target.to_owned())
+                    .unwrap_or(switch.default.to_owned())
+            } else if switch.cases.values().all(|case| *case == switch.default) {
+                switch.default.to_owned()
+            } else {
+                continue;
+            }
+        } else {
+            continue;
+        };
+
+        for succ in cfg.succs(index) {
+            if *cfg.name(*succ) != target.name {
+                removed_edges.push((block.name.to_owned(), cfg.name(*succ).to_owned()));
+            }
+        }
+
+        *node = Br::new(target);
+    }
+
+    if removed_edges.is_empty() {
+        return false;
+    }
+
+    // the phis can't recive values from the blocks which don't branch to them anymore
+    for (from, to) in &removed_edges {
+        let Some(to) = cfg.index(to) else { continue; };
+
+        prune_phis(&mut func.blocks[to], |name| name != from);
+    }
+
+    true
+}
+
+/// Merges a block into its predecessor if it is the only successor of it
+fn merge_block(func: &mut Function, cfg: &ControlFlowGraph) -> bool {
+    let mergeable = (1..func.blocks.len()).find(|block| {
+        let [pred] = cfg.preds(*block) else { return false; };
+
+        let pred_name = &func.blocks[*pred].name;
+
+        // a phi would recive two values from the predecessor
+        let duplicate = cfg.succs(*block).iter().any(|succ| {
+            func.blocks[*succ].nodes.iter().any(|node| {
+                node.as_any().downcast_ref::<Phi>().is_some_and(|phi| {
+                    phi.recive_from_blocks.iter().any(|(from, _)| from.name == *pred_name)
+                })
+            })
+        });
+
+        *pred != *block
+            && !duplicate
+            && cfg.succs(*pred) == [*block]
+            && func.blocks[*pred].nodes.last().is_some_and(|node| node.is_br())
+    });
+
+    let Some(block) = mergeable else { return false; };
+
+    let pred = cfg.preds(block)[0];
+    let pred_name = func.blocks[pred].name.to_owned();
+
+    let removed = func.blocks.remove(block).expect("the block exists");
+
+    // the phis only have one value
+    let nodes = removed.nodes.into_iter().map(|node| {
+        let Some(phi) = node.as_any().downcast_ref::<Phi>() else { return node; };
+
+        match phi.recive_from_blocks.iter().find(|(from, _)| from.name == pred_name) {
+            Some((_, var)) => Assign::new(phi.out.to_owned(), var.to_owned()),
+            None => node,
+        }
+    });
+
+    let pred = if pred > block { pred - 1 } else { pred };
+    let pred = &mut func.blocks[pred];
+
+    pred.nodes.pop();
+    pred.nodes.extend(nodes);
+
+    // the successors now recive their values from the predecessor
+    for block in func.blocks.iter_mut() {
+        rename_blocks_in(block, |name| (name == removed.name).then(|| pred_name.to_owned()));
+    }
+
+    true
+}
+
+/// Removes a block which only branches to another block and lets
+/// its predecessors branch directly to the other block
+fn remove_forwarding_block(func: &mut Function, cfg: &ControlFlowGraph, loops: &LoopAnalysis) -> bool {
+    let forwarding = (1..func.blocks.len()).find_map(|block| {
+        let [node] = func.blocks[block].nodes.as_slice() else { return None; };
+        let br = node.as_any().downcast_ref::<Br>()?;
+
+        let target = cfg.index(&br.inner1.name)?;
+
+        // the loop passes need the preheaders
+        if target == block || loops.loops().iter().any(|lp| lp.preheader == Some(block)) {
+            return None;
+        }
+
+        // a phi can't tell the predecessors apart anymore if they now branch to it directly
+        if func.blocks[target].nodes.iter().any(|node| node.is_phi()) {
+            match cfg.preds(block) {
+                [pred] if !cfg.preds(target).contains(pred) => {},
+                _ => return None,
+            }
+        }
+
+        Some((block, target))
+    });
+
+    let Some((block, target)) = forwarding else { return false; };
+
+    let name = func.blocks[block].name.to_owned();
+    let target_name = func.blocks[target].name.to_owned();
+
+    for pred in cfg.preds(block) {
+        let Some(node) = func.blocks[*pred].nodes.last_mut() else { continue; };
+
+        if let Some(renamed) = rename_blocks(node.as_ref(), |block| (block == name).then(|| target_name.to_owned())) {
+            *node = renamed;
+        }
+    }
+
+    if let [pred] = cfg.preds(block) {
+        let pred_name = func.blocks[*pred].name.to_owned();
+
+        rename_blocks_in(&mut func.blocks[target], |block| (block == name).then(|| pred_name.to_owned()));
+    }
+
+    func.blocks.remove(block);
+
+    true
+}
+
+/// Replaces a branch whose sides only compute the values of phis with `select`
+///
+/// The sides (or one side if the other side branches directly to the join block)
+/// need to be small and can't have side effects, because they are now always executed
+fn form_select(func: &mut Function, cfg: &ControlFlowGraph) -> bool {
+    // a block which only has `head` as predecessor and branches to another block
+    let side = |head: usize, block: usize| -> Option<usize> {
+        let nodes = &func.blocks[block].nodes;
+
+        if cfg.preds(block) != [head] || !nodes.last().is_some_and(|node| node.is_br()) || nodes.len() > SPECULATION_LIMIT + 1 {
+            return None;
+        }
+
+        if !nodes[..nodes.len() - 1].iter().all(|node| is_speculatable(node.as_ref())) {
+            return None;
+        }
+
+        match cfg.succs(block) {
+            [join] if *join != head => Some(*join),
+            _ => None,
+        }
+    };
+
+    for head in 0..func.blocks.len() {
+        let Some(br) = func.blocks[head].nodes.last().and_then(|node| node.as_any().downcast_ref::<BrCond>()) else { continue; };
+
+        let (Some(yes), Some(no)) = (cfg.index(&br.inner2.name), cfg.index(&br.inner3.name)) else { continue; };
+
+        if yes == no {
+            continue;
+        }
+
+        let cond = br.inner1.to_owned();
+
+        // the join block and the sides of the branch which need to be moved into the head
+        let (join, sides) = match (side(head, yes), side(head, no)) {
+            (Some(a), Some(b)) if a == b => (a, vec![yes, no]),
+            (Some(join), _) if join == no => (no, vec![yes]),
+            (_, Some(join)) if join == yes => (yes, vec![no]),
+            _ => continue,
+        };
+
+        if cfg.preds(join).len() != 2 {
+            continue;
+        }
+
+        // the blocks from which the join block recives the value of each side
+        let from_yes = if sides.contains(&yes) { cfg.name(yes) } else { cfg.name(head) };
+        let from_no = if sides.contains(&no) { cfg.name(no) } else { cfg.name(head) };
+
+        let mut selects = Vec::new();
+
+        for node in &func.blocks[join].nodes {
+            let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+
+            let value = |from: &String| phi.recive_from_blocks.iter()
+                .find(|(block, _)| block.name == *from)
+                .map(|(_, var)| var.to_owned());
+
+            let (Some(yes), Some(no)) = (value(from_yes), value(from_no)) else { break; };
+
+            selects.push(Box::new(Select {
+                out: phi.out.to_owned(),
+                cond: cond.to_owned(),
+                yes: IROperand::Var(yes),
+                no: IROperand::Var(no),
+            }) as Box<dyn Ir>);
+        }
+
+        let phis = func.blocks[join].nodes.iter().filter(|node| node.is_phi()).count();
+
+        if selects.len() != phis {
+            continue;
+        }
+
+        let mut speculated = Vec::new();
+        let mut removed = HashSet::new();
+
+        for side in sides {
+            let nodes = &func.blocks[side].nodes;
+
+            speculated.extend(nodes[..nodes.len() - 1].iter().map(|node| node.clone_box()));
+            removed.insert(func.blocks[side].name.to_owned());
+        }
+
+        let head = &mut func.blocks[head];
+
+        insert_before_terminator(head, speculated);
+        *head.nodes.last_mut().expect("the head ends with a branch") = Br::new(BlockId { name: cfg.name(join).to_owned() });
+
+        let join = &mut func.blocks[join];
+
+        join.nodes.retain(|node| !node.is_phi());
+        join.nodes.splice(0..0, selects);
+
+        remove_blocks(func, &removed);
+
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,360 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Optimizations::Analysis::{AnalysisCache, ControlFlowGraph};
+use crate::Optimizations::utils::phi_block;
+use crate::Optimizations::Pass;
+use crate::IR::{ir::*, BlockId, Function, Type, Var};
+
+/// ## Pass SparseConditionalConstantPropagation <br>
+/// propagates constants across blocks and removes branches which can't be taken
+///
+/// Every variable starts as undefined and only gets lowered to a constant
+/// or to overdefined (not a constant). Blocks are only evaluated once an executable
+/// edge leads to them, so phis only merge the values of the edges which can be taken
+/// and branches on constant conditions only mark one target as executable.
+///
+/// Afterwards the constant variables get replaced with their values, branches with
+/// constant conditions get replaced with `br` and the blocks which aren't executable
+/// can't be reached anymore (so `DeadBlockElimination` removes them).
+pub(crate) struct SparseConditionalConstantPropagation_;
+
+/// Creates a new SparseConditionalConstantPropagation pass which is heap allocated
+pub fn SparseConditionalConstantPropagation() -> Box<dyn Pass> {
+    Box::from( SparseConditionalConstantPropagation_ {} )
+}
+
+impl Pass for SparseConditionalConstantPropagation_ {
+    fn name(&self) -> &'static str {
+        "SparseConditionalConstantPropagation"
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        if func.blocks.is_empty() {
+            return false;
+        }
+
+        let cfg = analyses.get::<ControlFlowGraph>(func);
+
+        let mut solver = Solver {
+            cfg: &cfg,
+            values: HashMap::new(),
+            consts: HashMap::new(),
+            executable: HashSet::from([0]),
+            edges: HashSet::new(),
+        };
+
+        // the arguments can have any value
+        for (name, _) in &func.ty.args {
+            solver.values.insert(name.to_owned(), Lattice::Overdefined);
+        }
+
+        solver.solve(func);
+
+        solver.rewrite(func)
+    }
+}
+
+/// The value of a variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Lattice {
+    /// no value was assigned yet (e.g: the definition isn't executable)
+    Undefined,
+    Const(Type),
+    /// the variable can have multiple values
+    Overdefined,
+}
+
+impl Lattice {
+    fn meet(self, other: Lattice) -> Lattice {
+        match (self, other) {
+            (Lattice::Undefined, value) | (value, Lattice::Undefined) => value,
+            (Lattice::Const(a), Lattice::Const(b)) if a == b => Lattice::Const(a),
+            _ => Lattice::Overdefined,
+        }
+    }
+}
+
+/// Returns the value of the node if it is a constant assignment
+fn constant(node: &dyn Ir) -> Option<Type> {
+    node.as_any().downcast_ref::<Assign<Var, Type>>().map(|assign| assign.inner2)
+}
+
+/// Evaluates the node with the constant values of its inputs
+fn fold(node: &dyn Ir, consts: &HashMap<String, Type>) -> Option<Type> {
+    let mut node = node.clone_box();
+
+    // inline the constants, then evaluate (each can enable the other)
+    for _ in 0..3 {
+        if let Some(value) = constant(node.as_ref()) {
+            return Some(value);
+        }
+
+        if let Some(inlined) = node.maybe_inline(consts) {
+            if !inlined.eq(&node) {
+                node = inlined;
+                continue;
+            }
+        }
+
+        match node.eval() {
+            Some(evaluated) => node = evaluated,
+            None => break,
+        }
+    }
+
+    constant(node.as_ref())
+}
+
+struct Solver<'a> {
+    cfg: &'a ControlFlowGraph,
+    values: HashMap<String, Lattice>,
+    /// the variables which are constants
+    consts: HashMap<String, Type>,
+    executable: HashSet<usize>,
+    /// the executable edges (from, to)
+    edges: HashSet<(usize, usize)>,
+}
+
+impl Solver<'_> {
+    fn value(&self, var: &Var) -> Lattice {
+        self.values.get(&var.name).copied().unwrap_or(Lattice::Undefined)
+    }
+
+    fn operand(&self, operand: &IROperand) -> Lattice {
+        match operand {
+            IROperand::Var(var) => self.value(var),
+            IROperand::Type(value) => Lattice::Const(*value),
+        }
+    }
+
+    /// Lowers the value of the variable, returns if it changed
+    fn lower(&mut self, var: &Var, value: Lattice) -> bool {
+        let old = self.value(var);
+        let new = old.meet(value);
+
+        if new == old {
+            return false;
+        }
+
+        match new {
+            Lattice::Const(value) => { self.consts.insert(var.name.to_owned(), value); },
+            _ => { self.consts.remove(&var.name); },
+        }
+
+        self.values.insert(var.name.to_owned(), new);
+        true
+    }
+
+    fn mark_edge(&mut self, from: usize, to: &BlockId) -> bool {
+        let Some(to) = self.cfg.index(&to.name) else { return false; };
+
+        let new_edge = self.edges.insert((from, to));
+        let new_block = self.executable.insert(to);
+
+        new_edge || new_block
+    }
+
+    /// Evaluates the executable blocks till no value changes anymore
+    fn solve(&mut self, func: &Function) {
+        let order = self.cfg.reverse_postorder();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for &block in &order {
+                if !self.executable.contains(&block) {
+                    continue;
+                }
+
+                for node in &func.blocks[block].nodes {
+                    changed |= self.visit(block, node.as_ref());
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, block: usize, node: &dyn Ir) -> bool {
+        if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+            let mut value = Lattice::Undefined;
+
+            for (from, var) in &phi.recive_from_blocks {
+                let Some(from) = self.cfg.index(&from.name) else { continue; };
+
+                if self.edges.contains(&(from, block)) {
+                    value = value.meet(self.value(var));
+                }
+            }
+
+            return self.lower(&phi.out, value);
+        }
+
+        if let Some(br) = node.as_any().downcast_ref::<Br>() {
+            return self.mark_edge(block, &br.inner1);
+        }
+
+        if let Some(br) = node.as_any().downcast_ref::<BrCond>() {
+            return match self.value(&br.inner1) {
+                Lattice::Undefined => false,
+                Lattice::Const(cond) if cond.val() != 0.0 => self.mark_edge(block, &br.inner2),
+                Lattice::Const(_) => self.mark_edge(block, &br.inner3),
+                Lattice::Overdefined => {
+                    let yes = self.mark_edge(block, &br.inner2);
+                    let no = self.mark_edge(block, &br.inner3);
+                    yes || no
+                },
+            };
+        }
+
+        if let Some(switch) = node.as_any().downcast_ref::<Switch>() {
+            return match self.value(&switch.to_switch) {
+                Lattice::Undefined => false,
+                Lattice::Const(value) => {
+                    let target = switch_target(switch, value);
+                    self.mark_edge(block, &target)
+                },
+                Lattice::Overdefined => {
+                    let mut changed = self.mark_edge(block, &switch.default);
+
+                    for case in switch.cases.values() {
+                        changed |= self.mark_edge(block, case);
+                    }
+
+                    changed
+                },
+            };
+        }
+
+        if let Some(select) = node.as_any().downcast_ref::<Select>() {
+            let value = match self.value(&select.cond) {
+                Lattice::Undefined => return false,
+                Lattice::Const(cond) if cond.val() != 0.0 => self.operand(&select.yes),
+                Lattice::Const(_) => self.operand(&select.no),
+                Lattice::Overdefined => self.operand(&select.yes).meet(self.operand(&select.no)),
+            };
+
+            return self.lower(&select.out, value);
+        }
+
+        if let Some(assign) = node.as_any().downcast_ref::<Assign<Var, Var>>() {
+            return self.lower(&assign.inner1, self.value(&assign.inner2));
+        }
+
+        let Some(out) = node.output() else { return false; };
+
+        // we don't know what memory or other functions contain
+        if node.is_call() || node.is_load() || node.is_alloca() {
+            return self.lower(&out, Lattice::Overdefined);
+        }
+
+        let mut inputs_known = true;
+
+        for input in node.inputs() {
+            match self.value(&input) {
+                Lattice::Overdefined => return self.lower(&out, Lattice::Overdefined),
+                Lattice::Undefined => inputs_known = false,
+                Lattice::Const(_) => {},
+            }
+        }
+
+        if !inputs_known {
+            return false;
+        }
+
+        match fold(node, &self.consts) {
+            Some(value) => self.lower(&out, Lattice::Const(value)),
+            None => self.lower(&out, Lattice::Overdefined),
+        }
+    }
+
+    /// Replaces the constants and the branches which can only go one way
+    fn rewrite(&self, func: &mut Function) -> bool {
+        let mut changed = false;
+
+        for (index, block) in func.blocks.iter_mut().enumerate() {
+            if !self.executable.contains(&index) {
+                continue;
+            }
+
+            for node in block.nodes.iter_mut() {
+                if let Some(out) = node.output() {
+                    if let Lattice::Const(value) = self.value(&out) {
+                        if constant(node.as_ref()) != Some(value) && !node.is_call() {
+                            *node = Assign::new(out, value);
+                            changed = true;
+                        }
+
+                        continue;
+                    }
+                }
+
+                if let Some(br) = node.as_any().downcast_ref::<BrCond>() {
+                    if let Lattice::Const(cond) = self.value(&br.inner1) {
+                        let target = if cond.val() != 0.0 { &br.inner2 } else { &br.inner3 };
+
+                        *node = Br::new(target.to_owned());
+                        changed = true;
+                    }
+
+                    continue;
+                }
+
+                if let Some(switch) = node.as_any().downcast_ref::<Switch>() {
+                    if let Lattice::Const(value) = self.value(&switch.to_switch) {
+                        *node = Br::new(switch_target(switch, value));
+                        changed = true;
+                    }
+
+                    continue;
+                }
+
+                if node.is_phi() {
+                    continue;
+                }
+
+                if let Some(inlined) = node.maybe_inline(&self.consts) {
+                    if &inlined != node {
+                        *node = inlined;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        // the phis only recive values over the executable edges
+        for (index, block) in func.blocks.iter_mut().enumerate() {
+            if !self.executable.contains(&index) {
+                continue;
+            }
+
+            for node in block.nodes.iter_mut() {
+                let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+
+                let executable = |from: &str| self.cfg.index(from).is_some_and(|from| self.edges.contains(&(from, index)));
+
+                if phi.recive_from_blocks.iter().all(|(from, _)| executable(&from.name)) {
+                    continue;
+                }
+
+                let recives = phi.recive_from_blocks.iter()
+                    .filter(|(from, _)| executable(&from.name))
+                    .map(|(from, var)| (phi_block(&from.name), var.to_owned()))
+                    .collect();
+
+                *node = Box::new(Phi::new(phi.out.to_owned(), recives, phi.typ));
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+/// Returns the block to which the switch branches for the value
+fn switch_target(switch: &Switch, value: Type) -> BlockId {
+    switch.cases.iter()
+        .find(|(case, _)| case.val() == value.val())
+        .map(|(_, target)| target.to_owned())
+        .unwrap_or(switch.default.to_owned())
+}
@@ -5,9 +5,39 @@ mod DeadNodeElimination;
 #[allow(hidden_glob_reexports)]
 mod DeadBlockElimination;
 #[allow(hidden_glob_reexports)]
+mod DeadGlobalElimination;
+#[allow(hidden_glob_reexports)]
+mod DeadStoreElimination;
+#[allow(hidden_glob_reexports)]
+mod GlobalValueNumbering;
+#[allow(hidden_glob_reexports)]
 mod InstrCombine;
+#[allow(hidden_glob_reexports)]
+mod Inline;
+#[allow(hidden_glob_reexports)]
+mod LoopInvariantCodeMotion;
+#[allow(hidden_glob_reexports)]
+mod LoopUnroll;
+#[allow(hidden_glob_reexports)]
+mod Mem2Reg;
+#[allow(hidden_glob_reexports)]
+mod RedundantLoadElimination;
+#[allow(hidden_glob_reexports)]
+mod SimplifyCFG;
+#[allow(hidden_glob_reexports)]
+mod SparseConditionalConstantPropagation;
 
 pub use ConstantEvaluation::*;
 pub use DeadNodeElimination::*;
 pub use DeadBlockElimination::*;
-pub use InstrCombine::*;
\ No newline at end of file
+pub use DeadGlobalElimination::*;
+pub use DeadStoreElimination::*;
+pub use GlobalValueNumbering::*;
+pub use InstrCombine::*;
+pub use Inline::*;
+pub use LoopInvariantCodeMotion::*;
+pub use LoopUnroll::*;
+pub use Mem2Reg::*;
+pub use RedundantLoadElimination::*;
+pub use SimplifyCFG::*;
+pub use SparseConditionalConstantPropagation::*;
\ No newline at end of file
@@ -1,10 +1,33 @@
 use std::collections::VecDeque;
-use crate::IR::Block;
-use super::Pass;
+use crate::IR::{Block, Function, Module};
+use super::Analysis::{AnalysisCache, ModuleAnalyses};
+use super::{Pass, Passes};
+
+/// The optimization level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OptLevel {
+    /// No optimizations
+    O0,
+    /// Simple optimizations which are fast to run
+    O1,
+    /// Optimizations which also promote stack variables into registers
+    O2,
+    /// All optimizations
+    O3,
+}
+
+/// How often the standard pipelines are iterated at most
+const MAX_PIPELINE_ITERATIONS: usize = 16;
 
 /// The manager of all passes (PassManager)
+/// 
+/// The pass manager runs its passes in order. It can also iterate the entire pipeline
+/// till no pass changes anything anymore (see `PassManager::fixed_point`).
+/// 
+/// A pass manager is also a pass, so pipelines can be nested
 pub struct PassManager {
     pub(crate) passes: VecDeque<Box<dyn Pass>>,
+    pub(crate) max_iterations: usize,
 }
 
 impl PassManager {
@@ -12,9 +35,79 @@ impl PassManager {
     pub fn new() -> Self {
         Self {
             passes: VecDeque::new(),
+            max_iterations: 1,
         }
     }
 
+    /// Creates an new pass manager which runs the pass queue till it doesn't change anything
+    /// (but at most `max_iterations` times)
+    pub fn fixed_point(max_iterations: usize) -> Self {
+        Self {
+            passes: VecDeque::new(),
+            max_iterations: max_iterations,
+        }
+    }
+
+    /// Creates the standard optimization pipeline for the optimization level
+    /// 
+    /// The cleanup passes are iterated till they don't change anything anymore.
+    /// 
+    /// From `O2` on, internal functions and constants which aren't referenced get removed
+    pub fn for_level(level: OptLevel) -> Self {
+        let mut mngr = PassManager::new();
+
+        if level == OptLevel::O0 {
+            return mngr;
+        }
+
+        if level == OptLevel::O3 {
+            mngr.add( Passes::InlineWithThreshold(2 * Passes::DEFAULT_INLINE_THRESHOLD) );
+        } else if level == OptLevel::O2 {
+            mngr.add( Passes::Inline() );
+        }
+
+        if level >= OptLevel::O2 {
+            mngr.add( Passes::Mem2Reg() );
+        }
+
+        mngr.add( Box::new(PassManager::cleanup(level)) );
+
+        // the unrolled loops need another cleanup
+        if level == OptLevel::O3 {
+            mngr.add( Passes::LoopUnroll() );
+            mngr.add( Box::new(PassManager::cleanup(level)) );
+        }
+
+        // the inlined functions often aren't called anymore
+        if level >= OptLevel::O2 {
+            mngr.add( Passes::DeadGlobalElimination() );
+        }
+
+        mngr
+    }
+
+    /// The passes which are iterated till they don't change anything anymore
+    fn cleanup(level: OptLevel) -> Self {
+        let mut cleanup = PassManager::fixed_point(MAX_PIPELINE_ITERATIONS);
+
+        cleanup.add( Passes::InstrCombine() );
+        if level >= OptLevel::O2 {
+            cleanup.add( Passes::SparseConditionalConstantPropagation() );
+        }
+        cleanup.add( Passes::ConstantEvaluation() );
+        if level >= OptLevel::O2 {
+            cleanup.add( Passes::GlobalValueNumbering() );
+            cleanup.add( Passes::RedundantLoadElimination() );
+            cleanup.add( Passes::DeadStoreElimination() );
+            cleanup.add( Passes::LoopInvariantCodeMotion() );
+        }
+        cleanup.add( Passes::DeadBlockElimination() );
+        cleanup.add( Passes::SimplifyCFG() );
+        cleanup.add( Passes::DeadNodeElimination() );
+
+        cleanup
+    }
+
     /// Adds a new pass to the back of the pass queue
     pub fn add(&mut self, pass: Box<dyn Pass>)  {
         self.passes.push_back( pass );
@@ -25,10 +118,109 @@ impl PassManager {
         self.passes.push_front( pass );
     }
 
-    /// Executes the entire pass queue
-    pub fn run(&self, block: &mut Block) {
-        for pass in &self.passes {
-            pass.run(block);
+    /// Turns the pass manager into a pass which runs the entire pass queue
+    /// on one function after the other (instead of running each pass on all functions)
+    pub fn per_function(self) -> Box<dyn Pass> {
+        Box::new( FunctionPipeline { mngr: self } )
+    }
+
+    /// Returns the passes of the queue
+    pub fn passes(&self) -> impl Iterator<Item = &Box<dyn Pass>> {
+        self.passes.iter()
+    }
+
+    /// Runs the pass queue (multiple times if it is a fixed point pipeline)
+    fn iterate(&self, mut run: impl FnMut(&dyn Pass) -> bool) -> bool {
+        let mut changed = false;
+
+        for _ in 0..self.max_iterations {
+            let mut changed_now = false;
+
+            for pass in &self.passes {
+                changed_now |= run(pass.as_ref());
+            }
+
+            changed |= changed_now;
+
+            if !changed_now {
+                break;
+            }
+        }
+
+        changed
+    }
+}
+
+impl Pass for PassManager {
+    fn run(&self, block: &mut Block) -> bool {
+        self.iterate(|pass| pass.run(block))
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        self.iterate(|pass| {
+            let changed = pass.run_func(func, analyses);
+
+            if changed {
+                analyses.invalidate(pass.preserves_cfg());
+            }
+
+            changed
+        })
+    }
+
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        self.iterate(|pass| {
+            if module.debug_passes {
+                eprintln!("Running pass: {}", pass.name());
+            }
+
+            pass.run_mod(module, analyses)
+        })
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        self.passes.iter().all(|pass| pass.preserves_cfg())
+    }
+
+    fn name(&self) -> &'static str {
+        "PassManager"
+    }
+}
+
+/// Runs the entire pass queue of the pass manager on one function after the other
+pub(crate) struct FunctionPipeline {
+    pub(crate) mngr: PassManager,
+}
+
+impl Pass for FunctionPipeline {
+    fn run(&self, block: &mut Block) -> bool {
+        self.mngr.run(block)
+    }
+
+    fn run_func(&self, func: &mut Function, analyses: &mut AnalysisCache) -> bool {
+        self.mngr.run_func(func, analyses)
+    }
+
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        let mut changed = false;
+
+        for (name, func) in &mut module.funcs {
+            if module.debug_passes {
+                eprintln!("Running function pipeline on: {}", name);
+            }
+
+            // the pass manager already invalidates the analyses
+            changed |= self.mngr.run_func(func, analyses.func(name));
         }
+
+        changed
+    }
+
+    fn preserves_cfg(&self) -> bool {
+        self.mngr.preserves_cfg()
+    }
+
+    fn name(&self) -> &'static str {
+        "FunctionPipeline"
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
 mod mngr;
+mod registry;
 mod template;
+mod utils;
 /// all passes
 pub mod Passes;
 /// all analysis functions
@@ -8,7 +10,8 @@ pub mod Analysis;
 /// Instruction combining
 pub(crate) mod InstrCombine;
 
-pub use mngr::PassManager;
+pub use mngr::{OptLevel, PassManager};
+pub use registry::*;
 pub use template::Pass;
 /// used for optimizing
 pub trait Optimize<T> {
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::{Mutex, OnceLock};
+
+use super::{Pass, PassManager, Passes};
+
+/// The options of a pass in a pipeline string: `name<key=value;flag>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassOptions {
+    pub(crate) options: Vec<(String, Option<String>)>,
+}
+
+impl PassOptions {
+    /// Parses the options (the text between `<` and `>`)
+    pub fn parse(text: &str) -> Self {
+        let mut options = Vec::new();
+
+        for option in text.split(';') {
+            let option = option.trim();
+
+            if option.is_empty() {
+                continue;
+            }
+
+            match option.split_once('=') {
+                Some((key, value)) => options.push((key.trim().to_owned(), Some(value.trim().to_owned()))),
+                None => options.push((option.to_owned(), None)),
+            }
+        }
+
+        Self {
+            options: options,
+        }
+    }
+
+    /// Returns if no options were given
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Returns the names of all options
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.options.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// Returns the value of the option `key=value`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options.iter()
+            .find(|(name, _)| name == key)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Returns if the flag (an option without a value) was given
+    pub fn flag(&self, key: &str) -> bool {
+        self.options.iter().any(|(name, value)| name == key && value.is_none())
+    }
+
+    /// Returns the comma seperated values of all options `key=a,b,...`
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        self.options.iter()
+            .filter(|(name, _)| name == key)
+            .filter_map(|(_, value)| value.as_deref())
+            .flat_map(|value| value.split(','))
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Returns the value of the option `key=value` as a number
+    pub fn get_usize(&self, key: &str) -> Result<Option<usize>, String> {
+        match self.get(key) {
+            Some(value) => match value.parse::<usize>() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => Err(format!("expected a number for `{}` but found `{}`", key, value)),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// An error which occured while building a pass pipeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineError {
+    /// No pass with the name was registered
+    UnknownPass(String, Vec<String>),
+    /// The pipeline string is malformed (position, message)
+    Syntax(usize, String),
+    /// The options of the pass are invalid (pass, message)
+    InvalidOptions(String, String),
+}
+
+impl Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineError::UnknownPass(name, known) => write!(f, "unknown pass `{}` (known passes: {})", name, known.join(", ")),
+            PipelineError::Syntax(pos, msg) => write!(f, "invalid pass pipeline at character {}: {}", pos, msg),
+            PipelineError::InvalidOptions(pass, msg) => write!(f, "invalid options for pass `{}`: {}", pass, msg),
+        }
+    }
+}
+
+impl Error for PipelineError {}
+
+/// Constructs a pass
+#[derive(Clone, Copy)]
+pub enum PassConstructor {
+    /// The pass has no options
+    Simple(fn() -> Box<dyn Pass>),
+    /// The pass is constructed from its options
+    WithOptions(fn(&PassOptions) -> Result<Box<dyn Pass>, String>),
+}
+
+/// Maps the names of passes to their constructors
+///
+/// The names are case insensitive and `-`/`_` are ignored,
+/// so `DeadNodeElimination` can also be written as `dead-node-elimination`
+#[derive(Clone)]
+pub struct PassRegistry {
+    pub(crate) passes: HashMap<String, PassConstructor>,
+    pub(crate) names: Vec<String>,
+}
+
+/// The nested pipelines which are built into the pipeline syntax
+const PIPELINE_NAMES: [&str; 3] = ["module", "function", "repeat"];
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|chr| *chr != '-' && *chr != '_')
+        .flat_map(|chr| chr.to_lowercase())
+        .collect()
+}
+
+impl PassRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self {
+            passes: HashMap::new(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Creates a registry which contains all passes of ygen
+    pub fn with_builtin_passes() -> Self {
+        let mut registry = Self::new();
+
+        registry.register(Passes::ConstantEvaluation);
+        registry.alias("cp", "ConstantEvaluation");
+        registry.alias("const-eval", "ConstantEvaluation");
+        registry.alias("const-evaluation", "ConstantEvaluation");
+
+        registry.register(Passes::DeadNodeElimination);
+        registry.alias("dne", "DeadNodeElimination");
+        registry.alias("dead-node", "DeadNodeElimination");
+        registry.alias("dead-node-elim", "DeadNodeElimination");
+
+        registry.register(Passes::DeadBlockElimination);
+        registry.alias("dbe", "DeadBlockElimination");
+        registry.alias("dead-block", "DeadBlockElimination");
+        registry.alias("dead-block-elim", "DeadBlockElimination");
+
+        registry.register(Passes::InstrCombine);
+        registry.alias("instcombine", "InstrCombine");
+
+        registry.register(Passes::Mem2Reg);
+
+        registry.register(Passes::GlobalValueNumbering);
+        registry.alias("gvn", "GlobalValueNumbering");
+        registry.alias("cse", "GlobalValueNumbering");
+
+        registry.register(Passes::SparseConditionalConstantPropagation);
+        registry.alias("sccp", "SparseConditionalConstantPropagation");
+
+        registry.register(Passes::SimplifyCFG);
+
+        registry.register_with_options("DeadGlobalElimination", |options| {
+            Ok(Passes::DeadGlobalEliminationKeeping(options.get_list("keep")))
+        });
+        registry.alias("globaldce", "DeadGlobalElimination");
+        registry.alias("dge", "DeadGlobalElimination");
+
+        registry.register(Passes::DeadStoreElimination);
+        registry.alias("dse", "DeadStoreElimination");
+
+        registry.register(Passes::RedundantLoadElimination);
+        registry.alias("rle", "RedundantLoadElimination");
+        registry.alias("load-elim", "RedundantLoadElimination");
+
+        registry.register(Passes::LoopInvariantCodeMotion);
+        registry.alias("licm", "LoopInvariantCodeMotion");
+
+        registry.register_with_options("LoopUnroll", |options| {
+            let factor = options.get_usize("factor")?.unwrap_or(Passes::DEFAULT_UNROLL_FACTOR);
+            let max_trip_count = options.get_usize("max-trip-count")?.unwrap_or(Passes::DEFAULT_FULL_UNROLL_COUNT);
+
+            Ok(Passes::LoopUnrollWith(factor, max_trip_count))
+        });
+        registry.alias("unroll", "LoopUnroll");
+
+        registry.register_with_options("Inline", |options| {
+            let threshold = match options.get("threshold") {
+                Some(threshold) => threshold.parse::<isize>().map_err(|_| format!("expected a number for `threshold` but found `{}`", threshold))?,
+                None => Passes::DEFAULT_INLINE_THRESHOLD,
+            };
+
+            Ok(Passes::InlineWithThreshold(threshold))
+        });
+        registry.alias("inliner", "Inline");
+
+        registry
+    }
+
+    /// Registers a pass under the name it returns from `Pass::name`
+    pub fn register(&mut self, constructor: fn() -> Box<dyn Pass>) {
+        let name = constructor().name();
+
+        self.insert(name, PassConstructor::Simple(constructor));
+    }
+
+    /// Registers a pass which is constructed from options
+    pub fn register_with_options(&mut self, name: &str, constructor: fn(&PassOptions) -> Result<Box<dyn Pass>, String>) {
+        self.insert(name, PassConstructor::WithOptions(constructor));
+    }
+
+    /// Registers another name for an already registered pass
+    pub fn alias(&mut self, alias: &str, name: &str) {
+        let constructor = *self.passes.get(&normalize(name)).expect("the aliased pass needs to be registered");
+
+        self.passes.insert(normalize(alias), constructor);
+    }
+
+    fn insert(&mut self, name: &str, constructor: PassConstructor) {
+        if !self.names.iter().any(|known| known == name) {
+            self.names.push(name.to_owned());
+        }
+
+        self.passes.insert(normalize(name), constructor);
+    }
+
+    /// Returns the names of all registered passes (without aliases)
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Creates the pass with the name
+    pub fn create(&self, name: &str, options: &PassOptions) -> Result<Box<dyn Pass>, PipelineError> {
+        let Some(constructor) = self.passes.get(&normalize(name)) else {
+            return Err(PipelineError::UnknownPass(name.to_owned(), self.names.clone()));
+        };
+
+        match constructor {
+            PassConstructor::Simple(constructor) => {
+                if let Some(option) = options.keys().next() {
+                    return Err(PipelineError::InvalidOptions(name.to_owned(), format!("the pass has no option `{}`", option)));
+                }
+
+                Ok(constructor())
+            },
+            PassConstructor::WithOptions(constructor) => {
+                constructor(options).map_err(|msg| PipelineError::InvalidOptions(name.to_owned(), msg))
+            },
+        }
+    }
+
+    /// Parses a pass pipeline like: `const-eval,dne,function(instcombine),repeat<3>(dbe)`
+    ///
+    /// The pipeline is a comma seperated list of passes. A pass can have options (`name<key=value;flag>`).
+    ///
+    /// Passes can be nested into:
+    ///  * `module(...)`: runs each pass on all functions
+    ///  * `function(...)`: runs all passes on one function after the other
+    ///  * `repeat<N>(...)`: runs the passes till they don't change anything (but at most N times)
+    pub fn parse(&self, pipeline: &str) -> Result<PassManager, PipelineError> {
+        let mut parser = PipelineParser {
+            chars: pipeline.chars().collect(),
+            pos: 0,
+            registry: self,
+        };
+
+        let mut mngr = PassManager::new();
+
+        parser.parse_list(&mut mngr)?;
+
+        if let Some(chr) = parser.peek() {
+            return Err(PipelineError::Syntax(parser.pos, format!("unexpected `{}`", chr)));
+        }
+
+        Ok(mngr)
+    }
+}
+
+impl Default for PassRegistry {
+    fn default() -> Self {
+        Self::with_builtin_passes()
+    }
+}
+
+struct PipelineParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    registry: &'a PassRegistry,
+}
+
+impl PipelineParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|chr| chr.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), PipelineError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(chr) if chr == expected => {
+                self.pos += 1;
+                Ok(())
+            },
+            Some(chr) => Err(PipelineError::Syntax(self.pos, format!("expected `{}` but found `{}`", expected, chr))),
+            None => Err(PipelineError::Syntax(self.pos, format!("expected `{}` but the pipeline ended", expected))),
+        }
+    }
+
+    fn parse_list(&mut self, mngr: &mut PassManager) -> Result<(), PipelineError> {
+        loop {
+            mngr.add( self.parse_pass()? );
+
+            self.skip_whitespace();
+
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_pass(&mut self) -> Result<Box<dyn Pass>, PipelineError> {
+        self.skip_whitespace();
+
+        let start = self.pos;
+
+        while self.peek().is_some_and(|chr| chr.is_alphanumeric() || chr == '-' || chr == '_') {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            return Err(PipelineError::Syntax(self.pos, "expected the name of a pass".to_owned()));
+        }
+
+        let name: String = self.chars[start..self.pos].iter().collect();
+
+        self.skip_whitespace();
+
+        let options = if self.peek() == Some('<') {
+            self.pos += 1;
+
+            let start = self.pos;
+
+            while self.peek().is_some_and(|chr| chr != '>') {
+                self.pos += 1;
+            }
+
+            let text: String = self.chars[start..self.pos].iter().collect();
+
+            self.expect('>')?;
+
+            PassOptions::parse(&text)
+        } else {
+            PassOptions::default()
+        };
+
+        let name_lower = name.to_lowercase();
+
+        if !PIPELINE_NAMES.contains(&name_lower.as_str()) {
+            return self.registry.create(&name, &options);
+        }
+
+        let mut mngr = if name_lower == "repeat" {
+            let iterations = options.keys().next()
+                .and_then(|iterations| iterations.parse::<usize>().ok())
+                .ok_or(PipelineError::InvalidOptions(name.to_owned(), "expected the maximal amount of iterations: `repeat<N>(...)`".to_owned()))?;
+
+            PassManager::fixed_point(iterations)
+        } else {
+            if let Some(option) = options.keys().next() {
+                return Err(PipelineError::InvalidOptions(name.to_owned(), format!("unexpected option `{}`", option)));
+            }
+
+            PassManager::new()
+        };
+
+        self.expect('(')?;
+        self.parse_list(&mut mngr)?;
+        self.expect(')')?;
+
+        if name_lower == "function" {
+            Ok(mngr.per_function())
+        } else {
+            Ok(Box::new(mngr))
+        }
+    }
+}
+
+fn global_registry() -> &'static Mutex<PassRegistry> {
+    static REGISTRY: OnceLock<Mutex<PassRegistry>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| Mutex::new(PassRegistry::with_builtin_passes()))
+}
+
+/// Registers a pass in the global pass registry (which is used by `parse_pipeline`)
+/// under the name it returns from `Pass::name`
+pub fn register_pass(constructor: fn() -> Box<dyn Pass>) {
+    global_registry().lock().unwrap().register(constructor);
+}
+
+/// Registers a pass which is constructed from options in the global pass registry
+pub fn register_pass_with_options(name: &str, constructor: fn(&PassOptions) -> Result<Box<dyn Pass>, String>) {
+    global_registry().lock().unwrap().register_with_options(name, constructor);
+}
+
+/// Registers another name for a pass of the global pass registry
+pub fn register_pass_alias(alias: &str, name: &str) {
+    global_registry().lock().unwrap().alias(alias, name);
+}
+
+/// Parses the pass pipeline with the global pass registry
+/// (see `PassRegistry::parse` for the syntax)
+pub fn parse_pipeline(pipeline: &str) -> Result<PassManager, PipelineError> {
+    global_registry().lock().unwrap().parse(pipeline)
+}
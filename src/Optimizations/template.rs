@@ -1,13 +1,62 @@
-use crate::IR::{Block, Function};
+use crate::IR::{Block, Function, Module};
+
+use super::Analysis::{AnalysisCache, ModuleAnalyses};
 
 /// The trait all Passes need to implement
+/// 
+/// A pass can run on the entire module, on a function or on a block.
+/// The default implementations run the next lower level for all functions/blocks,
+/// so a pass only needs to implement the level it works on.
+/// 
+/// All run functions return if the pass changed anything
 pub trait Pass {
     /// Runs the pass on a block
-    fn run(&self, _block: &mut Block) {}
+    fn run(&self, _block: &mut Block) -> bool {
+        false
+    }
 
     /// Runs the pass on the entire function
-    fn run_func(&self, _func: &mut Function) {}
+    /// 
+    /// The analyses of the function can be requested from `analyses`
+    fn run_func(&self, func: &mut Function, _analyses: &mut AnalysisCache) -> bool {
+        let mut changed = false;
+
+        for block in &mut func.blocks {
+            changed |= self.run(block);
+        }
+
+        changed
+    }
+
+    /// Runs the pass on the entire module
+    /// 
+    /// #### NOTE:
+    /// 
+    /// Passes which overwrite this function need to invalidate the analyses
+    /// of the functions they change
+    fn run_mod(&self, module: &mut Module, analyses: &mut ModuleAnalyses) -> bool {
+        let mut changed = false;
+
+        for (name, func) in &mut module.funcs {
+            let cache = analyses.func(name);
+
+            if self.run_func(func, cache) {
+                cache.invalidate(self.preserves_cfg());
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Returns if the pass never changes the blocks or branches of a function.
+    /// 
+    /// Then the analyses of the control flow graph (like the dominator tree)
+    /// stay valid after the pass ran
+    fn preserves_cfg(&self) -> bool {
+        false
+    }
 
     /// Returns the name of the pass
     fn name(&self) -> &'static str;
-}
\ No newline at end of file
+}
@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::IR::{ir::*, Block, BlockId, Function};
+
+/// Returns if the node ends a block
+pub(crate) fn is_terminator(node: &dyn Ir) -> bool {
+    node.is_br() || node.is_brcond() || node.is_switch() || node.is_ret()
+}
+
+/// Returns if the node can be executed even if it wouldn't have been executed before
+/// (it has no side effects and can't trap)
+pub(crate) fn is_speculatable(node: &dyn Ir) -> bool {
+    if node.is_call() || node.is_load() || node.is_store() || node.is_alloca() || node.is_phi() || node.is_debug() || is_terminator(node) {
+        return false;
+    }
+
+    let divisor = if let Some(div) = node.as_any().downcast_ref::<Div>() {
+        Some(&div.inner2)
+    } else {
+        node.as_any().downcast_ref::<Rem>().map(|rem| &rem.inner2)
+    };
+
+    // divisions only if they can't divide by zero
+    if let Some(divisor) = divisor {
+        return matches!(divisor, IROperand::Type(value) if value.val() != 0.0);
+    }
+
+    node.output().is_some()
+}
+
+/// Inserts the nodes at the end of the block (but before the terminator)
+pub(crate) fn insert_before_terminator(block: &mut Block, nodes: Vec<Box<dyn Ir>>) {
+    let terminates = block.nodes.last().is_some_and(|node| is_terminator(node.as_ref()));
+
+    let pos = if terminates { block.nodes.len() - 1 } else { block.nodes.len() };
+
+    block.nodes.splice(pos..pos, nodes);
+}
+
+/// Returns the node with renamed branch targets and phi incoming blocks
+/// (`None` if the node doesn't reference a renamed block)
+pub(crate) fn rename_blocks(node: &dyn Ir, rename: impl Fn(&str) -> Option<String>) -> Option<Box<dyn Ir>> {
+    let block = |block: &BlockId| BlockId { name: rename(&block.name).unwrap_or(block.name.to_owned()) };
+
+    let renames = |name: &String| rename(name).is_some();
+
+    if let Some(br) = node.as_any().downcast_ref::<Br>() {
+        if renames(&br.inner1.name) {
+            return Some(Br::new(block(&br.inner1)));
+        }
+    } else if let Some(br) = node.as_any().downcast_ref::<BrCond>() {
+        if renames(&br.inner2.name) || renames(&br.inner3.name) {
+            return Some(BrCond::new(br.inner1.to_owned(), block(&br.inner2), block(&br.inner3)));
+        }
+    } else if let Some(switch) = node.as_any().downcast_ref::<Switch>() {
+        if renames(&switch.default.name) || switch.cases.values().any(|case| renames(&case.name)) {
+            let cases = switch.cases.iter()
+                .map(|(value, case)| (*value, block(case)))
+                .collect();
+
+            return Some(Box::new(Switch::new(switch.to_switch.to_owned(), cases, block(&switch.default))));
+        }
+    } else if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+        if phi.recive_from_blocks.iter().any(|(block, _)| renames(&block.name)) {
+            let recives = phi.recive_from_blocks.iter()
+                .map(|(recive, var)| (phi_block(&rename(&recive.name).unwrap_or(recive.name.to_owned())), var.to_owned()))
+                .collect();
+
+            return Some(Box::new(Phi::new(phi.out.to_owned(), recives, phi.typ)));
+        }
+    }
+
+    None
+}
+
+/// Renames the block references of all nodes of the block
+pub(crate) fn rename_blocks_in(block: &mut Block, rename: impl Fn(&str) -> Option<String>) {
+    for node in &mut block.nodes {
+        if let Some(renamed) = rename_blocks(node.as_ref(), &rename) {
+            *node = renamed;
+        }
+    }
+}
+
+/// Removes the phi incomings from the blocks for which `keep` returns false
+pub(crate) fn prune_phis(block: &mut Block, keep: impl Fn(&str) -> bool) {
+    for node in block.nodes.iter_mut() {
+        let Some(phi) = node.as_any().downcast_ref::<Phi>() else { continue; };
+
+        if phi.recive_from_blocks.iter().all(|(block, _)| keep(&block.name)) {
+            continue;
+        }
+
+        let recives = phi.recive_from_blocks.iter()
+            .filter(|(block, _)| keep(&block.name))
+            .map(|(block, var)| (phi_block(&block.name), var.to_owned()))
+            .collect();
+
+        *node = Box::new(Phi::new(phi.out.to_owned(), recives, phi.typ));
+    }
+}
+
+/// Removes the blocks and the phi incomings from them
+pub(crate) fn remove_blocks(func: &mut Function, removed: &HashSet<String>) {
+    func.blocks.retain(|block| !removed.contains(&block.name));
+
+    for block in func.blocks.iter_mut() {
+        prune_phis(block, |name| !removed.contains(name));
+    }
+}
+
+/// Renames the variables (inputs and output) of the node
+pub(crate) fn rename_vars(node: &mut Box<dyn Ir>, names: &HashMap<String, String>) {
+    for input in node.inputs_mut() {
+        if let Some(name) = names.get(&input.name) {
+            input.name = name.to_owned();
+        }
+    }
+
+    if let Some(out) = node.output_mut() {
+        if let Some(name) = names.get(&out.name) {
+            out.name = name.to_owned();
+        }
+    }
+}
+
+/// Phis only need the name of the blocks they recive from
+pub(crate) fn phi_block(name: &str) -> Block {
+    Block {
+        name: name.to_owned(),
+        nodes: Vec::new(),
+        varCount: 0,
+    }
+}
+
+/// Generates names which aren't used yet
+pub(crate) struct NameGenerator {
+    pub(crate) used: HashSet<String>,
+}
+
+impl NameGenerator {
+    /// Creates a name generator which doesn't generate any of the used names
+    pub(crate) fn new(used: HashSet<String>) -> Self {
+        Self {
+            used: used,
+        }
+    }
+
+    /// Returns an unused name which starts with `base`
+    pub(crate) fn fresh(&mut self, base: &str) -> String {
+        if self.used.insert(base.to_owned()) {
+            return base.to_owned();
+        }
+
+        let mut index = 0;
+
+        loop {
+            let name = format!("{}{}", base, index);
+
+            if self.used.insert(name.to_owned()) {
+                return name;
+            }
+
+            index += 1;
+        }
+    }
+}
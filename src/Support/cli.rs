@@ -140,9 +140,10 @@ impl Cli {
 
             if arg.contains("=") {
                 for cliarg in self.args.iter_mut() {
-                    let splited = arg.split('=').collect::<Vec<&str>>();
-                    let arg: String = splited[0].to_string();
-                    let val: String = splited[1].to_string();
+                    // only split at the first `=` (the value can contain `=` too)
+                    let (arg, val) = arg.split_once('=').unwrap_or((&arg, ""));
+                    let arg: String = arg.to_string();
+                    let val: String = val.to_string();
                     if arg == format!("-{}", cliarg.short) || arg == format!("--{}", cliarg.long) {
                         cliarg.was_there = true;
                         cliarg.value = val;
@@ -77,6 +77,8 @@ impl TargetRegistry {
         
         let mut backend = self.getBackendForFuncOrFork(arch, funct);
 
+        backend.check_calls(block, funct)?;
+
         if run_alloc {
             if let Some(helper) = &mut backend.helper {
                 helper.run_alloc(&funct);
@@ -106,6 +108,8 @@ impl TargetRegistry {
 
         let mut backend = self.getBackendForFuncOrFork(arch, funct);
 
+        backend.check_calls(block, funct)?;
+
         if run_alloc {
             if let Some(helper) = &mut backend.helper {
                 helper.run_alloc(&funct);
@@ -145,6 +149,8 @@ impl TargetRegistry {
 
         let mut backend = self.getBackendForFuncOrFork(arch, funct);
 
+        backend.check_calls(block, funct)?;
+
         if run_alloc {
             if let Some(helper) = &mut backend.helper {
                 helper.run_alloc(&funct);
@@ -203,6 +209,8 @@ impl TargetRegistry {
 
         let mut backend = self.getBackendForFuncOrFork(arch, funct);
 
+        backend.check_calls(block, funct)?;
+
         if run_alloc {
             if let Some(helper) = &mut backend.helper {
                 helper.run_alloc(&funct);
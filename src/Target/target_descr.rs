@@ -1,6 +1,6 @@
 use std::error::Error;
 use crate::debug::DebugLocation;
-use crate::prelude::{ir::*, Block, Var};
+use crate::prelude::{ir::*, Block, Function, Var};
 use crate::CodeGen::{IrCodeGenArea, IrCodeGenHelper, MCDocInstr, MCInstr};
 use crate::CodeGen::{compilation::CompilationHelper, MachineInstr};
 use crate::IR::{Const, Module, Type};
@@ -43,7 +43,7 @@ macro_rules! compile_func {
                         helper.$func(node, &mut vsink, block, module);
 
                         let mut vsink2 = Vec::new();
-                        for inst in &mut vsink {
+                        for mut inst in helper.scalarize_vectors(&vsink) {
                             inst.turn_into_float_if_needed();
                             vsink2.extend_from_slice(&inst.fix_const_imm(helper, module));
                         }
@@ -133,15 +133,9 @@ impl TargetBackendDescr {
                 ir_helper.compile_ret(node, &block, module);
 
                 if self.epilog {
-                    let mut epilog_instrs = vec![];
-                    helper.compile_epilog(&mut epilog_instrs);
-    
                     if let Some(last) = ir_helper.compiled.last_mut() {
-                        let backup = last.compiled.clone();
-                        last.compiled = epilog_instrs;
-                        last.compiled.extend_from_slice(&backup);
+                        ir_helper.helper.insert_epilog(&mut last.compiled);
                     } else { unreachable!() }
-
                 }
             } else {
                 node.compile_dir(&mut ir_helper, &block, module);
@@ -172,6 +166,25 @@ impl TargetBackendDescr {
         }
     }
 
+    /// Checks that the calling convention can pass the signature of the function and of all functions which are called in the block
+    pub(crate) fn check_calls(&self, block: &Block, funct: &Function) -> Result<(), Box<dyn Error>> {
+        let Some(helper) = &self.helper else {
+            return Ok(());
+        };
+
+        helper.call.check(&funct.ty)?;
+
+        for node in &block.nodes {
+            if let Some(call) = node.as_any().downcast_ref::<Call>() {
+                helper.call.check(&call.func.ty)?;
+            } else if let Some(call) = node.as_any().downcast_ref::<IndirectCall>() {
+                helper.call.check(&call.ty)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Used for lowering machine instructions into dyn MCInstr
     pub fn lower(&self, instrs: Vec<MachineInstr>) -> Result<Vec<Box<dyn MCInstr>>, Box<dyn Error>> {
         if let Some(helper) = &self.helper {
@@ -254,6 +267,7 @@ compile_func!(compile_ret, compile_ret, Return);
 compile_func!(compile_cast_var, compile_cast, Cast);
 
 compile_func!(compile_call, compile_call, Call);
+compile_func!(compile_indirect_call, compile_indirect_call, IndirectCall);
 
 compile_func!(compile_assign_var_type, compile_assign_var_type, Assign<Var, Type>);
 compile_func!(compile_assign_var_var, compile_assign_var_var, Assign<Var, Var>);
@@ -274,4 +288,8 @@ compile_func!(compile_neg, compile_neg, Neg);
 
 compile_func!(compile_select, compile_select, Select);
 
-compile_func!(compile_getelemptr, compile_getelemptr, GetElemPtr);
\ No newline at end of file
+compile_func!(compile_getelemptr, compile_getelemptr, GetElemPtr);
+
+compile_func!(compile_extractelement, compile_extractelement, ExtractElement);
+compile_func!(compile_insertelement, compile_insertelement, InsertElement);
+compile_func!(compile_shufflevector, compile_shufflevector, ShuffleVector);
\ No newline at end of file
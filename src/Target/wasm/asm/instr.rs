@@ -1,8 +1,9 @@
 use std::fmt::Display;
 
-use wasm_encoder::{BlockType, ValType};
+use wasm_encoder::{BlockType, MemArg};
 
 use crate::CodeGen::MCInstr;
+use crate::IR::FunctionType;
 
 /// A wasm instruction
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -184,6 +185,7 @@ impl WasmMCInstr {
                 }]
             },
             WasmMnemonic::Return => encoded = vec![0x0f],
+            WasmMnemonic::Select => encoded = vec![0x1b],
             WasmMnemonic::Eq | WasmMnemonic::Ne | WasmMnemonic::Gt | 
             WasmMnemonic::Gts | WasmMnemonic::Gtu | WasmMnemonic::Lt | 
             WasmMnemonic::Lts | WasmMnemonic::Ltu | WasmMnemonic::Ge | 
@@ -400,6 +402,42 @@ impl WasmMCInstr {
                     encoded = vec![0x0c, 0x00]
                 }
             },
+            WasmMnemonic::Call => {
+                if let Some(WasmOperand::Const(func)) = self.op1 {
+                    encoded = vec![0x10, func as u8]
+                } else {
+                    encoded = vec![0x10, 0x00]
+                }
+            },
+            WasmMnemonic::CallIndirect => {
+                if let Some(WasmOperand::Const(ty)) = self.op1 {
+                    encoded = vec![0x11, ty as u8, 0x00]
+                } else {
+                    encoded = vec![0x11, 0x00, 0x00]
+                }
+            },
+            WasmMnemonic::Load => {
+                let prefix = self.prefix.expect("load expects a prefix");
+
+                encoded = vec![match prefix {
+                    WasmPrefix::i32 => 0x28,
+                    WasmPrefix::i64 => 0x29,
+                    WasmPrefix::f32 => 0x2a,
+                    WasmPrefix::f64 => 0x2b,
+                    _ => panic!("illegal prefix for load: {}", prefix),
+                }, prefix.natural_align(), 0x00];
+            },
+            WasmMnemonic::Store => {
+                let prefix = self.prefix.expect("store expects a prefix");
+
+                encoded = vec![match prefix {
+                    WasmPrefix::i32 => 0x36,
+                    WasmPrefix::i64 => 0x37,
+                    WasmPrefix::f32 => 0x38,
+                    WasmPrefix::f64 => 0x39,
+                    _ => panic!("illegal prefix for store: {}", prefix),
+                }, prefix.natural_align(), 0x00];
+            },
             WasmMnemonic::Block => encoded = vec![0x02, 0x40],
             WasmMnemonic::End => encoded = vec![0x0b],
         }
@@ -425,6 +463,7 @@ pub enum WasmMnemonic {
     Rems,
     Remu,
     Return,
+    Select,
     Eq,
     Ne,
     Gt,
@@ -468,6 +507,12 @@ pub enum WasmMnemonic {
 
     Br,
 
+    Call,
+    CallIndirect,
+
+    Load,
+    Store,
+
     Block,
     End,
 }
@@ -487,6 +532,7 @@ impl From<String> for WasmMnemonic {
             "rem_s" => WasmMnemonic::Rems,
             "rem_u" => WasmMnemonic::Remu,
             "return" => WasmMnemonic::Return,
+            "select" => WasmMnemonic::Select,
             "eq" => WasmMnemonic::Eq,
             "ne" => WasmMnemonic::Ne,
             "gt" => WasmMnemonic::Gt,
@@ -521,6 +567,10 @@ impl From<String> for WasmMnemonic {
             "convert_i64_s" => WasmMnemonic::ConvertI64s,
             "convert_i64_u" => WasmMnemonic::ConvertI64u,
             "br" => WasmMnemonic::Br,
+            "call" => WasmMnemonic::Call,
+            "call_indirect" => WasmMnemonic::CallIndirect,
+            "load" => WasmMnemonic::Load,
+            "store" => WasmMnemonic::Store,
             "block" => WasmMnemonic::Block,
             "end" => WasmMnemonic::End,
             _ => panic!("unkown wasm mnemonic: {value}"),
@@ -543,6 +593,7 @@ impl Display for WasmMnemonic {
             WasmMnemonic::Rems => "rem_s",
             WasmMnemonic::Remu => "rem_u",
             WasmMnemonic::Return => "return",
+            WasmMnemonic::Select => "select",
             WasmMnemonic::Eq => "eq",
             WasmMnemonic::Ne => "ne",
             WasmMnemonic::Gt => "gt",
@@ -578,6 +629,10 @@ impl Display for WasmMnemonic {
             WasmMnemonic::TruncF64s => "trunc_f64_s",
             WasmMnemonic::TruncF64u => "trunc_f64_u",
             WasmMnemonic::Br => "br",
+            WasmMnemonic::Call => "call",
+            WasmMnemonic::CallIndirect => "call_indirect",
+            WasmMnemonic::Load => "load",
+            WasmMnemonic::Store => "store",
             WasmMnemonic::Block => "block",
             WasmMnemonic::End => "end",
         })
@@ -597,6 +652,17 @@ pub enum WasmPrefix {
     f64
 }
 
+impl WasmPrefix {
+    /// Returns the log2 of the natural alignment of loads and stores
+    pub(crate) fn natural_align(&self) -> u8 {
+        match self {
+            WasmPrefix::i32 | WasmPrefix::f32 => 2,
+            WasmPrefix::i64 | WasmPrefix::f64 => 3,
+            _ => panic!("{} has no alignment", self),
+        }
+    }
+}
+
 impl From<String> for WasmPrefix {
     fn from(value: String) -> Self {
         match value.as_str() {
@@ -632,6 +698,12 @@ pub enum WasmOperand {
     Var(i32),
     Const(f64),
     BlockLink(/*target*/String),
+    /// the index of the function (which is also its index in the function table)
+    FuncLink(/*target*/String),
+    /// the index of the type
+    TypeLink(FunctionType),
+    /// the adress of a symbol (the index of functions in the function table or the memory adress of data)
+    AdrLink(/*target*/String),
 }
 
 impl PartialEq for WasmOperand {
@@ -640,6 +712,9 @@ impl PartialEq for WasmOperand {
             (Self::Var(l0), Self::Var(r0)) => l0 == r0,
             (Self::Const(l0), Self::Const(r0)) => l0 == r0,
             (Self::BlockLink(l0), Self::BlockLink(r0)) => l0 == r0,
+            (Self::FuncLink(l0), Self::FuncLink(r0)) => l0 == r0,
+            (Self::TypeLink(l0), Self::TypeLink(r0)) => l0 == r0,
+            (Self::AdrLink(l0), Self::AdrLink(r0)) => l0 == r0,
             _ => false,
         }
     }
@@ -653,6 +728,13 @@ impl Display for WasmOperand {
             WasmOperand::Var(var) => format!("{var}"),
             WasmOperand::Const(imm) => format!("{:.5}", imm), 
             WasmOperand::BlockLink(target) => format!("{target}"),
+            WasmOperand::FuncLink(target) => target.to_owned(),
+            WasmOperand::AdrLink(target) => target.to_owned(),
+            WasmOperand::TypeLink(ty) => {
+                let args: Vec<String> = ty.args.iter().map(|(_, arg)| arg.to_string()).collect();
+
+                format!("({}) -> ({})", args.join(", "), ty.ret)
+            },
         })
     }
 }
@@ -765,6 +847,7 @@ impl<'a> Into<wasm_encoder::Instruction<'a>> for WasmMCInstr {
                 _ => panic!()
             }},
             WasmMnemonic::Return => Instruction::Return,
+            WasmMnemonic::Select => Instruction::Select,
             WasmMnemonic::Eq => { let Some(prefix) = self.prefix else { unreachable!()}; match prefix {
                 WasmPrefix::i32 => Instruction::I32Eq,
                 WasmPrefix::i64 => Instruction::I64Eq,
@@ -920,6 +1003,22 @@ impl<'a> Into<wasm_encoder::Instruction<'a>> for WasmMCInstr {
                 _ => panic!(),
             }},
             WasmMnemonic::Br => { if let Some(WasmOperand::Const(target)) = &self.op1 { Instruction::Br(*target as u32) } else { unreachable!()} },
+            WasmMnemonic::Call => { if let Some(WasmOperand::Const(func)) = &self.op1 { Instruction::Call(*func as u32) } else { unreachable!()} },
+            WasmMnemonic::CallIndirect => { if let Some(WasmOperand::Const(ty)) = &self.op1 { Instruction::CallIndirect { type_index: *ty as u32, table_index: 0 } } else { unreachable!()} },
+            WasmMnemonic::Load => { let Some(prefix) = self.prefix else { unreachable!()}; let arg = MemArg { offset: 0, align: prefix.natural_align() as u32, memory_index: 0 }; match prefix {
+                WasmPrefix::i32 => Instruction::I32Load(arg),
+                WasmPrefix::i64 => Instruction::I64Load(arg),
+                WasmPrefix::f32 => Instruction::F32Load(arg),
+                WasmPrefix::f64 => Instruction::F64Load(arg),
+                _ => panic!(),
+            }},
+            WasmMnemonic::Store => { let Some(prefix) = self.prefix else { unreachable!()}; let arg = MemArg { offset: 0, align: prefix.natural_align() as u32, memory_index: 0 }; match prefix {
+                WasmPrefix::i32 => Instruction::I32Store(arg),
+                WasmPrefix::i64 => Instruction::I64Store(arg),
+                WasmPrefix::f32 => Instruction::F32Store(arg),
+                WasmPrefix::f64 => Instruction::F64Store(arg),
+                _ => panic!(),
+            }},
             WasmMnemonic::Block => Instruction::Block(BlockType::Empty),
             WasmMnemonic::End => Instruction::End,
         }
@@ -48,9 +48,12 @@ pub(crate) fn wasm_lower_instr(sink: &mut Vec<super::asm::WasmMCInstr>, instr: M
         crate::CodeGen::MachineMnemonic::Zext(start_ty) => cast::wasm_lower_cast(sink, &instr, start_ty),
         crate::CodeGen::MachineMnemonic::Downcast(start_ty) => cast::wasm_lower_cast(sink, &instr, start_ty),
         crate::CodeGen::MachineMnemonic::Call(func) => call::wasm_lower_call(sink, &instr, func),
+        crate::CodeGen::MachineMnemonic::CallIndirect(ty) => call::wasm_lower_call_indirect(sink, &instr, ty),
         crate::CodeGen::MachineMnemonic::Br(block) => br::wasm_lower_br(sink, &instr, block),
         crate::CodeGen::MachineMnemonic::Return => ret::wasm_lower_return(sink, &instr),
         crate::CodeGen::MachineMnemonic::AdressLoad(constant) => stack::wasm_lower_adress_load(sink, &instr, constant),
+        // wasm only has one thread
+        crate::CodeGen::MachineMnemonic::TlsAdressLoad(constant, _) => stack::wasm_lower_adress_load(sink, &instr, constant),
         crate::CodeGen::MachineMnemonic::StackAlloc => stack::wasm_lower_alloc(sink, &instr),
         crate::CodeGen::MachineMnemonic::Store => stack::wasm_lower_store(sink, &instr),
         crate::CodeGen::MachineMnemonic::Load => stack::wasm_lower_load(sink, &instr),
@@ -64,6 +67,8 @@ pub(crate) fn wasm_lower_instr(sink: &mut Vec<super::asm::WasmMCInstr>, instr: M
         crate::CodeGen::MachineMnemonic::Switch(cases) => switch::wasm_lower_switch(sink, &instr, cases),
         crate::CodeGen::MachineMnemonic::MovIfZero => cmove::wasm_lower_cmove(sink, &instr),
         crate::CodeGen::MachineMnemonic::MovIfNotZero => cmove::wasm_lower_cmovne(sink, &instr),
+        // only aggregates are copied and the calling convention check rejects them for wasm
+        crate::CodeGen::MachineMnemonic::MemCopy => unreachable!("aggregates can't be passed over the wasm operand stack"),
     }
 }
 
@@ -92,14 +97,18 @@ pub(crate) fn wasm_construct_local_types(instrs: &Vec<MachineInstr>) -> HashMap<
     let mut types = HashMap::new();
 
     for instr in instrs {
-        for operand in &instr.operands {
+        for operand in instr.operands.iter().chain(instr.out.iter()) {
             match operand {
                 MachineOperand::Imm(_) => {},
                 MachineOperand::Reg(reg) => match reg {
-                    crate::CodeGen::Reg::wasm(num, type_metadata) => if let Some(element) = types.get_mut(num) {
-                        *element = *type_metadata;
-                    } else {
-                        types.insert(*num, *type_metadata);
+                    // the lanes of vectors are stored in consecutive locals
+                    crate::CodeGen::Reg::wasm(num, type_metadata) => match type_metadata.vector_lanes() {
+                        Some((elem, lanes)) => for lane in 0..lanes as i32 {
+                            types.insert(*num + lane, elem);
+                        },
+                        None => {
+                            types.insert(*num, *type_metadata);
+                        },
                     },
                     _ => panic!("wasm functions expect wasm registers")
                 },
@@ -133,6 +142,8 @@ impl Into<WasmOperand> for &MachineOperand {
 impl Into<WasmPrefix> for TypeMetadata {
     fn into(self) -> WasmPrefix {
         match self {
+            // booleans are stored in an i32
+            TypeMetadata::i1 => WasmPrefix::i32,
             TypeMetadata::i32 => WasmPrefix::i32,
             TypeMetadata::i64 => WasmPrefix::i64,
             TypeMetadata::ptr => WasmPrefix::i64,
@@ -1,6 +1,25 @@
 use super::super::asm::*;
 use crate::CodeGen::MachineInstr;
+use crate::IR::FunctionType;
 
+/// The arguments were already pushed onto the operand stack and the return value is popped into the output
 pub(crate) fn wasm_lower_call(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr, func: String) {
-    todo!()
-}
\ No newline at end of file
+    sink.push( WasmMCInstr::with1(None, WasmMnemonic::Call, WasmOperand::FuncLink(func)) );
+
+    if let Some(out) = instr.out {
+        sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Set, out.into()) );
+    }
+}
+
+/// Function pointers are indices into the function table (which is indexed with an i32)
+pub(crate) fn wasm_lower_call_indirect(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr, ty: FunctionType) {
+    let callee = instr.operands.first().expect("expected a callee");
+
+    sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, callee.into()) );
+    sink.push( WasmMCInstr::with0(Some(WasmPrefix::i32), WasmMnemonic::Wrap) );
+    sink.push( WasmMCInstr::with1(None, WasmMnemonic::CallIndirect, WasmOperand::TypeLink(ty)) );
+
+    if let Some(out) = instr.out {
+        sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Set, out.into()) );
+    }
+}
@@ -1,10 +1,55 @@
 use super::super::asm::*;
-use crate::CodeGen::MachineInstr;
+use crate::CodeGen::{MachineInstr, MachineOperand, Reg};
+use crate::IR::TypeMetadata;
+
+/// Pushes the operand onto the stack
+fn wasm_push(sink: &mut Vec<WasmMCInstr>, op: &MachineOperand, ty: TypeMetadata) {
+    let op = op.into();
+
+    if let WasmOperand::Const(_) = op {
+        sink.push( WasmMCInstr::with1(Some(ty.into()), WasmMnemonic::Const, op));
+    } else {
+        sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, op));
+    }
+}
+
+/// Pushes if the condition is nonzero (as an `i32` like `select` expects it)
+fn wasm_push_cond(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
+    let cond = instr.operands.first().expect("expected condition for valid cmov");
+
+    let ty = match cond {
+        MachineOperand::Reg(Reg::wasm(_, ty)) => *ty,
+        _ => TypeMetadata::i32,
+    };
+
+    wasm_push(sink, cond, ty);
+    sink.push( WasmMCInstr::with1(Some(ty.into()), WasmMnemonic::Const, WasmOperand::Const(0.0)));
+    sink.push( WasmMCInstr::with0(Some(ty.into()), WasmMnemonic::Ne));
+}
+
+/// `select` keeps the first value if the condition is nonzero and the second one otherwise
+fn wasm_lower_select(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr, if_zero: bool) {
+    let out = instr.out.expect("expected output for valid cmov");
+    let value = instr.operands.get(1).expect("expected value for valid cmov");
+
+    if if_zero {
+        wasm_push(sink, &out, instr.meta);
+        wasm_push(sink, value, instr.meta);
+    } else {
+        wasm_push(sink, value, instr.meta);
+        wasm_push(sink, &out, instr.meta);
+    }
+
+    wasm_push_cond(sink, instr);
+
+    sink.push( WasmMCInstr::with0(None, WasmMnemonic::Select));
+    sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Set, out.into()));
+}
 
 pub(crate) fn wasm_lower_cmove(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
-    todo!()
+    wasm_lower_select(sink, instr, true)
 }
 
 pub(crate) fn wasm_lower_cmovne(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
-    todo!()
-}
\ No newline at end of file
+    wasm_lower_select(sink, instr, false)
+}
@@ -3,13 +3,22 @@ use crate::CodeGen::MachineInstr;
 
 pub(crate) fn wasm_lower_return(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
     let op = instr.operands.get(0).expect("return expects operand");
-    let op = op.into();
 
-    if let WasmOperand::Const(_) = op {
-        sink.push( WasmMCInstr::with1(Some(instr.meta.into()), WasmMnemonic::Const, op) );
-    } else {
-        sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, op) );
+    // vectors are returned as their lanes
+    let ops = match instr.meta.vector_lanes() {
+        Some((elem, lanes)) => (0..lanes).map(|lane| (op.lane(elem, lane), elem)).collect(),
+        None => vec![(*op, instr.meta)],
+    };
+
+    for (op, ty) in ops {
+        let op: WasmOperand = op.into();
+
+        if let WasmOperand::Const(_) = op {
+            sink.push( WasmMCInstr::with1(Some(ty.into()), WasmMnemonic::Const, op) );
+        } else {
+            sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, op) );
+        }
     }
 
     sink.push( WasmMCInstr::with0(None, WasmMnemonic::Return) );
-}
\ No newline at end of file
+}
@@ -1,20 +1,56 @@
 use super::super::asm::*;
-use crate::CodeGen::MachineInstr;
+use crate::CodeGen::{MachineInstr, MachineOperand};
 
 pub(crate) fn wasm_lower_alloc(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
     todo!()
 }
 
 pub(crate) fn wasm_lower_store(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
-    todo!()
+    let ptr = instr.out.expect("store expects a pointer");
+    let value = instr.operands.first().expect("store expects a value");
+
+    wasm_lower_ptr(sink, ptr.into(), instr.operands.get(1));
+
+    let value: WasmOperand = value.into();
+
+    if let WasmOperand::Const(_) = value {
+        sink.push( WasmMCInstr::with1(Some(instr.meta.into()), WasmMnemonic::Const, value) );
+    } else {
+        sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, value) );
+    }
+
+    sink.push( WasmMCInstr::with0(Some(instr.meta.into()), WasmMnemonic::Store) );
 }
 
 pub(crate) fn wasm_lower_load(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
-    todo!()
+    let out = instr.out.expect("load expects out");
+    let ptr = instr.operands.first().expect("load expects a pointer");
+
+    wasm_lower_ptr(sink, ptr.into(), instr.operands.get(1));
+
+    sink.push( WasmMCInstr::with0(Some(instr.meta.into()), WasmMnemonic::Load) );
+    sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Set, out.into()) );
+}
+
+/// Pushes the pointer plus the optional displacement as a memory adress (pointers are i64 but the memory is indexed with i32)
+fn wasm_lower_ptr(sink: &mut Vec<WasmMCInstr>, ptr: WasmOperand, displ: Option<&MachineOperand>) {
+    sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, ptr) );
+    sink.push( WasmMCInstr::with0(Some(WasmPrefix::i32), WasmMnemonic::Wrap) );
+
+    if let Some(MachineOperand::Imm(displ)) = displ {
+        if *displ != 0.0 {
+            sink.push( WasmMCInstr::with1(Some(WasmPrefix::i32), WasmMnemonic::Const, WasmOperand::Const(*displ)) );
+            sink.push( WasmMCInstr::with0(Some(WasmPrefix::i32), WasmMnemonic::Add) );
+        }
+    }
 }
 
+/// The adress of a function is its index in the function table and the adress of data is its memory adress
 pub(crate) fn wasm_lower_adress_load(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr, constant: String) {
-    todo!()
+    let out = instr.out.expect("adress load expects out");
+
+    sink.push( WasmMCInstr::with1(Some(WasmPrefix::i64), WasmMnemonic::Const, WasmOperand::AdrLink(constant)) );
+    sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Set, out.into()) );
 }
 
 pub(crate) fn wasm_lower_push(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr) {
@@ -24,6 +60,6 @@ pub(crate) fn wasm_lower_push(sink: &mut Vec<WasmMCInstr>, instr: &MachineInstr)
     if let WasmOperand::Const(_) = op {
         sink.push( WasmMCInstr::with1(Some(instr.meta.into()), WasmMnemonic::Const, op));
     } else {
-        sink.push( WasmMCInstr::with1(Some(instr.meta.into()), WasmMnemonic::Get, op));
+        sink.push( WasmMCInstr::with1(Some(WasmPrefix::Local), WasmMnemonic::Get, op));
     }
 }
\ No newline at end of file
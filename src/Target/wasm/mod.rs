@@ -10,6 +10,7 @@ mod reg_alloc;
 use std::collections::HashMap;
 
 use crate::CodeGen::{Allocator, CompilationHelper, ConstImmRules, MachineCallingConvention};
+use crate::IR::TypeMetadata;
 
 use super::{Arch, CallConv, TargetBackendDescr, WhiteList};
 
@@ -44,6 +45,10 @@ pub fn initializeWasmTarget(_: CallConv) -> TargetBackendDescr {
         ffpregs: Vec::new(),
         call: MachineCallingConvention { call_conv: CallConv::WasmBasicCAbi },
         callee_save_registers: Vec::new(),
+
+        ret_ty: TypeMetadata::Void,
+        sret: None,
+        arg_spills: Vec::new(),
     };
 
     let mut compiler = CompilationHelper::new(
@@ -1,6 +1,6 @@
 use std::{collections::{BTreeMap, HashMap}, error::Error};
 use crate::{debug::DebugRegistry, Obj::ObjectBuilder, Optimizations::Optimize, Target::*, IR::TypeMetadata};
-use wasm::{asm::{WasmMCInstr, WasmMnemonic, WasmOperand}, lower::wasm_construct_local_types};
+use wasm::{asm::{WasmMCInstr, WasmMnemonic, WasmOperand}, lower::wasm_construct_local_types, reg_alloc::wasm_locals};
 use wasm_encoder::*;
 
 pub(crate) fn wasm_emit_mccode(registry: &mut TargetRegistry, debug: bool, module: &mut crate::IR::Module) -> Result<(ObjectBuilder, Option<DebugRegistry>), Box<dyn Error>> {
@@ -14,23 +14,80 @@ pub(crate) fn wasm_emit_mccode(registry: &mut TargetRegistry, debug: bool, modul
 
     let mut types = TypeSection::new();
     let mut functions = FunctionSection::new();
+    let mut tables = TableSection::new();
+    let mut memories = MemorySection::new();
     let mut exports = ExportSection::new();
+    let mut elements = ElementSection::new();
     let mut codes = CodeSection::new();
+    let mut datas = DataSection::new();
 
-    let mut idx = 0;
-    for (name, func) in module.funcs.clone() {
-        // type
-        let mut params = Vec::new();
-        let mut ret = Vec::new();
+    // the index of a function is also its index in the function table
+    let names: Vec<String> = module.funcs.keys().cloned().collect();
+
+    let mut links = WasmLinks {
+        funcs: names.iter().enumerate().map(|(idx, name)| (name.to_owned(), idx as u32)).collect(),
+        types: Vec::new(),
+        data: HashMap::new(),
+    };
+
+    // the constants and globals are placed into the linear memory (after the null pointer)
+    let mut consts: Vec<&crate::IR::Const> = module.consts.values().collect();
+    consts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut adr = WASM_DATA_START;
+
+    for consta in &consts {
+        adr = adr.next_multiple_of(consta.align.unwrap_or(1) as u64);
+
+        links.data.insert(consta.name.to_owned(), adr);
 
-        if func.ty.ret != TypeMetadata::Void {
-            ret.push(func.ty.ret.into());
+        adr += consta.size() as u64;
+    }
+
+    for consta in &consts {
+        // the memory starts zeroed, so zero initialized data doesn't need a segment
+        if consta.zero_init.is_some() || consta.data.is_empty() {
+            continue;
         }
 
-        for (_, arg) in &func.ty.args {
-            params.push((*arg).into());
+        let mut data = consta.data.to_owned();
+
+        for (at, target, addend) in consta.relocs() {
+            let adr = match (links.funcs.get(&target), links.data.get(&target)) {
+                (Some(index), _) => *index as u64,
+                (None, Some(adr)) => *adr,
+                (None, None) => panic!("unknown symbol: {}", target),
+            };
+
+            data[at..at + 8].copy_from_slice(&(adr as i64 + addend).to_le_bytes());
         }
 
+        datas.active(0, &ConstExpr::i32_const(links.data[&consta.name] as i32), data);
+    }
+
+    memories.memory(MemoryType {
+        minimum: adr.div_ceil(WASM_PAGE_SIZE),
+        maximum: None,
+        memory64: false,
+        shared: false,
+        page_size_log2: None,
+    });
+
+    exports.export("memory", ExportKind::Memory, 0);
+
+    let mut bodies = Vec::new();
+
+    let mut idx = 0;
+    for name in &names {
+        let func = module.funcs.get(name).expect("expected valid function").clone();
+
+        // code (first, so signatures wasm can't express are rejected before their type is built)
+
+        bodies.push(wasm_build_instrs(&func, registry, module, &mut links)?);
+
+        // type
+        let (params, ret) = wasm_func_type(&func.ty);
+
         types.ty().function(params, ret);
 
         
@@ -42,10 +99,17 @@ pub(crate) fn wasm_emit_mccode(registry: &mut TargetRegistry, debug: bool, modul
 
         exports.export(name.as_str(), ExportKind::Func, idx);
 
-        // code
+        idx += 1;
+    }
+
+    // the types of indirect calls come after the types of the functions
+    for ty in &links.types {
+        let (params, ret) = wasm_func_type(ty);
 
-        let (instrs, locals) = wasm_build_instrs(&func, registry, module)?;
+        types.ty().function(params, ret);
+    }
 
+    for (instrs, locals) in bodies {
         let mut func = Function::new(locals);
 
         for instr in instrs {
@@ -53,17 +117,36 @@ pub(crate) fn wasm_emit_mccode(registry: &mut TargetRegistry, debug: bool, modul
             func.instruction(&instr.into());
         }
 
-        codes.function(&func);
+        // every block returns or branches, so the end of the function is never reached
+        func.instruction(&Instruction::Unreachable);
+        func.instruction(&Instruction::End);
 
-        idx += 1;
+        codes.function(&func);
     }
 
+    // every function is in the table, so function pointers can be called with call_indirect
+    tables.table(TableType {
+        element_type: RefType::FUNCREF,
+        table64: false,
+        minimum: idx as u64,
+        maximum: Some(idx as u64),
+        shared: false,
+    });
+
+    let table: Vec<u32> = (0..idx).collect();
+
+    elements.active(None, &ConstExpr::i32_const(0), Elements::Functions(table.into()));
+
     // actuall code end
 
     wasm.section(&types);
     wasm.section(&functions);
+    wasm.section(&tables);
+    wasm.section(&memories);
     wasm.section(&exports);
+    wasm.section(&elements);
     wasm.section(&codes);
+    wasm.section(&datas);
 
     let encoded = wasm.finish();
 
@@ -83,11 +166,13 @@ pub(crate) fn wasm_emit_mccode(registry: &mut TargetRegistry, debug: bool, modul
 impl Into<ValType> for TypeMetadata {
     fn into(self) -> ValType {
         match self {
+            TypeMetadata::i1 => ValType::I32,
             TypeMetadata::u32 => ValType::I32,
             TypeMetadata::u64 => ValType::I64,
             TypeMetadata::i32 => ValType::I32,
             TypeMetadata::i64 => ValType::I64,
-            TypeMetadata::ptr => ValType::Ref(RefType::ANYREF),
+            // pointers are stored in an i64 (like in `WasmPrefix`)
+            TypeMetadata::ptr => ValType::I64,
             TypeMetadata::f32 => ValType::F32,
             TypeMetadata::f64 => ValType::F64,
             _ => panic!("unsupported type for wasm: {}", self),
@@ -95,7 +180,59 @@ impl Into<ValType> for TypeMetadata {
     }
 }
 
-fn wasm_build_instrs(func: &crate::IR::Function, registry: &mut TargetRegistry, module: &mut crate::IR::Module) -> Result<(Vec<WasmMCInstr>, Vec<(u32, ValType)>), Box<dyn Error>> {
+/// The memory adress of the first data (so no data is at the null pointer)
+const WASM_DATA_START: u64 = 16;
+
+/// The size of one page of the linear memory
+const WASM_PAGE_SIZE: u64 = 65536;
+
+/// The instructions and the locals of a function
+type WasmBody = (Vec<WasmMCInstr>, Vec<(u32, ValType)>);
+
+/// The indices of the functions, the types of the indirect calls and the memory adresses of the data
+struct WasmLinks {
+    funcs: HashMap<String, u32>,
+    types: Vec<crate::IR::FunctionType>,
+    data: HashMap<String, u64>,
+}
+
+impl WasmLinks {
+    /// Returns the index of the type (the types of the functions are first in the type section)
+    fn ty(&mut self, ty: &crate::IR::FunctionType) -> u32 {
+        let index = match self.types.iter().position(|known| known == ty) {
+            Some(index) => index,
+            None => {
+                self.types.push(ty.to_owned());
+                self.types.len() - 1
+            },
+        };
+
+        (self.funcs.len() + index) as u32
+    }
+}
+
+fn wasm_func_type(ty: &crate::IR::FunctionType) -> (Vec<ValType>, Vec<ValType>) {
+    let mut params = Vec::new();
+    let mut ret = Vec::new();
+
+    // vectors are passed and returned as their lanes
+    let lanes = |ty: TypeMetadata, out: &mut Vec<ValType>| match ty.vector_lanes() {
+        Some((elem, lanes)) => out.extend((0..lanes).map(|_| -> ValType { elem.into() })),
+        None => out.push(ty.into()),
+    };
+
+    if ty.ret != TypeMetadata::Void {
+        lanes(ty.ret, &mut ret);
+    }
+
+    for (_, arg) in &ty.args {
+        lanes(*arg, &mut params);
+    }
+
+    (params, ret)
+}
+
+fn wasm_build_instrs(func: &crate::IR::Function, registry: &mut TargetRegistry, module: &mut crate::IR::Module, links: &mut WasmLinks) -> Result<WasmBody, Box<dyn Error>> {
     let mut blocked_instrs: BTreeMap<&String, Vec<crate::CodeGen::MachineInstr>> = BTreeMap::new();
 
     for block in &func.blocks {
@@ -114,10 +251,16 @@ fn wasm_build_instrs(func: &crate::IR::Function, registry: &mut TargetRegistry,
 
     let types = wasm_construct_local_types(&merged);
 
+    // the arguments are the first locals, so only the locals after them are declared
+    let arg_locals: i64 = func.ty.args.iter().map(|(_, ty)| wasm_locals(*ty)).sum();
+    let count = types.keys().max().map_or(0, |max| *max as i64 + 1);
+
     let mut locals = Vec::new();
 
-    for (num, ty) in types {
-        locals.push((num as u32, ty.into()));
+    for num in arg_locals..count {
+        let ty = types.get(&(num as i32)).copied().unwrap_or(TypeMetadata::i32);
+
+        locals.push((1, ty.into()));
     }
 
     // lower the machine instr into mc instrs
@@ -149,10 +292,32 @@ fn wasm_build_instrs(func: &crate::IR::Function, registry: &mut TargetRegistry,
     // now we finally link
 
     for instr in &mut lowered {
-        if let Some(WasmOperand::BlockLink(ref target)) = instr.op1 {
-            let Some(index) = indexes.get(target) else { panic!("unknown block: {}", target) };
-
-            instr.op1 = Some(WasmOperand::Const(*index as f64));
+        match instr.op1 {
+            Some(WasmOperand::BlockLink(ref target)) => {
+                let Some(index) = indexes.get(target) else { panic!("unknown block: {}", target) };
+
+                instr.op1 = Some(WasmOperand::Const(*index as f64));
+            },
+            Some(WasmOperand::FuncLink(ref target)) => {
+                let Some(index) = links.funcs.get(target) else { panic!("unknown function: {}", target) };
+
+                instr.op1 = Some(WasmOperand::Const(*index as f64));
+            },
+            Some(WasmOperand::AdrLink(ref target)) => {
+                let adr = match (links.funcs.get(target), links.data.get(target)) {
+                    (Some(index), _) => *index as u64,
+                    (None, Some(adr)) => *adr,
+                    (None, None) => panic!("unknown symbol: {}", target),
+                };
+
+                instr.op1 = Some(WasmOperand::Const(adr as f64));
+            },
+            Some(WasmOperand::TypeLink(ref ty)) => {
+                let index = links.ty(ty);
+
+                instr.op1 = Some(WasmOperand::Const(index as f64));
+            },
+            _ => {},
         }
     }
 
@@ -1,6 +1,7 @@
 use crate::{Obj::Linkage, Target::{Arch, AsmPrinter, CallConv}};
 
 use super::lower::wasm_construct_local_types;
+use super::reg_alloc::wasm_locals;
 
 /// prints assembly in wasm style
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,15 +37,15 @@ impl AsmPrinter for WasmAsmPrinter {
             fmt_ty.push_str(&format!(") -> ({})", func.ty.ret));
 
 
-            if func.linkage == Linkage::Extern {
+            if func.linkage.is_import() {
                 lines.push( format!(".globl {}\n", name) );
                 continue;
             }
 
-            if func.linkage == Linkage::External {
-                lines.push( format!(".globl {}\n", name) );
-            } else {
-                lines.push( format!(".hidden {}\n", name) );
+            match func.linkage {
+                Linkage::External => lines.push( format!(".globl {}\n", name) ),
+                Linkage::Weak | Linkage::LinkOnce => lines.push( format!(".weak {}\n", name) ),
+                _ => lines.push( format!(".hidden {}\n", name) ),
             }
 
             let mut mc_instrs = Vec::new();
@@ -57,16 +58,19 @@ impl AsmPrinter for WasmAsmPrinter {
 
             let types = wasm_construct_local_types(&mc_instrs);
 
+            // the locals of the arguments (vectors use one for every lane)
+            let arg_locals: i64 = func.ty.args.iter().map(|(_, ty)| wasm_locals(*ty)).sum();
+
             let mut fmt_types = String::new();
             let mut index = 0;
 
             for (_, ty) in types {
-                if index < func.ty.args.iter().count() {
+                if index < arg_locals {
                     index += 1;
                     continue;
                 }
 
-                if index > func.ty.args.iter().count() {
+                if index > arg_locals {
                     fmt_types.push(',');
                     fmt_types.push(' ');
                 }
@@ -120,6 +124,15 @@ impl AsmPrinter for WasmAsmPrinter {
         lines.push(".data\n\n".to_string());
 
         for (_, consta) in &module.consts {
+            if let Some(align) = consta.align {
+                lines.push(format!(".p2align {}\n", align.trailing_zeros()));
+            }
+
+            if let Some(size) = consta.zero_init {
+                lines.push(format!("{}: .zero {}\n", consta.name, size));
+                continue;
+            }
+
             lines.push(format!("{}: {:02X?} # {}\n", consta.name, consta.data, consta.data.iter()                                      
                 .filter_map(|&byte| {
                     if byte >= 32 && byte <= 126 {
@@ -10,12 +10,17 @@ fn arg_prep(alloc: &mut Allocator, func: &Function) {
         alloc.vars.insert(name.to_owned(), VarLocation::Reg(Reg::wasm(index, *ty)));
         alloc.var_types.insert(name.to_owned(), *ty);
 
-        alloc.stack_off += 1; // the stack off is in reality really only the amount of current variables
+        alloc.stack_off += wasm_locals(*ty); // the stack off is in reality really only the amount of current variables
 
-        index += 1;
+        index += wasm_locals(*ty) as i32;
     }
 }
 
+/// Returns how many locals the variable needs (the lanes of vectors are stored in consecutive locals)
+pub(crate) fn wasm_locals(ty: TypeMetadata) -> i64 {
+    ty.vector_lanes().map_or(1, |(_, lanes)| lanes as i64)
+}
+
 pub(crate) fn wasm_alloc(alloc: &mut Allocator, func: &Function) {
     arg_prep(alloc, func);
 
@@ -87,9 +92,9 @@ pub(crate) fn wasm_alloc_var(alloc: &mut Allocator, ty: TypeMetadata) -> VarLoca
         }
     }
 
-    alloc.stack_off += 1; // the stack off is in reality really only the amount of current variables
+    alloc.stack_off += wasm_locals(ty); // the stack off is in reality really only the amount of current variables
 
-    VarLocation::Reg(Reg::wasm((alloc.stack_off - 1) as i32, ty))
+    VarLocation::Reg(Reg::wasm((alloc.stack_off - wasm_locals(ty)) as i32, ty))
 }
 
 pub(crate) fn phi_prep(alloc: &mut Allocator, phi: &Phi) {
@@ -3,7 +3,7 @@ use iced_x86::{BlockEncoder, BlockEncoderOptions, Code, Instruction, Instruction
 use object::RelocationEncoding;
 
 use crate::CodeGen::MCInstr;
-use crate::Obj::Link;
+use crate::Obj::{Link, TlsModel};
 use crate::Support::{ColorClass, ColorProfile};
 use crate::Target::x64::X64Reg;
 
@@ -92,6 +92,8 @@ impl X64MCInstr {
                     at: 0, addend: *addend, 
                     special: false,
                     kind: RelocationEncoding::X86Branch,
+                    tls: None,
+
                 })));
             } else if let Some(Operand::BlockLinkDestination(dst, addend)) = &self.op1 {
                 return Ok((vec![], Some(Link { 
@@ -100,12 +102,37 @@ impl X64MCInstr {
                     at: 0, addend: *addend, 
                     special: true,
                     kind: RelocationEncoding::Generic,
+                    tls: None,
+
                  })));
             } else {
                 return Ok((vec![], None));
             }
         }
 
+        // the linker only relaxes general dynamic accesses if they are exactly this sequence
+        if let (Mnemonic::Lea, Some(Operand::Tls(target, TlsModel::GeneralDynamic))) = (&self.mnemonic, &self.op2) {
+            return Ok((vec![0x66, 0x48, 0x8d, 0x3d, 0, 0, 0, 0], Some(Link {
+                from: "".into(),
+                to: target.to_owned(),
+                at: 0,
+                addend: -4,
+                special: false,
+                kind: RelocationEncoding::Generic,
+                tls: Some(TlsModel::GeneralDynamic),
+            })));
+        } else if let (Mnemonic::Call, Some(Operand::TlsGetAddr)) = (&self.mnemonic, &self.op1) {
+            return Ok((vec![0x66, 0x66, 0x48, 0xe8, 0, 0, 0, 0], Some(Link {
+                from: "".into(),
+                to: "__tls_get_addr".into(),
+                at: 0,
+                addend: -4,
+                special: false,
+                kind: RelocationEncoding::X86Branch,
+                tls: None,
+            })));
+        }
+
         if Mnemonic::Debug == self.mnemonic || Mnemonic::StartOptimization == self.mnemonic || Mnemonic::EndOptimization == self.mnemonic {
             return Ok((vec![], None))
         }
@@ -114,7 +141,12 @@ impl X64MCInstr {
             Mnemonic::Link | Mnemonic::Debug | Mnemonic::StartOptimization | Mnemonic::EndOptimization => unreachable!(),
             Mnemonic::Add => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
-                    if let Some(Operand::Reg(op2)) = &self.op2 {
+                    if let Some(Operand::Tls(_, TlsModel::LocalExec)) = &self.op2 {
+                        // the offset from the thread pointer is written by the linker
+                        Instruction::with2::<Register, i32>(Code::Add_rm64_imm32, (*op1).into(), 0)?
+                    } else if let Some(Operand::Tls(_, _)) = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Add_r64_rm64, (*op1).into(), MemoryOperand::with_base_displ(Register::RIP, 7))?
+                    } else if let Some(Operand::Reg(op2)) = &self.op2 {
                         if op1.is_gr8() {
                             Instruction::with2::<Register, Register>(Code::Add_rm8_r8, (*op1).into(), (*op2).into())?
                         } else if op1.is_gr16() {
@@ -206,6 +238,53 @@ impl X64MCInstr {
                     } else { todo!("{}", self) }
                 } else { todo!("{}", self) }
             },
+            Mnemonic::Sbb => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2)) = &self.op2 {
+                        if op1.is_gr8() {
+                            Instruction::with2::<Register, Register>(Code::Sbb_rm8_r8, (*op1).into(), (*op2).into())?
+                        } else if op1.is_gr16() {
+                            Instruction::with2::<Register, Register>(Code::Sbb_rm16_r16, (*op1).into(), (*op2).into())?
+                        } else if op1.is_gr32() {
+                            Instruction::with2::<Register, Register>(Code::Sbb_rm32_r32, (*op1).into(), (*op2).into())?
+                        } else if op1.is_gr64() {
+                            Instruction::with2::<Register, Register>(Code::Sbb_rm64_r64, (*op1).into(), (*op2).into())?
+                        } else { todo!("{}", self) }
+                    } else if let Some(Operand::Mem(op2)) = &self.op2 {
+                        if op1.is_gr8() {
+                            Instruction::with2::<Register, MemoryOperand>(Code::Sbb_r8_rm8, (*op1).into(), op2.into())?
+                        } else if op1.is_gr16() {
+                            Instruction::with2::<Register, MemoryOperand>(Code::Sbb_r16_rm16, (*op1).into(), op2.into())?
+                        } else if op1.is_gr32() {
+                            Instruction::with2::<Register, MemoryOperand>(Code::Sbb_r32_rm32, (*op1).into(), op2.into())?
+                        } else if op1.is_gr64() {
+                            Instruction::with2::<Register, MemoryOperand>(Code::Sbb_r64_rm64, (*op1).into(), op2.into())?
+                        } else { todo!("{}", self) }
+                    } else if let Some(Operand::Imm(imm)) = &self.op2 {
+                        if op1.is_gr8() {
+                            Instruction::with2::<Register, i32>(Code::Sbb_rm8_imm8, (*op1).into(), *imm as i32)?
+                        } else if op1.is_gr16() {
+                            Instruction::with2::<Register, i32>(Code::Sbb_rm16_imm16, (*op1).into(), *imm as i32)?
+                        } else if op1.is_gr32() {
+                            Instruction::with2::<Register, i32>(Code::Sbb_rm32_imm32, (*op1).into(), *imm as i32)?
+                        } else if op1.is_gr64() {
+                            Instruction::with2::<Register, i32>(Code::Sbb_rm64_imm32, (*op1).into(), *imm as i32)?
+                        } else { todo!("{}", self) }
+                    } else { todo!("{}", self) }
+                } else if let Some(Operand::Mem(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2)) = &self.op2 {
+                        if op2.is_gr8() {
+                            Instruction::with2::<MemoryOperand, Register>(Code::Sbb_rm8_r8, op1.into(), (*op2).into())?
+                        } else if op2.is_gr16() {
+                            Instruction::with2::<MemoryOperand, Register>(Code::Sbb_rm16_r16, op1.into(), (*op2).into())?
+                        } else if op2.is_gr32() {
+                            Instruction::with2::<MemoryOperand, Register>(Code::Sbb_rm32_r32, op1.into(), (*op2).into())?
+                        } else if op2.is_gr64() {
+                            Instruction::with2::<MemoryOperand, Register>(Code::Sbb_rm64_r64, op1.into(), (*op2).into())?
+                        } else { todo!("{}", self) }
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
             Mnemonic::And => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
                     if let Some(Operand::Reg(op2)) = &self.op2 {
@@ -456,6 +535,21 @@ impl X64MCInstr {
                     } else { todo!("{}", self) }
                 } else { todo!("{}", self) }
             },
+            Mnemonic::Test => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2)) = &self.op2 {
+                        if op1.is_gr8() {
+                            Instruction::with2::<Register, Register>(Code::Test_rm8_r8, (*op1).into(), (*op2).into())?
+                        } else if op1.is_gr16() {
+                            Instruction::with2::<Register, Register>(Code::Test_rm16_r16, (*op1).into(), (*op2).into())?
+                        } else if op1.is_gr32() {
+                            Instruction::with2::<Register, Register>(Code::Test_rm32_r32, (*op1).into(), (*op2).into())?
+                        } else if op1.is_gr64() {
+                            Instruction::with2::<Register, Register>(Code::Test_rm64_r64, (*op1).into(), (*op2).into())?
+                        } else { todo!("{}", self) }
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
             Mnemonic::Lea => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
                     if let Some(Operand::Mem(op2)) = &self.op2 {
@@ -473,7 +567,9 @@ impl X64MCInstr {
             },
             Mnemonic::Mov => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
-                    if let Some(Operand::Reg(op2)) = &self.op2 {
+                    if let Some(Operand::ThreadPointer) = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Mov_r64_rm64, (*op1).into(), MemoryOperand::new(Register::None, Register::None, 1, 0, 8, false, Register::FS))?
+                    } else if let Some(Operand::Reg(op2)) = &self.op2 {
                         if op1.is_gr8() {
                             Instruction::with2::<Register, Register>(Code::Mov_rm8_r8, (*op1).into(), (*op2).into())?
                         } else if op1.is_gr16() {
@@ -500,8 +596,10 @@ impl X64MCInstr {
                             Instruction::with2::<Register, i32>(Code::Mov_rm16_imm16, (*op1).into(), *imm as i32)?
                         } else if op1.is_gr32() {
                             Instruction::with2::<Register, i32>(Code::Mov_rm32_imm32, (*op1).into(), *imm as i32)?
-                        } else if op1.is_gr64() {
+                        } else if op1.is_gr64() && i32::try_from(*imm).is_ok() {
                             Instruction::with2::<Register, i32>(Code::Mov_rm64_imm32, (*op1).into(), *imm as i32)?
+                        } else if op1.is_gr64() {
+                            Instruction::with2::<Register, u64>(Code::Mov_r64_imm64, (*op1).into(), *imm as u64)?
                         } else { todo!("{}", self) }
                     } else { todo!("{}", self) }
                 } else if let Some(Operand::Mem(op1)) = &self.op1 {
@@ -559,6 +657,8 @@ impl X64MCInstr {
                                 Instruction::with2::<Register, Register>(Code::Movsx_r64_rm16, (*op1).into(), (*op2).into())?
                             } else if op2.is_gr8() {
                                 Instruction::with2::<Register, Register>(Code::Movsx_r64_rm8, (*op1).into(), (*op2).into())?
+                            } else if op2.is_gr32() {
+                                Instruction::with2::<Register, Register>(Code::Movsxd_r64_rm32, (*op1).into(), (*op2).into())?
                             } else { todo!("{}", self) }
                         } else if op1.is_gr32() {
                             if op2.is_gr16() {
@@ -594,7 +694,7 @@ impl X64MCInstr {
                 } else if let Some(Operand::Mem(op1)) = &self.op1 {
                     Instruction::with1::<MemoryOperand>(Code::Push_rm64, op1.into())?
                 } else if let Some(Operand::Imm(imm)) = &self.op1 {
-                    Instruction::with1(Code::Pushd_imm32, *imm as i32)?
+                    Instruction::with1(Code::Pushq_imm32, *imm as i32)?
                 } else { todo!("{}", self) }
             },
             Mnemonic::Pop => {
@@ -803,6 +903,42 @@ impl X64MCInstr {
                     Instruction::with_branch(Code::Jg_rel32_64, 0)?
                 } else { todo!("{}", self) }
             },
+            Mnemonic::Ja => {
+                if let Some(Operand::Imm(op1)) = &self.op1 {
+                    Instruction::with_branch(Code::Ja_rel32_64, *op1 as u64)?
+                } else if let Some(Operand::LinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Ja_rel32_64, 0)?
+                } else if let Some(Operand::BlockLinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Ja_rel32_64, 0)?
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Jb => {
+                if let Some(Operand::Imm(op1)) = &self.op1 {
+                    Instruction::with_branch(Code::Jb_rel32_64, *op1 as u64)?
+                } else if let Some(Operand::LinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Jb_rel32_64, 0)?
+                } else if let Some(Operand::BlockLinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Jb_rel32_64, 0)?
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Jae => {
+                if let Some(Operand::Imm(op1)) = &self.op1 {
+                    Instruction::with_branch(Code::Jae_rel32_64, *op1 as u64)?
+                } else if let Some(Operand::LinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Jae_rel32_64, 0)?
+                } else if let Some(Operand::BlockLinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Jae_rel32_64, 0)?
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Jbe => {
+                if let Some(Operand::Imm(op1)) = &self.op1 {
+                    Instruction::with_branch(Code::Jbe_rel32_64, *op1 as u64)?
+                } else if let Some(Operand::LinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Jbe_rel32_64, 0)?
+                } else if let Some(Operand::BlockLinkDestination(..)) = &self.op1 {
+                    Instruction::with_branch(Code::Jbe_rel32_64, 0)?
+                } else { todo!("{}", self) }
+            },
             Mnemonic::Endbr64 => Instruction::with(Code::Endbr64),
             Mnemonic::Sete => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
@@ -846,6 +982,34 @@ impl X64MCInstr {
                     Instruction::with1::<MemoryOperand>(Code::Setle_rm8, op1.into())?
                 } else { todo!("{}", self) }
             },
+            Mnemonic::Seta => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    Instruction::with1::<Register>(Code::Seta_rm8, (*op1).into())?
+                } else if let Some(Operand::Mem(op1)) = &self.op1 {
+                    Instruction::with1::<MemoryOperand>(Code::Seta_rm8, op1.into())?
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Setb => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    Instruction::with1::<Register>(Code::Setb_rm8, (*op1).into())?
+                } else if let Some(Operand::Mem(op1)) = &self.op1 {
+                    Instruction::with1::<MemoryOperand>(Code::Setb_rm8, op1.into())?
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Setae => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    Instruction::with1::<Register>(Code::Setae_rm8, (*op1).into())?
+                } else if let Some(Operand::Mem(op1)) = &self.op1 {
+                    Instruction::with1::<MemoryOperand>(Code::Setae_rm8, op1.into())?
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Setbe => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    Instruction::with1::<Register>(Code::Setbe_rm8, (*op1).into())?
+                } else if let Some(Operand::Mem(op1)) = &self.op1 {
+                    Instruction::with1::<MemoryOperand>(Code::Setbe_rm8, op1.into())?
+                } else { todo!("{}", self) }
+            },
             Mnemonic::Cmove => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
                     if let Some(Operand::Reg(op2)) = &self.op2 {
@@ -1099,6 +1263,186 @@ impl X64MCInstr {
                     } else { todo!("{}", self) }
                 } else { todo!("{}", self) }
             },
+            Mnemonic::Addps => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Addps_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Addps_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Addpd => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Addpd_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Addpd_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Subps => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Subps_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Subps_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Subpd => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Subpd_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Subpd_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Mulps => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Mulps_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Mulps_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Mulpd => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Mulpd_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Mulpd_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Divps => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Divps_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Divps_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Divpd => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Divpd_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Divpd_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Paddb => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Paddb_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Paddb_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Paddw => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Paddw_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Paddw_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Paddd => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Paddd_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Paddd_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Paddq => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Paddq_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Paddq_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Psubb => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Psubb_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Psubb_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Psubw => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Psubw_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Psubw_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Psubd => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Psubd_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Psubd_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Psubq => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Psubq_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Psubq_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Pmullw => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Pmullw_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Pmullw_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Pand => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Pand_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Pand_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Por => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Por_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Por_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
+            Mnemonic::Pxor => {
+                if let Some(Operand::Reg(op1)) = &self.op1 {
+                    if let Some(Operand::Reg(op2))  = &self.op2 {
+                        Instruction::with2::<Register, Register>(Code::Pxor_xmm_xmmm128, (*op1).into(), (*op2).into())?
+                    } else if let Some(Operand::Mem(op2))  = &self.op2 {
+                        Instruction::with2::<Register, MemoryOperand>(Code::Pxor_xmm_xmmm128, (*op1).into(), op2.into())?
+                    } else { todo!("{}", self) }
+                } else { todo!("{}", self) }
+            },
             Mnemonic::Ucomiss => {
                 if let Some(Operand::Reg(op1)) = &self.op1 {
                     if let Some(Operand::Reg(op2)) = &self.op2 {
@@ -1227,6 +1571,8 @@ impl X64MCInstr {
                 addend: *addend,
                 special: false,
                 kind: RelocationEncoding::X86Branch,
+                tls: None,
+
             })
         } else if let Some(Operand::BlockLinkDestination(target, addend)) = &self.op1 {
             links = Some(Link {
@@ -1236,6 +1582,19 @@ impl X64MCInstr {
                 addend: *addend,
                 special: true,
                 kind: RelocationEncoding::Generic,
+                tls: None,
+
+            })
+        } else if let Some(Operand::Tls(target, model)) = &self.op2 {
+            links = Some(Link {
+                from: "".into(),
+                to: target.to_owned(),
+                at: 0,
+                // the initial exec offset is loaded rip relative
+                addend: if *model == TlsModel::LocalExec { 0 } else { -4 },
+                special: false,
+                kind: RelocationEncoding::Generic,
+                tls: Some(*model),
             })
         } else if let Some(Operand::RipRelative(target)) = &self.op2 {
             links = Some(Link {
@@ -1245,6 +1604,8 @@ impl X64MCInstr {
                 addend: -4,
                 special: false,
                 kind: RelocationEncoding::X86RipRelative,
+                tls: None,
+
             })
         }
 
@@ -1279,6 +1640,7 @@ impl X64MCInstr {
                 Operand::LinkDestination(_, _) => "".to_string(),
                 Operand::BlockLinkDestination(_, _) => "".to_string(),
                 Operand::Debug(s) => s.to_string(),
+                Operand::ThreadPointer | Operand::Tls(..) | Operand::TlsGetAddr => profile.markup(&op1.to_string(), ColorClass::Var),
             }));
             if let Some(op2) = &self.op2 {
                 string.push_str(&format!(", {}", match op2 {
@@ -1289,6 +1651,7 @@ impl X64MCInstr {
                     Operand::LinkDestination(_, _) => "".to_string(),
                     Operand::BlockLinkDestination(_, _) => "".to_string(),
                     Operand::Debug(s) => s.to_string(),
+                    Operand::ThreadPointer | Operand::Tls(..) | Operand::TlsGetAddr => profile.markup(&op2.to_string(), ColorClass::Var),
                 }));
             }
         }
@@ -1368,6 +1731,7 @@ impl std::error::Error for InstrEncodingError {}
 pub enum Mnemonic {
     Add,
     Adc,
+    Sbb,
     And,
     Or,
     Xor,
@@ -1376,6 +1740,7 @@ pub enum Mnemonic {
     Neg,
 
     Cmp,
+    Test,
 
     Lea,
     Mov,
@@ -1414,6 +1779,10 @@ pub enum Mnemonic {
     Setl,
     Setge,
     Setle,
+    Seta,
+    Setb,
+    Setae,
+    Setbe,
 
     Cmove,
     Cmovne,
@@ -1438,6 +1807,27 @@ pub enum Mnemonic {
     Subss,
     Subsd,
 
+    Addps,
+    Addpd,
+    Subps,
+    Subpd,
+    Mulps,
+    Mulpd,
+    Divps,
+    Divpd,
+    Paddb,
+    Paddw,
+    Paddd,
+    Paddq,
+    Psubb,
+    Psubw,
+    Psubd,
+    Psubq,
+    Pmullw,
+    Pand,
+    Por,
+    Pxor,
+
     Ucomiss,
     Ucomisd,
 
@@ -1452,6 +1842,10 @@ pub enum Mnemonic {
     Jl,
     Jge,
     Jle,
+    Ja,
+    Jb,
+    Jae,
+    Jbe,
 
     Cbw,
     Cwd,
@@ -1466,6 +1860,7 @@ impl FromStr for Mnemonic {
         match s {
             "add" => Ok(Mnemonic::Add),
             "adc" => Ok(Mnemonic::Adc),
+            "sbb" => Ok(Mnemonic::Sbb),
             "and" => Ok(Mnemonic::And),
             "or" => Ok(Mnemonic::Or),
             "xor" => Ok(Mnemonic::Xor),
@@ -1484,12 +1879,17 @@ impl FromStr for Mnemonic {
             "jne" => Ok(Mnemonic::Jne),
             "je" => Ok(Mnemonic::Je),
             "cmp" => Ok(Mnemonic::Cmp),
+            "test" => Ok(Mnemonic::Test),
             "sete" => Ok(Mnemonic::Sete),
             "setne" => Ok(Mnemonic::Setne),
             "setg" => Ok(Mnemonic::Setg),
             "setl" => Ok(Mnemonic::Setl),
             "setge" => Ok(Mnemonic::Setge),
             "setle" => Ok(Mnemonic::Setle),
+            "seta" => Ok(Mnemonic::Seta),
+            "setb" => Ok(Mnemonic::Setb),
+            "setae" => Ok(Mnemonic::Setae),
+            "setbe" => Ok(Mnemonic::Setbe),
             "neg" => Ok(Mnemonic::Neg),
             "cmove" => Ok(Mnemonic::Cmove),
             "cmovne" => Ok(Mnemonic::Cmovne),
@@ -1512,6 +1912,26 @@ impl FromStr for Mnemonic {
             "mulsd" => Ok(Mnemonic::Mulsd),
             "subss" => Ok(Mnemonic::Subss),
             "subsd" => Ok(Mnemonic::Subsd),
+            "addps" => Ok(Mnemonic::Addps),
+            "addpd" => Ok(Mnemonic::Addpd),
+            "subps" => Ok(Mnemonic::Subps),
+            "subpd" => Ok(Mnemonic::Subpd),
+            "mulps" => Ok(Mnemonic::Mulps),
+            "mulpd" => Ok(Mnemonic::Mulpd),
+            "divps" => Ok(Mnemonic::Divps),
+            "divpd" => Ok(Mnemonic::Divpd),
+            "paddb" => Ok(Mnemonic::Paddb),
+            "paddw" => Ok(Mnemonic::Paddw),
+            "paddd" => Ok(Mnemonic::Paddd),
+            "paddq" => Ok(Mnemonic::Paddq),
+            "psubb" => Ok(Mnemonic::Psubb),
+            "psubw" => Ok(Mnemonic::Psubw),
+            "psubd" => Ok(Mnemonic::Psubd),
+            "psubq" => Ok(Mnemonic::Psubq),
+            "pmullw" => Ok(Mnemonic::Pmullw),
+            "pand" => Ok(Mnemonic::Pand),
+            "por" => Ok(Mnemonic::Por),
+            "pxor" => Ok(Mnemonic::Pxor),
             "ucomiss" => Ok(Mnemonic::Ucomiss),
             "ucomisd" => Ok(Mnemonic::Ucomisd),
             "cvtss2si"  => Ok(Mnemonic::Cvtss2si),
@@ -1524,6 +1944,10 @@ impl FromStr for Mnemonic {
             "jl" => Ok(Mnemonic::Jl),
             "jge" => Ok(Mnemonic::Jge),
             "jle" => Ok(Mnemonic::Jle),
+            "ja" => Ok(Mnemonic::Ja),
+            "jb" => Ok(Mnemonic::Jb),
+            "jae" => Ok(Mnemonic::Jae),
+            "jbe" => Ok(Mnemonic::Jbe),
             "cbw" => Ok(Mnemonic::Cbw),
             "cwd" => Ok(Mnemonic::Cwd),
             "cdq" => Ok(Mnemonic::Cdq),
@@ -1539,6 +1963,7 @@ impl Display for Mnemonic {
         write!(f, "{}", match self {    
             Mnemonic::Add => "add",
             Mnemonic::Adc => "adc",
+            Mnemonic::Sbb => "sbb",
             Mnemonic::And => "and",
             Mnemonic::Or => "or",
             Mnemonic::Xor => "xor",
@@ -1561,12 +1986,17 @@ impl Display for Mnemonic {
             Mnemonic::Jne => "jne",
             Mnemonic::Je => "je",
             Mnemonic::Cmp => "cmp",
+            Mnemonic::Test => "test",
             Mnemonic::Sete => "sete",
             Mnemonic::Setg => "setg",
             Mnemonic::Setl => "setl",
             Mnemonic::Setge => "setge",
             Mnemonic::Setle => "setle",
             Mnemonic::Setne => "setne",
+            Mnemonic::Seta => "seta",
+            Mnemonic::Setb => "setb",
+            Mnemonic::Setae => "setae",
+            Mnemonic::Setbe => "setbe",
             Mnemonic::Neg => "neg",
             Mnemonic::Cmove => "cmove",
             Mnemonic::Cmovne => "cmovne",
@@ -1578,7 +2008,7 @@ impl Display for Mnemonic {
             Mnemonic::Movq => "movq",
             Mnemonic::Movd => "movd",
             Mnemonic::Movss=> "movss",
-            Mnemonic::Movsd=> "movss",
+            Mnemonic::Movsd=> "movsd",
             Mnemonic::Movups=> "movups",
             Mnemonic::Movupd => "movupd",
             Mnemonic::Addss => "addss",
@@ -1589,6 +2019,26 @@ impl Display for Mnemonic {
             Mnemonic::Mulsd => "mulsd",
             Mnemonic::Subss => "subss",
             Mnemonic::Subsd => "subsd",
+            Mnemonic::Addps => "addps",
+            Mnemonic::Addpd => "addpd",
+            Mnemonic::Subps => "subps",
+            Mnemonic::Subpd => "subpd",
+            Mnemonic::Mulps => "mulps",
+            Mnemonic::Mulpd => "mulpd",
+            Mnemonic::Divps => "divps",
+            Mnemonic::Divpd => "divpd",
+            Mnemonic::Paddb => "paddb",
+            Mnemonic::Paddw => "paddw",
+            Mnemonic::Paddd => "paddd",
+            Mnemonic::Paddq => "paddq",
+            Mnemonic::Psubb => "psubb",
+            Mnemonic::Psubw => "psubw",
+            Mnemonic::Psubd => "psubd",
+            Mnemonic::Psubq => "psubq",
+            Mnemonic::Pmullw => "pmullw",
+            Mnemonic::Pand => "pand",
+            Mnemonic::Por => "por",
+            Mnemonic::Pxor => "pxor",
             Mnemonic::Ucomiss => "ucomiss",
             Mnemonic::Ucomisd => "ucomisd",
             Mnemonic::Cvtss2si => "cvtss2si",
@@ -1601,6 +2051,10 @@ impl Display for Mnemonic {
             Mnemonic::Jl => "jl",
             Mnemonic::Jge => "jge",
             Mnemonic::Jle => "jle",
+            Mnemonic::Ja => "ja",
+            Mnemonic::Jb => "jb",
+            Mnemonic::Jae => "jae",
+            Mnemonic::Jbe => "jbe",
             Mnemonic::Cbw => "cbw",
             Mnemonic::Cwd => "cwd",
             Mnemonic::Cdq => "cdq",
@@ -1627,6 +2081,12 @@ pub enum Operand {
     Debug(String),
     /// A rip relative
     RipRelative(String),
+    /// The thread pointer (`fs:[0]`)
+    ThreadPointer,
+    /// The offset of a thread local symbol from the thread pointer (how it is reached depends on the model)
+    Tls(String, TlsModel),
+    /// `__tls_get_addr` which returns the adress of a general dynamic thread local
+    TlsGetAddr,
 }
 
 impl Operand {
@@ -1642,7 +2102,7 @@ impl Operand {
 
     /// Returns if the operand is a memory displacmenet or rip relative
     pub fn is_mem(&self) -> bool {
-        matches!(self, Operand::Mem(_) | Operand::RipRelative(_))
+        matches!(self, Operand::Mem(_) | Operand::RipRelative(_) | Operand::ThreadPointer)
     }
 }
 
@@ -1655,6 +2115,9 @@ impl PartialEq for Operand {
             (Self::LinkDestination(l0, l1), Self::LinkDestination(r0, r1)) => l0 == r0 && l1 == r1,
             (Self::Debug(l0), Self::Debug(r0)) => l0 == r0,
             (Self::RipRelative(l0), Self::RipRelative(r0)) => l0 == r0,
+            (Self::ThreadPointer, Self::ThreadPointer) => true,
+            (Self::Tls(l0, l1), Self::Tls(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::TlsGetAddr, Self::TlsGetAddr) => true,
             _ => false,
         }
     }
@@ -1670,6 +2133,11 @@ impl Display for Operand {
             Operand::BlockLinkDestination(target, _) =>target.to_string(),
             Operand::Debug(s) => s.to_string(),
             Operand::RipRelative(target) => format!("[rel {}]", target),
+            Operand::ThreadPointer => "qword [fs:0]".to_string(),
+            Operand::Tls(target, TlsModel::LocalExec) => format!("{}@tpoff", target),
+            Operand::Tls(target, TlsModel::InitialExec) => format!("qword [rel {}@gottpoff]", target),
+            Operand::Tls(target, TlsModel::GeneralDynamic) => format!("[rel {}@tlsgd]", target),
+            Operand::TlsGetAddr => "__tls_get_addr@plt".to_string(),
         })
     }
 }
@@ -1950,6 +2418,7 @@ macro_rules! IsCheckerOps0 {
 
 IsCheckerOps0!(is_add, Mnemonic::Add);
 IsCheckerOps0!(is_adc, Mnemonic::Adc);
+IsCheckerOps0!(is_sbb, Mnemonic::Sbb);
 IsCheckerOps0!(is_and, Mnemonic::And);
 IsCheckerOps0!(is_or, Mnemonic::Or);
 IsCheckerOps0!(is_xor, Mnemonic::Xor);
@@ -1975,6 +2444,10 @@ IsCheckerOps0!(is_jg, Mnemonic::Jg);
 IsCheckerOps0!(is_jl, Mnemonic::Jl);
 IsCheckerOps0!(is_jge, Mnemonic::Jge);
 IsCheckerOps0!(is_jle, Mnemonic::Jle);
+IsCheckerOps0!(is_ja, Mnemonic::Ja);
+IsCheckerOps0!(is_jb, Mnemonic::Jb);
+IsCheckerOps0!(is_jae, Mnemonic::Jae);
+IsCheckerOps0!(is_jbe, Mnemonic::Jbe);
 IsCheckerOps0!(is_endbr64, Mnemonic::Endbr64);
 IsCheckerOps0!(is_sete, Mnemonic::Sete);
 IsCheckerOps0!(is_setne, Mnemonic::Setne);
@@ -1982,6 +2455,10 @@ IsCheckerOps0!(is_setg, Mnemonic::Setg);
 IsCheckerOps0!(is_setl, Mnemonic::Setl);
 IsCheckerOps0!(is_setge, Mnemonic::Setge);
 IsCheckerOps0!(is_setle, Mnemonic::Setle);
+IsCheckerOps0!(is_seta, Mnemonic::Seta);
+IsCheckerOps0!(is_setb, Mnemonic::Setb);
+IsCheckerOps0!(is_setae, Mnemonic::Setae);
+IsCheckerOps0!(is_setbe, Mnemonic::Setbe);
 IsCheckerOps0!(is_cmove, Mnemonic::Cmove);
 IsCheckerOps0!(is_cmovne, Mnemonic::Cmovne);
 IsCheckerOps0!(is_sal, Mnemonic::Sal);
@@ -2001,6 +2478,26 @@ IsCheckerOps0!(is_mulss, Mnemonic::Mulss);
 IsCheckerOps0!(is_mulsd, Mnemonic::Mulsd);
 IsCheckerOps0!(is_subss, Mnemonic::Subss);
 IsCheckerOps0!(is_subsd, Mnemonic::Subsd);
+IsCheckerOps0!(is_addps, Mnemonic::Addps);
+IsCheckerOps0!(is_addpd, Mnemonic::Addpd);
+IsCheckerOps0!(is_subps, Mnemonic::Subps);
+IsCheckerOps0!(is_subpd, Mnemonic::Subpd);
+IsCheckerOps0!(is_mulps, Mnemonic::Mulps);
+IsCheckerOps0!(is_mulpd, Mnemonic::Mulpd);
+IsCheckerOps0!(is_divps, Mnemonic::Divps);
+IsCheckerOps0!(is_divpd, Mnemonic::Divpd);
+IsCheckerOps0!(is_paddb, Mnemonic::Paddb);
+IsCheckerOps0!(is_paddw, Mnemonic::Paddw);
+IsCheckerOps0!(is_paddd, Mnemonic::Paddd);
+IsCheckerOps0!(is_paddq, Mnemonic::Paddq);
+IsCheckerOps0!(is_psubb, Mnemonic::Psubb);
+IsCheckerOps0!(is_psubw, Mnemonic::Psubw);
+IsCheckerOps0!(is_psubd, Mnemonic::Psubd);
+IsCheckerOps0!(is_psubq, Mnemonic::Psubq);
+IsCheckerOps0!(is_pmullw, Mnemonic::Pmullw);
+IsCheckerOps0!(is_pand, Mnemonic::Pand);
+IsCheckerOps0!(is_por, Mnemonic::Por);
+IsCheckerOps0!(is_pxor, Mnemonic::Pxor);
 IsCheckerOps0!(is_ucomiss, Mnemonic::Ucomiss);
 IsCheckerOps0!(is_ucomisd, Mnemonic::Ucomisd);
 IsCheckerOps0!(is_cvtss2si, Mnemonic::Cvtss2si);
@@ -2023,6 +2520,7 @@ macro_rules! IsCheckerOps1 {
 
 IsCheckerOps1!(is_add1, Mnemonic::Add);
 IsCheckerOps1!(is_adc1, Mnemonic::Adc);
+IsCheckerOps1!(is_sbb1, Mnemonic::Sbb);
 IsCheckerOps1!(is_and1, Mnemonic::And);
 IsCheckerOps1!(is_or1, Mnemonic::Or);
 IsCheckerOps1!(is_xor1, Mnemonic::Xor);
@@ -2044,6 +2542,10 @@ IsCheckerOps1!(is_setg1, Mnemonic::Setg);
 IsCheckerOps1!(is_setl1, Mnemonic::Setl);
 IsCheckerOps1!(is_setge1, Mnemonic::Setge);
 IsCheckerOps1!(is_setle1, Mnemonic::Setle);
+IsCheckerOps1!(is_seta1, Mnemonic::Seta);
+IsCheckerOps1!(is_setb1, Mnemonic::Setb);
+IsCheckerOps1!(is_setae1, Mnemonic::Setae);
+IsCheckerOps1!(is_setbe1, Mnemonic::Setbe);
 IsCheckerOps1!(is_cmove1, Mnemonic::Cmove);
 IsCheckerOps1!(is_cmovne1, Mnemonic::Cmovne);
 IsCheckerOps1!(is_sal1, Mnemonic::Sal);
@@ -2062,6 +2564,26 @@ IsCheckerOps1!(is_mulss1, Mnemonic::Mulss);
 IsCheckerOps1!(is_mulsd1, Mnemonic::Mulsd);
 IsCheckerOps1!(is_subss1, Mnemonic::Subss);
 IsCheckerOps1!(is_subsd1, Mnemonic::Subsd);
+IsCheckerOps1!(is_addps1, Mnemonic::Addps);
+IsCheckerOps1!(is_addpd1, Mnemonic::Addpd);
+IsCheckerOps1!(is_subps1, Mnemonic::Subps);
+IsCheckerOps1!(is_subpd1, Mnemonic::Subpd);
+IsCheckerOps1!(is_mulps1, Mnemonic::Mulps);
+IsCheckerOps1!(is_mulpd1, Mnemonic::Mulpd);
+IsCheckerOps1!(is_divps1, Mnemonic::Divps);
+IsCheckerOps1!(is_divpd1, Mnemonic::Divpd);
+IsCheckerOps1!(is_paddb1, Mnemonic::Paddb);
+IsCheckerOps1!(is_paddw1, Mnemonic::Paddw);
+IsCheckerOps1!(is_paddd1, Mnemonic::Paddd);
+IsCheckerOps1!(is_paddq1, Mnemonic::Paddq);
+IsCheckerOps1!(is_psubb1, Mnemonic::Psubb);
+IsCheckerOps1!(is_psubw1, Mnemonic::Psubw);
+IsCheckerOps1!(is_psubd1, Mnemonic::Psubd);
+IsCheckerOps1!(is_psubq1, Mnemonic::Psubq);
+IsCheckerOps1!(is_pmullw1, Mnemonic::Pmullw);
+IsCheckerOps1!(is_pand1, Mnemonic::Pand);
+IsCheckerOps1!(is_por1, Mnemonic::Por);
+IsCheckerOps1!(is_pxor1, Mnemonic::Pxor);
 IsCheckerOps1!(is_ucomiss1, Mnemonic::Ucomiss);
 IsCheckerOps1!(is_ucomisd1, Mnemonic::Ucomisd);
 IsCheckerOps1!(is_cvtss2si1, Mnemonic::Cvtss2si);
@@ -2084,6 +2606,7 @@ macro_rules! IsCheckerOps2 {
 
 IsCheckerOps2!(is_add2, Mnemonic::Add);
 IsCheckerOps2!(is_adc2, Mnemonic::Adc);
+IsCheckerOps2!(is_sbb2, Mnemonic::Sbb);
 IsCheckerOps2!(is_and2, Mnemonic::And);
 IsCheckerOps2!(is_or2, Mnemonic::Or);
 IsCheckerOps2!(is_xor2, Mnemonic::Xor);
@@ -2111,6 +2634,26 @@ IsCheckerOps2!(is_mulss2, Mnemonic::Mulss);
 IsCheckerOps2!(is_mulsd2, Mnemonic::Mulsd);
 IsCheckerOps2!(is_subss2, Mnemonic::Subss);
 IsCheckerOps2!(is_subsd2, Mnemonic::Subsd);
+IsCheckerOps2!(is_addps2, Mnemonic::Addps);
+IsCheckerOps2!(is_addpd2, Mnemonic::Addpd);
+IsCheckerOps2!(is_subps2, Mnemonic::Subps);
+IsCheckerOps2!(is_subpd2, Mnemonic::Subpd);
+IsCheckerOps2!(is_mulps2, Mnemonic::Mulps);
+IsCheckerOps2!(is_mulpd2, Mnemonic::Mulpd);
+IsCheckerOps2!(is_divps2, Mnemonic::Divps);
+IsCheckerOps2!(is_divpd2, Mnemonic::Divpd);
+IsCheckerOps2!(is_paddb2, Mnemonic::Paddb);
+IsCheckerOps2!(is_paddw2, Mnemonic::Paddw);
+IsCheckerOps2!(is_paddd2, Mnemonic::Paddd);
+IsCheckerOps2!(is_paddq2, Mnemonic::Paddq);
+IsCheckerOps2!(is_psubb2, Mnemonic::Psubb);
+IsCheckerOps2!(is_psubw2, Mnemonic::Psubw);
+IsCheckerOps2!(is_psubd2, Mnemonic::Psubd);
+IsCheckerOps2!(is_psubq2, Mnemonic::Psubq);
+IsCheckerOps2!(is_pmullw2, Mnemonic::Pmullw);
+IsCheckerOps2!(is_pand2, Mnemonic::Pand);
+IsCheckerOps2!(is_por2, Mnemonic::Por);
+IsCheckerOps2!(is_pxor2, Mnemonic::Pxor);
 IsCheckerOps2!(is_ucomiss2, Mnemonic::Ucomiss);
 IsCheckerOps2!(is_ucomisd2, Mnemonic::Ucomisd);
 IsCheckerOps2!(is_cvtss2si2, Mnemonic::Cvtss2si);
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use crate::CodeGen::{calling_convention::MachineCallingConvention, compilation::CompilationHelper, Allocator, ConstImmRules, Reg};
 use crate::Target::{Arch, CallConv};
+use crate::IR::TypeMetadata;
 
 use super::X64Reg;
 use super::reg_alloc;
@@ -59,6 +60,10 @@ pub(crate) fn construct_compilation_helper(call_conv: CallConv) -> CompilationHe
         call: calling_convention,
 
         callee_save_registers: Vec::new(), // will be set in the allocation
+
+        ret_ty: TypeMetadata::Void,
+        sret: None,
+        arg_spills: Vec::new(),
     };
 
     alloc.fregs.reverse();
@@ -75,6 +80,8 @@ pub(crate) fn construct_compilation_helper(call_conv: CallConv) -> CompilationHe
 
     helper.fp_imm = ConstImmRules::CreateConst;
 
+    helper.vectors = true;
+
 
     helper
 }
@@ -1,4 +1,4 @@
-use crate::CodeGen::{MCInstr, MachineCallingConvention, MachineInstr, MachineMnemonic, MachineOperand};
+use crate::CodeGen::{MCInstr, MachineInstr, MachineMnemonic, MachineOperand};
 use crate::Target::CallConv;
 
 mod adr;
@@ -15,6 +15,8 @@ mod stack;
 mod switch;
 mod zext;
 mod cmov;
+mod wide;
+mod vector;
 
 mod fmath;
 mod fcmp;
@@ -22,7 +24,7 @@ mod fmove;
 mod fcast;
 
 use super::optimizer::X64AsmOpt;
-use super::{instr::{Mnemonic, Operand, X64MCInstr}, X64Reg};
+use super::{instr::{MemOp, Mnemonic, Operand, X64MCInstr}, X64Reg};
 
 //pub(crate) static mut USE_SP_FOR_STACK: bool = false;
 //pub(crate) static mut SP_OFF: i32 = -4;
@@ -31,7 +33,7 @@ macro_rules! x64_stack {
     ($off:expr) => {
         //unsafe {
             //if !USE_SP_FOR_STACK {
-                Operand::Mem(X64Reg::Rbp - $off as u32)
+                Operand::Mem(MemOp { base: Some(X64Reg::Rbp), index: None, scale: 1, displ: -($off as isize), rip: false })
             /*} else {
                 Operand::Mem(X64Reg::Rsp + ($off + SP_OFF) as u32)
             }*/
@@ -40,23 +42,23 @@ macro_rules! x64_stack {
 }
 
 pub(crate) fn x64_lower_instr(conv: CallConv, sink: &mut Vec<X64MCInstr>, instr: MachineInstr) {
+    if wide::x64_is_wide(&instr) {
+        return wide::x64_lower_wide(sink, &instr);
+    }
+
+    if vector::x64_is_vector(&instr) {
+        return vector::x64_lower_vector(conv, sink, &instr);
+    }
+
     match &instr.mnemonic {        
     MachineMnemonic::CallStackPrepare => {
-        sink.push(X64MCInstr::with2(
-            Mnemonic::Sub, Operand::Reg(X64Reg::Rsp), 
-            Operand::Imm(
-                MachineCallingConvention {
-                call_conv: conv
-            }.shadow(crate::Target::Arch::X86_64) - 8
-        )));
+        let Some(MachineOperand::Imm(size)) = instr.operands.first() else { panic!("expected the size of the reserved call stack") };
+
+        sink.push(X64MCInstr::with2(Mnemonic::Sub, Operand::Reg(X64Reg::Rsp), Operand::Imm(*size as i64)));
     },MachineMnemonic::CallStackRedo => {
-        sink.push(X64MCInstr::with2(
-            Mnemonic::Add, Operand::Reg(X64Reg::Rsp), 
-            Operand::Imm(
-                MachineCallingConvention {
-                call_conv: conv
-            }.shadow(crate::Target::Arch::X86_64) - 8
-        )));
+        let Some(MachineOperand::Imm(size)) = instr.operands.first() else { panic!("expected the size of the reserved call stack") };
+
+        sink.push(X64MCInstr::with2(Mnemonic::Add, Operand::Reg(X64Reg::Rsp), Operand::Imm(*size as i64)));
     },
         MachineMnemonic::Move =>                                         mov::x64_lower_move(sink, &instr),
         MachineMnemonic::Add =>                                          math::x64_lower_add(sink, &instr),
@@ -72,8 +74,10 @@ pub(crate) fn x64_lower_instr(conv: CallConv, sink: &mut Vec<X64MCInstr>, instr:
         MachineMnemonic::Zext(_) =>                                      zext::x64_lower_zext(sink, &instr),
         MachineMnemonic::Downcast(_) =>                                  downcast::x64_lower_downcast(sink, &instr),
         MachineMnemonic::Call(to) =>                            call::x64_lower_call(conv, sink, &instr, to),
+        MachineMnemonic::CallIndirect(_) =>                              call::x64_lower_call_indirect(conv, sink, &instr),
         MachineMnemonic::Return =>                                       ret::x64_lower_return(sink, &instr),
         MachineMnemonic::AdressLoad(to) =>                      adr::x64_lower_adr_load(sink, &instr, to),
+        MachineMnemonic::TlsAdressLoad(to, model) =>           adr::x64_lower_tls_adr_load(sink, &instr, to, *model),
         MachineMnemonic::Br(to) =>                              br::x64_lower_br(sink, &instr, to),
         MachineMnemonic::BrCond(iftrue, iffalse) =>    br::x64_lower_cond_br(sink, &instr, iftrue, iffalse),
         MachineMnemonic::Compare(mode) =>                      cmp::x64_lower_cmp(sink, &instr, mode),
@@ -89,6 +93,7 @@ pub(crate) fn x64_lower_instr(conv: CallConv, sink: &mut Vec<X64MCInstr>, instr:
         MachineMnemonic::Neg =>                                          math::x64_lower_neg(sink, &instr),
         MachineMnemonic::MovIfZero =>                                    cmov::x64_lower_cmov_zero(sink, &instr),
         MachineMnemonic::MovIfNotZero =>                                 cmov::x64_lower_cmov_not_zero(sink, &instr),
+        MachineMnemonic::MemCopy =>                                      stack::x64_lower_memcpy(sink, &instr),
         MachineMnemonic::FMove =>                                        fmove::x64_lower_fmove(sink, &instr),
         MachineMnemonic::FAdd =>                                         fmath::x64_lower_fadd(sink, &instr),
         MachineMnemonic::FDiv =>                                         fmath::x64_lower_fdiv(sink, &instr),
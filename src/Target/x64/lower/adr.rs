@@ -1,4 +1,5 @@
 use crate::CodeGen::MachineInstr;
+use crate::Obj::TlsModel;
 use crate::Target::x64::X64Reg;
 use crate::Target::x64::asm::instr::*;
 
@@ -42,3 +43,36 @@ pub(crate) fn x64_lower_adrm(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
         sink.push(X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
     }
 }
+
+pub(crate) fn x64_lower_tls_adr_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, symbol: &String, model: TlsModel) {
+    let out = instr.out.expect("expected a output operand");
+
+    let out: Operand = out.into();
+
+    let tls = Operand::Tls(symbol.to_owned(), model);
+
+    if model == TlsModel::GeneralDynamic {
+        // the registers were already saved by the codegen (like for a call)
+        sink.extend_from_slice(&[
+            X64MCInstr::with2(Mnemonic::Lea, Operand::Reg(X64Reg::Rdi), tls),
+            X64MCInstr::with1(Mnemonic::Call, Operand::TlsGetAddr),
+        ]);
+
+        if out != Operand::Reg(X64Reg::Rax) {
+            sink.push(X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax)));
+        }
+
+        return;
+    }
+
+    let reg = if let Operand::Reg(reg) = out { reg } else { X64Reg::Rax };
+
+    sink.extend_from_slice(&[
+        X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(reg), Operand::ThreadPointer),
+        X64MCInstr::with2(Mnemonic::Add, Operand::Reg(reg), tls),
+    ]);
+
+    if out != Operand::Reg(reg) {
+        sink.push(X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(reg)));
+    }
+}
@@ -11,3 +11,14 @@ pub(crate) fn x64_lower_call(conv: CallConv, sink: &mut Vec<X64MCInstr>, _: &Mac
 
     sink.push( X64MCInstr::with1(Mnemonic::Call, Operand::LinkDestination(func.to_string(), -4)) );
 }
+
+pub(crate) fn x64_lower_call_indirect(conv: CallConv, sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let callee = (*instr.operands.first().expect("expected a callee")).into();
+
+    if conv == CallConv::SystemV{
+        sink.push( X64MCInstr::with2(Mnemonic::Xor, Operand::Reg(X64Reg::Eax), Operand::Reg(X64Reg::Eax)) );
+    }
+
+    // call reg or call [mem]
+    sink.push( X64MCInstr::with1(Mnemonic::Call, callee) );
+}
@@ -1,112 +1,81 @@
-use crate::CodeGen::MachineInstr; 
+use crate::CodeGen::{MachineInstr, MachineOperand};
 use crate::Target::x64::instr::{Mnemonic, Operand, X64MCInstr};
 use crate::Target::x64::X64Reg;
 use crate::IR::TypeMetadata;
 
-pub(crate) fn x64_lower_cmov_zero(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
-    if instr.meta.float() {
-        return x64_lower_fcmov0(sink, instr);
-    }
+/// Tests the condition against itself (a nonzero condition is true)
+/// 
+/// A condition on the stack is loaded with its own size (booleans only occupy one byte of their slot)
+fn x64_test_cond(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let cond = instr.operands.first().expect("expected condition for valid cmov");
 
-    let cond = instr.operands.get(0).expect("expected condition for valid cmov");
-    let cond = (*cond).into();
+    let cond = if let MachineOperand::Stack(_, ty) = cond {
+        let tmp = Operand::Reg(X64Reg::R11.sub_ty(*ty));
 
-    let value = instr.operands.get(1).expect("expected value for valid cmov");
-    let value: Operand = (*value).into();
-
-    let out = instr.out.expect("expected output for valid cmov");
-    let out: Operand = out.into();
-
-    if out.is_mem() || value.is_imm() {
-        sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), value.clone()) );
-    }
-
-     if let Operand::Mem(_) = cond {
-        sink.extend_from_slice(&[
-            X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), Operand::Imm(1)),
-            X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Reg(X64Reg::R11))
-            ]
-        );
+        sink.push( X64MCInstr::with2(Mnemonic::Mov, tmp.clone(), (*cond).into()) );
+        tmp
     } else {
-        sink.push( X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Imm(1)));
+        (*cond).into()
     };
 
-    match (&out, &value) {
-        (Operand::Reg(_), Operand::Imm(_)) => {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmove, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-        },
-        (Operand::Reg(_), Operand::Reg(_)) | (Operand::Reg(_), Operand::Mem(_)) => {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmove, out, value));
-        },
-
-        (Operand::Mem(_), Operand::Imm(_)) => {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmove, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-            sink.push( X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-        },
-
-        (Operand::Mem(_), Operand::Reg(_)) | (Operand::Mem(_), Operand::Mem(_))=> {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmove, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), value));
-            sink.push( X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-        },
-
-        _ => todo!(),
+    sink.push( X64MCInstr::with2(Mnemonic::Test, cond.clone(), cond) );
+}
+
+pub(crate) fn x64_lower_cmov_zero(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    if instr.meta.float() {
+        return x64_lower_fcmov0(sink, instr);
     }
-}  
+
+    x64_lower_cmov(sink, instr, Mnemonic::Cmove);
+}
 
 pub(crate) fn x64_lower_cmov_not_zero(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     if instr.meta.float() {
         return x64_lower_fcmovne0(sink, instr);
     }
 
-    let cond = instr.operands.get(0).expect("expected condition for valid cmov");
-    let cond = (*cond).into();
+    x64_lower_cmov(sink, instr, Mnemonic::Cmovne);
+}
 
+/// `cmovcc` only writes registers and doesn't take immediates:
+///  - an output in memory is loaded into `rax` (so it keeps its value if nothing is moved) and written back
+///  - an immediate is moved into `r11`
+/// 
+/// Both are moved after the test (`mov` doesn't change the flags)
+fn x64_lower_cmov(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, cmov: Mnemonic) {
     let value = instr.operands.get(1).expect("expected value for valid cmov");
     let value: Operand = (*value).into();
 
     let out = instr.out.expect("expected output for valid cmov");
     let out: Operand = out.into();
 
-    if out.is_mem() || value.is_imm() {
-        sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), value.clone()) );
-    }
+    let rax = Operand::Reg(X64Reg::Rax.sub_ty(instr.meta));
+    let r11 = Operand::Reg(X64Reg::R11.sub_ty(instr.meta));
 
-     if let Operand::Mem(_) = cond {
-        sink.extend_from_slice(&[
-            X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), Operand::Imm(1)),
-            X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Reg(X64Reg::R11))
-            ]
-        );
+    x64_test_cond(sink, instr);
+
+    let dst = if out.is_mem() {
+        sink.push( X64MCInstr::with2(Mnemonic::Mov, rax.clone(), out.clone()) );
+        rax
+    } else {
+        out.clone()
+    };
+
+    let value = if value.is_imm() {
+        sink.push( X64MCInstr::with2(Mnemonic::Mov, r11.clone(), value) );
+        r11
     } else {
-        sink.push( X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Imm(1)));
+        value
     };
 
-    match (&out, &value) {
-        (Operand::Reg(_), Operand::Imm(_)) => {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmovne, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-        },
-        (Operand::Reg(_), Operand::Reg(_)) | (Operand::Reg(_), Operand::Mem(_)) => {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmovne, out, value));
-        },
-
-        (Operand::Mem(_), Operand::Imm(_)) => {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmovne, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-            sink.push( X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-        },
-
-        (Operand::Mem(_), Operand::Reg(_)) | (Operand::Mem(_), Operand::Mem(_))=> {
-            sink.push( X64MCInstr::with2(Mnemonic::Cmovne, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), value));
-            sink.push( X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
-        },
-
-        _ => todo!(),
+    sink.push( X64MCInstr::with2(cmov, dst.clone(), value) );
+
+    if out.is_mem() {
+        sink.push( X64MCInstr::with2(Mnemonic::Mov, out, dst) );
     }
-}  
+}
 
 pub(crate) fn x64_lower_fcmov0(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
-    let cond = instr.operands.get(0).expect("expected condition for valid cmov");
-    let cond: Operand = (*cond).into();
-
     let value = instr.operands.get(1).expect("expected value for valid cmov");
     let value: Operand = (*value).into();
 
@@ -122,12 +91,7 @@ pub(crate) fn x64_lower_fcmov0(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
         Mnemonic::Movq
     };
 
-    sink.extend_from_slice(&if let Operand::Mem(_) = cond {
-        vec![X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), Operand::Imm(1)),
-             X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Reg(X64Reg::R11))]
-    } else {
-        vec![X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Imm(1))]
-    });
+    x64_test_cond(sink, instr);
 
     if let Operand::Reg(_) = out {
         sink.extend_from_slice(&[
@@ -147,9 +111,6 @@ pub(crate) fn x64_lower_fcmov0(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
 } 
 
 pub(crate) fn x64_lower_fcmovne0(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
-    let cond = instr.operands.get(0).expect("expected condition for valid cmov");
-    let cond: Operand = (*cond).into();
-
     let value = instr.operands.get(1).expect("expected value for valid cmov");
     let value: Operand = (*value).into();
 
@@ -165,12 +126,7 @@ pub(crate) fn x64_lower_fcmovne0(sink: &mut Vec<X64MCInstr>, instr: &MachineInst
         Mnemonic::Movq
     };
 
-    sink.extend_from_slice(&if let Operand::Mem(_) = cond {
-        vec![X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), Operand::Imm(1)),
-             X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Reg(X64Reg::R11))]
-    } else {
-        vec![X64MCInstr::with2(Mnemonic::Cmp, cond, Operand::Imm(1))]
-    });
+    x64_test_cond(sink, instr);
 
     if let Operand::Reg(_) = out {
         sink.extend_from_slice(&[
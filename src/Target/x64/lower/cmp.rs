@@ -68,13 +68,18 @@ fn x64_lower_cmp(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, mode: &CmpMod
         _ => out,
     };
 
-    let mne = match mode {
-        CmpMode::Eqal => Mnemonic::Sete,
-        CmpMode::NotEqal => Mnemonic::Setne,
-        CmpMode::GreaterThan => Mnemonic::Setg,
-        CmpMode::LessThan => Mnemonic::Setl,
-        CmpMode::GreaterThanOrEqual => Mnemonic::Setge,
-        CmpMode::LessThanOrEqual => Mnemonic::Setle,
+    // unsigned integers (and pointers) are compared with the "above"/"below" conditions
+    let mne = match (mode, instr.meta.signed()) {
+        (CmpMode::Eqal, _) => Mnemonic::Sete,
+        (CmpMode::NotEqal, _) => Mnemonic::Setne,
+        (CmpMode::GreaterThan, true) => Mnemonic::Setg,
+        (CmpMode::LessThan, true) => Mnemonic::Setl,
+        (CmpMode::GreaterThanOrEqual, true) => Mnemonic::Setge,
+        (CmpMode::LessThanOrEqual, true) => Mnemonic::Setle,
+        (CmpMode::GreaterThan, false) => Mnemonic::Seta,
+        (CmpMode::LessThan, false) => Mnemonic::Setb,
+        (CmpMode::GreaterThanOrEqual, false) => Mnemonic::Setae,
+        (CmpMode::LessThanOrEqual, false) => Mnemonic::Setbe,
     };
 
     sink.push( X64MCInstr::with1(mne, out) );
@@ -2,6 +2,7 @@ use crate::CodeGen::MachineInstr;
 //use crate::Target::x64Reg;
 use crate::Target::x64::asm::instr::*;
 use crate::Target::x64::X64Reg;
+use crate::IR::TypeMetadata;
 
 pub(crate) fn x64_lower_downcast(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     let out = instr.out.expect("downcast expects output").into();
@@ -14,8 +15,12 @@ pub(crate) fn x64_lower_downcast(sink: &mut Vec<X64MCInstr>, instr: &MachineInst
         crate::CodeGen::MachineOperand::Stack(off, _) => Operand::Mem(X64Reg::Rbp - *off as u32),
     };
 
-    sink.extend_from_slice(&[
-        X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), op),
-        X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))),
-    ]);
+    sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), op));
+
+    // only the lowest bit is kept for booleans
+    if instr.meta == TypeMetadata::i1 {
+        sink.push(X64MCInstr::with2(Mnemonic::And, Operand::Reg(X64Reg::Al), Operand::Imm(1)));
+    }
+
+    sink.push(X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
 }
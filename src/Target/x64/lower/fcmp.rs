@@ -23,13 +23,14 @@ pub(crate) fn x64_lower_fcmp(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, m
 
     sink.push(X64MCInstr::with2(mnemonic, ls, rs));
 
+    // ucomiss/ucomisd set the flags like an unsigned compare
     let mne = match mode {
         CmpMode::Eqal => Mnemonic::Sete,
         CmpMode::NotEqal => Mnemonic::Setne,
-        CmpMode::GreaterThan => Mnemonic::Setg,
-        CmpMode::LessThan => Mnemonic::Setl,
-        CmpMode::GreaterThanOrEqual => Mnemonic::Setge,
-        CmpMode::LessThanOrEqual => Mnemonic::Setle,
+        CmpMode::GreaterThan => Mnemonic::Seta,
+        CmpMode::LessThan => Mnemonic::Setb,
+        CmpMode::GreaterThanOrEqual => Mnemonic::Setae,
+        CmpMode::LessThanOrEqual => Mnemonic::Setbe,
     };
 
     sink.push( X64MCInstr::with1(mne, out) );
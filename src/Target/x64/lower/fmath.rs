@@ -15,6 +15,19 @@ macro_rules! FMathLowerImpl {
             let op2: Operand = (*op2).into();
             let out: Operand = out.into();
 
+            // stack slots (e.g: arguments passed over the stack) are read with movss/movsd into xmm15
+            let fp_or_mem = |op: &Operand| matches!(op, Operand::Mem(_)) || matches!(op, Operand::Reg(reg) if reg.is_xmm());
+
+            if [&op1, &op2, &out].iter().any(|op| matches!(op, Operand::Mem(_))) && [&op1, &op2, &out].iter().all(|op| fp_or_mem(op)) {
+                let (mov, mne) = if TypeMetadata::f32 == instr.meta { (Mnemonic::Movss, $f32m) } else { (Mnemonic::Movsd, $f64m) };
+
+                sink.push(X64MCInstr::with2(mov.to_owned(), Operand::Reg(X64Reg::Xmm15), op1));
+                sink.push(X64MCInstr::with2(mne, Operand::Reg(X64Reg::Xmm15), op2));
+                sink.push(X64MCInstr::with2(mov, out, Operand::Reg(X64Reg::Xmm15)));
+
+                return;
+            }
+
             let mut variant = FInstrVariant::Normal;
 
             if let Operand::Reg(op1) = op1 {
@@ -30,6 +30,16 @@ pub(crate) fn x64_lower_fmove(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
     let op1: Operand = (*op1).into();
     let out: Operand = out.into();
 
+    // memory to memory moves go over xmm15
+    if let (Operand::Mem(_), Operand::Mem(_)) = (&op1, &out) {
+        let mne = if TypeMetadata::f32 == instr.meta { Mnemonic::Movss } else { Mnemonic::Movsd };
+
+        sink.push(X64MCInstr::with2(mne.to_owned(), Operand::Reg(X64Reg::Xmm15), op1));
+        sink.push(X64MCInstr::with2(mne, out, Operand::Reg(X64Reg::Xmm15)));
+
+        return;
+    }
+
     let mut variant = FInstrVariant::Normal;
 
     if let Operand::Reg(op1) = op1 {
@@ -109,6 +109,22 @@ pub(crate) fn x64_lower_neg(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     }
 }
 
+/// Returns the instruction which extends rax into rdx for a division
+/// (signed values are sign extended, unsigned values are zero extended)
+fn x64_rdx_prep(ty: TypeMetadata) -> X64MCInstr {
+    if ty.signed() {
+        X64MCInstr::with0(match ty {
+            TypeMetadata::i8 => Mnemonic::Cbw,
+            TypeMetadata::i16 => Mnemonic::Cwd,
+            TypeMetadata::i32 => Mnemonic::Cdq,
+            TypeMetadata::i64 => Mnemonic::Cqo,
+            _ => panic!("type {} was labeld as signed but shouldn't be", ty)
+        })
+    } else {
+        X64MCInstr::with2(Mnemonic::Xor, Operand::Reg(X64Reg::Rdx), Operand::Reg(X64Reg::Rdx))
+    }
+}
+
 pub(crate) fn x64_lower_div(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     // core logic:
 
@@ -142,29 +158,16 @@ pub(crate) fn x64_lower_div(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
         Mnemonic::Div
     };
 
-    let rdx_prep_instr = if instr.meta.signed() {
-        X64MCInstr::with0(match instr.meta {
-            TypeMetadata::i8 => Mnemonic::Cbw,
-            TypeMetadata::i16 => Mnemonic::Cwd,
-            TypeMetadata::i32 => Mnemonic::Cdq,
-            TypeMetadata::i64 => Mnemonic::Cqo,
-            _ => panic!("type {} was labeld as signed but shouldn't be", instr.meta)
-        })
-    } else {
-        X64MCInstr::with2(Mnemonic::Xor, Operand::Reg(X64Reg::Rdx), Operand::Reg(X64Reg::Rdx))
-    };
+    let rdx_prep_instr = x64_rdx_prep(instr.meta);
 
     let out_is_rdx = if let Operand::Reg(reg) = out { reg.sub64() == X64Reg::Rdx } else { false };
     
     if !out_is_rdx {
-        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R15), Operand::Reg(X64Reg::Rdx))); // save rdx
+        sink.push(X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rdx))); // save rdx
     }
 
     // assembly code is here
-    let div_instr = if op2.is_imm() {
-        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)), op2));
-        X64MCInstr::with1(div_mnemonic, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)))
-    } else if matches!(op2, Operand::Reg(X64Reg::Rdx) | Operand::Reg(X64Reg::Edx)) {
+    let div_instr = if op2.is_imm() || matches!(op2, Operand::Mem(_) | Operand::Reg(X64Reg::Rdx) | Operand::Reg(X64Reg::Edx)) {
         sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)), op2));
         X64MCInstr::with1(div_mnemonic, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)))
     } else {
@@ -179,17 +182,17 @@ pub(crate) fn x64_lower_div(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     ]);
 
     if !out_is_rdx {
-        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R15), Operand::Reg(X64Reg::Rdx))); // restore rdx
+        sink.push(X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rdx))); // restore rdx
     }
 }
 
 pub(crate) fn x64_lower_rem(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
-        let op1 = instr.operands.get(0).expect("expected a first operand");
+    let op1 = instr.operands.first().expect("expected a first operand");
     let op2 = instr.operands.get(1).expect("expected a second operand");
     let out = instr.out.expect("expected a output operand");
 
-    let mut op1 = (*op1).into();
-    let mut op2 = (*op2).into();
+    let op1: Operand = (*op1).into();
+    let op2: Operand = (*op2).into();
     let out: Operand = out.into();
 
     let mnemonic = if instr.meta.signed() {
@@ -198,61 +201,30 @@ pub(crate) fn x64_lower_rem(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
         Mnemonic::Div
     };
 
-    if out != Operand::Reg(X64Reg::Rdx.sub_ty(instr.meta)) {
-        sink.push( X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rdx)).into() );
-    }
-
-    let mut pop_rcx = false;
-    let mut pop_rsi = false;
-
-    if let Operand::Reg(reg) = op1 {
-        if X64Reg::Rdx == reg.sub_ty(TypeMetadata::i64) {
-            sink.push( X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rcx)));
-            sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rcx.sub_ty(instr.meta)), op1) );
-            op1 = Operand::Reg( X64Reg::Rcx.sub_ty(instr.meta) );
+    let rdx = Operand::Reg(X64Reg::Rdx.sub_ty(instr.meta));
 
-            pop_rcx = true;
-        }
-    } 
-    if let Operand::Reg(reg) = op2 {
-        if X64Reg::Rdx == reg.sub_ty(TypeMetadata::i64) {
-            sink.push( X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rsi)) );
-            sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rsi.sub_ty(instr.meta)), op2) );
-            op2 = Operand::Reg( X64Reg::Rsi.sub_ty(instr.meta) );
-
-            pop_rsi = true;
-        }
+    if out != rdx {
+        sink.push( X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rdx)) );
     }
 
-    sink.push( X64MCInstr::with2(Mnemonic::Xor, Operand::Reg(X64Reg::Rdx), Operand::Reg(X64Reg::Rdx)) );
-
-    let rax = || Operand::Reg(X64Reg::Rax.sub_ty(instr.meta));
+    sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), op1));
 
-    sink.push(X64MCInstr::with2(Mnemonic::Mov, rax(), op1.clone()));
-    
-    // mul/imul only accept r/m
-    if let Operand::Imm(_) = op2 {
-        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)), op2.clone()));
-        sink.push(X64MCInstr::with1(mnemonic, Operand::Reg(X64Reg::R11.sub_ty(instr.meta))));
-    } else if let Operand::Mem(_) = op2 {
-        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)), op2.clone()));
-        sink.push(X64MCInstr::with1(mnemonic, Operand::Reg(X64Reg::R11.sub_ty(instr.meta))));
+    // div/idiv only accept r/m and rdx gets overwritten by the extension of rax
+    let divisor = if op2.is_imm() || matches!(op2, Operand::Mem(_)) || op2 == rdx {
+        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11.sub_ty(instr.meta)), op2));
+        Operand::Reg(X64Reg::R11.sub_ty(instr.meta))
     } else {
-        sink.push(X64MCInstr::with1(mnemonic, op2.clone()));
-    }
-
-    if pop_rsi {
-        sink.push( X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rsi)));
-    }
-    
-    if pop_rcx {
-        sink.push( X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rcx)));
-    }
+        op2
+    };
 
-    sink.push(X64MCInstr::with2(Mnemonic::Mov, out.to_owned(), Operand::Reg(X64Reg::Rdx.sub_ty(instr.meta))));
+    sink.extend_from_slice(&[
+        x64_rdx_prep(instr.meta),
+        X64MCInstr::with1(mnemonic, divisor),
+        X64MCInstr::with2(Mnemonic::Mov, out.to_owned(), rdx.to_owned()),
+    ]);
 
-    if out != Operand::Reg(X64Reg::Rdx.sub_ty(instr.meta)) {
-        sink.push( X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rdx)).into() );
+    if out != rdx {
+        sink.push( X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rdx)) );
     }
 }
 
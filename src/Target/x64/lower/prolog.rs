@@ -2,37 +2,60 @@ use crate::CodeGen::{MachineInstr, MachineOperand};
 use crate::Target::x64::X64Reg;
 use crate::Target::x64::asm::instr::*;
 
+/// The frame looks like this:
+/// ```text
+/// [return adress]
+/// [callee saved registers]
+/// [old rbp]               <- rbp
+/// [stack variables]       (rbp - off)
+///                         <- rsp (16 byte aligned)
+/// ```
 pub(crate) fn x64_lower_prolog(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     //sink.push( X64MCInstr::with0(Mnemonic::Endbr64) );
 
+    let mut pushed = 0;
+
     for op in instr.operands.iter() { // we remove the stack_off
-        let MachineOperand::Reg(crate::CodeGen::Reg::x64(callee_save)) = op else { continue; }; 
-        
+        let MachineOperand::Reg(crate::CodeGen::Reg::x64(callee_save)) = op else { continue; };
+
         if callee_save.is_xmm() {
             sink.extend_from_slice(&[
-                X64MCInstr::with2(Mnemonic::Movq, Operand::Mem(MemOp { base: Some(X64Reg::Rsp), index: None, scale: 1, displ: 0, rip: false }), Operand::Reg(*callee_save)),
                 X64MCInstr::with2(Mnemonic::Sub, Operand::Reg(X64Reg::Rsp), Operand::Imm(8)),
+                X64MCInstr::with2(Mnemonic::Movq, Operand::Mem(MemOp { base: Some(X64Reg::Rsp), index: None, scale: 1, displ: 0, rip: false }), Operand::Reg(*callee_save)),
             ]);
         } else {
             sink.push(X64MCInstr::with1(Mnemonic::Push, Operand::Reg(*callee_save)));
         }
+
+        pushed += 1;
     }
 
     let Some(MachineOperand::Imm(stack_off)) = instr.operands.get(0) else { panic!("expected valid stack_off for prolog")};
     let stack_off = *stack_off as i64;
 
-    if stack_off > 0 {
-        sink.push( X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rbp) ) );
-        sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rbp), Operand::Reg(X64Reg::Rsp)  ) );
-        sink.push( X64MCInstr::with2(Mnemonic::Sub, Operand::Reg(X64Reg::Rbp),  Operand::Imm(stack_off)) );
+    // the stack_off starts with 8 and always points to the next free slot
+    let mut size = stack_off - 8;
+
+    // the return adress, the callee saved registers and rbp are also on the stack
+    while (size + 8 * (pushed + 2)) % 16 != 0 {
+        size += 8;
     }
 
+    sink.push( X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rbp) ) );
+    sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rbp), Operand::Reg(X64Reg::Rsp)  ) );
+
+    if size > 0 {
+        sink.push( X64MCInstr::with2(Mnemonic::Sub, Operand::Reg(X64Reg::Rsp),  Operand::Imm(size)) );
+    }
 }
 
 pub(crate) fn x64_lower_epilog(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    sink.push( X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rsp), Operand::Reg(X64Reg::Rbp)  ) );
+    sink.push( X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rbp) ) );
+
     for op in instr.operands.iter().rev() { // we remove the stack_off
-        let MachineOperand::Reg(crate::CodeGen::Reg::x64(callee_save)) = op else { continue; }; 
-        
+        let MachineOperand::Reg(crate::CodeGen::Reg::x64(callee_save)) = op else { continue; };
+
         if callee_save.is_xmm() {
             sink.extend_from_slice(&[
                 X64MCInstr::with2(Mnemonic::Movq, Operand::Reg(*callee_save), Operand::Mem(MemOp { base: Some(X64Reg::Rsp), index: None, scale: 1, displ: 0, rip: false })),
@@ -42,6 +65,4 @@ pub(crate) fn x64_lower_epilog(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
             sink.push(X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(*callee_save)));
         }
     }
-
-    sink.push( X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rbp) ) );
 }
@@ -1,15 +1,48 @@
 use crate::CodeGen::MachineInstr;
 use crate::Target::x64::X64Reg;
 use crate::Target::x64::asm::instr::*;
+use crate::IR::DataLayout;
 
 pub(crate) fn x64_lower_push(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     let input = instr.operands.get(0).expect("push needs an operand");
 
-    sink.push(
-        X64MCInstr::with1(Mnemonic::Push, (*input).into())
-    );
+    let input: Operand = (*input).into();
 
-    sink.push(X64MCInstr::with2(Mnemonic::Sub, Operand::Reg(X64Reg::Rsp), Operand::Imm(8))); // for 16 byte alignment
+    // aggregates are pushed from the adress in the operand (in eightbytes, so the first one is at the lowest adress)
+    if instr.meta.is_aggregate() {
+        let size = DataLayout::default().size(instr.meta) as isize;
+
+        if input != Operand::Reg(X64Reg::Rax) {
+            sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax), input));
+        }
+
+        for eightbyte in (0..(size + 7) / 8).rev() {
+            sink.extend_from_slice(&[
+                X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), Operand::Mem(MemOp { base: Some(X64Reg::Rax), index: None, scale: 1, displ: eightbyte * 8, rip: false })),
+                X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::R11)),
+            ]);
+        }
+
+        return;
+    }
+
+    match input {
+        // fp registers can't be pushed
+        Operand::Reg(reg) if reg.is_xmm() => sink.extend_from_slice(&[
+            X64MCInstr::with2(Mnemonic::Sub, Operand::Reg(X64Reg::Rsp), Operand::Imm(8)),
+            X64MCInstr::with2(Mnemonic::Movq, Operand::Mem(MemOp { base: Some(X64Reg::Rsp), index: None, scale: 1, displ: 0, rip: false }), Operand::Reg(reg)),
+        ]),
+        // every push is 8 bytes wide
+        Operand::Reg(reg) => sink.push(X64MCInstr::with1(Mnemonic::Push, Operand::Reg(reg.sub64()))),
+        // push only takes sign extended 32 bit immediates
+        Operand::Imm(imm) if i32::try_from(imm).is_ok() => sink.push(X64MCInstr::with1(Mnemonic::Push, input)),
+        // the stack slots are 8 bytes wide (r11 isn't allocated, so it doesn't hold a live value)
+        Operand::Mem(_) | Operand::Imm(_) => sink.extend_from_slice(&[
+            X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), input),
+            X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::R11)),
+        ]),
+        _ => sink.push(X64MCInstr::with1(Mnemonic::Push, input)),
+    }
 }
 
 pub(crate) fn x64_lower_push_cleanup(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
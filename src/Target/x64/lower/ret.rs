@@ -1,7 +1,6 @@
 use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand, Reg};
 use crate::Target::x64::asm::instr::*;
 use crate::Target::x64::X64Reg;
-use crate::IR::TypeMetadata;
 
 use super::fmove::x64_lower_fmove;
 
@@ -15,13 +14,15 @@ pub(crate) fn x64_lower_return(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
 
         x64_lower_fmove(sink, &instr);
     } else {
-        let mut mne = Mnemonic::Mov;
+        let rax = Operand::Reg(X64Reg::Rax.sub_ty(instr.meta));
+        let op: Operand = (*op).into();
 
-        if instr.meta == TypeMetadata::ptr {
-            mne = Mnemonic::Lea;
-        }
+        // vectors are returned in xmm0
+        let mov = if instr.meta.is_vector() { Mnemonic::Movups } else { Mnemonic::Mov };
 
-        sink.push( X64MCInstr::with2(mne, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), (*op).into()));
+        if op != rax {
+            sink.push( X64MCInstr::with2(mov, rax, op));
+        }
     }
 
     sink.push( X64MCInstr::with0(Mnemonic::Ret).into() );
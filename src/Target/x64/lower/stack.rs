@@ -1,7 +1,7 @@
 use crate::CodeGen::{MachineInstr, MachineOperand};
 use crate::Target::x64::X64Reg;
 use crate::Target::x64::asm::instr::*;
-use crate::IR::TypeMetadata;
+use crate::IR::{DataLayout, TypeMetadata};
 
 pub(crate) fn x64_lower_salloc(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     let out = instr.out.expect("stack allocations need outputs");
@@ -35,12 +35,21 @@ pub(crate) fn x64_lower_store(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
     let ptr = ptr.into();
     let value = (*value).into();
 
+    // the value is moved through a scratch register which isn't the pointer
+    let tmp = if ptr == Operand::Reg(X64Reg::Rax) { X64Reg::R11 } else { X64Reg::Rax };
+
+    // the optional second operand is a displacement for pointers in registers
+    let displ = match instr.operands.get(1) {
+        Some(MachineOperand::Imm(displ)) => *displ as isize,
+        _ => 0,
+    };
+
     if let Operand::Reg(ptr) = ptr {
         let ptr = Operand::Mem(MemOp {
             base: Some(ptr),
             index: None,
             scale: 1,
-            displ: 0,
+            displ: displ,
             rip: false,
         }); 
 
@@ -75,8 +84,8 @@ pub(crate) fn x64_lower_store(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr)
                 }
             } else {
                 sink.extend_from_slice(&[
-                    X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)), value),
-                    X64MCInstr::with2(Mnemonic::Mov, ptr, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta)))
+                    X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(tmp.sub_ty(instr.meta)), value),
+                    X64MCInstr::with2(Mnemonic::Mov, ptr, Operand::Reg(tmp.sub_ty(instr.meta)))
                 ])
             }
         }
@@ -123,17 +132,23 @@ pub(crate) fn x64_lower_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     
     let out = out.into();
 
+    // the optional second operand is a displacement for pointers in registers
+    let displ = match instr.operands.get(1) {
+        Some(MachineOperand::Imm(displ)) => *displ as isize,
+        _ => 0,
+    };
+
+    // a pointer in a register is dereferenced (stack slots are accessed directly)
+    let src = match ptr {
+        Operand::Reg(ptr) => Operand::Mem(MemOp { base: Some(ptr), index: None, scale: 1, displ: displ, rip: false }),
+        _ => ptr.clone(),
+    };
+
     if let Operand::Reg(out_reg) = out {
         if !out_reg.is_xmm() {
-            if let Operand::Reg(ptr) = ptr {
+            if let Operand::Reg(_) = ptr {
                 sink.push( 
-                    X64MCInstr::with2(Mnemonic::Mov, out, Operand::Mem(MemOp {
-                        base: Some(ptr),
-                        index: None,
-                        scale: 1,
-                        displ: 0,
-                        rip: false,
-                    }))
+                    X64MCInstr::with2(Mnemonic::Mov, out, src)
                 )
             } else {
                 sink.push( 
@@ -142,15 +157,9 @@ pub(crate) fn x64_lower_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
             }
         } else {
             if instr.meta == TypeMetadata::f32 {
-                if let Operand::Reg(ptr) = ptr {
+                if let Operand::Reg(_) = ptr {
                     sink.push( 
-                        X64MCInstr::with2(Mnemonic::Movd, out, Operand::Mem(MemOp {
-                            base: Some(ptr),
-                            index: None,
-                            scale: 1,
-                            displ: 0,
-                            rip: false,
-                        }))
+                        X64MCInstr::with2(Mnemonic::Movd, out, src)
                     )
                 } else {
                     sink.push( 
@@ -158,15 +167,9 @@ pub(crate) fn x64_lower_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
                     );
                 }
             } else { // xmm now should have a f64 cuz of check for f32
-                if let Operand::Reg(ptr) = ptr {
+                if let Operand::Reg(_) = ptr {
                     sink.push( 
-                        X64MCInstr::with2(Mnemonic::Movq, out, Operand::Mem(MemOp {
-                            base: Some(ptr),
-                            index: None,
-                            scale: 1,
-                            displ: 0,
-                            rip: false,
-                        }))
+                        X64MCInstr::with2(Mnemonic::Movq, out, src)
                     )
                 } else {
                     sink.push( 
@@ -180,14 +183,14 @@ pub(crate) fn x64_lower_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
         if instr.meta.float() {
             if instr.meta == TypeMetadata::f32 {
                 sink.push( 
-                    X64MCInstr::with2(Mnemonic::Movd, Operand::Reg(X64Reg::Xmm15), ptr)
+                    X64MCInstr::with2(Mnemonic::Movd, Operand::Reg(X64Reg::Xmm15), src)
                 );
                 sink.push(
                     X64MCInstr::with2(Mnemonic::Movd, out, Operand::Reg(X64Reg::Xmm15))
                 );
             } else {
                 sink.push( 
-                    X64MCInstr::with2(Mnemonic::Movq, Operand::Reg(X64Reg::Xmm15), ptr)
+                    X64MCInstr::with2(Mnemonic::Movq, Operand::Reg(X64Reg::Xmm15), src)
                 );
                 sink.push(
                     X64MCInstr::with2(Mnemonic::Movq, out, Operand::Reg(X64Reg::Xmm15))
@@ -195,7 +198,7 @@ pub(crate) fn x64_lower_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
             }
         } else {
             sink.push( 
-                X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta).sub_ty(instr.meta)), ptr)
+                X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta).sub_ty(instr.meta)), src)
             );
         
             sink.push( 
@@ -205,3 +208,39 @@ pub(crate) fn x64_lower_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     }
 
 }
+
+/// Copies the aggregate over `r11` (destination) and `rax` (source) with `rcx` as the data register
+pub(crate) fn x64_lower_memcpy(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let dst: Operand = instr.out.expect("memcpy needs a destination").into();
+    let src: Operand = (*instr.operands.first().expect("memcpy needs a source")).into();
+
+    let size = DataLayout::default().size(instr.meta);
+
+    sink.extend_from_slice(&[
+        X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::R11), dst),
+        X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Rax), src),
+        X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::Rcx)),
+    ]);
+
+    let mut offset = 0;
+
+    while offset < size {
+        let (chunk, data) = match size - offset {
+            8.. => (8, X64Reg::Rcx),
+            4..=7 => (4, X64Reg::Ecx),
+            2..=3 => (2, X64Reg::Cx),
+            _ => (1, X64Reg::Cl),
+        };
+
+        let at = |base| Operand::Mem(MemOp { base: Some(base), index: None, scale: 1, displ: offset as isize, rip: false });
+
+        sink.extend_from_slice(&[
+            X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(data), at(X64Reg::Rax)),
+            X64MCInstr::with2(Mnemonic::Mov, at(X64Reg::R11), Operand::Reg(data)),
+        ]);
+
+        offset += chunk;
+    }
+
+    sink.push(X64MCInstr::with1(Mnemonic::Pop, Operand::Reg(X64Reg::Rcx)));
+}
@@ -0,0 +1,176 @@
+use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand, Reg};
+use crate::Target::x64::X64Reg;
+use crate::Target::x64::asm::instr::*;
+use crate::Target::CallConv;
+use crate::IR::TypeMetadata;
+
+use super::x64_lower_instr;
+
+/// Returns if the instruction works on a vector
+pub(crate) fn x64_is_vector(instr: &MachineInstr) -> bool {
+    instr.meta.is_vector() && matches!(instr.mnemonic,
+        MachineMnemonic::Move | MachineMnemonic::Load | MachineMnemonic::Store | MachineMnemonic::Push |
+        MachineMnemonic::Add | MachineMnemonic::Sub | MachineMnemonic::Mul | MachineMnemonic::Div | MachineMnemonic::Rem |
+        MachineMnemonic::And | MachineMnemonic::Or | MachineMnemonic::Xor | MachineMnemonic::Shl | MachineMnemonic::Shr |
+        MachineMnemonic::Neg | MachineMnemonic::MovIfZero | MachineMnemonic::MovIfNotZero
+    )
+}
+
+/// Vectors are lowered to packed SSE2 instructions:
+///  - their variables live in 16 byte stack slots (the first lane is at the lowest adress)
+///  - the operations work on `xmm15` and `xmm14`
+///  - operations without a packed instruction are split into operations on the lanes
+pub(crate) fn x64_lower_vector(conv: CallConv, sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let Some((elem, _)) = instr.meta.vector_lanes() else { unreachable!() };
+
+    if instr.operands.iter().any(|op| matches!(op, MachineOperand::Imm(_))) && instr.mnemonic != MachineMnemonic::Load {
+        return x64_lower_lanes(conv, sink, instr);
+    }
+
+    let packed = match (&instr.mnemonic, elem) {
+        (MachineMnemonic::Move, _) => return x64_lower_vector_move(sink, instr),
+        (MachineMnemonic::Load, _) => return x64_lower_vector_load(sink, instr),
+        (MachineMnemonic::Store, _) => return x64_lower_vector_store(sink, instr),
+        (MachineMnemonic::Push, _) => todo!("vectors can't be passed over the stack yet"),
+
+        (MachineMnemonic::Add, TypeMetadata::f32) => Mnemonic::Addps,
+        (MachineMnemonic::Add, TypeMetadata::f64) => Mnemonic::Addpd,
+        (MachineMnemonic::Sub, TypeMetadata::f32) => Mnemonic::Subps,
+        (MachineMnemonic::Sub, TypeMetadata::f64) => Mnemonic::Subpd,
+        (MachineMnemonic::Mul, TypeMetadata::f32) => Mnemonic::Mulps,
+        (MachineMnemonic::Mul, TypeMetadata::f64) => Mnemonic::Mulpd,
+        (MachineMnemonic::Div, TypeMetadata::f32) => Mnemonic::Divps,
+        (MachineMnemonic::Div, TypeMetadata::f64) => Mnemonic::Divpd,
+
+        (MachineMnemonic::Add, elem) if !elem.float() => padd(elem),
+        (MachineMnemonic::Sub, elem) if !elem.float() => psub(elem),
+        (MachineMnemonic::Mul, TypeMetadata::i16 | TypeMetadata::u16) => Mnemonic::Pmullw,
+
+        (MachineMnemonic::And, _) => Mnemonic::Pand,
+        (MachineMnemonic::Or, _) => Mnemonic::Por,
+        (MachineMnemonic::Xor, _) => Mnemonic::Pxor,
+
+        // -x = 0 - x
+        (MachineMnemonic::Neg, elem) if !elem.float() => {
+            let op = vector(instr.operands.first().expect("expected a operand"));
+            let out = vector(&instr.out.expect("expected a output operand"));
+
+            sink.extend_from_slice(&[
+                X64MCInstr::with2(Mnemonic::Pxor, Operand::Reg(X64Reg::Xmm15), Operand::Reg(X64Reg::Xmm15)),
+                X64MCInstr::with2(Mnemonic::Movups, Operand::Reg(X64Reg::Xmm14), op),
+                X64MCInstr::with2(psub(elem), Operand::Reg(X64Reg::Xmm15), Operand::Reg(X64Reg::Xmm14)),
+                X64MCInstr::with2(Mnemonic::Movups, out, Operand::Reg(X64Reg::Xmm15)),
+            ]);
+
+            return;
+        },
+
+        _ => return x64_lower_lanes(conv, sink, instr),
+    };
+
+    let ls = vector(instr.operands.first().expect("expected a first operand"));
+    let rs = vector(instr.operands.get(1).expect("expected a second operand"));
+    let out = vector(&instr.out.expect("expected a output operand"));
+
+    sink.extend_from_slice(&[
+        X64MCInstr::with2(Mnemonic::Movups, Operand::Reg(X64Reg::Xmm15), ls),
+        X64MCInstr::with2(Mnemonic::Movups, Operand::Reg(X64Reg::Xmm14), rs),
+        X64MCInstr::with2(packed, Operand::Reg(X64Reg::Xmm15), Operand::Reg(X64Reg::Xmm14)),
+        X64MCInstr::with2(Mnemonic::Movups, out, Operand::Reg(X64Reg::Xmm15)),
+    ]);
+}
+
+fn padd(elem: TypeMetadata) -> Mnemonic {
+    match elem.byteSize() {
+        1 => Mnemonic::Paddb,
+        2 => Mnemonic::Paddw,
+        4 => Mnemonic::Paddd,
+        _ => Mnemonic::Paddq,
+    }
+}
+
+fn psub(elem: TypeMetadata) -> Mnemonic {
+    match elem.byteSize() {
+        1 => Mnemonic::Psubb,
+        2 => Mnemonic::Psubw,
+        4 => Mnemonic::Psubd,
+        _ => Mnemonic::Psubq,
+    }
+}
+
+/// Splits the instruction into instructions on the lanes and lowers them
+fn x64_lower_lanes(conv: CallConv, sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    for mut lane in instr.scalarize() {
+        lane.turn_into_float_if_needed();
+
+        x64_lower_instr(conv, sink, lane);
+    }
+}
+
+/// Returns the operand for the vector (which is in a stack slot or a xmm register)
+fn vector(op: &MachineOperand) -> Operand {
+    match *op {
+        MachineOperand::Stack(off, _) => slot(off),
+        MachineOperand::Reg(Reg::x64(reg)) if reg.is_xmm() => Operand::Reg(reg),
+        op => panic!("vectors live in stack slots or xmm registers and not in {:?}", op),
+    }
+}
+
+fn slot(off: i64) -> Operand {
+    Operand::Mem(MemOp { base: Some(X64Reg::Rbp), index: None, scale: 1, displ: -(off as isize), rip: false })
+}
+
+/// Returns the memory the pointer points to (stack slots are accessed directly)
+fn mem(ptr: &MachineOperand, displ: isize) -> Operand {
+    match ptr {
+        MachineOperand::Reg(Reg::x64(ptr)) => Operand::Mem(MemOp { base: Some(*ptr), index: None, scale: 1, displ: displ, rip: false }),
+        MachineOperand::Stack(off, _) => slot(*off - displ as i64),
+        ptr => panic!("expected a pointer and not {:?}", ptr),
+    }
+}
+
+fn x64_lower_vector_move(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let src = vector(instr.operands.first().expect("expected a operand"));
+    let out = vector(&instr.out.expect("expected a output operand"));
+
+    if src == out {
+        return;
+    }
+
+    if let (Operand::Reg(_), _) | (_, Operand::Reg(_)) = (&src, &out) {
+        sink.push(X64MCInstr::with2(Mnemonic::Movups, out, src));
+        return;
+    }
+
+    sink.extend_from_slice(&[
+        X64MCInstr::with2(Mnemonic::Movups, Operand::Reg(X64Reg::Xmm15), src),
+        X64MCInstr::with2(Mnemonic::Movups, out, Operand::Reg(X64Reg::Xmm15)),
+    ]);
+}
+
+fn x64_lower_vector_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let ptr = instr.operands.first().expect("expected a pointer");
+
+    // the optional second operand is a displacement for pointers in registers
+    let displ = match instr.operands.get(1) {
+        Some(MachineOperand::Imm(displ)) => *displ as isize,
+        _ => 0,
+    };
+
+    let out = vector(&instr.out.expect("expected a output operand"));
+
+    sink.extend_from_slice(&[
+        X64MCInstr::with2(Mnemonic::Movups, Operand::Reg(X64Reg::Xmm15), mem(ptr, displ)),
+        X64MCInstr::with2(Mnemonic::Movups, out, Operand::Reg(X64Reg::Xmm15)),
+    ]);
+}
+
+fn x64_lower_vector_store(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let value = vector(instr.operands.first().expect("expected a value"));
+    let dst = mem(&instr.out.expect("expected a pointer"), 0);
+
+    sink.extend_from_slice(&[
+        X64MCInstr::with2(Mnemonic::Movups, Operand::Reg(X64Reg::Xmm15), value),
+        X64MCInstr::with2(Mnemonic::Movups, dst, Operand::Reg(X64Reg::Xmm15)),
+    ]);
+}
@@ -0,0 +1,267 @@
+use crate::prelude::CmpMode;
+use crate::CodeGen::{MachineInstr, MachineMnemonic, MachineOperand, Reg};
+use crate::Target::x64::X64Reg;
+use crate::Target::x64::asm::instr::*;
+use crate::IR::TypeMetadata;
+
+use super::zext;
+
+/// Returns if the instruction works on 128 bit integers
+pub(crate) fn x64_is_wide(instr: &MachineInstr) -> bool {
+    instr.meta.is_int128()
+}
+
+/// 128 bit integers are legalized into pairs of eightbytes:
+///  - their variables live in 16 byte stack slots (the lower half is at the lower adress)
+///  - the operations work on the halves over `rax` and `r11` (the carry goes from the lower into the upper half)
+pub(crate) fn x64_lower_wide(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    match &instr.mnemonic {
+        MachineMnemonic::Move => x64_lower_wide_move(sink, instr),
+        MachineMnemonic::Add => x64_lower_wide_carry(sink, instr, Mnemonic::Add, Mnemonic::Adc),
+        MachineMnemonic::Sub => x64_lower_wide_carry(sink, instr, Mnemonic::Sub, Mnemonic::Sbb),
+        MachineMnemonic::And => x64_lower_wide_carry(sink, instr, Mnemonic::And, Mnemonic::And),
+        MachineMnemonic::Or => x64_lower_wide_carry(sink, instr, Mnemonic::Or, Mnemonic::Or),
+        MachineMnemonic::Xor => x64_lower_wide_carry(sink, instr, Mnemonic::Xor, Mnemonic::Xor),
+        MachineMnemonic::Neg => x64_lower_wide_neg(sink, instr),
+        MachineMnemonic::Mul => x64_lower_wide_mul(sink, instr),
+        MachineMnemonic::Compare(mode) => x64_lower_wide_cmp(sink, instr, mode),
+        MachineMnemonic::Zext(from) => x64_lower_wide_zext(sink, instr, *from),
+        MachineMnemonic::Load => x64_lower_wide_load(sink, instr),
+        MachineMnemonic::Store => x64_lower_wide_store(sink, instr),
+        MachineMnemonic::Push => x64_lower_wide_push(sink, instr),
+        _ => todo!("128 bit integers aren't supported for {}", instr.mnemonic),
+    }
+}
+
+/// Returns the lower and the upper half of the 128 bit operand
+fn halves(op: &MachineOperand) -> (Operand, Operand) {
+    match *op {
+        MachineOperand::Stack(off, _) => (slot(off), slot(off - 8)),
+        MachineOperand::Imm(imm) => {
+            let imm = imm as i128;
+
+            (Operand::Imm(imm as i64), Operand::Imm((imm >> 64) as i64))
+        },
+        MachineOperand::Reg(reg) => panic!("128 bit integers live on the stack and not in {:?}", reg),
+    }
+}
+
+fn slot(off: i64) -> Operand {
+    Operand::Mem(MemOp { base: Some(X64Reg::Rbp), index: None, scale: 1, displ: -(off as isize), rip: false })
+}
+
+/// Returns the halves of the memory the pointer points to (stack slots are accessed directly)
+fn mem_halves(ptr: &MachineOperand, displ: isize) -> (Operand, Operand) {
+    match ptr {
+        MachineOperand::Reg(Reg::x64(ptr)) => {
+            let at = |displ| Operand::Mem(MemOp { base: Some(*ptr), index: None, scale: 1, displ: displ, rip: false });
+
+            (at(displ), at(displ + 8))
+        },
+        ptr => halves(ptr),
+    }
+}
+
+fn mov(sink: &mut Vec<X64MCInstr>, dst: Operand, src: Operand) {
+    sink.push(X64MCInstr::with2(Mnemonic::Mov, dst, src));
+}
+
+fn operands(instr: &MachineInstr) -> (MachineOperand, MachineOperand, MachineOperand) {
+    let ls = *instr.operands.first().expect("expected a first operand");
+    let rs = *instr.operands.get(1).expect("expected a second operand");
+    let out = instr.out.expect("expected a output operand");
+
+    (ls, rs, out)
+}
+
+fn x64_lower_wide_move(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let src = halves(instr.operands.first().expect("expected a operand"));
+    let out = halves(&instr.out.expect("expected a output operand"));
+
+    mov(sink, Operand::Reg(X64Reg::R11), src.0);
+    mov(sink, out.0, Operand::Reg(X64Reg::R11));
+    mov(sink, Operand::Reg(X64Reg::R11), src.1);
+    mov(sink, out.1, Operand::Reg(X64Reg::R11));
+}
+
+/// The upper halves are combined with the second mnemonic (which adds the carry of the lower halves for `adc` and `sbb`)
+///
+/// The halves are combined in registers, so the optimizer doesn't turn the first operation into a `lea` (which loses the carry)
+fn x64_lower_wide_carry(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, lo: Mnemonic, hi: Mnemonic) {
+    let (ls, rs, out) = operands(instr);
+
+    let (ls, rs, out) = (halves(&ls), halves(&rs), halves(&out));
+
+    for (mnemonic, ls, rs, out) in [(lo, ls.0, rs.0, out.0), (hi, ls.1, rs.1, out.1)] {
+        mov(sink, Operand::Reg(X64Reg::Rax), ls);
+        mov(sink, Operand::Reg(X64Reg::R11), rs);
+        sink.push(X64MCInstr::with2(mnemonic, Operand::Reg(X64Reg::Rax), Operand::Reg(X64Reg::R11)));
+        mov(sink, out, Operand::Reg(X64Reg::Rax));
+    }
+}
+
+/// `-x` is `0 - x`: the upper half is `0 - hi - borrow` (`neg` borrows if the lower half isn't zero)
+fn x64_lower_wide_neg(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let src = halves(instr.operands.first().expect("expected a operand"));
+    let out = halves(&instr.out.expect("expected a output operand"));
+
+    mov(sink, Operand::Reg(X64Reg::R11), src.1);
+    mov(sink, Operand::Reg(X64Reg::Rax), src.0);
+    sink.push(X64MCInstr::with1(Mnemonic::Neg, Operand::Reg(X64Reg::Rax)));
+    mov(sink, out.0, Operand::Reg(X64Reg::Rax));
+    mov(sink, Operand::Reg(X64Reg::Rax), Operand::Imm(0));
+    sink.push(X64MCInstr::with2(Mnemonic::Sbb, Operand::Reg(X64Reg::Rax), Operand::Reg(X64Reg::R11)));
+    mov(sink, out.1, Operand::Reg(X64Reg::Rax));
+}
+
+/// The lower 128 bits of the product are:
+/// `lo = lo(a.lo * b.lo)` and `hi = hi(a.lo * b.lo) + a.lo * b.hi + a.hi * b.lo`
+fn x64_lower_wide_mul(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let (ls, rs, out) = operands(instr);
+
+    let (ls, rs, out) = (halves(&ls), halves(&rs), halves(&out));
+
+    let (rax, rdx, r11) = (Operand::Reg(X64Reg::Rax), Operand::Reg(X64Reg::Rdx), Operand::Reg(X64Reg::R11));
+
+    sink.push(X64MCInstr::with1(Mnemonic::Push, rdx.clone()));
+
+    // the cross products
+    mov(sink, r11.clone(), rs.1);
+    mov(sink, rax.clone(), ls.0.clone());
+    sink.push(X64MCInstr::with2(Mnemonic::Imul, rax.clone(), r11.clone()));
+    mov(sink, r11.clone(), rs.0);
+    mov(sink, rdx.clone(), ls.1);
+    sink.push(X64MCInstr::with2(Mnemonic::Imul, rdx.clone(), r11.clone()));
+    sink.push(X64MCInstr::with2(Mnemonic::Add, rdx.clone(), rax.clone()));
+    sink.push(X64MCInstr::with1(Mnemonic::Push, rdx.clone()));
+
+    // rdx:rax = a.lo * b.lo
+    mov(sink, rax.clone(), ls.0);
+    sink.push(X64MCInstr::with1(Mnemonic::Mul, r11.clone()));
+
+    sink.push(X64MCInstr::with1(Mnemonic::Pop, r11.clone()));
+    sink.push(X64MCInstr::with2(Mnemonic::Add, rdx.clone(), r11));
+
+    mov(sink, out.0, rax);
+    mov(sink, out.1, rdx.clone());
+
+    sink.push(X64MCInstr::with1(Mnemonic::Pop, rdx));
+}
+
+/// Equality is checked over `(a.lo ^ b.lo) | (a.hi ^ b.hi)`.
+/// The other comparisons subtract the values (`cmp` and `sbb`) and use the flags of the upper half.
+fn x64_lower_wide_cmp(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, mode: &CmpMode) {
+    let (ls, rs, out) = operands(instr);
+
+    let out = match out.into() {
+        Operand::Reg(reg) => Operand::Reg(reg.sub8()),
+        out => out,
+    };
+
+    let (rax, r11) = (Operand::Reg(X64Reg::Rax), Operand::Reg(X64Reg::R11));
+
+    let signed = instr.meta.signed();
+
+    // `a > b` is `b < a` and `a <= b` is `b >= a`
+    let ((x, y), mnemonic) = match (mode, signed) {
+        (CmpMode::Eqal, _) => ((ls, rs), Mnemonic::Sete),
+        (CmpMode::NotEqal, _) => ((ls, rs), Mnemonic::Setne),
+        (CmpMode::LessThan, true) => ((ls, rs), Mnemonic::Setl),
+        (CmpMode::GreaterThanOrEqual, true) => ((ls, rs), Mnemonic::Setge),
+        (CmpMode::GreaterThan, true) => ((rs, ls), Mnemonic::Setl),
+        (CmpMode::LessThanOrEqual, true) => ((rs, ls), Mnemonic::Setge),
+        (CmpMode::LessThan, false) => ((ls, rs), Mnemonic::Setb),
+        (CmpMode::GreaterThanOrEqual, false) => ((ls, rs), Mnemonic::Setae),
+        (CmpMode::GreaterThan, false) => ((rs, ls), Mnemonic::Setb),
+        (CmpMode::LessThanOrEqual, false) => ((rs, ls), Mnemonic::Setae),
+    };
+
+    let (x, y) = (halves(&x), halves(&y));
+
+    if matches!(mode, CmpMode::Eqal | CmpMode::NotEqal) {
+        mov(sink, rax.clone(), x.1);
+        mov(sink, r11.clone(), y.1);
+        sink.push(X64MCInstr::with2(Mnemonic::Xor, rax.clone(), r11.clone()));
+        sink.push(X64MCInstr::with1(Mnemonic::Push, rax.clone()));
+        mov(sink, rax.clone(), x.0);
+        mov(sink, r11.clone(), y.0);
+        sink.push(X64MCInstr::with2(Mnemonic::Xor, rax.clone(), r11.clone()));
+        sink.push(X64MCInstr::with1(Mnemonic::Pop, r11.clone()));
+        sink.push(X64MCInstr::with2(Mnemonic::Or, rax, r11));
+    } else {
+        mov(sink, rax.clone(), x.0);
+        mov(sink, r11.clone(), y.0);
+        sink.push(X64MCInstr::with2(Mnemonic::Cmp, rax.clone(), r11.clone()));
+        mov(sink, rax.clone(), x.1);
+        mov(sink, r11.clone(), y.1);
+        sink.push(X64MCInstr::with2(Mnemonic::Sbb, rax, r11));
+    }
+
+    sink.push(X64MCInstr::with1(mnemonic, out));
+}
+
+/// The value is extended into the lower half, the upper half is filled with its sign (or zeros)
+fn x64_lower_wide_zext(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr, from: TypeMetadata) {
+    let out = halves(&instr.out.expect("expected a output operand"));
+
+    let mut lo = instr.to_owned();
+    lo.mnemonic = MachineMnemonic::Zext(from);
+    lo.set_out(MachineOperand::Reg(Reg::x64(X64Reg::Rax)));
+    lo.meta = if instr.meta.signed() { TypeMetadata::i64 } else { TypeMetadata::u64 };
+
+    zext::x64_lower_zext(sink, &lo);
+
+    let r11 = Operand::Reg(X64Reg::R11);
+
+    mov(sink, out.0.clone(), Operand::Reg(X64Reg::Rax));
+
+    if instr.meta.signed() {
+        // `add` shifts the sign into the carry, `sbb` turns it into 0 or -1
+        // (the lower half is reloaded, because the optimizer treats `rax` as dead after it got stored)
+        mov(sink, r11.clone(), out.0);
+        sink.push(X64MCInstr::with2(Mnemonic::Add, r11.clone(), r11.clone()));
+        sink.push(X64MCInstr::with2(Mnemonic::Sbb, r11.clone(), r11.clone()));
+    } else {
+        mov(sink, r11.clone(), Operand::Imm(0));
+    }
+
+    mov(sink, out.1, r11);
+}
+
+fn x64_lower_wide_load(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let ptr = instr.operands.first().expect("expected a pointer");
+
+    // the optional second operand is a displacement for pointers in registers
+    let displ = match instr.operands.get(1) {
+        Some(MachineOperand::Imm(displ)) => *displ as isize,
+        _ => 0,
+    };
+
+    let src = mem_halves(ptr, displ);
+    let out = halves(&instr.out.expect("expected a output operand"));
+
+    mov(sink, Operand::Reg(X64Reg::R11), src.0);
+    mov(sink, out.0, Operand::Reg(X64Reg::R11));
+    mov(sink, Operand::Reg(X64Reg::R11), src.1);
+    mov(sink, out.1, Operand::Reg(X64Reg::R11));
+}
+
+fn x64_lower_wide_store(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let value = halves(instr.operands.first().expect("expected a value"));
+    let dst = mem_halves(&instr.out.expect("expected a pointer"), 0);
+
+    mov(sink, Operand::Reg(X64Reg::R11), value.0);
+    mov(sink, dst.0, Operand::Reg(X64Reg::R11));
+    mov(sink, Operand::Reg(X64Reg::R11), value.1);
+    mov(sink, dst.1, Operand::Reg(X64Reg::R11));
+}
+
+/// The upper half is pushed first, so the lower half is at the lower adress
+fn x64_lower_wide_push(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
+    let value = halves(instr.operands.first().expect("expected a operand"));
+
+    for half in [value.1, value.0] {
+        mov(sink, Operand::Reg(X64Reg::R11), half);
+        sink.push(X64MCInstr::with1(Mnemonic::Push, Operand::Reg(X64Reg::R11)));
+    }
+}
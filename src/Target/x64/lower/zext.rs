@@ -1,4 +1,5 @@
-use crate::CodeGen::MachineInstr;
+use crate::CodeGen::{MachineInstr, MachineMnemonic};
+use crate::IR::TypeMetadata;
 use crate::Target::x64::X64Reg;
 use crate::Target::x64::asm::instr::*;
 
@@ -12,6 +13,38 @@ pub(crate) fn x64_lower_zext(sink: &mut Vec<X64MCInstr>, instr: &MachineInstr) {
     
     let out = out.into();
 
+    // booleans are always zero extended (a true `i1` is 1 and not -1)
+    if matches!(instr.mnemonic, MachineMnemonic::Zext(TypeMetadata::i1)) {
+        let op1 = match op1 {
+            Operand::Reg(reg) => Operand::Reg(reg.sub8()),
+            op1 => op1,
+        };
+
+        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Al), op1));
+        sink.push(X64MCInstr::with2(Mnemonic::Movzx, Operand::Reg(X64Reg::Eax), Operand::Reg(X64Reg::Al)));
+        sink.push(X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax.sub_ty(instr.meta))));
+
+        return;
+    }
+
+    // 32 bit values are sign extended with movsxd and zero extended by a 32 bit mov
+    if matches!(instr.mnemonic, MachineMnemonic::Zext(from) if from.bitSize() == 32) && instr.meta.bitSize() == 64 {
+        let op1 = match op1 {
+            Operand::Reg(reg) => Operand::Reg(reg.sub32()),
+            op1 => op1,
+        };
+        
+        sink.push(X64MCInstr::with2(Mnemonic::Mov, Operand::Reg(X64Reg::Eax), op1));
+
+        if instr.meta.signed() {
+            sink.push(X64MCInstr::with2(Mnemonic::Movsx, Operand::Reg(X64Reg::Rax), Operand::Reg(X64Reg::Eax)));
+        }
+
+        sink.push(X64MCInstr::with2(Mnemonic::Mov, out, Operand::Reg(X64Reg::Rax)));
+
+        return;
+    }
+
     if let Operand::Reg(op1) = op1 {
         if let Operand::Reg(out) = out {
             if (op1.is_gr16() | op1.is_gr8()) && (out.is_gr32() | out.is_gr64()) { // movxz allowes a gr8/16 zext into gr32/64
@@ -97,6 +97,11 @@ fn X64MergeMove(instr0: &X64MCInstr, instr1: &X64MCInstr) -> Option<Vec<X64MCIns
         return None;
     }
 
+    // a 32 bit mov clears the upper half of rax, so `mov eax, ...` followed by `mov ..., rax` isn't a plain copy
+    if instr0.op1 != instr1.op2 {
+        return None;
+    }
+
     Some(vec![X64MCInstr {
         mnemonic: Mnemonic::Mov,
         op1: instr1.op1.clone(),
@@ -238,7 +243,11 @@ fn X64MergeBrCond(instr0: &X64MCInstr, instr1: &X64MCInstr, instr2: &X64MCInstr)
          instr0.is_setg()  || 
          instr0.is_setl()  || 
          instr0.is_setge() || 
-         instr0.is_setle()) {
+         instr0.is_setle() ||
+         instr0.is_seta()  ||
+         instr0.is_setb()  ||
+         instr0.is_setae() ||
+         instr0.is_setbe()) {
         return None;
     }
 
@@ -257,6 +266,10 @@ fn X64MergeBrCond(instr0: &X64MCInstr, instr1: &X64MCInstr, instr2: &X64MCInstr)
         Mnemonic::Setl => Mnemonic::Jl,
         Mnemonic::Setge => Mnemonic::Jge,
         Mnemonic::Setle => Mnemonic::Jle,
+        Mnemonic::Seta => Mnemonic::Ja,
+        Mnemonic::Setb => Mnemonic::Jb,
+        Mnemonic::Setae => Mnemonic::Jae,
+        Mnemonic::Setbe => Mnemonic::Jbe,
         _ => unreachable!()
     };
 
@@ -16,12 +16,12 @@ impl AsmPrinter for X64AsmPrinter {
         lines.push("section .text\n\n".to_string());
 
         for (name, func) in module.funcs.clone() {
-            if func.linkage == Linkage::Extern {
+            if func.linkage.is_import() {
                 lines.push( format!("global {}\n", name) );
                 continue;
             }
 
-            if func.linkage == Linkage::External {
+            if func.linkage != Linkage::Internal {
                 lines.push( format!("global {}\n", name) );
             } 
 
@@ -60,9 +60,27 @@ impl AsmPrinter for X64AsmPrinter {
             }
         }
 
-        lines.push("section .rodata\n\n".to_string());
-
         for (_, consta) in &module.consts {
+            let section = match &consta.section {
+                Some(section) => section.as_str(),
+                None if consta.thread_local && consta.zero_init.is_some() => ".tbss",
+                None if consta.thread_local => ".tdata",
+                None if consta.zero_init.is_some() => ".bss",
+                None if consta.mutable => ".data",
+                None => ".rodata",
+            };
+
+            lines.push(format!("section {}\n\n", section));
+
+            if let Some(align) = consta.align {
+                lines.push(format!("align {}\n", align));
+            }
+
+            if let Some(size) = consta.zero_init {
+                lines.push(format!("{}: resb {}\n", consta.name, size));
+                continue;
+            }
+
             let mut data = String::new();
 
             let mut first = true;
@@ -90,7 +90,7 @@ impl X64Reg {
         use X64Reg::*;
         match self {
             Rax | Eax | Ax | Al => Rax,
-            Rbx | Ebx | Bx | Bl => R11,
+            Rbx | Ebx | Bx | Bl => Rbx,
             Rcx | Ecx | Cx | Cl => Rcx,
             Rdx | Edx | Dx | Dl => Rdx,
             Rsi | Esi | Si | Sil => Rsi,
@@ -195,7 +195,7 @@ impl X64Reg {
     
     /// gets the subvariant based on the type
     pub fn sub_ty(&self, ty: TypeMetadata) -> X64Reg {
-        if TypeMetadata::f32 == ty || TypeMetadata::f64 == ty {
+        if TypeMetadata::f32 == ty || TypeMetadata::f64 == ty || ty.is_vector() {
             use X64Reg::*;
             return match self {
                 Rax | Eax | Ax | Al => Xmm0,
@@ -313,6 +313,7 @@ impl X64Reg {
 
         match self.sub64() {
             Rbx | Rbp | R12 | R13 | R14 | R15 | Rsp => true,
+            Rsi | Rdi => call == CallConv::WindowsFastCall,
             _ => false,
         }
     }
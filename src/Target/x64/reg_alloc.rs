@@ -1,112 +1,316 @@
-use crate::CodeGen::{Allocator, MachineCallingConvention, Reg, VarLocation};
-use crate::prelude::{Alloca, Function, Ir, Phi, TypeMetadata};
+use crate::CodeGen::{Allocator, ArgPassing, LiveRange, Liveness, MachineCallingConvention, Reg, VarLocation};
+use crate::prelude::{Alloca, Function, Phi, TypeMetadata};
 use crate::Target::Arch;
 use crate::IR::Var;
 
-fn arg_prep(alloc: &mut Allocator, func: &Function, call: MachineCallingConvention) {
-    let mut index = 0;
+/// A variable which currently occupies a register
+#[derive(Debug, Clone)]
+struct Active {
+    name: String,
+    range: LiveRange,
+    reg: Reg,
+    /// arguments are bound to their abi register and can't be spilled
+    fixed: bool,
+}
+
+/// Returns the arguments which are passed over the stack
+fn arg_prep(alloc: &mut Allocator, func: &Function, call: MachineCallingConvention, live: &Liveness, active: &mut Vec<Active>) -> Vec<(String, TypeMetadata)> {
+    let mut stack_args = Vec::new();
+
+    let arg_types: Vec<TypeMetadata> = func.ty.args.iter().map(|(_, ty)| *ty).collect();
+
+    alloc.ret_ty = func.ty.ret;
+
+    // the adress for a return value in memory is passed in the first argument register
+    if call.ret_passing(Arch::X86_64, func.ty.ret) == ArgPassing::Memory {
+        let reg = call.arg(Arch::X86_64, TypeMetadata::ptr, 0).expect("expected a register for the return adress");
+
+        let VarLocation::Mem(off, _) = x64_alloc_stack(alloc, TypeMetadata::ptr) else { unreachable!() };
+
+        alloc.arg_spills.push((reg, off, TypeMetadata::ptr));
+        alloc.sret = Some(off);
+    }
+
+    for ((name, ty), passing) in func.ty.args.iter().zip(call.arg_passing(Arch::X86_64, &arg_types, func.ty.ret)) {
+        if let ArgPassing::Regs(regs) = passing {
+            // aggregate (or 128 bit integer) in registers: the eightbytes are stored into a stack slot in the prolog
+            let location = x64_alloc_stack(alloc, *ty);
+            let VarLocation::Mem(off, _) = location else { unreachable!() };
+
+            for (index, (reg, eightbyte)) in regs.into_iter().enumerate() {
+                alloc.arg_spills.push((reg, off - 8 * index as i64, eightbyte));
+            }
+
+            alloc.vars.insert(name.to_owned(), location);
+            alloc.var_types.insert(name.to_owned(), *ty);
+
+            if ty.is_aggregate() {
+                alloc.allocated_vars.push(name.to_owned());
+            }
+        } else if let ArgPassing::Reg(reg) = passing {
+            // vectors are passed in an xmm register but always live in a stack slot
+            if ty.is_vector() {
+                let location = x64_alloc_stack(alloc, *ty);
+                let VarLocation::Mem(off, _) = location else { unreachable!() };
+
+                alloc.arg_spills.push((reg, off, *ty));
+
+                alloc.vars.insert(name.to_owned(), location);
+                alloc.var_types.insert(name.to_owned(), *ty);
+
+                continue;
+            }
 
-    for (name, ty) in &func.ty.args {
-        if let Some(reg) = call.arg(Arch::X86_64, *ty, index) {
             // argument in an register
 
             alloc.vars.insert(name.to_owned(), VarLocation::Reg(reg));
             alloc.var_types.insert(name.to_owned(), *ty);
 
-            let mut removeal_index = 0;
+            let pool = if ty.float() { &mut alloc.ffpregs } else { &mut alloc.fregs };
 
-            for freg in alloc.fregs.clone() {
-                if freg.is(&reg) {
-                    alloc.fregs.remove(removeal_index);
-                    break;
-                }
+            if let Some(removeal_index) = pool.iter().position(|freg| freg.is(&reg)) {
+                pool.remove(removeal_index);
+            }
 
-                removeal_index += 1;
+            if let Some(range) = live.ranges.get(name) {
+                active.push(Active { name: name.to_owned(), range: *range, reg: reg, fixed: true });
             }
         } else {
             // argument on stack
-            todo!("x64 currently doesn't support arguments which are passed over the stack");
+            // (the location is set after the allocation, cuz it depends on the amount of saved registers)
+            stack_args.push((name.to_owned(), *ty));
+            alloc.epilog = true;
+        }
+    }
+
+    stack_args
+}
+
+/// The stack arguments are stored in the frame of the caller:
+/// ```text
+/// [stack arguments]
+/// [shadow space]          (only on windows)
+/// [return adress]
+/// [callee saved registers]
+/// [old rbp]               <- rbp
+/// ```
+fn stack_arg_prep(alloc: &mut Allocator, stack_args: Vec<(String, TypeMetadata)>) {
+    let mut off = 16 + 8 * alloc.callee_save_registers.len() as i64 + alloc.call.shadow(Arch::X86_64);
+
+    for (name, ty) in stack_args {
+        alloc.vars.insert(name.to_owned(), VarLocation::Mem(-off, ty));
+        alloc.var_types.insert(name.to_owned(), ty);
+
+        // aggregates are passed by value, so the argument is the memory itself
+        if ty.is_aggregate() {
+            alloc.allocated_vars.push(name);
         }
 
-        index += 1;
+        off += ty.byteSize().max(1).div_ceil(8) as i64 * 8;
     }
 }
 
+/// A linear scan register allocator.
+///
+/// It first runs a liveness analysis over the entire function
+/// and then assigns the registers in order of the start of the live ranges.
+/// Registers of variables which aren't used anymore get reused.
+/// If no register is left, the variable which lives the longest gets spilled onto the stack.
 pub(crate) fn x64_alloc(alloc: &mut Allocator, func: &Function) {
-    arg_prep(alloc, func, alloc.call);
+    let live = Liveness::analyze(func);
+
+    let mut active: Vec<Active> = Vec::new();
+
+    let stack_args = arg_prep(alloc, func, alloc.call, &live, &mut active);
 
-    // run phis
     for block in &func.blocks {
         for node in &block.nodes {
-            if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
-                phi_prep( alloc, phi );
+            // allocas live on the stack for the entire function
+            if let Some(alloca) = node.as_any().downcast_ref::<Alloca>() {
+                let location = x64_alloc_stack(alloc, alloca.inner2);
+
+                alloc.vars.insert(alloca.inner1.name.to_owned(), location);
+                alloc.var_types.insert(alloca.inner1.name.to_owned(), alloca.inner2);
+
+                alloc.allocated_vars.push(alloca.inner1.name.to_owned());
             }
+
+            // calls need a stack frame for saving the registers
+            if node.is_call() {
+                alloc.epilog = true;
+
+                // aggregates are returned into a stack slot
+                if let Some(out) = node.output().filter(|out| out.ty.is_aggregate()) {
+                    let location = x64_alloc_stack(alloc, out.ty);
+
+                    alloc.vars.insert(out.name.to_owned(), location);
+                    alloc.var_types.insert(out.name.to_owned(), out.ty);
+
+                    alloc.allocated_vars.push(out.name.to_owned());
+                }
+            }
+        }
+    }
+
+    let mut ranges: Vec<(&String, &LiveRange)> = live.ranges.iter()
+        .filter(|(name, _)| !alloc.vars.contains_key(*name) && !stack_args.iter().any(|(arg, _)| arg == *name))
+        .collect();
+
+    ranges.sort_by(|(a_name, a), (b_name, b)| a.start.cmp(&b.start).then(a_name.cmp(b_name)));
+
+    // the registers which got used anywhere in the function
+    let mut used_regs: Vec<Reg> = Vec::new();
+
+    for (name, range) in ranges {
+        let ty = *live.types.get(name).expect("expected type for variable");
+
+        // 128 bit integers don't fit into one register and vectors are lowered over
+        // the scratch xmm registers, so they always live in a stack slot
+        if ty.is_int128() || ty.is_vector() {
+            let location = x64_alloc_stack(alloc, ty);
+
+            alloc.vars.insert(name.to_owned(), location);
+            alloc.var_types.insert(name.to_owned(), ty);
+
+            continue;
         }
+
+        // expire the old ranges
+        active.retain(|current| {
+            if current.range.end < range.start {
+                x64_free(alloc, VarLocation::Reg(current.reg));
+                false
+            } else { true }
+        });
+
+        let pool = if ty.float() { &mut alloc.ffpregs } else { &mut alloc.fregs };
+
+        let location = if let Some(reg) = pool.pop() {
+            if !used_regs.contains(&reg) {
+                used_regs.push(reg);
+            }
+
+            active.push(Active { name: name.to_owned(), range: *range, reg: reg, fixed: false });
+
+            x64_reg_location(alloc, reg, ty)
+        } else {
+            // we need to spill the variable which lives the longest
+            let spill = active.iter()
+                .enumerate()
+                .filter(|(_, current)| !current.fixed && current.reg.is_fp() == ty.float())
+                .max_by_key(|(_, current)| current.range.end)
+                .map(|(index, current)| (index, current.range.end));
+
+            match spill {
+                Some((index, end)) if end > range.end => {
+                    let spilled = active.remove(index);
+
+                    let spilled_ty = *live.types.get(&spilled.name).expect("expected type for variable");
+                    let stack = x64_alloc_stack(alloc, spilled_ty);
+                    alloc.vars.insert(spilled.name.to_owned(), stack);
+
+                    active.push(Active { name: name.to_owned(), range: *range, reg: spilled.reg, fixed: false });
+
+                    x64_reg_location(alloc, spilled.reg, ty)
+                },
+                _ => x64_alloc_stack(alloc, ty),
+            }
+        };
+
+        alloc.vars.insert(name.to_owned(), location);
+        alloc.var_types.insert(name.to_owned(), ty);
     }
 
+    stack_arg_prep(alloc, stack_args);
+
+    // the phi output is written where the incoming variables are defined
     for block in &func.blocks {
         for node in &block.nodes {
-            node_prep(alloc, node);
+            if let Some(phi) = node.as_any().downcast_ref::<Phi>() {
+                if let Some(out) = alloc.vars.get(&phi.out.name).cloned() {
+                    for (_, var) in &phi.recive_from_blocks {
+                        alloc.phi_vars.insert(var.name.to_owned(), out);
+                    }
+                }
+            }
         }
     }
+
+    scopes_prep(alloc, func, &live);
+
+    // the registers which are still free can be used while generating the code
+    // (for example: temporarys or saves around calls).
+    // Callee saved registers which weren't saved already are removed, because
+    // the epilogs of already compiled blocks wouldn't restore them.
+    let call_conv = alloc.call.call_conv;
+    let callee_saves = alloc.callee_save_registers.clone();
+
+    let keep = |reg: &Reg| {
+        let Reg::x64(x64) = reg else { return false; };
+
+        !used_regs.contains(reg) && (!x64.callee_saved(call_conv) || callee_saves.contains(reg))
+    };
+
+    alloc.fregs.retain(keep);
+    alloc.ffpregs.retain(keep);
 }
 
-fn node_prep(alloc: &mut Allocator, node: &Box<dyn Ir>) {
-    let inputs = node.inputs();
+/// The scope of a node are all variables which hold a value while the node gets executed
+/// (without the output of the node)
+fn scopes_prep(alloc: &mut Allocator, func: &Function, live: &Liveness) {
+    for (block_index, block) in func.blocks.iter().enumerate() {
+        for (node_index, node) in block.nodes.iter().enumerate() {
+            let pos = live.positions[block_index][node_index];
 
-    for _input in inputs {
-        // potential freeing here
-    }
+            let out = node.output().map(|out| out.name);
 
-    let mut scopes = Vec::new();
+            let mut scopes = Vec::new();
 
-    for (name, location) in &alloc.vars {
-        scopes.push( (Var {
-            name: name.to_owned(),
-            ty: *alloc.var_types.get(name).unwrap(),
-        }, *location) );
-    }
+            for name in live.live_at(pos) {
+                let range = live.ranges[&name];
 
-    alloc.scopes.insert(node.dump(), scopes);
+                if range.start == pos || Some(&name) == out.as_ref() {
+                    continue;
+                }
 
-    // handle specific nodes here (like alloca)
-    if let Some(alloca) = node.as_any().downcast_ref::<Alloca>() {
-        let location = x64_alloc_stack(alloc, alloca.inner2);
+                let Some(location) = alloc.vars.get(&name) else { continue; };
 
-        alloc.vars.insert(alloca.inner1.name.to_owned(), location);
-        alloc.var_types.insert(alloca.inner1.name.to_owned(), alloca.inner2);
+                scopes.push( (Var {
+                    name: name.to_owned(),
+                    ty: *alloc.var_types.get(&name).unwrap(),
+                }, *location) );
+            }
 
-        alloc.allocated_vars.push(alloca.inner1.name.to_owned());
-        return;
-    }
+            // nodes which look the same share one scope
+            let entry = alloc.scopes.entry(node.dump()).or_default();
 
-    if let Some(_) = node.as_any().downcast_ref::<Phi>() { // phis were handled before
-        return;
+            for (var, location) in scopes {
+                if !entry.iter().any(|(existing, _)| existing.name == var.name) {
+                    entry.push((var, location));
+                }
+            }
+        }
     }
+}
 
-    if let Some(out) = node.output() {
-        let location = x64_alloc_rv(alloc, out.ty);
+fn x64_reg_location(alloc: &mut Allocator, reg: Reg, ty: TypeMetadata) -> VarLocation {
+    let Reg::x64(x64) = reg else { panic!("x64 reg alloc expects x64 regs") };
 
-        alloc.vars.insert(out.name.to_owned(), location);
-        alloc.var_types.insert(out.name.to_owned(), out.ty);
+    if x64.callee_saved(alloc.call.call_conv) {
+        if !alloc.callee_save_registers.contains(&reg) {
+            alloc.callee_save_registers.push(reg);
+        }
+        alloc.epilog = true;
     }
+
+    VarLocation::Reg(Reg::x64(if x64.is_xmm() { x64 } else { x64.sub_ty(ty) }))
 }
 
 pub(crate) fn x64_alloc_rv(alloc: &mut Allocator, ty: TypeMetadata) -> VarLocation {
     let vec = if ty.float() { &mut alloc.ffpregs } else { &mut alloc.fregs }; // select free registers vec
 
     if let Some(reg) = vec.pop() {
-        let Reg::x64(x64) = reg else { panic!("x64 reg alloc expects x64 regs") };
-        
-        if x64.callee_saved(alloc.call.call_conv) {
-            alloc.callee_save_registers.push(Reg::x64(x64));
-            alloc.epilog = true;
-        }
-
-        VarLocation::Reg(match reg { // this code here just changes the register to be the fitting type 
-            Reg::x64(x64) => Reg::x64(x64.sub_ty(ty)),
-            _ => panic!("the x64 register allocator just got an non x64 register")
-        })
+        x64_reg_location(alloc, reg, ty)
     } else {
         x64_alloc_stack(alloc, ty)
     }
@@ -115,29 +319,21 @@ pub(crate) fn x64_alloc_rv(alloc: &mut Allocator, ty: TypeMetadata) -> VarLocati
 pub(crate) fn x64_alloc_stack(alloc: &mut Allocator, ty: TypeMetadata) -> VarLocation {
     alloc.epilog = true;
 
-    let ret = VarLocation::Mem(alloc.stack_off, ty);    
-    alloc.stack_off += 8; // alignment
-    
-    ret
-}
+    // every slot is 8 byte aligned and the variable starts at the lowest adress of its slot
+    let size = ty.byteSize().max(1).div_ceil(8) as i64 * 8;
 
-pub(crate) fn phi_prep(alloc: &mut Allocator, phi: &Phi) {
-    let out = x64_alloc_rv(alloc, phi.typ);
+    let ret = VarLocation::Mem(alloc.stack_off + size - 8, ty);
+    alloc.stack_off += size;
 
-    for (_, var) in &phi.recive_from_blocks {
-        alloc.phi_vars.insert(var.name.to_owned(), out);
-    }
-
-    alloc.vars.insert(phi.out.name.to_owned(), out);
-    alloc.var_types.insert(phi.out.name.to_owned(), phi.typ);
+    ret
 }
 
 pub(crate) fn x64_free(alloc: &mut Allocator, loc: VarLocation) {
-    if let VarLocation::Reg(reg) = loc {
-        if reg.is_gr() {
-            alloc.fregs.push(reg);
-        } else if reg.is_fp() {
-            alloc.ffpregs.push(reg);
+    if let VarLocation::Reg(Reg::x64(reg)) = loc {
+        if reg.is_xmm() {
+            alloc.ffpregs.push(Reg::x64(reg));
+        } else {
+            alloc.fregs.push(Reg::x64(reg.sub64()));
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,96 @@
+use ygen::prelude::*;
+use ygen::Optimizations::Analysis::*;
+
+/// Builds:
+/// ```text
+/// entry -> outer
+/// outer -> inner, exit
+/// inner -> inner, latch
+/// latch -> outer
+/// exit
+/// ```
+fn nested_loops() -> Module {
+    Module::parse("define i32 @loops(i32 %0) {
+  entry:
+    br outer
+  outer:
+    %1 = cmp eq i32 %0, 0
+    br cond %1 inner, exit
+  inner:
+    %2 = cmp eq i32 %0, 1
+    br cond %2 inner, latch
+  latch:
+    br outer
+  exit:
+    ret i32 %0
+}
+").unwrap()
+}
+
+#[test]
+pub fn dominators() {
+    let module = nested_loops();
+
+    let cfg = ControlFlowGraph::analyze(module.getFunc("loops").unwrap());
+    let (entry, outer, inner, latch, exit) = (0, 1, 2, 3, 4);
+
+    assert_eq!(cfg.index("latch"), Some(latch));
+    assert_eq!(cfg.preds(outer), &[entry, latch]);
+    assert_eq!(cfg.succs(inner), &[inner, latch]);
+
+    let dom = DominatorTree::analyze(&cfg);
+
+    assert_eq!(dom.idom(entry), None);
+    assert_eq!(dom.idom(outer), Some(entry));
+    assert_eq!(dom.idom(inner), Some(outer));
+    assert_eq!(dom.idom(latch), Some(inner));
+    assert_eq!(dom.idom(exit), Some(outer));
+    assert!(dom.dominates(outer, latch));
+    assert!(!dom.dominates(inner, exit));
+
+    let frontiers = dom.frontiers(&cfg);
+
+    assert_eq!(frontiers.frontier(latch), &[outer]);
+    assert_eq!(frontiers.frontier(inner), &[outer, inner]);
+    assert_eq!(frontiers.iterated(&[latch]), vec![outer]);
+
+    let pdom = PostDominatorTree::analyze(&cfg);
+
+    assert_eq!(pdom.exits(), &[exit]);
+    assert_eq!(pdom.ipdom(entry), Some(outer));
+    assert_eq!(pdom.ipdom(inner), Some(latch));
+    assert_eq!(pdom.ipdom(latch), Some(outer));
+    assert!(pdom.post_dominates(exit, inner));
+    assert!(!pdom.post_dominates(inner, outer));
+}
+
+#[test]
+pub fn loops() {
+    let module = nested_loops();
+
+    let cfg = ControlFlowGraph::analyze(module.getFunc("loops").unwrap());
+    let dom = DominatorTree::analyze(&cfg);
+    let loops = LoopAnalysis::analyze(&cfg, &dom);
+    let (entry, outer, inner, latch, exit) = (0, 1, 2, 3, 4);
+
+    assert_eq!(loops.loops().len(), 2);
+
+    let outer_loop = loops.get(loops.loop_with_header(outer).unwrap());
+
+    assert_eq!(outer_loop.blocks, vec![outer, inner, latch]);
+    assert_eq!(outer_loop.latches, vec![latch]);
+    assert_eq!(outer_loop.exits, vec![exit]);
+    assert_eq!(outer_loop.preheader, Some(entry));
+    assert_eq!(outer_loop.depth, 1);
+
+    let inner_loop = loops.get(loops.loop_with_header(inner).unwrap());
+
+    assert_eq!(inner_loop.blocks, vec![inner]);
+    assert_eq!(inner_loop.preheader, None); // outer also branches to exit
+    assert_eq!(inner_loop.parent, loops.loop_with_header(outer));
+    assert_eq!(inner_loop.depth, 2);
+
+    assert_eq!(loops.depth(entry), 0);
+    assert_eq!(loops.depth(latch), 1);
+    assert_eq!(loops.depth(inner), 2);
+}
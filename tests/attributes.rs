@@ -0,0 +1,190 @@
+use std::error::Error;
+use ygen::prelude::*;
+use ygen::Optimizations::{Analysis::ControlFlowGraph, Passes};
+use ygen::Target::initializeAllTargets;
+
+/// Counts the calls to `name` in the entry block of main
+fn calls(module: &Module, name: &str) -> usize {
+    let entry = module.getFunc("main").unwrap().block("entry").unwrap();
+    let callee = format!(" call i32 {name} ");
+
+    entry.nodes().iter().filter(|node| node.is_call() && node.dump().contains(&callee)).count()
+}
+
+#[test]
+pub fn dump_attributes() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let exit = module.add("exit", &FnTy(vec![TypeMetadata::i32], TypeMetadata::Void));
+    exit.import();
+    exit.noreturn();
+    exit.cold();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let square = module.add("square", &ty);
+    square.readnone();
+    square.alwaysinline();
+    square.addBlock("entry");
+    let out = square.BuildMul(ty.arg(0), ty.arg(0));
+    square.BuildRet(out);
+
+    let entry = module.add("entry", &ty);
+    entry.naked();
+    entry.noinline();
+    entry.addBlock("entry");
+    entry.BuildRet(ty.arg(0));
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("declare void @exit(i32 %0) noreturn cold"));
+    assert!(dumped.contains("define local i32 @square(i32 %0) readnone alwaysinline {"));
+    assert!(dumped.contains("define local i32 @entry(i32 %0) naked noinline {"));
+
+    let parsed = Module::parse(dumped.to_owned())?;
+
+    for line in dumped.lines() {
+        assert!(parsed.dump().contains(line));
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn unknown_attribute() {
+    assert!(Module::parse("declare i32 @f() fast\n".to_owned()).is_err());
+}
+
+#[test]
+pub fn removes_unused_readnone_calls() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let square = module.add("square", &ty);
+    square.readnone();
+    square.addBlock("entry");
+    let out = square.BuildMul(ty.arg(0), ty.arg(0));
+    square.BuildRet(out);
+    let square = square.id();
+
+    let rand = module.add("rand", &FnTy(vec![], TypeMetadata::i32));
+    rand.import();
+    let rand = rand.id();
+
+    let main = module.add("main", &ty);
+    main.extrn();
+    main.addBlock("entry");
+    main.BuildCall(&square, vec![IROperand::Var(ty.arg(0))]);
+    main.BuildCall(&rand, vec![]);
+    let used = main.BuildCall(&square, vec![IROperand::Var(ty.arg(0))]);
+    main.BuildRet(used);
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::DeadNodeElimination());
+
+    assert!(module.runPassMngr(mngr));
+
+    // the result of the second square call is used and rand could have side effects
+    assert_eq!(calls(&module, "square"), 1);
+    assert_eq!(calls(&module, "rand"), 1);
+}
+
+#[test]
+pub fn removes_code_after_noreturn_calls() {
+    let mut module = Module::parse("declare void @abort() noreturn
+
+define i32 @main() {
+  entry:
+    %0 = call void abort
+    br after
+  after:
+    ret i32 1
+}
+".to_owned()).unwrap();
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::DeadBlockElimination());
+
+    assert!(module.runPassMngr(mngr));
+
+    // the block with the call still ends with a terminator
+    module.verify().expect("expected a valid module");
+
+    let main = module.getFunc("main").unwrap();
+    let cfg = ControlFlowGraph::analyze(main);
+
+    assert_eq!(cfg.len(), 1);
+    assert_eq!(cfg.index("after"), None);
+
+    let nodes = main.block("entry").unwrap().nodes();
+
+    assert_eq!(nodes.len(), 2);
+    assert!(nodes[0].is_call());
+    assert_eq!(nodes[1].dump(), "ret i32 0");
+}
+
+#[test]
+pub fn inliner_skips_cold_and_naked() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let cold = module.add("cold", &ty);
+    cold.cold();
+    cold.addBlock("entry");
+    cold.BuildRet(ty.arg(0));
+    let cold = cold.id();
+
+    let naked = module.add("naked", &ty);
+    naked.naked();
+    naked.alwaysinline();
+    naked.addBlock("entry");
+    naked.BuildRet(ty.arg(0));
+    let naked = naked.id();
+
+    let main = module.add("main", &ty);
+    main.extrn();
+    main.addBlock("entry");
+    let value = main.BuildCall(&cold, vec![IROperand::Var(ty.arg(0))]);
+    let value = main.BuildCall(&naked, vec![IROperand::Var(value)]);
+    main.BuildRet(value);
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::Inline());
+
+    assert!(!module.runPassMngr(mngr));
+
+    assert_eq!(calls(&module, "cold"), 1);
+    assert_eq!(calls(&module, "naked"), 1);
+}
+
+/// Returns the assembly of a function which needs a stack frame
+fn stack_frame_asm(naked: bool) -> Result<String, Box<dyn Error>> {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let func = module.add("func", &ty);
+    func.extrn();
+    if naked {
+        func.naked();
+    }
+    func.addBlock("entry");
+    let slot = func.BuildAlloca(TypeMetadata::i32);
+    func.BuildStore(slot.to_owned(), ty.arg(0));
+    let out = func.BuildLoad(slot, TypeMetadata::i32);
+    func.BuildRet(out);
+
+    let triple = Triple::from("x86_64-unknown-linux")?;
+
+    module.emitAsm(triple, &mut initializeAllTargets(triple)?)
+}
+
+#[test]
+pub fn naked_has_no_prolog() -> Result<(), Box<dyn Error>> {
+    assert!(stack_frame_asm(false)?.contains("push rbp"));
+    assert!(!stack_frame_asm(true)?.contains("push rbp"));
+
+    Ok(())
+}
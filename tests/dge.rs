@@ -0,0 +1,75 @@
+use ygen::prelude::*;
+use ygen::Optimizations::{Passes, PassRegistry};
+
+/// Creates a module with an external `main` which calls `used`, which loads the adress of `str`
+fn module() -> Module {
+    let mut module = Module();
+
+    let ty = FnTy(vec![], TypeMetadata::i32);
+
+    let string = module.addConst("str");
+    string.set(b"hello\0".to_vec());
+    let string = string.to_owned();
+
+    module.addConst("unused_str").set(b"unused\0".to_vec());
+
+    let used = module.add("used", &ty);
+    used.addBlock("entry");
+    used.BuildAssign(&string);
+    used.BuildRet(Type::i32(0));
+    let used = used.id();
+
+    let unused = module.add("unused", &ty);
+    unused.addBlock("entry");
+    unused.BuildRet(Type::i32(1));
+
+    let main = module.add("main", &ty);
+    main.extrn();
+    main.addBlock("entry");
+    let out = main.BuildCall(&used, vec![]);
+    main.BuildRet(out);
+
+    module
+}
+
+#[test]
+pub fn removes_unreferenced() {
+    let mut module = module();
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::DeadGlobalElimination());
+
+    assert!(module.runPassMngr(mngr));
+
+    let dump = module.dump();
+
+    assert!(dump.contains("@main"));
+    assert!(dump.contains("@used"));
+    assert!(dump.contains("str"));
+    assert!(!dump.contains("@unused"));
+    assert!(!dump.contains("unused_str"));
+}
+
+#[test]
+pub fn keeps_symbols() {
+    let mut module = module();
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::DeadGlobalEliminationKeeping(vec!["unused".to_owned(), "unused_str".to_owned()]));
+
+    assert!(!module.runPassMngr(mngr));
+}
+
+#[test]
+pub fn keep_list_option() {
+    let mut module = module();
+
+    let mngr = PassRegistry::default().parse("globaldce<keep=unused>").unwrap();
+
+    assert!(module.runPassMngr(mngr));
+
+    let dump = module.dump();
+
+    assert!(dump.contains("@unused"));
+    assert!(!dump.contains("unused_str"));
+}
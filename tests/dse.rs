@@ -0,0 +1,62 @@
+use ygen::prelude::*;
+use ygen::Optimizations::Passes;
+
+#[test]
+pub fn forward_and_remove_stores() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let func = module.add("func", &ty);
+    func.extrn();
+
+    func.addBlock("entry");
+    let ptr = func.BuildAlloca(TypeMetadata::i32);
+    let unused = func.BuildAlloca(TypeMetadata::i32);
+    func.BuildStore(ptr.to_owned(), Type::i32(1));
+    func.BuildStore(ptr.to_owned(), ty.arg(0));
+    func.BuildStore(unused, Type::i32(2));
+    let value = func.BuildLoad(ptr, TypeMetadata::i32);
+    func.BuildRet(value);
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::RedundantLoadElimination());
+    mngr.add(Passes::DeadStoreElimination());
+    mngr.add(Passes::DeadNodeElimination());
+
+    assert!(module.runPassMngr(mngr));
+
+    let dump = module.dump();
+
+    assert!(!dump.contains("load"));
+    assert!(!dump.contains("store"));
+    assert!(!dump.contains("alloca"));
+}
+
+#[test]
+pub fn escaped_stores_stay() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::ptr], TypeMetadata::i32);
+
+    let other = module.add("other", &FnTy(vec![TypeMetadata::ptr], TypeMetadata::i32));
+    other.import();
+    let other = other.id();
+
+    let func = module.add("func", &ty);
+    func.extrn();
+
+    func.addBlock("entry");
+    let ptr = func.BuildAlloca(TypeMetadata::i32);
+    func.BuildStore(ptr.to_owned(), Type::i32(1));
+    func.BuildStore(ty.arg(0), Type::i32(2));
+    func.BuildCall(&other, vec![IROperand::Var(ptr.to_owned())]);
+    let value = func.BuildLoad(ptr, TypeMetadata::i32);
+    func.BuildRet(value);
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::RedundantLoadElimination());
+    mngr.add(Passes::DeadStoreElimination());
+
+    assert!(!module.runPassMngr(mngr));
+}
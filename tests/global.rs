@@ -0,0 +1,421 @@
+use std::error::Error;
+use object::{Object, ObjectComdat, ObjectSection, ObjectSymbol, SectionKind};
+use ygen::{prelude::*, Target::initializeAllTargets};
+
+fn build(module: &mut Module) {
+    let counter = module.addGlobal("counter");
+    counter.extrn();
+    counter.zero_init(4);
+    counter.align(8);
+
+    let value = module.addGlobal("value");
+    value.set(vec![5, 0, 0, 0]);
+    value.section(".data.value");
+
+    let step = module.addConst("step");
+    step.set(vec![1, 0, 0, 0]);
+}
+
+#[test]
+pub fn dump() {
+    let mut module = Module();
+
+    build(&mut module);
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("global extern counter = zeroinit 4 align 8"));
+    assert!(dumped.contains("global intern value = [5, 0, 0, 0] section \".data.value\""));
+    assert!(dumped.contains("const intern step = [1, 0, 0, 0]"));
+}
+
+#[test]
+pub fn parse_dumped() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build(&mut module);
+
+    let parsed = Module::parse(module.dump())?;
+
+    assert_eq!(parsed.dump().lines().count(), 3);
+
+    for line in module.dump().lines() {
+        assert!(parsed.dump().contains(line));
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn sections() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build(&mut module);
+
+    let path = std::env::temp_dir().join("ygen_global_sections.o");
+
+    let (obj, _) = module.emitMachineCode(Triple::from("x86_64-unknown-linux")?, &mut initializeAllTargets(Triple::host())?, false)?;
+    obj.emit(std::fs::File::create(&path)?, None)?;
+
+    let bytes = std::fs::read(&path)?;
+    let file = object::File::parse(&*bytes)?;
+
+    let section_of = |name: &str| {
+        let symbol = file.symbol_by_name(name).expect("expected the symbol");
+        file.section_by_index(symbol.section_index().expect("expected a defined symbol")).expect("expected a section")
+    };
+
+    let bss = section_of("counter");
+    assert_eq!(bss.name()?, ".bss");
+    assert_eq!(bss.kind(), SectionKind::UninitializedData);
+    assert_eq!(bss.align(), 8);
+
+    assert_eq!(section_of("value").name()?, ".data.value");
+    assert_eq!(section_of("value").data()?, &[5, 0, 0, 0]);
+
+    assert_eq!(section_of("step").name()?, ".rodata");
+
+    Ok(())
+}
+
+#[test]
+pub fn wasm_memory() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let counter = module.addGlobal("counter");
+    counter.zero_init(4);
+
+    let counter = counter.clone();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let func = module.add("store", &ty);
+    func.addBlock("entry");
+
+    let ptr = func.BuildAssign(&counter);
+    func.BuildStore(ptr.clone(), ty.arg(0));
+    let out = func.BuildLoad(ptr, TypeMetadata::i32);
+    func.BuildRet(out);
+
+    module.verify()?;
+
+    let triple = Triple::from("wasm64-unknown-unknown")?;
+
+    let asm = module.emitAsm(triple, &mut initializeAllTargets(triple)?)?;
+
+    // the adress of the global is its offset in the linear memory
+    assert!(asm.contains("i64.const counter"));
+    assert!(asm.contains("i32.store"));
+    assert!(asm.contains("i32.load"));
+
+    Ok(())
+}
+
+fn build_table(module: &mut Module) {
+    let values = module.addConst("values");
+    values.set_elements(vec![ConstElement::Value(Type::i32(1)), ConstElement::Value(Type::i32(41))]);
+
+    let table = module.addGlobal("table");
+    table.set_elements(vec![
+        ConstElement::Adr("values".into(), 4),
+        ConstElement::Array(vec![ConstElement::Value(Type::i8(1)), ConstElement::Value(Type::f32(1.5))]),
+    ]);
+}
+
+#[test]
+pub fn dump_elements() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_table(&mut module);
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("const intern values = { i32 1, i32 41 }"));
+    assert!(dumped.contains("global intern table = { ptr values 4, { i8 1, f32 1.5 } }"));
+
+    let parsed = Module::parse(dumped.to_owned())?;
+
+    for line in dumped.lines() {
+        assert!(parsed.dump().contains(line));
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn unknown_symbol_in_initializer() {
+    assert!(Module::parse("const table = { ptr unknown }").is_err());
+}
+
+#[test]
+pub fn data_relocations() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_table(&mut module);
+
+    let path = std::env::temp_dir().join("ygen_global_relocations.o");
+
+    let (obj, _) = module.emitMachineCode(Triple::from("x86_64-unknown-linux")?, &mut initializeAllTargets(Triple::host())?, false)?;
+    obj.emit(std::fs::File::create(&path)?, None)?;
+
+    let bytes = std::fs::read(&path)?;
+    let file = object::File::parse(&*bytes)?;
+
+    let table = file.symbol_by_name("table").expect("expected the table");
+    let section = file.section_by_index(table.section_index().expect("expected a defined symbol"))?;
+
+    // the pointer is 8 bytes and the nested elements follow
+    assert_eq!(section.data()?.len(), 8 + 1 + 4);
+
+    let relocs: Vec<(u64, object::Relocation)> = section.relocations().collect();
+    assert_eq!(relocs.len(), 1);
+
+    let (offset, reloc) = &relocs[0];
+    assert_eq!(*offset, table.address());
+    assert_eq!(reloc.kind(), object::RelocationKind::Absolute);
+    assert_eq!(reloc.size(), 64);
+    assert_eq!(reloc.addend(), 4);
+
+    Ok(())
+}
+
+fn build_thread_locals(module: &mut Module) {
+    let counter = module.addGlobal("counter");
+    counter.zero_init(4);
+    counter.thread_local();
+
+    let counter = counter.clone();
+
+    let base = module.addGlobal("base");
+    base.set(vec![40, 0, 0, 0]);
+    base.thread_local();
+
+    let base = base.clone();
+
+    let imported = module.addGlobal("imported");
+    imported.import();
+    imported.zero_init(4);
+    imported.thread_local();
+
+    let imported = imported.clone();
+
+    let ty = FnTy(vec![], TypeMetadata::ptr);
+
+    for (name, global) in [("get_counter", counter), ("get_base", base), ("get_imported", imported)] {
+        let func = module.add(name, &ty);
+        func.addBlock("entry");
+
+        let ptr = func.BuildAssign(&global);
+        func.BuildRet(ptr);
+    }
+}
+
+fn thread_local_relocations(module: &mut Module, name: &str) -> Result<Vec<(String, u32)>, Box<dyn Error>> {
+    let path = std::env::temp_dir().join(name);
+
+    let (obj, _) = module.emitMachineCode(Triple::from("x86_64-unknown-linux")?, &mut initializeAllTargets(Triple::host())?, false)?;
+    obj.emit(std::fs::File::create(&path)?, None)?;
+
+    let bytes = std::fs::read(&path)?;
+    let file = object::File::parse(&*bytes)?;
+
+    let text = file.section_by_name(".text").expect("expected the code");
+
+    let mut relocs = vec![];
+
+    for (_, reloc) in text.relocations() {
+        let object::RelocationTarget::Symbol(index) = reloc.target() else { continue; };
+        let object::RelocationFlags::Elf { r_type } = reloc.flags() else { continue; };
+
+        relocs.push((file.symbol_by_index(index)?.name()?.to_owned(), r_type));
+    }
+
+    Ok(relocs)
+}
+
+#[test]
+pub fn dump_thread_locals() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_thread_locals(&mut module);
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("global intern counter = zeroinit 4 thread_local"));
+    assert!(dumped.contains("global import imported = zeroinit 4 thread_local"));
+
+    let globals: Vec<&str> = dumped.lines().filter(|line| line.starts_with("global")).collect();
+
+    let parsed = Module::parse(globals.iter().map(|line| format!("{}\n", line)).collect::<String>())?;
+
+    for line in globals {
+        assert!(parsed.dump().contains(line));
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn thread_local_sections() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_thread_locals(&mut module);
+
+    let path = std::env::temp_dir().join("ygen_global_tls_sections.o");
+
+    let (obj, _) = module.emitMachineCode(Triple::from("x86_64-unknown-linux")?, &mut initializeAllTargets(Triple::host())?, false)?;
+    obj.emit(std::fs::File::create(&path)?, None)?;
+
+    let bytes = std::fs::read(&path)?;
+    let file = object::File::parse(&*bytes)?;
+
+    for (name, section, kind) in [("counter", ".tbss", SectionKind::UninitializedTls), ("base", ".tdata", SectionKind::Tls)] {
+        let symbol = file.symbol_by_name(name).expect("expected the symbol");
+        assert_eq!(symbol.kind(), object::SymbolKind::Tls);
+
+        let placed = file.section_by_index(symbol.section_index().expect("expected a defined symbol"))?;
+        assert_eq!(placed.name()?, section);
+        assert_eq!(placed.kind(), kind);
+    }
+
+    assert_eq!(file.symbol_by_name("imported").expect("expected the import").kind(), object::SymbolKind::Tls);
+
+    Ok(())
+}
+
+#[test]
+pub fn thread_local_models() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_thread_locals(&mut module);
+
+    let relocs = thread_local_relocations(&mut module, "ygen_global_tls_exec.o")?;
+
+    // local exec for the own thread locals and initial exec for the imported one
+    assert!(relocs.contains(&("counter".into(), object::elf::R_X86_64_TPOFF32)));
+    assert!(relocs.contains(&("base".into(), object::elf::R_X86_64_TPOFF32)));
+    assert!(relocs.contains(&("imported".into(), object::elf::R_X86_64_GOTTPOFF)));
+
+    let mut module = Module();
+
+    build_thread_locals(&mut module);
+    module.activate_pic();
+
+    let relocs = thread_local_relocations(&mut module, "ygen_global_tls_pic.o")?;
+
+    for name in ["counter", "base", "imported"] {
+        assert!(relocs.contains(&(name.into(), object::elf::R_X86_64_TLSGD)));
+    }
+
+    assert!(relocs.contains(&("__tls_get_addr".into(), object::elf::R_X86_64_PLT32)));
+
+    Ok(())
+}
+
+fn build_linkages(module: &mut Module) {
+    let shared = module.addGlobal("shared");
+    shared.common(4);
+    shared.align(4);
+
+    let base = module.addConst("base");
+    base.set(vec![40, 0, 0, 0]);
+    base.linkonce();
+
+    let base = base.clone();
+
+    let maybe = module.addGlobal("maybe");
+    maybe.import_weak();
+
+    let maybe = maybe.clone();
+
+    let ty = FnTy(vec![], TypeMetadata::ptr);
+
+    for (name, global, linkage) in [("weak_fn", base, Function::weak as fn(&mut Function)), ("hidden_fn", maybe, Function::hidden)] {
+        let func = module.add(name, &ty);
+        linkage(func);
+        func.addBlock("entry");
+
+        let ptr = func.BuildAssign(&global);
+        func.BuildRet(ptr);
+    }
+
+    let inline = module.add("inline_fn", &FnTy(vec![], TypeMetadata::i32));
+    inline.linkonce();
+    inline.addBlock("entry");
+    inline.BuildRet(Type::i32(1));
+}
+
+#[test]
+pub fn dump_linkages() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_linkages(&mut module);
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("global common shared = zeroinit 4 align 4"));
+    assert!(dumped.contains("const linkonce base = [40, 0, 0, 0]"));
+    assert!(dumped.contains("global extern_weak maybe = []"));
+    assert!(dumped.contains("define weak ptr @weak_fn()"));
+    assert!(dumped.contains("define hidden ptr @hidden_fn()"));
+    assert!(dumped.contains("define linkonce i32 @inline_fn()"));
+
+    let parsed = Module::parse(dumped.to_owned())?;
+
+    for line in dumped.lines() {
+        assert!(parsed.dump().contains(line));
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn parse_weak_declaration() -> Result<(), Box<dyn Error>> {
+    let module = Module::parse("declare extern_weak i32 @maybe()\n".to_owned())?;
+
+    assert!(module.dump().contains("declare extern_weak i32 @maybe()"));
+
+    Ok(())
+}
+
+#[test]
+pub fn linkage_symbols() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build_linkages(&mut module);
+
+    let path = std::env::temp_dir().join("ygen_global_linkages.o");
+
+    let (obj, _) = module.emitMachineCode(Triple::from("x86_64-unknown-linux")?, &mut initializeAllTargets(Triple::host())?, false)?;
+    obj.emit(std::fs::File::create(&path)?, None)?;
+
+    let bytes = std::fs::read(&path)?;
+    let file = object::File::parse(&*bytes)?;
+
+    let symbol = |name: &str| file.symbol_by_name(name).expect("expected the symbol");
+
+    assert!(symbol("weak_fn").is_weak());
+    assert_eq!(symbol("weak_fn").scope(), object::SymbolScope::Dynamic);
+
+    assert!(!symbol("hidden_fn").is_weak());
+    assert_eq!(symbol("hidden_fn").scope(), object::SymbolScope::Linkage);
+
+    assert!(symbol("maybe").is_weak());
+    assert!(symbol("maybe").is_undefined());
+
+    assert!(symbol("shared").is_common());
+    assert_eq!(symbol("shared").size(), 4);
+
+    // every linkonce symbol has its own section in its own group
+    for (name, section) in [("inline_fn", ".text.inline_fn"), ("base", ".rodata.base")] {
+        assert!(symbol(name).is_weak());
+
+        let placed = file.section_by_index(symbol(name).section_index().expect("expected a defined symbol"))?;
+        assert_eq!(placed.name()?, section);
+
+        let comdat = file.comdats().find(|comdat| comdat.name().ok() == Some(name)).expect("expected a comdat");
+        assert_eq!(comdat.sections().collect::<Vec<_>>(), vec![placed.index()]);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use ygen::prelude::*;
+use ygen::Optimizations::{Analysis::ControlFlowGraph, Passes};
+
+fn nodes<'a>(module: &'a Module, block: &str) -> &'a [Box<dyn Ir>] {
+    module.getFunc("func").unwrap().block(block).unwrap().nodes()
+}
+
+fn optimize(module: &mut Module) {
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::GlobalValueNumbering());
+    mngr.add(Passes::DeadNodeElimination());
+
+    module.runPassMngr(mngr);
+}
+
+#[test]
+pub fn dominated_blocks() {
+    let mut module = Module::parse("define i32 @func(i32 %a, i32 %b) {
+  entry:
+    %sum = add i32 %a, %b
+    %cond = cmp eq i32 %sum, 0
+    br cond %cond then, exit
+  then:
+    %other = add i32 %b, %a
+    %prod = mul i32 %other, %sum
+    ret i32 %prod
+  exit:
+    %diff = sub i32 %a, %b
+    ret i32 %diff
+}
+").unwrap();
+
+    optimize(&mut module);
+
+    let cfg = ControlFlowGraph::analyze(module.getFunc("func").unwrap());
+    assert_eq!(cfg.len(), 3);
+
+    let entry = nodes(&module, "entry");
+    let sum = entry[0].output().unwrap();
+    assert!(entry[0].is_add());
+
+    // the commutative add in `then` is replaced by the dominating one
+    let then = nodes(&module, "then");
+    assert!(!then.iter().any(|node| node.is_add()));
+
+    let prod = then.iter().find(|node| node.is_mul()).unwrap();
+    assert_eq!(prod.inputs(), vec![sum.to_owned(), sum]);
+
+    // `exit` has nothing to reuse
+    assert!(nodes(&module, "exit").iter().any(|node| node.is_sub()));
+}
+
+#[test]
+pub fn loads() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let func = module.add("func", &ty);
+    func.extrn();
+
+    func.addBlock("entry");
+    let ptr = func.BuildAlloca(TypeMetadata::i32);
+    func.BuildStore(ptr.to_owned(), ty.arg(0));
+
+    let first = func.BuildLoad(ptr.to_owned(), TypeMetadata::i32);
+    let second = func.BuildLoad(ptr.to_owned(), TypeMetadata::i32);
+    let sum = func.BuildAdd(first, second);
+
+    // the store changes the value
+    func.BuildStore(ptr.to_owned(), sum.to_owned());
+    let third = func.BuildLoad(ptr.to_owned(), TypeMetadata::i32);
+    let sum = func.BuildAdd(sum, third);
+
+    func.BuildRet(sum);
+
+    optimize(&mut module);
+
+    let entry = nodes(&module, "entry");
+    assert_eq!(entry.iter().filter(|node| node.is_load()).count(), 2);
+}
@@ -0,0 +1,78 @@
+use std::error::Error;
+use ygen::{prelude::*, Target::initializeAllTargets};
+
+fn build(module: &mut Module) -> FunctionType {
+    let add_ty = FnTy(vec![TypeMetadata::i32, TypeMetadata::i32], TypeMetadata::i32);
+
+    let add = module.add("add", &add_ty);
+    add.extrn();
+    add.addBlock("entry");
+
+    let ret = add.BuildAdd(add_ty.arg(0), add_ty.arg(1));
+    add.BuildRet(ret);
+
+    let add = add.id();
+
+    let test_ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let test = module.add("test", &test_ty);
+    test.extrn();
+    test.addBlock("entry");
+
+    let callee = test.BuildAssign(&add);
+    let out = test.BuildIndirectCall(callee, &add_ty, vec![IROperand::Var(test_ty.arg(0)), IROperand::Type(Type::i32(3))]);
+    test.BuildRet(out);
+
+    add_ty
+}
+
+#[test]
+pub fn dump() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build(&mut module);
+
+    module.verify()?;
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("%1 = ptr add"));
+    assert!(dumped.contains("%2 = call i32 %1 %0 3"));
+
+    Ok(())
+}
+
+#[test]
+pub fn callee_needs_to_be_ptr() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i64], TypeMetadata::i32);
+
+    let func = module.add("test", &ty);
+    func.addBlock("entry");
+
+    let out = func.BuildIndirectCall(ty.arg(0), &FnTy(vec![], TypeMetadata::i32), vec![]);
+    func.BuildRet(out);
+
+    assert!(module.verify().is_err());
+}
+
+#[test]
+pub fn wasm_call_indirect() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    build(&mut module);
+
+    module.verify()?;
+
+    let triple = Triple::from("wasm64-unknown-unknown")?;
+
+    let asm = module.emitAsm(triple, &mut initializeAllTargets(triple)?)?;
+
+    // the function pointer is an index into the function table
+    assert!(asm.contains("i64.const add"));
+    assert!(asm.contains("i32.wrap_i64"));
+    assert!(asm.contains("call_indirect (i32, i32) -> (i32)"));
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use ygen::prelude::*;
+use ygen::Optimizations::{Analysis::ControlFlowGraph, Passes};
+
+fn calls(module: &Module, name: &str) -> usize {
+    module.dump().matches(&format!("call i32 {} ", name)).count()
+}
+
+#[test]
+pub fn attributes() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    // too big for the default threshold
+    let big = module.add("big", &ty);
+    big.add_attribute(FunctionAttribute::AlwaysInline);
+    big.addBlock("entry");
+
+    let mut value = ty.arg(0);
+    for _ in 0..100 {
+        value = big.BuildAdd(value, Type::i32(1));
+    }
+    big.BuildRet(value);
+    let big = big.id();
+
+    let small = module.add("small", &ty);
+    small.add_attribute(FunctionAttribute::NoInline);
+    small.addBlock("entry");
+    small.BuildRet(ty.arg(0));
+    let small = small.id();
+
+    let main = module.add("main", &ty);
+    main.extrn();
+    main.addBlock("entry");
+    let value = main.BuildCall(&big, vec![IROperand::Var(ty.arg(0))]);
+    let value = main.BuildCall(&small, vec![IROperand::Var(value)]);
+    main.BuildRet(value);
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::Inline());
+
+    assert!(module.runPassMngr(mngr));
+
+    assert_eq!(calls(&module, "big"), 0);
+    assert_eq!(calls(&module, "small"), 1);
+}
+
+#[test]
+pub fn renames_select_operands() {
+    let mut module = Module();
+
+    let ty = FnTy(vec![TypeMetadata::i32, TypeMetadata::i32, TypeMetadata::i32], TypeMetadata::i32);
+
+    let pick = module.add("pick", &ty);
+    pick.addBlock("entry");
+    let picked = pick.BuildSelect(ty.arg(0), ty.arg(1), ty.arg(2));
+    pick.BuildRet(picked);
+    let pick = pick.id();
+
+    let main = module.add("main", &ty);
+    main.extrn();
+    main.addBlock("entry");
+    let yes = main.BuildAdd(ty.arg(1), Type::i32(1));
+    let value = main.BuildCall(&pick, vec![IROperand::Var(ty.arg(0)), IROperand::Var(yes), IROperand::Var(ty.arg(2))]);
+    main.BuildRet(value);
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::Inline());
+
+    assert!(module.runPassMngr(mngr));
+
+    let main = module.getFunc("main").unwrap();
+    let cfg = ControlFlowGraph::analyze(main);
+
+    let select = (0..cfg.len())
+        .flat_map(|block| main.block(cfg.name(block)).unwrap().nodes())
+        .find(|node| node.is_select())
+        .expect("expected the inlined select");
+
+    // the select reads the copies of the arguments and not the arguments of the caller which have the same names
+    for arg in 0..3 {
+        assert!(!select.inputs().contains(&ty.arg(arg)));
+    }
+}
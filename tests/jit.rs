@@ -69,6 +69,124 @@ pub fn call() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+#[test]
+pub fn indirect_call() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let test_ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+    let add_ty = FnTy(vec![TypeMetadata::i32, TypeMetadata::i32], TypeMetadata::i32);
+
+    let add = module.add("add", &add_ty);
+    add.addBlock("entry");
+
+    let ret = add.BuildAdd(add_ty.arg(0), add_ty.arg(1));
+    add.BuildRet(ret);
+
+    let add = add.id();
+
+    let test = module.add("test", &test_ty);
+    test.addBlock("entry");
+
+    let callee = test.BuildAssign(&add);
+    let out = test.BuildIndirectCall(callee, &add_ty, vec![IROperand::Var(test_ty.arg(0)), IROperand::Type(Type::i32(3))]);
+    test.BuildRet(out);
+
+    module.verify()?;
+
+    let mut funcs = module.jitMap(&mut initializeAllTargets(Triple::host())? )?;
+
+    let mut test: JitFunction<unsafe extern "C" fn(i32) -> i32> = funcs.get_function("test").expect("hmm shouldn't happen");
+
+    unsafe {
+        let ret = test.call(5);
+
+        assert_eq!(ret, 8);
+    }
+
+    Ok(())
+}
+#[test]
+pub fn global_variable() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let counter = module.addGlobal("counter");
+    counter.zero_init(4);
+    counter.align(8);
+
+    let counter = counter.clone();
+
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let func = module.add("increment", &ty);
+    func.addBlock("entry");
+
+    let ptr = func.BuildAssign(&counter);
+    let old = func.BuildLoad(ptr.clone(), TypeMetadata::i32);
+    let new = func.BuildAdd(old, ty.arg(0));
+    func.BuildStore(ptr, new.clone());
+    func.BuildRet(new);
+
+    module.verify()?;
+
+    let mut funcs = module.jitMap(&mut initializeAllTargets(Triple::host())? )?;
+
+    let mut increment: JitFunction<unsafe extern "C" fn(i32) -> i32> = funcs.get_function("increment").expect("hmm shouldn't happen");
+
+    unsafe {
+        assert_eq!(increment.call(5), 5);
+        assert_eq!(increment.call(3), 8);
+    }
+
+    Ok(())
+}
+#[test]
+pub fn pointer_table() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let values = module.addConst("values");
+    values.set_elements(vec![ConstElement::Value(Type::i32(1)), ConstElement::Value(Type::i32(41))]);
+
+    let ty = FnTy(vec![TypeMetadata::i32, TypeMetadata::i32], TypeMetadata::i32);
+
+    let add = module.add("add", &ty);
+    add.addBlock("entry");
+
+    let ret = add.BuildAdd(ty.arg(0), ty.arg(1));
+    add.BuildRet(ret);
+
+    let table = module.addConst("table");
+    table.set_elements(vec![ConstElement::Adr("values".into(), 4), ConstElement::Adr("add".into(), 0)]);
+
+    let table = table.clone();
+
+    let test = module.add("test", &FnTy(vec![], TypeMetadata::i32));
+    test.addBlock("entry");
+
+    let table_ptr = test.BuildAssign(&table);
+
+    let value_ptr = test.BuildLoad(table_ptr.clone(), TypeMetadata::ptr);
+    let value = test.BuildLoad(value_ptr, TypeMetadata::i32);
+
+    let func_ptr = test.BuildGetelemptr(table_ptr, Type::i64(1), TypeMetadata::ptr);
+    let func_ptr = test.BuildLoad(func_ptr, TypeMetadata::ptr);
+
+    let out = test.BuildIndirectCall(func_ptr, &ty, vec![IROperand::Var(value), IROperand::Type(Type::i32(1))]);
+    test.BuildRet(out);
+
+    module.verify()?;
+
+    let mut funcs = module.jitMap(&mut initializeAllTargets(Triple::host())? )?;
+
+    let mut test: JitFunction<unsafe extern "C" fn() -> i32> = funcs.get_function("test").expect("hmm shouldn't happen");
+
+    unsafe {
+        assert_eq!(test.call(), 42);
+        // the pointers are written again for the new adress of the code
+        assert_eq!(test.call(), 42);
+    }
+
+    Ok(())
+}
 /*
 
 // NOT YET SUPPORTED
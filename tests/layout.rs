@@ -0,0 +1,81 @@
+use std::error::Error;
+use ygen::{prelude::*, Target::initializeAllTargets};
+use ygen::IR::DataLayout;
+
+#[test]
+pub fn struct_layout() {
+    let layout = DataLayout::default();
+
+    let ty = TypeMetadata::structure(vec![TypeMetadata::i8, TypeMetadata::i64, TypeMetadata::i32]);
+
+    assert_eq!(layout.size(ty), 24);
+    assert_eq!(layout.align(ty), 8);
+
+    assert_eq!(layout.offset(ty, 0), 0);
+    assert_eq!(layout.offset(ty, 1), 8);
+    assert_eq!(layout.offset(ty, 2), 16);
+}
+
+#[test]
+pub fn array_layout() {
+    let layout = DataLayout::default();
+
+    let elem = TypeMetadata::structure(vec![TypeMetadata::i32, TypeMetadata::i8]);
+    let ty = TypeMetadata::array(elem, 3);
+
+    assert_eq!(layout.size(elem), 8);
+    assert_eq!(layout.size(ty), 24);
+    assert_eq!(layout.offset(ty, 2), 16);
+
+    assert_eq!(layout.flatten(ty).last(), Some(&(20, TypeMetadata::i8)));
+}
+
+#[test]
+pub fn aggregates_are_interned() {
+    let a = TypeMetadata::structure(vec![TypeMetadata::f64, TypeMetadata::ptr]);
+    let b = TypeMetadata::structure(vec![TypeMetadata::f64, TypeMetadata::ptr]);
+
+    assert_eq!(a, b);
+    assert_eq!(a.to_string(), "{f64, ptr}");
+}
+
+#[test]
+pub fn vector_layout() {
+    let layout = DataLayout::default();
+
+    let ty = TypeMetadata::vector(TypeMetadata::f32, 4);
+
+    assert_eq!(layout.size(ty), 16);
+    assert_eq!(layout.align(ty), 16);
+
+    assert_eq!(ty, TypeMetadata::vector(TypeMetadata::f32, 4));
+    assert_eq!(ty.to_string(), "<4 x f32>");
+    assert_eq!(ty.vector_lanes(), Some((TypeMetadata::f32, 4)));
+}
+
+#[test]
+pub fn aggregates_unsupported_on_wasm() -> Result<(), Box<dyn Error>> {
+    let ty = TypeMetadata::structure(vec![TypeMetadata::i64, TypeMetadata::i64, TypeMetadata::i64]);
+
+    for fn_ty in [FnTy(vec![ty], TypeMetadata::i32), FnTy(vec![TypeMetadata::i32], ty)] {
+        let mut module = Module();
+
+        let func = module.add("agg", &fn_ty);
+        func.extrn();
+        func.addBlock("entry");
+
+        let value = func.BuildAlloca(ty);
+        let value = func.BuildLoad(value, ty);
+        let ret = if fn_ty.ret == ty { value } else { func.BuildAssign(Type::i32(0)) };
+        func.BuildRet(ret);
+
+        let triple = Triple::from("wasm64-unknown-unknown")?;
+
+        // the operand stack of wasm can't hold aggregates
+        let err = module.emitMachineCode(triple, &mut initializeAllTargets(triple)?, false).expect_err("expected an unsupported type");
+
+        assert!(err.to_string().contains("doesn't support passing"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use ygen::prelude::*;
+use ygen::Optimizations::{Analysis::*, Pass, PassRegistry, PipelineError};
+
+/// Changes the block the first `changes` times it runs
+struct CountingPass {
+    runs: Rc<Cell<usize>>,
+    changes: usize,
+}
+
+impl Pass for CountingPass {
+    fn run(&self, _block: &mut Block) -> bool {
+        self.runs.set(self.runs.get() + 1);
+
+        self.runs.get() <= self.changes
+    }
+
+    fn name(&self) -> &'static str {
+        "CountingPass"
+    }
+}
+
+fn func() -> Function {
+    let ty = FnTy(vec![TypeMetadata::i32], TypeMetadata::i32);
+
+    let mut func = Func("func".to_owned(), ty.to_owned());
+
+    func.addBlock("entry");
+    func.BuildRet(ty.arg(0));
+
+    func
+}
+
+#[test]
+pub fn fixed_point() {
+    let runs = Rc::new(Cell::new(0));
+
+    let mut mngr = PassManager::fixed_point(10);
+    mngr.add(Box::new(CountingPass { runs: runs.clone(), changes: 3 }));
+
+    assert!(func().runPassMngr(&mngr));
+    // three runs which change something and one which doesn't
+    assert_eq!(runs.get(), 4);
+
+    let runs = Rc::new(Cell::new(0));
+
+    let mut mngr = PassManager::fixed_point(2);
+    mngr.add(Box::new(CountingPass { runs: runs.clone(), changes: 3 }));
+
+    func().runPassMngr(&mngr);
+    assert_eq!(runs.get(), 2);
+
+    let runs = Rc::new(Cell::new(0));
+
+    let mut mngr = PassManager::new();
+    mngr.add(Box::new(CountingPass { runs: runs.clone(), changes: 0 }));
+
+    assert!(!func().runPassMngr(&mngr));
+    assert_eq!(runs.get(), 1);
+}
+
+#[test]
+pub fn analysis_cache() {
+    let func = func();
+
+    let mut cache = AnalysisCache::new();
+
+    let dom = cache.get::<DominatorTree>(&func);
+
+    assert!(cache.cached::<ControlFlowGraph>().is_some());
+    assert!(Rc::ptr_eq(&dom, &cache.get::<DominatorTree>(&func)));
+
+    // the analyses only depend on the control flow graph
+    cache.invalidate(true);
+    assert!(Rc::ptr_eq(&dom, &cache.get::<DominatorTree>(&func)));
+
+    cache.invalidate(false);
+    assert!(cache.cached::<DominatorTree>().is_none());
+    assert!(cache.cached::<ControlFlowGraph>().is_none());
+}
+
+struct UnrollLike {
+    factor: usize,
+}
+
+impl Pass for UnrollLike {
+    fn name(&self) -> &'static str {
+        if self.factor == 4 { "UnrollLike4" } else { "UnrollLike" }
+    }
+}
+
+#[test]
+pub fn pipeline_parsing() {
+    let mut registry = PassRegistry::with_builtin_passes();
+
+    let mngr = registry.parse("const-eval, dne,function(instcombine),repeat<3>(dbe)").unwrap();
+    let names: Vec<&str> = mngr.passes().map(|pass| pass.name()).collect();
+
+    assert_eq!(names, vec!["ConstantEvaluation", "DeadNodeElimination", "FunctionPipeline", "PassManager"]);
+
+    assert!(matches!(registry.parse("dne,unknown-pass"), Err(PipelineError::UnknownPass(name, _)) if name == "unknown-pass"));
+    assert!(matches!(registry.parse("repeat<3>(dne"), Err(PipelineError::Syntax(_, _))));
+    assert!(matches!(registry.parse("dne<factor=2>"), Err(PipelineError::InvalidOptions(_, _))));
+
+    // out of tree passes
+    registry.register_with_options("unroll-like", |options| {
+        Ok(Box::new(UnrollLike { factor: options.get_usize("factor")?.unwrap_or(2) }))
+    });
+
+    let mngr = registry.parse("UnrollLike<factor=4>").unwrap();
+    assert_eq!(mngr.passes().next().unwrap().name(), "UnrollLike4");
+
+    assert!(matches!(registry.parse("unroll-like<factor=x>"), Err(PipelineError::InvalidOptions(_, _))));
+}
+
+#[test]
+pub fn folded_terminators_invalidate_the_cfg() {
+    let mut module = Module::parse("define i32 @func() {
+  entry:
+    %c = i32 0
+    br cond %c dead, live
+  dead:
+    ret i32 1
+  live:
+    ret i32 2
+}
+").unwrap();
+
+    // the second dbe runs on the control flow graph the first one cached
+    let mngr = PassRegistry::with_builtin_passes().parse("dbe,const-eval,dbe").unwrap();
+    assert!(module.runPassMngr(mngr));
+
+    let func = module.getFunc("func").unwrap();
+    let cfg = ControlFlowGraph::analyze(func);
+
+    assert_eq!(cfg.len(), 2);
+    assert_eq!(cfg.index("dead"), None);
+    assert_eq!(cfg.succs(0), &[cfg.index("live").unwrap()]);
+    assert!(func.block("entry").unwrap().nodes().last().unwrap().is_br());
+}
@@ -0,0 +1,36 @@
+use ygen::prelude::*;
+use ygen::Optimizations::{Analysis::ControlFlowGraph, Passes};
+
+#[test]
+pub fn constant_branches() {
+    let mut module = Module::parse("define i32 @func(i32 %0) {
+  entry:
+    %two = i32 2
+    %cond = cmp ge i32 %two, 1
+    br cond %cond then, other
+  then:
+    %value = add i32 %two, 3
+    ret i32 %value
+  other:
+    ret i32 %0
+}
+").unwrap();
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::SparseConditionalConstantPropagation());
+    mngr.add(Passes::DeadBlockElimination());
+
+    assert!(module.runPassMngr(mngr));
+
+    let func = module.getFunc("func").unwrap();
+    let cfg = ControlFlowGraph::analyze(func);
+
+    assert_eq!(cfg.len(), 2);
+    assert_eq!(cfg.index("other"), None);
+    assert_eq!(cfg.succs(0), &[cfg.index("then").unwrap()]);
+    assert!(func.block("entry").unwrap().nodes().last().unwrap().is_br());
+
+    // the add is folded into the returned constant
+    let ret = func.block("then").unwrap().nodes().last().unwrap();
+    assert_eq!(ret.dump(), "ret i32 5");
+}
@@ -0,0 +1,40 @@
+use ygen::prelude::*;
+use ygen::Optimizations::{Analysis::ControlFlowGraph, Passes};
+
+#[test]
+pub fn diamond_to_select() {
+    let mut module = Module::parse("define i32 @func(i32 %a, i32 %b) {
+  entry:
+    %cond = cmp ge i32 %a, %b
+    br cond %cond left, right
+  left:
+    %x = add i32 %a, 1
+    br join
+  right:
+    %y = add i32 %b, 2
+    br forward
+  forward:
+    br join
+  join:
+    %out = phi i32 [%x, left %y, forward]
+    ret i32 %out
+}
+").unwrap();
+
+    let mut mngr = PassManager::new();
+    mngr.add(Passes::SimplifyCFG());
+
+    assert!(module.runPassMngr(mngr));
+
+    let func = module.getFunc("func").unwrap();
+    let cfg = ControlFlowGraph::analyze(func);
+
+    // the whole diamond collapses into the entry block
+    assert_eq!(cfg.len(), 1);
+    assert!(cfg.succs(0).is_empty());
+
+    let nodes = func.block("entry").unwrap().nodes();
+    assert_eq!(nodes.iter().filter(|node| node.is_select()).count(), 1);
+    assert!(!nodes.iter().any(|node| node.is_phi() || node.is_br() || node.is_brcond()));
+    assert!(nodes.last().unwrap().is_ret());
+}
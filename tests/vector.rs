@@ -0,0 +1,166 @@
+use std::error::Error;
+use ygen::{prelude::*, Target::initializeAllTargets};
+
+#[test]
+pub fn scalarized_for_wasm() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let vec = TypeMetadata::vector(TypeMetadata::i32, 4);
+    let ty = FnTy(vec![vec, vec], TypeMetadata::i32);
+
+    let func = module.add("sum", &ty);
+    func.extrn();
+    func.addBlock("entry");
+
+    let sum = func.BuildAdd(ty.arg(0), ty.arg(1));
+    let lane = func.BuildExtractElement(sum, 2);
+    func.BuildRet(lane);
+
+    module.verify()?;
+
+    let triple = Triple::from("wasm64-unknown-unknown")?;
+
+    let funcs = module.emitMachineInstrs(triple, &mut initializeAllTargets(triple)?)?;
+
+    let (_, instrs) = funcs.iter().find(|(name, _)| name == "sum").expect("expected the function");
+
+    // the vector addition is split into one addition per lane
+    let adds = instrs.iter().filter(|instr| instr.to_string().starts_with("add ")).count();
+
+    assert_eq!(adds, 4);
+
+    Ok(())
+}
+
+#[test]
+pub fn scalarized_store_for_wasm() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let vec = TypeMetadata::vector(TypeMetadata::i32, 4);
+    let ty = FnTy(vec![TypeMetadata::ptr, vec], TypeMetadata::i32);
+
+    let func = module.add("store", &ty);
+    func.extrn();
+    func.addBlock("entry");
+
+    func.BuildStore(ty.arg(0), ty.arg(1));
+    func.BuildRet(Type::i32(0));
+
+    module.verify()?;
+
+    let triple = Triple::from("wasm64-unknown-unknown")?;
+
+    let funcs = module.emitMachineInstrs(triple, &mut initializeAllTargets(triple)?)?;
+
+    let (_, instrs) = funcs.iter().find(|(name, _)| name == "store").expect("expected the function");
+
+    // the vector store is split into one store per lane at the offset of the lane
+    let stores = instrs.iter()
+        .filter(|instr| instr.to_string().starts_with("store "))
+        .map(|instr| instr.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(stores.len(), 4);
+
+    for (lane, store) in stores.iter().enumerate() {
+        assert!(store.contains(&format!("{:#x?}", (lane * 4) as f64)), "{}", store);
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn select_on_x64() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let vec = TypeMetadata::vector(TypeMetadata::i32, 4);
+    let ty = FnTy(vec![TypeMetadata::i32, vec, vec], vec);
+
+    let func = module.add("pick", &ty);
+    func.extrn();
+    func.addBlock("entry");
+
+    let picked = func.BuildSelect(ty.arg(0), ty.arg(1), ty.arg(2));
+    func.BuildRet(picked);
+
+    module.verify()?;
+
+    let triple = Triple::from("x86_64-unknown-linux")?;
+
+    // the lanes are selected one by one and the vector is returned in xmm0
+    let asm = module.emitAsm(triple, &mut initializeAllTargets(triple)?)?;
+
+    assert_eq!(asm.matches("cmovne").count(), 4);
+    assert_eq!(asm.matches("cmove ").count(), 4);
+    assert!(asm.contains("movups xmm0"));
+
+    module.emitMachineCode(triple, &mut initializeAllTargets(triple)?, false)?;
+
+    Ok(())
+}
+
+#[test]
+pub fn comparison_rejected() {
+    let mut module = Module();
+
+    let vec = TypeMetadata::vector(TypeMetadata::i32, 4);
+    let ty = FnTy(vec![vec, vec], TypeMetadata::i32);
+
+    let func = module.add("cmp", &ty);
+    func.addBlock("entry");
+
+    // a comparison yields one i1 so the lanes can't be compared
+    let cond = func.BuildCmp(CmpMode::Eqal, ty.arg(0), ty.arg(1));
+    let ret = func.BuildCast(cond, TypeMetadata::i32);
+    func.BuildRet(ret);
+
+    assert_eq!(module.verify(), Err(VerifyError::VectorCmp(vec)));
+}
+
+#[test]
+pub fn dump() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let vec = TypeMetadata::vector(TypeMetadata::f32, 4);
+    let ty = FnTy(vec![vec, vec, TypeMetadata::f32], vec);
+
+    let func = module.add("mix", &ty);
+    func.addBlock("entry");
+
+    let shuffled = func.BuildShuffleVector(ty.arg(0), ty.arg(1), vec![0, 5, 2, 7]);
+    let inserted = func.BuildInsertElement(shuffled, ty.arg(2), 1);
+    func.BuildRet(inserted);
+
+    module.verify()?;
+
+    let dumped = module.dump();
+
+    assert!(dumped.contains("shufflevector <4 x f32> %0, %1, [0, 5, 2, 7]"));
+    assert!(dumped.contains("insertelement <4 x f32>"));
+
+    Ok(())
+}
+
+#[test]
+pub fn unsupported_on_windows() -> Result<(), Box<dyn Error>> {
+    let mut module = Module();
+
+    let vec = TypeMetadata::vector(TypeMetadata::i32, 4);
+    let ty = FnTy(vec![vec], vec);
+
+    let func = module.add("id", &ty);
+    func.extrn();
+    func.addBlock("entry");
+    func.BuildRet(ty.arg(0));
+
+    module.verify()?;
+
+    let triple = Triple::from("x86_64-pc-windows-msvc")?;
+
+    // windows passes vectors over a pointer to a copy which isn't supported
+    let err = module.emitMachineInstrs(triple, &mut initializeAllTargets(triple)?).expect_err("expected an unsupported type");
+
+    assert!(err.to_string().contains("doesn't support passing"));
+
+    Ok(())
+}
@@ -2,17 +2,20 @@ from base64 import decode
 import glob
 from colorama import init, Fore, Style
 import subprocess
+import sys
 
 regex = "tests/**/*.yl"
 tests = glob.glob(regex, recursive=True)
 
+opt = sys.argv[1:] # optional optimization level (e.g. `python tools/test.py 2`)
+
 init()
 
 failed = 0
 passed = 0
 
 for test in tests:
-    process = subprocess.run(["cargo", "run", "-p", "ytest", "--", f"-t={test}"], capture_output=True)
+    process = subprocess.run(["cargo", "run", "-p", "ytest", "--", f"-t={test}"] + [f"-opt={level}" for level in opt], capture_output=True)
     if process.returncode == 0:
         print(Fore.GREEN + "SUCESS " + test + Style.RESET_ALL)
         passed += 1
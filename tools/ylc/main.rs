@@ -5,7 +5,7 @@ use std::process::exit;
 use std::error::Error;
 
 use ygen::prelude::{DebugNode, PassManager};
-use ygen::Optimizations::Passes;
+use ygen::Optimizations::{parse_pipeline, OptLevel};
 use ygen::Support::{ColorProfile, Colorize};
 use ygen::Target::initializeAllTargets;
 use ygen::{Support::Cli, Target::Triple};
@@ -34,10 +34,14 @@ fn main() -> Result<(), Box<dyn Error>> {
     cli.add_opt("fmt-clr", "format-colored", "Reprints the ir to stderr with color information");
     cli.add_opt("fmt", "format", "Prints the ir formatted to stdout");
     
-    cli.add_opt("O", "optimize-simple", "Run simple optimizations");
+    cli.add_opt("O", "optimize-simple", "Run simple optimizations (same as -O1)");
+    cli.add_opt("O1", "optimize-1", "Run simple optimizations");
+    cli.add_opt("O2", "optimize-2", "Run the standard optimizations");
+    cli.add_opt("O3", "optimize-3", "Run all optimizations");
     cli.add_arg("passes", "optimization-passes", "The optimization passes to run", false);
     
     cli.add_opt("g", "debug", "Adds debugging metadata");
+    cli.add_opt("pic", "position-independent", "Generates position independent code (for shared libraries)");
 
     cli.scan();
 
@@ -136,7 +140,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     for stmt in &parser.out {
         match stmt {
-            ygen::IR::parser::parser::IrStmt::Func { name: _, ret: _, args: _, body, scope: _, location: _ } => {
+            ygen::IR::parser::parser::IrStmt::Func { name: _, ret: _, args: _, body, scope: _, attributes: _, location: _ } => {
                 for (_, block) in body {
                     for node in &block.body {
                         if let Some(dbg) = node.inst.as_any().downcast_ref::<DebugNode>() {
@@ -161,35 +165,26 @@ fn main() -> Result<(), Box<dyn Error>> {
         &std::path::PathBuf::from(dbg_file)
     );
 
-    if let Some(passes) = cli.arg_val("passes") {
-        let mut opts = PassManager::new();
-
-        let passes = passes.split(',').collect::<Vec<&str>>();
-
-        for pass in passes {
-            let pass = match pass.to_lowercase().as_str() {
-                "cp" | "const_eval" | "const_evaluation" | "const-eval" | "const-evaluation" =>     Some( Passes::ConstantEvaluation() ),
-                "dne" | "dead_node" | "dead_node_elim" | "dead-node" | "dead-node-elimination" =>   Some( Passes::DeadNodeElimination() ),
-                "dbe" | "dead_block" | "dead_block_elim" | "dead-block" | "dead-block-elimination" =>   Some( Passes::DeadBlockElimination() ),
-                "instcombine" | "instrcombine" =>   Some( Passes::InstrCombine() ),
-                _ => {eprintln!("unkown pass: {}", pass); None },
-            };
-
-            if let Some(pass) = pass {
-                opts.add( pass );
-            }
-        }
-
-        module.runPassMngr(opts);
-    } else if cli.opt("O") {
-        let mut opts = PassManager::new();
+    if cli.opt("pic") {
+        module.activate_pic();
+    }
 
-        opts.add( Passes::InstrCombine() );
-        opts.add( Passes::ConstantEvaluation() );
-        opts.add( Passes::DeadBlockElimination() );
-        opts.add( Passes::DeadNodeElimination() );
+    if let Some(passes) = cli.arg_val("passes") {
+        let opts = match parse_pipeline(&passes) {
+            Ok(opts) => opts,
+            Err(err) => {
+                println!("{}: {}", "Error".red().bold(), err);
+                exit(-1);
+            },
+        };
 
         module.runPassMngr(opts);
+    } else if cli.opt("O3") {
+        module.runPassMngr(PassManager::for_level(OptLevel::O3));
+    } else if cli.opt("O2") {
+        module.runPassMngr(PassManager::for_level(OptLevel::O2));
+    } else if cli.opt("O") || cli.opt("O1") {
+        module.runPassMngr(PassManager::for_level(OptLevel::O1));
     }
 
     if cli.opt("fmt-clr") {
@@ -19,6 +19,7 @@ fn main() {
     cli.add_opt("neg-exit", "exit-code-neg", "Ytest exits automaticly even with `no-exit` if the programm returned with code -1");
 
     cli.add_arg("t", "test", "The file to the testcase", true);
+    cli.add_arg("opt", "opt-level", "Compiles the testcase with the given optimization level (only for tests which don't check the output)", false);
 
     cli.scan();
 
@@ -104,9 +105,16 @@ fn main() {
 
     let mut code = 0;
 
+    let opt = cli.arg_val("opt").filter(|_| parsed.expected_out.is_none());
+
     for cmd in parsed.cmd {
         let mut args = cmd  .replace("%s", path_str)
                                 .replace("%c", path2_str);
+        if let Some(level) = &opt {
+            if !args.contains(" -O") && !args.contains("-passes") {
+                args = args.replace("ylc -- ", &format!("ylc -- -O{} ", level));
+            }
+        }
         if cfg!(target_os = "windows") {
             args = args.replace("./", "");
         }